@@ -0,0 +1,182 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+//! Simple timing harness for hot paths of nt-hive.
+//!
+//! Run it in release mode for meaningful numbers:
+//! `cargo run --release --example bench -- <FILENAME> [ITERATIONS]`
+
+use std::env;
+use std::fs::File;
+use std::hint::black_box;
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use nt_hive::{Hive, KeyNode};
+use zerocopy::SplitByteSlice;
+
+fn main() -> Result<(), String> {
+    // Parse arguments.
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        println!("Usage: bench <FILENAME> [ITERATIONS]");
+        return Ok(());
+    }
+
+    let iterations = match args.get(2) {
+        Some(iterations) => iterations
+            .parse::<u32>()
+            .map_err(|e| format!("Invalid iteration count: {e}"))?,
+        None => 10,
+    };
+
+    // Read the hive file.
+    let filename = &args[1];
+    let mut f = File::open(filename).map_err(|e| format!("Error opening hive file: {e}"))?;
+    let mut buffer = Vec::<u8>::new();
+    f.read_to_end(&mut buffer)
+        .map_err(|e| format!("Error reading hive file: {e}"))?;
+
+    // Parse the hive.
+    let hive = Hive::new(buffer.as_ref()).map_err(|e| format!("Error parsing hive file: {e}"))?;
+    let root_key = hive
+        .root_key_node()
+        .map_err(|e| format!("Error getting root key: {e}"))?;
+
+    // Both timeline approaches must agree before their timings are worth comparing.
+    let fast = key_timestamps(&hive)?;
+    let naive = naive_key_timestamps(&root_key)?;
+    if fast != naive {
+        return Err("Hive::key_timestamps and the naive walk return different results".to_string());
+    }
+    println!("{} keys", fast.0);
+
+    let key_timestamps_time = measure(iterations, || key_timestamps(&hive))?;
+    let naive_time = measure(iterations, || naive_key_timestamps(&root_key))?;
+    println!("Hive::key_timestamps:        {key_timestamps_time:?} per iteration");
+    println!("naive walk and format paths: {naive_time:?} per iteration");
+
+    let value_count = iterate_values(&root_key)?;
+    println!("{value_count} values");
+
+    let values_time = measure(iterations, || iterate_values(&root_key))?;
+    println!("KeyNode::values accessors:   {values_time:?} per iteration");
+
+    Ok(())
+}
+
+/// Calls `f` `iterations` times and returns the average time of a single call.
+fn measure<T, F>(iterations: u32, mut f: F) -> Result<Duration, String>
+where
+    F: FnMut() -> Result<T, String>,
+{
+    let start = Instant::now();
+    for _i in 0..iterations {
+        black_box(f()?);
+    }
+
+    Ok(start.elapsed() / iterations.max(1))
+}
+
+/// Returns the number of keys and a checksum over their depths and timestamps, using [`Hive::key_timestamps`].
+fn key_timestamps<B>(hive: &Hive<B>) -> Result<(usize, u64), String>
+where
+    B: SplitByteSlice,
+{
+    let mut count = 0;
+    let mut checksum = 0u64;
+
+    hive.key_timestamps(|path, timestamp| {
+        count += 1;
+        checksum = checksum.wrapping_add(timestamp ^ path.len() as u64);
+    })
+    .map_err(|e| format!("Error getting key timestamps: {e}"))?;
+
+    Ok((count, checksum))
+}
+
+/// Like [`key_timestamps`], but recursively visits every key and formats its full path as a string,
+/// as a consumer without [`Hive::key_timestamps`] would do.
+fn naive_key_timestamps<B>(root_key: &KeyNode<B>) -> Result<(usize, u64), String>
+where
+    B: SplitByteSlice,
+{
+    fn walk<B>(
+        key_node: &KeyNode<B>,
+        path: &str,
+        depth: usize,
+        count: &mut usize,
+        checksum: &mut u64,
+    ) -> Result<(), String>
+    where
+        B: SplitByteSlice,
+    {
+        *count += 1;
+        *checksum = checksum.wrapping_add(key_node.timestamp() ^ depth as u64);
+
+        if let Some(subkeys) = key_node.subkeys() {
+            let subkeys = subkeys.map_err(|e| format!("Error getting subkeys: {e}"))?;
+
+            for subkey in subkeys {
+                let subkey = subkey.map_err(|e| format!("Error enumerating key: {e}"))?;
+                let name = subkey
+                    .name()
+                    .map_err(|e| format!("Error getting key name: {e}"))?;
+                let subkey_path = if path.is_empty() {
+                    name.to_string_lossy()
+                } else {
+                    format!("{path}\\{name}")
+                };
+
+                walk(&subkey, &subkey_path, depth + 1, count, checksum)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut count = 0;
+    let mut checksum = 0u64;
+    walk(root_key, "", 0, &mut count, &mut checksum)?;
+
+    Ok((count, checksum))
+}
+
+/// Visits every value of every key and calls the accessors that depend on the Key Value header.
+/// Returns the number of values.
+fn iterate_values<B>(root_key: &KeyNode<B>) -> Result<usize, String>
+where
+    B: SplitByteSlice,
+{
+    let mut count = 0;
+    let mut key_nodes = vec![root_key.clone()];
+
+    while let Some(key_node) = key_nodes.pop() {
+        if let Some(values) = key_node.values() {
+            let values = values.map_err(|e| format!("Error creating value iterator: {e}"))?;
+
+            for value in values {
+                let value = value.map_err(|e| format!("Error enumerating value: {e}"))?;
+                black_box(
+                    value
+                        .name()
+                        .map_err(|e| format!("Error getting value name: {e}"))?,
+                );
+                black_box(value.data_type().ok());
+                black_box(value.data_size());
+                black_box(value.data().ok());
+                count += 1;
+            }
+        }
+
+        if let Some(subkeys) = key_node.subkeys() {
+            let subkeys = subkeys.map_err(|e| format!("Error getting subkeys: {e}"))?;
+
+            for subkey in subkeys {
+                key_nodes.push(subkey.map_err(|e| format!("Error enumerating key: {e}"))?);
+            }
+        }
+    }
+
+    Ok(count)
+}
@@ -5,7 +5,7 @@ use std::env;
 use std::fs::File;
 use std::io::Read;
 
-use nt_hive::{Hive, KeyNode, KeyValueData, KeyValueDataType, Result};
+use nt_hive::{Hive, KeyNode, KeyValueParsedData};
 use zerocopy::SplitByteSlice;
 
 fn main() -> Result<(), String> {
@@ -85,51 +85,26 @@ where
 
                     // First line: Value Name, Data Type, and Data Size
                     print_indentation(level);
-                    println!("  ○ {value_name} - {value_type:?} - {data_size}");
+                    println!("  ○ {value_name} - {value_type} - {data_size}");
 
                     // Second line: The actual Value Data
                     print_indentation(level);
                     print!("    ");
 
-                    match value_type {
-                        KeyValueDataType::RegSZ | KeyValueDataType::RegExpandSZ => {
-                            let string_data = value
-                                .string_data()
-                                .map_err(|e| format!("Error getting string data: {e}"))?;
-                            println!("{string_data}")
-                        }
-                        KeyValueDataType::RegBinary => {
-                            let binary_data = value
-                                .data()
-                                .map_err(|e| format!("Error getting binary data: {e}"))?;
-                            match binary_data {
-                                KeyValueData::Small(data) => println!("{data:?}"),
-                                KeyValueData::Big(_iter) => println!("BIG DATA"),
-                            }
-                        }
-                        KeyValueDataType::RegDWord | KeyValueDataType::RegDWordBigEndian => {
-                            let dword_data = value
-                                .dword_data()
-                                .map_err(|e| format!("Error getting DWORD data: {e}"))?;
-                            println!("{dword_data}")
-                        }
-                        KeyValueDataType::RegMultiSZ => {
-                            let multi_string_data = value
-                                .multi_string_data()
-                                .map_err(|e| format!("Error getting multi string data: {e}"))?
-                                .collect::<Result<Vec<_>>>()
-                                .map_err(|e| {
-                                    format!("Error getting multi string element data: {e}")
-                                })?;
-                            println!("{multi_string_data:?}")
-                        }
-                        KeyValueDataType::RegQWord => {
-                            let qword_data = value
-                                .qword_data()
-                                .map_err(|e| format!("Error getting QWORD data: {e}"))?;
-                            println!("{qword_data}")
-                        }
-                        _ => println!(),
+                    let parsed_data = value
+                        .parse_data()
+                        .map_err(|e| format!("Error getting value data: {e}"))?;
+
+                    match parsed_data {
+                        KeyValueParsedData::None => println!(),
+                        KeyValueParsedData::String(string)
+                        | KeyValueParsedData::ExpandString(string)
+                        | KeyValueParsedData::Link(string) => println!("{string}"),
+                        KeyValueParsedData::MultiString(strings) => println!("{strings:?}"),
+                        KeyValueParsedData::U32(dword) => println!("{dword}"),
+                        KeyValueParsedData::U64(qword) => println!("{qword}"),
+                        KeyValueParsedData::Binary(data)
+                        | KeyValueParsedData::Unknown { data, .. } => println!("{data:?}"),
                     }
                 }
             }
@@ -0,0 +1,105 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use zerocopy::SplitByteSlice;
+
+use crate::error::Result;
+use crate::hive::Hive;
+
+/// Processor architecture of the Windows installation a hive originates from, as returned by
+/// [`Hive::detect_architecture`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Arch {
+    /// 32-bit Windows.
+    X86,
+    /// 64-bit Windows (x64 or ARM64), which keeps separate registry views for 32-bit applications.
+    X64,
+}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Tries to find out whether this SOFTWARE hive originates from a 32-bit or 64-bit Windows installation.
+    ///
+    /// 64-bit Windows redirects registry accesses of 32-bit applications to `WOW6432Node` keys.
+    /// If `Classes\WOW6432Node` or `WOW6432Node` exists, [`Arch::X64`] is returned.
+    /// If only `Classes` exists, [`Arch::X86`] is returned.
+    ///
+    /// Returns `None` if this hive has no `Classes` key, which is the case for all hives other than SOFTWARE.
+    /// This is only a heuristic and can be fooled by hives that have been edited by hand.
+    pub fn detect_architecture(&self) -> Option<Result<Arch>> {
+        let root_key_node = iter_try!(self.root_key_node());
+        let classes_key_node = iter_try!(root_key_node.subkey("Classes")?);
+
+        let wow64_key_node = classes_key_node
+            .subkey("WOW6432Node")
+            .or_else(|| root_key_node.subkey("WOW6432Node"));
+        if let Some(wow64_key_node) = wow64_key_node {
+            iter_try!(wow64_key_node);
+            return Some(Ok(Arch::X64));
+        }
+
+        Some(Ok(Arch::X86))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::{key_node_field, rename_key_node};
+    use crate::key_node::KeyNodeField;
+    use crate::*;
+
+    #[test]
+    fn test_detect_architecture() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        // testhive is no SOFTWARE hive.
+        assert!(hive.detect_architecture().is_none());
+
+        // Rename "character-encoding-test" to "Classes", keeping the alphabetical order of the root key's subkeys.
+        let root_key_node = hive.root_key_node().unwrap();
+        let classes_offset = root_key_node
+            .subkey("character-encoding-test")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+        let subpath_test = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        let subpath_test_offset = subpath_test.cell_offset();
+        let with_two_levels_offset = subpath_test
+            .subkey("with-two-levels-of-subkeys")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+
+        let mut x86_testhive = testhive.clone();
+        rename_key_node(&mut x86_testhive, classes_offset, "Classes");
+        let hive = Hive::new(x86_testhive.as_ref()).unwrap();
+        assert_eq!(hive.detect_architecture(), Some(Ok(Arch::X86)));
+
+        // Additionally rename "subpath-test" to "WOW6432Node", which is still the last subkey.
+        let mut x64_testhive = x86_testhive.clone();
+        rename_key_node(&mut x64_testhive, subpath_test_offset, "WOW6432Node");
+        let hive = Hive::new(x64_testhive.as_ref()).unwrap();
+        assert_eq!(hive.detect_architecture(), Some(Ok(Arch::X64)));
+
+        // Alternatively, let "Classes" share the subkeys of "subpath-test" and rename the last one of them
+        // ("with-two-levels-of-subkeys") to "WOW6432Node".
+        let mut x64_testhive = x86_testhive;
+        let subkeys_list_fields = key_node_field(subpath_test_offset, KeyNodeField::SubkeyCount)
+            ..key_node_field(subpath_test_offset, KeyNodeField::VolatileSubkeysListOffset);
+        x64_testhive.copy_within(
+            subkeys_list_fields,
+            key_node_field(classes_offset, KeyNodeField::SubkeyCount),
+        );
+        rename_key_node(&mut x64_testhive, with_two_levels_offset, "WOW6432Node");
+        let hive = Hive::new(x64_testhive.as_ref()).unwrap();
+        assert_eq!(hive.detect_architecture(), Some(Ok(Arch::X64)));
+        assert!(hive
+            .root_key_node()
+            .unwrap()
+            .subpath("Classes\\WOW6432Node")
+            .is_some());
+    }
+}
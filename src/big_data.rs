@@ -15,6 +15,9 @@ use crate::error::{NtHiveError, Result};
 use crate::helpers::byte_subrange;
 use crate::hive::Hive;
 
+#[cfg(feature = "alloc")]
+use crate::dump::StructLayout;
+
 /// Number of bytes that a single Big Data segment can hold.
 /// Every Big Data segment contains that many data bytes except for the last one.
 ///
@@ -25,16 +28,23 @@ pub(crate) const BIG_DATA_SEGMENT_SIZE: usize = 16344;
 
 /// On-Disk Structure of a Big Data header.
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
+#[repr(C, packed)]
 struct BigDataHeader {
     signature: [u8; 2],
     segment_count: U16<LittleEndian>,
     segment_list_offset: U32<LittleEndian>,
 }
 
+#[cfg(feature = "alloc")]
+pub(crate) const BIG_DATA_HEADER_LAYOUT: StructLayout = struct_layout!(BigDataHeader {
+    signature,
+    segment_count,
+    segment_list_offset,
+});
+
 /// On-Disk Structure of a Big Data list item.
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
+#[repr(C, packed)]
 struct BigDataListItem {
     segment_offset: U32<LittleEndian>,
 }
@@ -47,7 +57,8 @@ impl BigDataListItemRange {
     where
         B: SplitByteSlice,
     {
-        let item = Ref::<&[u8], BigDataListItem>::from_bytes(&hive.data[self.0.clone()]).unwrap();
+        let item =
+            Ref::<&[u8], BigDataListItem>::from_bytes(hive.data_slice(self.0.clone())).unwrap();
         item.segment_offset.get()
     }
 }
@@ -71,17 +82,18 @@ struct BigDataListItemRanges {
 }
 
 impl BigDataListItemRanges {
+    /// Returns the ranges of all segment list items required for `data_size` bytes.
+    ///
+    /// If the segment list cell is too small for them, the ranges of all items that fit are returned along
+    /// with the error to report once these items have been used up.
     fn new<B>(
         hive: &Hive<B>,
         data_size: u32,
-        data_size_field_offset: usize,
         header_cell_range: Range<usize>,
-    ) -> Result<Self>
+    ) -> Result<(Self, Option<NtHiveError>)>
     where
         B: SplitByteSlice,
     {
-        let data_size = data_size as usize;
-
         // The passed `header_cell_range` contains just the `BigDataHeader`.
         // Verify this header.
         let header_range = byte_subrange(&header_cell_range, mem::size_of::<BigDataHeader>())
@@ -91,37 +103,58 @@ impl BigDataListItemRanges {
                 actual: header_cell_range.len(),
             })?;
 
-        let header = Ref::from_bytes(&hive.data[header_range]).unwrap();
+        let header = Ref::from_bytes(hive.data_slice(header_range)).unwrap();
         Self::validate_signature(hive, &header)?;
 
         // Check the `segment_count` of the `BigDataHeader`.
-        // Verify that we have enough segments to contain the entire data.
+        // Derive the required number of segments from the data size instead of trusting the header.
+        // There must be enough segments to contain the entire data, and Windows never writes more.
         let segment_count = header.segment_count.get();
-        let max_data_size = segment_count as usize * BIG_DATA_SEGMENT_SIZE;
-        if data_size > max_data_size {
-            return Err(NtHiveError::InvalidSizeField {
-                offset: data_size_field_offset,
-                expected: max_data_size,
-                actual: data_size,
+        let required_segment_count = data_size.div_ceil(BIG_DATA_SEGMENT_SIZE as u32);
+        let segment_count_is_valid = if hive.requires_exact_big_data_segment_count() {
+            u32::from(segment_count) == required_segment_count
+        } else {
+            u32::from(segment_count) >= required_segment_count
+        };
+
+        if !segment_count_is_valid {
+            return Err(NtHiveError::InvalidSegmentCount {
+                offset: hive.offset_of_field(&header.segment_count),
+                expected: required_segment_count,
+                actual: segment_count,
             });
         }
 
         // Get the Big Data segment list referenced by the `segment_list_offset`.
         let segment_list_offset = header.segment_list_offset.get();
-        let segment_list_cell_range = hive.cell_range_from_data_offset(segment_list_offset)?;
+        let segment_list_cell_range = hive.cell_range_from_offset_field(
+            segment_list_offset,
+            hive.offset_of_field(&header.segment_list_offset),
+        )?;
 
         // Finally calculate the range of Big Data list items we want to iterate over.
-        let byte_count = segment_count as usize * mem::size_of::<BigDataListItem>();
-
-        let items_range = byte_subrange(&segment_list_cell_range, byte_count).ok_or_else(|| {
-            NtHiveError::InvalidSizeField {
-                offset: hive.offset_of_field(&header.segment_count),
-                expected: byte_count,
-                actual: segment_list_cell_range.len(),
-            }
-        })?;
+        // Excess segments of a tolerated larger `segment_count` are ignored.
+        let byte_count = usize::try_from(required_segment_count)
+            .ok()
+            .and_then(|count| count.checked_mul(mem::size_of::<BigDataListItem>()))
+            .unwrap_or(usize::MAX);
+
+        if let Some(items_range) = byte_subrange(&segment_list_cell_range, byte_count) {
+            return Ok((Self { items_range }, None));
+        }
 
-        Ok(Self { items_range })
+        // Leave it to the caller whether to use the segments that are there.
+        let truncation_error = NtHiveError::InvalidSizeField {
+            offset: hive.offset_of_field(&header.segment_count),
+            expected: byte_count,
+            actual: segment_list_cell_range.len(),
+        };
+        let available_byte_count = segment_list_cell_range.len()
+            - segment_list_cell_range.len() % mem::size_of::<BigDataListItem>();
+        let items_range =
+            segment_list_cell_range.start..segment_list_cell_range.start + available_byte_count;
+
+        Ok((Self { items_range }, Some(truncation_error)))
     }
 
     fn validate_signature<B>(hive: &Hive<B>, header: &Ref<&[u8], BigDataHeader>) -> Result<()>
@@ -191,11 +224,27 @@ impl FusedIterator for BigDataListItemRanges {}
 /// On-Disk Signature: `db`
 ///
 /// [`KeyValueData`]: crate::key_value::KeyValueData
-#[derive(Clone)]
 pub struct BigDataSlices<'h, B: SplitByteSlice> {
     hive: &'h Hive<B>,
     big_data_list_item_ranges: BigDataListItemRanges,
     bytes_left: usize,
+    /// Error returned after the last segment if the segment list is too short for the data size.
+    truncation_error: Option<NtHiveError>,
+}
+
+// Implemented manually, because `#[derive(Clone)]` would needlessly require `B: Clone`.
+impl<B> Clone for BigDataSlices<'_, B>
+where
+    B: SplitByteSlice,
+{
+    fn clone(&self) -> Self {
+        Self {
+            hive: self.hive,
+            big_data_list_item_ranges: self.big_data_list_item_ranges.clone(),
+            bytes_left: self.bytes_left,
+            truncation_error: self.truncation_error.clone(),
+        }
+    }
 }
 
 impl<'h, B> BigDataSlices<'h, B>
@@ -205,18 +254,32 @@ where
     pub(crate) fn new(
         hive: &'h Hive<B>,
         data_size: u32,
-        data_size_field_offset: usize,
         header_cell_range: Range<usize>,
     ) -> Result<Self> {
-        let big_data_list_item_ranges =
-            BigDataListItemRanges::new(hive, data_size, data_size_field_offset, header_cell_range)?;
+        let (big_data_list_item_ranges, truncation_error) =
+            BigDataListItemRanges::new(hive, data_size, header_cell_range)?;
 
         Ok(Self {
             hive,
             big_data_list_item_ranges,
             bytes_left: data_size as usize,
+            truncation_error,
         })
     }
+
+    /// Returns the number of data bytes that are yet to be returned by this iterator.
+    pub(crate) fn bytes_left(&self) -> usize {
+        self.bytes_left
+    }
+
+    /// Turns this iterator into a [`BigDataSlicesLossy`] iterator, which reports unreadable segments
+    /// as [`BigDataGap`]s and continues with the next segment.
+    pub fn into_lossy(self) -> BigDataSlicesLossy<'h, B> {
+        BigDataSlicesLossy {
+            data_size: self.bytes_left,
+            inner: self,
+        }
+    }
 }
 
 impl<'h, B> Iterator for BigDataSlices<'h, B>
@@ -233,13 +296,22 @@ where
         }
 
         // Get the next segment offset and adjust `bytes_left` accordingly.
-        let big_data_list_item_range = self.big_data_list_item_ranges.next()?;
+        // If the segment list ends too early, all remaining bytes are lost at once.
+        let Some(big_data_list_item_range) = self.big_data_list_item_ranges.next() else {
+            let truncation_error = self.truncation_error.take()?;
+            self.bytes_left = 0;
+            return Some(Err(truncation_error));
+        };
         let segment_offset = big_data_list_item_range.segment_offset(self.hive);
         self.bytes_left -= bytes_to_return;
 
         // Get the cell belonging to that offset and check if it contains as many bytes
         // as we expect.
-        let cell_range = iter_try!(self.hive.cell_range_from_data_offset(segment_offset));
+        let cell_range = iter_try!(self.hive.cell_range_from_offset_field(
+            segment_offset,
+            self.hive
+                .offset_of_data_offset(big_data_list_item_range.start),
+        ));
         let data_range = iter_try!(byte_subrange(&cell_range, bytes_to_return).ok_or_else(|| {
             NtHiveError::InvalidDataSize {
                 offset: self.hive.offset_of_data_offset(cell_range.start),
@@ -249,11 +321,12 @@ where
         }));
 
         // Return a byte slice containing this segment's data.
-        Some(Ok(&self.hive.data[data_range]))
+        Some(Ok(self.hive.data_slice(data_range)))
     }
 
     fn count(self) -> usize {
-        self.big_data_list_item_ranges.count()
+        let (size, _) = self.size_hint();
+        size
     }
 
     fn last(mut self) -> Option<Self::Item> {
@@ -267,6 +340,18 @@ where
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
         // `n` is arbitrary and usize, so we may hit boundaries here. Check that!
+        let items_left = self.big_data_list_item_ranges.len();
+        if n >= items_left {
+            // Skip all remaining segments and return the truncation error if it comes next.
+            self.big_data_list_item_ranges.items_range.start =
+                self.big_data_list_item_ranges.items_range.end;
+            if n > items_left {
+                self.truncation_error = None;
+            }
+
+            return self.next();
+        }
+
         let bytes_to_skip = n.checked_mul(BIG_DATA_SEGMENT_SIZE)?;
         self.bytes_left = self.bytes_left.saturating_sub(bytes_to_skip);
         if self.bytes_left == 0 {
@@ -281,15 +366,86 @@ where
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        self.big_data_list_item_ranges.size_hint()
+        let (size, _) = self.big_data_list_item_ranges.size_hint();
+        let size = size + usize::from(self.truncation_error.is_some());
+        (size, Some(size))
     }
 }
 
 impl<B> ExactSizeIterator for BigDataSlices<'_, B> where B: SplitByteSlice {}
 impl<B> FusedIterator for BigDataSlices<'_, B> where B: SplitByteSlice {}
 
+/// A Big Data segment that could not be read, as returned by [`BigDataSlicesLossy`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BigDataGap {
+    /// Range of the lost bytes, relative to the beginning of the Key Value data.
+    pub data_range: Range<usize>,
+    /// The error that occurred while reading the segment.
+    pub error: NtHiveError,
+}
+
+/// Iterator over
+///   a contiguous range of data bytes containing Big Data list items,
+///   returning a constant byte slice for each readable segment and a [`BigDataGap`] for each unreadable one.
+///
+/// In contrast to [`BigDataSlices`], this reports every segment that cannot be read (e.g. because its cell
+/// has been reused) as a gap and continues with the next segment.
+/// This makes it possible to salvage the readable parts of a partially overwritten Big Data value.
+/// If the segment list is too short for the data size, all bytes after its last segment are reported
+/// as a single trailing gap.
+/// The Big Data header still needs to be intact, as the segment list cannot be found otherwise.
+///
+/// Created by [`BigDataSlices::into_lossy`].
+///
+/// On-Disk Signature: `db`
+pub struct BigDataSlicesLossy<'h, B: SplitByteSlice> {
+    inner: BigDataSlices<'h, B>,
+    data_size: usize,
+}
+
+// Implemented manually, because `#[derive(Clone)]` would needlessly require `B: Clone`.
+impl<B> Clone for BigDataSlicesLossy<'_, B>
+where
+    B: SplitByteSlice,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            data_size: self.data_size,
+        }
+    }
+}
+
+impl<'h, B> Iterator for BigDataSlicesLossy<'h, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = core::result::Result<&'h [u8], BigDataGap>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // `BigDataSlices` advances to the next segment even if the current one cannot be read,
+        // so we only need to calculate the range of bytes belonging to the current segment.
+        let segment_start = self.data_size - self.inner.bytes_left();
+        let item = self.inner.next()?;
+        let segment_end = self.data_size - self.inner.bytes_left();
+
+        Some(item.map_err(|error| BigDataGap {
+            data_range: segment_start..segment_end,
+            error,
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<B> ExactSizeIterator for BigDataSlicesLossy<'_, B> where B: SplitByteSlice {}
+impl<B> FusedIterator for BigDataSlicesLossy<'_, B> where B: SplitByteSlice {}
+
 #[cfg(test)]
 mod tests {
+    use crate::helpers::tests::{cell_data, read_u16, read_u32, write_u32};
     use crate::*;
 
     #[test]
@@ -329,4 +485,211 @@ mod tests {
         assert!(matches!(key_value_data, KeyValueData::Big(_)));
         assert_eq!(key_value_data.into_vec().unwrap(), expected_data);
     }
+
+    /// Returns the offsets of the Key Value header and Big Data header of the Key Value "C" of "big-data-test".
+    fn big_data_c_headers(testhive: &[u8]) -> (usize, usize) {
+        let hive = Hive::new(testhive).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+
+        let (values_count, values_list_offset) = key_node.values_list_info();
+        let values_list = cell_data(values_list_offset);
+        let key_value_header = (0..values_count as usize)
+            .map(|i| cell_data(read_u32(testhive, values_list + i * 4)))
+            .find(|&header| read_u16(testhive, header + 2) == 1 && testhive[header + 20] == b'C')
+            .unwrap();
+        let big_data_header = cell_data(read_u32(testhive, key_value_header + 8));
+
+        (key_value_header, big_data_header)
+    }
+
+    fn big_data_c<B>(hive: &Hive<B>) -> Result<KeyValueData<'_, B>>
+    where
+        B: zerocopy::SplitByteSlice,
+    {
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("C").unwrap().unwrap();
+        key_value.data()
+    }
+
+    #[test]
+    fn test_segment_count() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let (key_value_header, big_data_header) = big_data_c_headers(&testhive);
+        let lenient = ValidationOptions {
+            big_data_segment_count: false,
+            ..ValidationOptions::strict()
+        };
+
+        // "C" has 16345 bytes, which need exactly 2 segments.
+        assert_eq!(read_u16(&testhive, big_data_header + 2), 2);
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert!(matches!(big_data_c(&hive), Ok(KeyValueData::Big(_))));
+
+        // A larger count is only tolerated if the validation is disabled, and the excess segment is ignored.
+        // The segment list cell of "C" has enough room for a third item.
+        let mut corrupted = testhive.clone();
+        corrupted[big_data_header + 2..big_data_header + 4].copy_from_slice(&3u16.to_le_bytes());
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        assert_eq!(
+            big_data_c(&hive).err(),
+            Some(NtHiveError::InvalidSegmentCount {
+                offset: big_data_header + 2,
+                expected: 2,
+                actual: 3,
+            })
+        );
+
+        for hive in [
+            Hive::with_options(corrupted.as_ref(), lenient).unwrap(),
+            Hive::without_validation(corrupted.as_ref()).unwrap(),
+        ] {
+            let KeyValueData::Big(iter) = big_data_c(&hive).unwrap() else {
+                panic!("Expected Big Data");
+            };
+            assert_eq!(iter.clone().count(), 2);
+            assert_eq!(
+                KeyValueData::Big(iter).into_vec().unwrap(),
+                vec![b'C'; 16345]
+            );
+        }
+
+        // A smaller count is always rejected.
+        let mut corrupted = testhive.clone();
+        corrupted[big_data_header + 2..big_data_header + 4].copy_from_slice(&1u16.to_le_bytes());
+        let expected_error = Some(NtHiveError::InvalidSegmentCount {
+            offset: big_data_header + 2,
+            expected: 2,
+            actual: 1,
+        });
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        assert_eq!(big_data_c(&hive).err(), expected_error);
+        let hive = Hive::with_options(corrupted.as_ref(), lenient).unwrap();
+        assert_eq!(big_data_c(&hive).err(), expected_error);
+
+        // 2 segments are enough for up to 2 * 16344 bytes, but not a single byte more.
+        let mut corrupted = testhive.clone();
+        write_u32(&mut corrupted, key_value_header + 4, 2 * 16344);
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        assert!(big_data_c(&hive).is_ok());
+
+        write_u32(&mut corrupted, key_value_header + 4, 2 * 16344 + 1);
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        assert_eq!(
+            big_data_c(&hive).err(),
+            Some(NtHiveError::InvalidSegmentCount {
+                offset: big_data_header + 2,
+                expected: 3,
+                actual: 2,
+            })
+        );
+
+        // Even the maximum segment count cannot cover more than 65535 * 16344 bytes.
+        write_u32(&mut corrupted, key_value_header + 4, 65535 * 16344 + 1);
+        corrupted[big_data_header + 2..big_data_header + 4]
+            .copy_from_slice(&u16::MAX.to_le_bytes());
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        assert!(matches!(
+            big_data_c(&hive),
+            Err(NtHiveError::InvalidSegmentCount {
+                expected: 65536,
+                actual: u16::MAX,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_into_vec_lossy() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+
+        // Small data never has gaps.
+        let key_value = key_node.value("A").unwrap().unwrap();
+        let (data, gaps) = key_value.data().unwrap().into_vec_lossy();
+        assert_eq!(data, vec![b'A'; 16343]);
+        assert!(gaps.is_empty());
+
+        // Find the Key Value header of "C", which has two Big Data segments.
+        let (values_count, values_list_offset) = key_node.values_list_info();
+        let values_list = cell_data(values_list_offset);
+        let key_value_header = (0..values_count as usize)
+            .map(|i| cell_data(read_u32(&testhive, values_list + i * 4)))
+            .find(|&header| read_u16(&testhive, header + 2) == 1 && testhive[header + 20] == b'C')
+            .unwrap();
+
+        // Turn "C" into a three-segment value. Its segment list cell has enough room for a third item.
+        // Let the third segment point to the first one and poison the second one.
+        let big_data_header = cell_data(read_u32(&testhive, key_value_header + 8));
+        assert_eq!(read_u16(&testhive, big_data_header + 2), 2);
+        testhive[big_data_header + 2..big_data_header + 4].copy_from_slice(&3u16.to_le_bytes());
+
+        let segment_list = cell_data(read_u32(&testhive, big_data_header + 4));
+        let first_segment_offset = read_u32(&testhive, segment_list);
+        write_u32(&mut testhive, segment_list + 4, u32::MAX - 7);
+        write_u32(&mut testhive, segment_list + 8, first_segment_offset);
+
+        let data_size = 2 * 16344 + 10;
+        write_u32(&mut testhive, key_value_header + 4, data_size as u32);
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("C").unwrap().unwrap();
+
+        // The strict variant fails.
+        assert!(key_value.data().unwrap().into_vec().is_err());
+
+        // The lossy variant recovers the first and third segment.
+        let (data, gaps) = key_value.data().unwrap().into_vec_lossy();
+        assert_eq!(data.len(), data_size);
+        assert_eq!(&data[..16344], &[b'C'; 16344][..]);
+        assert_eq!(&data[16344..2 * 16344], &[0; 16344][..]);
+        assert_eq!(&data[2 * 16344..], &[b'C'; 10][..]);
+
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].data_range, 16344..2 * 16344);
+    }
+
+    #[test]
+    fn test_truncated_segment_list() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let (_, big_data_header) = big_data_c_headers(&testhive);
+
+        // Shrink the segment list cell of "C" so that it only holds the first of its two segments.
+        let segment_list_cell = 4096 + read_u32(&testhive, big_data_header + 4) as usize;
+        write_u32(&mut testhive, segment_list_cell, (-8i32) as u32);
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        let KeyValueData::Big(iter) = big_data_c(&hive).unwrap() else {
+            panic!("Expected Big Data");
+        };
+        assert_eq!(iter.len(), 2);
+        let expected_error = NtHiveError::InvalidSizeField {
+            offset: big_data_header + 2,
+            expected: 8,
+            actual: 4,
+        };
+        assert_eq!(iter.clone().last(), Some(Err(expected_error.clone())));
+        assert_eq!(
+            KeyValueData::Big(iter.clone()).into_vec(),
+            Err(expected_error.clone())
+        );
+
+        // The lossy variant returns the full data size with a trailing gap for the missing segment.
+        let (data, gaps) = KeyValueData::Big(iter).into_vec_lossy();
+        assert_eq!(data.len(), 16345);
+        assert_eq!(&data[..16344], &[b'C'; 16344][..]);
+        assert_eq!(data[16344], 0);
+        assert_eq!(
+            gaps,
+            [BigDataGap {
+                data_range: 16344..16345,
+                error: expected_error,
+            }]
+        );
+    }
 }
@@ -0,0 +1,238 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use std::io::{self, Write};
+use std::time::SystemTime;
+
+use zerocopy::SplitByteSlice;
+
+use crate::filetime::filetime_to_system_time;
+use crate::hive::Hive;
+use crate::key_node::KeyNode;
+use crate::key_value::KeyValue;
+use crate::string::NtHiveNameString;
+use crate::walker::{TraversalOrder, WalkEvent, WalkEvents};
+
+/// Name written for the default value of a key, which has an empty name.
+const DEFAULT_VALUE_NAME: &str = "(Default)";
+
+/// Options for [`Hive::export_bodyfile_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct BodyfileOptions {
+    /// Also write a line for every value, as a child entry of its key.
+    ///
+    /// Values have no timestamps, so all timestamps of these lines are zero.
+    /// The size field contains the data size of the value.
+    pub include_values: bool,
+    /// Whether the values of a key are written directly after the key or after all of its subkeys.
+    ///
+    /// Defaults to [`TraversalOrder::ValuesFirst`], so the lines of a key and its values stay together.
+    /// Only relevant if [`BodyfileOptions::include_values`] is set.
+    pub order: TraversalOrder,
+}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Writes a line in the Sleuthkit bodyfile format for every key of this hive.
+    ///
+    /// This is a shortcut for [`Hive::export_bodyfile_with_options`] with the default options.
+    pub fn export_bodyfile<W>(&self, writer: W, hive_prefix: &str) -> io::Result<()>
+    where
+        W: Write,
+    {
+        self.export_bodyfile_with_options(writer, hive_prefix, &BodyfileOptions::default())
+    }
+
+    /// Writes a line in the Sleuthkit bodyfile format for every key of this hive.
+    ///
+    /// Each line has the form `0|path|0|0|0|0|0|0|mtime|0|0`, where `mtime` is the last write timestamp
+    /// of the key in seconds since the Unix epoch (or `0` if it predates the Unix epoch).
+    /// The path of each key is `hive_prefix` followed by the backslash-separated names of all keys below
+    /// the root key, so the root key itself is written as `hive_prefix`.
+    ///
+    /// Pipes, carriage returns, and newlines in key names (and the prefix) would break the format and are
+    /// therefore escaped as `\x7c`, `\x0d`, and `\x0a`.
+    ///
+    /// Keys are written in depth-first order, just like they are returned by [`KeyNode::descendants`].
+    /// Values are written in the [`TraversalOrder`] given in the options (see [`Hive::walk_events`]).
+    /// Parse errors of the hive are returned as I/O errors of kind [`io::ErrorKind::Other`] wrapping
+    /// the respective [`NtHiveError`].
+    ///
+    /// [`NtHiveError`]: crate::error::NtHiveError
+    pub fn export_bodyfile_with_options<W>(
+        &self,
+        mut writer: W,
+        hive_prefix: &str,
+        options: &BodyfileOptions,
+    ) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let mut path = String::new();
+        push_escaped(&mut path, hive_prefix);
+        let prefix_len = path.len();
+
+        let root_key_node = self.root_key_node().map_err(io::Error::other)?;
+        let mut events = WalkEvents::new(&root_key_node, options.order, options.include_values)
+            .map_err(io::Error::other)?;
+
+        while let Some(event) = events.next() {
+            match event.map_err(io::Error::other)? {
+                WalkEvent::EnterKey(key_node) => {
+                    set_key_path(&mut path, prefix_len, events.path());
+                    write_key_node(&mut writer, &path, &key_node)?;
+                }
+                WalkEvent::Value(key_value) => {
+                    write_key_value(&mut writer, &path, &key_value)?;
+                }
+                WalkEvent::LeaveKey(_) => {
+                    // Values of the parent key may follow.
+                    set_key_path(&mut path, prefix_len, events.path());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn push_escaped(output: &mut String, name: &str) {
+    for c in name.chars() {
+        match c {
+            '|' => output.push_str("\\x7c"),
+            '\r' => output.push_str("\\x0d"),
+            '\n' => output.push_str("\\x0a"),
+            c => output.push(c),
+        }
+    }
+}
+
+/// Replaces everything after the first `prefix_len` bytes of `path` by the escaped key names.
+fn set_key_path(path: &mut String, prefix_len: usize, names: &[NtHiveNameString]) {
+    path.truncate(prefix_len);
+    for name in names {
+        path.push('\\');
+        push_escaped(path, &name.to_string_lossy());
+    }
+}
+
+fn unix_seconds(filetime: u64) -> u64 {
+    filetime_to_system_time(filetime)
+        .and_then(|system_time| system_time.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn write_key_node<B, W>(writer: &mut W, path: &str, key_node: &KeyNode<B>) -> io::Result<()>
+where
+    B: SplitByteSlice,
+    W: Write,
+{
+    let mtime = unix_seconds(key_node.timestamp());
+    writeln!(writer, "0|{path}|0|0|0|0|0|0|{mtime}|0|0")
+}
+
+fn write_key_value<B, W>(writer: &mut W, key_path: &str, key_value: &KeyValue<B>) -> io::Result<()>
+where
+    B: SplitByteSlice,
+    W: Write,
+{
+    let name = key_value
+        .name()
+        .map_err(io::Error::other)?
+        .to_string_lossy();
+    let name = if name.is_empty() {
+        DEFAULT_VALUE_NAME
+    } else {
+        &name
+    };
+
+    let mut value_path = String::from(key_path);
+    value_path.push('\\');
+    push_escaped(&mut value_path, name);
+
+    let size = key_value.data_size();
+    writeln!(writer, "0|{value_path}|0|0|0|0|{size}|0|0|0|0")
+}
+
+#[cfg(test)]
+mod tests {
+    use core::iter;
+
+    use crate::*;
+
+    #[test]
+    fn test_export_bodyfile() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        let mut output = Vec::new();
+        hive.export_bodyfile(&mut output, "HKLM\\TEST").unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output, include_str!("../testdata/testhive.bodyfile"));
+    }
+
+    #[test]
+    fn test_export_bodyfile_with_values() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        let options = BodyfileOptions {
+            include_values: true,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        hive.export_bodyfile_with_options(&mut output, "HKLM\\TEST", &options)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let lines = output.lines().collect::<Vec<_>>();
+        let data_test_index = lines
+            .iter()
+            .position(|line| line.starts_with("0|HKLM\\TEST\\data-test|"))
+            .unwrap();
+        assert!(lines[data_test_index + 1..].iter().take(9).all(|line| line
+            .starts_with("0|HKLM\\TEST\\data-test\\")
+            && line.ends_with("|0|0|0|0")));
+        assert!(lines.contains(&"0|HKLM\\TEST\\data-test\\dword|0|0|0|0|4|0|0|0|0"));
+
+        // The key lines are the same as without values, and every value has a line.
+        let mut key_lines = lines.iter();
+        for expected_line in include_str!("../testdata/testhive.bodyfile").lines() {
+            assert!(key_lines.any(|line| *line == expected_line));
+        }
+
+        let root_key_node = hive.root_key_node().unwrap();
+        let value_count = iter::once(root_key_node.clone())
+            .chain(
+                root_key_node
+                    .descendants()
+                    .unwrap()
+                    .map(|key_node| key_node.unwrap()),
+            )
+            .map(|key_node| key_node.value_count() as usize)
+            .sum::<usize>();
+        assert_eq!(lines.len(), 528 + value_count);
+    }
+
+    #[test]
+    fn test_export_bodyfile_escaping() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let cell_offset = key_node.cell_offset();
+        crate::helpers::tests::rename_key_node(&mut testhive, cell_offset, "data|te\nt");
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let mut output = Vec::new();
+        hive.export_bodyfile(&mut output, "A|B").unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("0|A\\x7cB|0|0|0|0|0|0|"));
+        assert!(output.contains("\n0|A\\x7cB\\data\\x7cte\\x0at|0|0|0|0|0|0|"));
+        assert_eq!(output.lines().count(), 528);
+        assert!(output.lines().all(|line| line.split('|').count() == 11));
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Number of keys visited between two checks of the [`CancelToken`] of a hive.
+pub(crate) const CANCEL_CHECK_INTERVAL: usize = 64;
+
+/// A token to cooperatively cancel long-running operations on a [`Hive`].
+///
+/// Clones of a token share the same state.
+/// Keep one clone and pass another one to [`Hive::set_cancel_token`].
+/// After calling [`CancelToken::cancel`] on any clone (e.g. from another thread when a deadline has passed),
+/// all operations walking the keys or Hive Bins of that hive stop with [`NtHiveError::Cancelled`]
+/// after at most 64 further keys or a single further Hive Bin.
+///
+/// [`Hive`]: crate::hive::Hive
+/// [`Hive::set_cancel_token`]: crate::hive::Hive::set_cancel_token
+/// [`NtHiveError::Cancelled`]: crate::error::NtHiveError::Cancelled
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Creates a new token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels all operations on hives using this token.
+    ///
+    /// There is no way to undo this. Create a new token for further operations.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if [`CancelToken::cancel`] has been called on this token or any of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+
+    use crate::cancel::CANCEL_CHECK_INTERVAL;
+    use crate::*;
+
+    /// Cancels the given token on the `limit`-th call of [`CancelAfter::tick`].
+    struct CancelAfter {
+        cancel_token: CancelToken,
+        calls: usize,
+        limit: usize,
+    }
+
+    impl CancelAfter {
+        fn new(cancel_token: &CancelToken, limit: usize) -> Self {
+            Self {
+                cancel_token: cancel_token.clone(),
+                calls: 0,
+                limit,
+            }
+        }
+
+        fn tick(&mut self) {
+            self.calls += 1;
+            if self.calls == self.limit {
+                self.cancel_token.cancel();
+            }
+        }
+    }
+
+    impl Write for CancelAfter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.calls += buf.iter().filter(|byte| **byte == b'\n').count();
+            if self.calls >= self.limit {
+                self.cancel_token.cancel();
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_cancel_key_timestamps() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let mut hive = Hive::new(testhive.as_ref()).unwrap();
+        let cancel_token = CancelToken::new();
+        hive.set_cancel_token(cancel_token.clone());
+
+        let mut cancel_after = CancelAfter::new(&cancel_token, 10);
+        assert_eq!(
+            hive.key_timestamps(|_, _| cancel_after.tick()),
+            Err(NtHiveError::Cancelled)
+        );
+        assert!(cancel_after.calls <= 10 + CANCEL_CHECK_INTERVAL);
+        assert!(cancel_after.calls < 528);
+    }
+
+    #[test]
+    fn test_cancel_iterators() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let mut hive = Hive::new(testhive.as_ref()).unwrap();
+        let cancel_token = CancelToken::new();
+        hive.set_cancel_token(cancel_token.clone());
+
+        // An unused token doesn't change anything.
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(root_key_node.descendants().unwrap().count(), 527);
+
+        // The iteration returns `Cancelled` once and then ends.
+        let mut cancel_after = CancelAfter::new(&cancel_token, 10);
+        let mut descendants = root_key_node.descendants().unwrap();
+        let mut results = Vec::new();
+        for key_node in &mut descendants {
+            cancel_after.tick();
+            results.push(key_node);
+        }
+        assert!(results.len() <= 10 + CANCEL_CHECK_INTERVAL + 1);
+        assert_eq!(results.last(), Some(&Err(NtHiveError::Cancelled)));
+        assert!(results[..results.len() - 1]
+            .iter()
+            .all(|result| result.is_ok()));
+        assert!(descendants.next().is_none());
+
+        // Searches stop as well.
+        let mut matches = hive.search_value_data(b"\0").unwrap();
+        assert_eq!(matches.next(), Some(Err(NtHiveError::Cancelled)));
+        assert_eq!(matches.next(), None);
+    }
+
+    #[test]
+    fn test_cancel_export_bodyfile() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let mut hive = Hive::new(testhive.as_ref()).unwrap();
+        let cancel_token = CancelToken::new();
+        hive.set_cancel_token(cancel_token.clone());
+
+        let mut cancel_after = CancelAfter::new(&cancel_token, 10);
+        let error = hive
+            .export_bodyfile(&mut cancel_after, "HKLM\\TEST")
+            .unwrap_err();
+        assert_eq!(
+            error.get_ref().unwrap().downcast_ref::<NtHiveError>(),
+            Some(&NtHiveError::Cancelled)
+        );
+        assert!(cancel_after.calls <= 10 + CANCEL_CHECK_INTERVAL);
+    }
+
+    #[test]
+    fn test_cancel_verify_and_recovery() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let mut hive = Hive::new(testhive.as_ref()).unwrap();
+        let cancel_token = CancelToken::new();
+        hive.set_cancel_token(cancel_token.clone());
+        assert!(hive.verify().is_clean());
+        assert!(hive.deleted_keys_report().is_ok());
+
+        // The report of an integrity check contains everything found before the cancellation.
+        cancel_token.cancel();
+        let report = hive.verify();
+        assert_eq!(
+            report.findings,
+            [VerifyFinding::Fatal(NtHiveError::Cancelled)]
+        );
+
+        assert_eq!(hive.deleted_keys_report(), Err(NtHiveError::Cancelled));
+        assert_eq!(hive.inline_candidates(), Err(NtHiveError::Cancelled));
+    }
+}
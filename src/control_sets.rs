@@ -0,0 +1,184 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use core::str;
+
+use zerocopy::SplitByteSlice;
+
+use crate::error::Result;
+use crate::hive::Hive;
+use crate::key_node::KeyNode;
+
+/// Length of the "ControlSet" prefix plus the maximum number of decimal digits of a `u32`.
+const CONTROL_SET_NAME_BUFFER_SIZE: usize = 10 + 10;
+
+/// Selects one of the control sets referenced by the `Select` key of a SYSTEM hive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ControlSetSelector {
+    /// The control set that is used by the running system (`Select\Current`).
+    Current,
+    /// The control set that is used during the next boot (`Select\Default`).
+    Default,
+    /// The control set that has been replaced after a failed boot (`Select\Failed`).
+    Failed,
+    /// The last control set that booted successfully (`Select\LastKnownGood`).
+    LastKnownGood,
+}
+
+impl ControlSetSelector {
+    fn value_name(self) -> &'static str {
+        match self {
+            Self::Current => "Current",
+            Self::Default => "Default",
+            Self::Failed => "Failed",
+            Self::LastKnownGood => "LastKnownGood",
+        }
+    }
+}
+
+/// Formats the name of the `ControlSetNNN` key for the given number into `buffer`.
+fn control_set_name(number: u32, buffer: &mut [u8; CONTROL_SET_NAME_BUFFER_SIZE]) -> &str {
+    const PREFIX: &[u8] = b"ControlSet";
+    const MIN_DIGITS: usize = 3;
+
+    buffer[..PREFIX.len()].copy_from_slice(PREFIX);
+
+    // Write the digits from right to left, padded with zeros to at least `MIN_DIGITS`.
+    let mut digits = [b'0'; CONTROL_SET_NAME_BUFFER_SIZE - PREFIX.len()];
+    let mut remaining = number;
+    let mut digit_count = 0;
+
+    while remaining > 0 || digit_count < MIN_DIGITS {
+        digits[digits.len() - 1 - digit_count] = b'0' + (remaining % 10) as u8;
+        remaining /= 10;
+        digit_count += 1;
+    }
+
+    let name_length = PREFIX.len() + digit_count;
+    buffer[PREFIX.len()..name_length].copy_from_slice(&digits[digits.len() - digit_count..]);
+
+    // We have only written ASCII characters.
+    str::from_utf8(&buffer[..name_length]).unwrap()
+}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns the number of the control set selected by `selector`, as stored in the `Select` key of a SYSTEM hive.
+    ///
+    /// Returns `None` if this hive has no `Select` key or the key lacks the requested value.
+    pub fn control_set_number(&self, selector: ControlSetSelector) -> Option<Result<u32>> {
+        let root_key_node = iter_try!(self.root_key_node());
+        let select_key_node = iter_try!(root_key_node.subkey("Select")?);
+        let key_value = iter_try!(select_key_node.value(selector.value_name())?);
+
+        Some(key_value.dword_data())
+    }
+
+    /// Resolves the control set selected by `selector` and returns the [`KeyNode`] of the corresponding
+    /// `ControlSetNNN` key of a SYSTEM hive.
+    ///
+    /// Returns `None` if the `Select` key doesn't reference such a control set or the referenced
+    /// control set doesn't exist.
+    pub fn control_set(&self, selector: ControlSetSelector) -> Option<Result<KeyNode<'_, B>>> {
+        let number = iter_try!(self.control_set_number(selector)?);
+
+        let mut buffer = [0u8; CONTROL_SET_NAME_BUFFER_SIZE];
+        let name = control_set_name(number, &mut buffer);
+
+        let root_key_node = iter_try!(self.root_key_node());
+        root_key_node.subkey(name)
+    }
+
+    /// Returns the [`KeyNode`] of the control set used by the running system.
+    ///
+    /// This is a shortcut for calling [`Hive::control_set`] with [`ControlSetSelector::Current`].
+    pub fn current_control_set(&self) -> Option<Result<KeyNode<'_, B>>> {
+        self.control_set(ControlSetSelector::Current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::{cell_data, rename_key_node, write_u16, write_u32};
+    use crate::*;
+
+    /// Turns the Key Value at the given data offset into a REG_DWORD with the given name.
+    /// The new name must not be longer than the old one.
+    fn make_dword_value(testhive: &mut [u8], cell_offset: u32, name: &str, dword: u32) {
+        let header = cell_data(cell_offset);
+        write_u16(testhive, header + 2, name.len() as u16);
+        write_u32(testhive, header + 4, 0x8000_0004);
+        write_u32(testhive, header + 8, dword);
+        write_u32(testhive, header + 12, 4);
+        testhive[header + 20..header + 20 + name.len()].copy_from_slice(name.as_bytes());
+    }
+
+    #[test]
+    fn test_control_set_name() {
+        let mut buffer = [0u8; super::CONTROL_SET_NAME_BUFFER_SIZE];
+        assert_eq!(super::control_set_name(1, &mut buffer), "ControlSet001");
+        assert_eq!(super::control_set_name(0, &mut buffer), "ControlSet000");
+        assert_eq!(super::control_set_name(1234, &mut buffer), "ControlSet1234");
+        assert_eq!(
+            super::control_set_name(u32::MAX, &mut buffer),
+            "ControlSet4294967295"
+        );
+    }
+
+    #[test]
+    fn test_control_set() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        // testhive is no SYSTEM hive.
+        assert!(hive.current_control_set().is_none());
+
+        // Turn testhive into a minimal SYSTEM hive by renaming "character-encoding-test" to "ControlSet001"
+        // and "data-test" to "Select", keeping the alphabetical order of the root key's subkeys.
+        // Turn two values of "data-test" into the "Current" and "Default" DWORDs.
+        let root_key_node = hive.root_key_node().unwrap();
+        let control_set_offset = root_key_node
+            .subkey("character-encoding-test")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+        let data_test = root_key_node.subkey("data-test").unwrap().unwrap();
+        let select_offset = data_test.cell_offset();
+        let (_, values_list_offset) = data_test.values_list_info();
+        let values_list = cell_data(values_list_offset);
+        let value_offsets = [0, 1].map(|i| {
+            u32::from_le_bytes(
+                testhive[values_list + i * 4..values_list + i * 4 + 4]
+                    .try_into()
+                    .unwrap(),
+            )
+        });
+
+        rename_key_node(&mut testhive, control_set_offset, "ControlSet001");
+        rename_key_node(&mut testhive, select_offset, "Select");
+        make_dword_value(&mut testhive, value_offsets[0], "Current", 1);
+        make_dword_value(&mut testhive, value_offsets[1], "Default", 2);
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(
+            hive.control_set_number(ControlSetSelector::Current),
+            Some(Ok(1))
+        );
+        assert_eq!(
+            hive.control_set_number(ControlSetSelector::Default),
+            Some(Ok(2))
+        );
+        assert!(hive
+            .control_set_number(ControlSetSelector::LastKnownGood)
+            .is_none());
+
+        let key_node = hive.current_control_set().unwrap().unwrap();
+        assert_eq!(key_node.cell_offset(), control_set_offset);
+        assert_eq!(key_node.name().unwrap(), "ControlSet001");
+
+        // "ControlSet002" doesn't exist.
+        assert!(hive.control_set(ControlSetSelector::Default).is_none());
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use core::iter::FusedIterator;
+
+use zerocopy::SplitByteSlice;
+
+use crate::error::{NtHiveError, Result};
+use crate::key_node::KeyNode;
+use crate::walker::KeyNodeWalker;
+
+/// Iterator over
+///   all keys below a [`KeyNode`] in depth-first order,
+///   returning a constant [`KeyNode`] for each key.
+///
+/// The traversal state is kept on the heap, so arbitrarily deep key trees don't cause a stack overflow.
+/// Keys referenced more than once (e.g. a subkeys list referencing an ancestor in a corrupted hive) are
+/// detected by the underlying walker and end the iteration with an error.
+///
+/// Created by [`KeyNode::descendants`].
+pub struct KeyNodeDescendants<'h, B: SplitByteSlice> {
+    walker: KeyNodeWalker<'h, B>,
+    finished: bool,
+}
+
+impl<'h, B> KeyNodeDescendants<'h, B>
+where
+    B: SplitByteSlice,
+{
+    fn new(key_node: &KeyNode<'h, B>) -> Result<Self> {
+        let walker = KeyNodeWalker::new(key_node)?;
+
+        Ok(Self {
+            walker,
+            finished: false,
+        })
+    }
+}
+
+impl<'h, B> Iterator for KeyNodeDescendants<'h, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<KeyNode<'h, B>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let result = self.walker.next()?;
+
+        if let Err(NtHiveError::RepeatedKeyNode { .. }) = result {
+            self.finished = true;
+        }
+
+        Some(result)
+    }
+}
+
+impl<B> FusedIterator for KeyNodeDescendants<'_, B> where B: SplitByteSlice {}
+
+impl<'h, B> KeyNode<'h, B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns an iterator over all keys below this key in depth-first order.
+    ///
+    /// The iterator is lazy and doesn't include this key itself.
+    /// Returns an error if the Subkeys List of this key is corrupted.
+    pub fn descendants(&self) -> Result<KeyNodeDescendants<'h, B>> {
+        KeyNodeDescendants::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::{cell_data, key_node_field, read_u32};
+    use crate::key_node::KeyNodeField;
+    use crate::*;
+
+    #[test]
+    fn test_descendants() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        let descendants = root_key_node
+            .descendants()
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(descendants.len(), 527);
+        assert_eq!(descendants[0].name().unwrap(), "big-data-test");
+
+        let key_node = root_key_node
+            .subpath("subpath-test\\with-two-levels-of-subkeys")
+            .unwrap()
+            .unwrap();
+        let names = key_node
+            .descendants()
+            .unwrap()
+            .map(|key_node| key_node.unwrap().name().unwrap().to_string_lossy())
+            .collect::<Vec<String>>();
+        assert_eq!(names, ["subkey1", "subkey2"]);
+
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert_eq!(key_node.descendants().unwrap().count(), 0);
+
+        // Let the first subkey of the root key point back to the root key.
+        let root_cell_offset = root_key_node.cell_offset();
+        let subkeys_list_offset = read_u32(
+            &testhive,
+            key_node_field(root_cell_offset, KeyNodeField::SubkeysListOffset),
+        );
+        let first_item_field = cell_data(subkeys_list_offset) + 4;
+        testhive[first_item_field..first_item_field + 4]
+            .copy_from_slice(&root_cell_offset.to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let mut descendants = root_key_node.descendants().unwrap();
+        assert!(matches!(
+            descendants.next(),
+            Some(Err(NtHiveError::RepeatedKeyNode { offset })) if offset == cell_data(root_cell_offset)
+        ));
+        assert!(descendants.next().is_none());
+    }
+}
@@ -0,0 +1,49 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use core::mem;
+
+use crate::hive::{CellHeader, Hive};
+
+/// Hive data for the examples in the documentation of this crate.
+#[doc(hidden)]
+pub struct DoctestHive {
+    /// The unmodified `testdata/testhive`.
+    pub intact: Vec<u8>,
+    /// The same hive, but with the signature of the Subkeys List of the root key overwritten.
+    pub broken_subkeys_list: Vec<u8>,
+    /// The same hive, but with the cell of the Key Values List of "data-test" marked as unallocated.
+    pub freed_values_list: Vec<u8>,
+}
+
+/// Loads `testdata/testhive` and derives the corrupted variants of [`DoctestHive`] from it.
+///
+/// All structures are found by following the offsets stored in the hive, so the corruptions always hit
+/// the intended cells.
+#[doc(hidden)]
+pub fn doctest_hive() -> DoctestHive {
+    let intact = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/testhive")).unwrap();
+    let hive = Hive::new(intact.as_ref()).unwrap();
+    let root_key_node = hive.root_key_node().unwrap();
+
+    // The signature is the first field of every Subkeys List.
+    let mut broken_subkeys_list = intact.clone();
+    let subkeys_list = hive.offset_of_data_offset(root_key_node.subkeys_list_offset() as usize)
+        + mem::size_of::<CellHeader>();
+    broken_subkeys_list[subkeys_list..subkeys_list + 2].copy_from_slice(b"xx");
+
+    // A positive cell size denotes an unallocated cell.
+    let mut freed_values_list = intact.clone();
+    let data_test = root_key_node.subkey("data-test").unwrap().unwrap();
+    let (_, values_list_offset) = data_test.values_list_info();
+    let cell_header = hive.offset_of_data_offset(values_list_offset as usize);
+    let cell_header = cell_header..cell_header + mem::size_of::<CellHeader>();
+    let cell_size = i32::from_le_bytes(freed_values_list[cell_header.clone()].try_into().unwrap());
+    freed_values_list[cell_header].copy_from_slice(&cell_size.unsigned_abs().to_le_bytes());
+
+    DoctestHive {
+        intact,
+        broken_subkeys_list,
+        freed_values_list,
+    }
+}
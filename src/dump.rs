@@ -0,0 +1,317 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::mem;
+use core::ops::Range;
+
+use zerocopy::SplitByteSlice;
+
+use crate::big_data::BIG_DATA_HEADER_LAYOUT;
+use crate::error::Result;
+use crate::hive::{CellHeader, Hive};
+use crate::index_root::INDEX_ROOT_ITEM_LAYOUT;
+use crate::key_node::{
+    KEY_NODE_HEADER_LAYOUT, KEY_NODE_NAME_LENGTHS_LAYOUT, KEY_NODE_STATISTICS_LAYOUT,
+};
+use crate::key_security::KEY_SECURITY_HEADER_LAYOUT;
+use crate::key_value::KEY_VALUE_HEADER_LAYOUT;
+use crate::leaf::{FAST_LEAF_ITEM_LAYOUT, HASH_LEAF_ITEM_LAYOUT, INDEX_LEAF_ITEM_LAYOUT};
+use crate::subkeys_list::SUBKEYS_LIST_HEADER_LAYOUT;
+
+/// Number of bytes output per line of a hex dump.
+const BYTES_PER_LINE: usize = 16;
+
+/// Names and offsets of all fields of an On-Disk Structure, built via the `struct_layout!` macro.
+pub(crate) struct StructLayout {
+    /// Name and offset of each field, in ascending order of offsets.
+    pub(crate) fields: &'static [(&'static str, usize)],
+    /// Size of the entire structure in bytes.
+    pub(crate) size: usize,
+}
+
+impl StructLayout {
+    /// Returns the name and relative byte range of each field.
+    fn field_ranges(&self) -> impl Iterator<Item = (&'static str, Range<usize>)> + '_ {
+        self.fields.iter().enumerate().map(|(i, (name, offset))| {
+            let end = self
+                .fields
+                .get(i + 1)
+                .map_or(self.size, |(_, next_offset)| *next_offset);
+            (*name, *offset..end)
+        })
+    }
+}
+
+/// A labeled byte range of a cell, relative to the start of the cell header.
+struct Annotation {
+    label: String,
+    range: Range<usize>,
+}
+
+/// Collects the [`Annotation`]s of a cell of `cell_size` bytes (including the cell header).
+struct Annotations {
+    annotations: Vec<Annotation>,
+    cell_size: usize,
+    position: usize,
+}
+
+impl Annotations {
+    fn new(cell_size: usize) -> Self {
+        Self {
+            annotations: Vec::new(),
+            cell_size,
+            position: 0,
+        }
+    }
+
+    fn push(&mut self, label: String, size: usize) {
+        let start = self.position;
+        let end = start.saturating_add(size).min(self.cell_size);
+        if start < end {
+            self.annotations.push(Annotation {
+                label,
+                range: start..end,
+            });
+        }
+
+        self.position = end;
+    }
+
+    fn push_struct(&mut self, layout: &StructLayout, prefix: &str) {
+        for (name, range) in layout.field_ranges() {
+            self.push(format!("{prefix}{name}"), range.len());
+        }
+    }
+
+    fn finish(mut self, remainder_label: &str) -> Vec<Annotation> {
+        self.push(remainder_label.into(), self.cell_size - self.position);
+        self.annotations
+    }
+}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns a classic hex dump (16 bytes per line with an ASCII column) of the cell at the given data offset.
+    ///
+    /// The dump includes the cell header and starts with a line describing the cell.
+    /// All offsets are relative to the very beginning of the hive bytes.
+    /// Unallocated cells are dumped as well, which is useful for debugging corrupted hives.
+    ///
+    /// If `annotate` is `true` and the cell has a known signature (`nk`, `vk`, `lf`, `lh`, `li`, `ri`, `sk`, `db`),
+    /// lines are additionally split at field boundaries and the first line of each field is followed
+    /// by the field name.
+    /// This is the preferred artifact to attach to bug reports about parsing problems.
+    pub fn dump_cell(&self, data_offset: u32, annotate: bool) -> Result<String> {
+        let cell_data_range = self.any_cell_range_from_data_offset(data_offset)?;
+        let data_offset = data_offset as usize;
+        let cell_bytes = self.data_slice(data_offset..cell_data_range.end);
+        let cell_size = cell_bytes.len();
+        let allocated = i32::from_le_bytes(cell_bytes[..4].try_into().unwrap()) < 0;
+
+        let signature = cell_bytes
+            .get(mem::size_of::<CellHeader>()..mem::size_of::<CellHeader>() + 2)
+            .filter(|signature| signature.iter().all(u8::is_ascii_alphanumeric));
+
+        let mut output = format!(
+            "Cell at offset {:#010x}, {cell_size} bytes, {}",
+            self.offset_of_data_offset(data_offset),
+            if allocated {
+                "allocated"
+            } else {
+                "unallocated"
+            }
+        );
+        if let Some(signature) = signature {
+            let _ = write!(
+                output,
+                ", signature \"{}\"",
+                String::from_utf8_lossy(signature)
+            );
+        }
+        output.push('\n');
+
+        let annotations = if annotate {
+            signature.and_then(|signature| {
+                annotate_cell(signature, cell_bytes, self.is_legacy_version())
+            })
+        } else {
+            None
+        };
+        let annotations = annotations.unwrap_or_else(|| {
+            Vec::from([Annotation {
+                label: String::new(),
+                range: 0..cell_size,
+            }])
+        });
+
+        let base_offset = self.offset_of_data_offset(data_offset);
+        for annotation in annotations {
+            let mut line_start = annotation.range.start;
+            let mut label = Some(annotation.label.as_str()).filter(|label| !label.is_empty());
+
+            while line_start < annotation.range.end {
+                let line_end = (line_start + BYTES_PER_LINE).min(annotation.range.end);
+                write_line(
+                    &mut output,
+                    base_offset + line_start,
+                    &cell_bytes[line_start..line_end],
+                    label.take(),
+                );
+                line_start = line_end;
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+/// Returns the [`Annotation`]s for a cell with the given signature, or `None` if the signature is unknown.
+///
+/// `legacy` denotes a hive of Windows NT 3.x, whose Key Nodes lack the statistics fields.
+fn annotate_cell(signature: &[u8], cell_bytes: &[u8], legacy: bool) -> Option<Vec<Annotation>> {
+    let mut annotations = Annotations::new(cell_bytes.len());
+    annotations.push("cell_size".into(), mem::size_of::<CellHeader>());
+
+    let item_layout = match signature {
+        b"nk" => {
+            annotations.push_struct(&KEY_NODE_HEADER_LAYOUT, "");
+            if !legacy {
+                annotations.push_struct(&KEY_NODE_STATISTICS_LAYOUT, "");
+            }
+            annotations.push_struct(&KEY_NODE_NAME_LENGTHS_LAYOUT, "");
+            return Some(annotations.finish("data"));
+        }
+        b"vk" => {
+            annotations.push_struct(&KEY_VALUE_HEADER_LAYOUT, "");
+            return Some(annotations.finish("data"));
+        }
+        b"sk" => {
+            annotations.push_struct(&KEY_SECURITY_HEADER_LAYOUT, "");
+            return Some(annotations.finish("data"));
+        }
+        b"db" => {
+            annotations.push_struct(&BIG_DATA_HEADER_LAYOUT, "");
+            return Some(annotations.finish("unused"));
+        }
+        b"lf" => &FAST_LEAF_ITEM_LAYOUT,
+        b"lh" => &HASH_LEAF_ITEM_LAYOUT,
+        b"li" => &INDEX_LEAF_ITEM_LAYOUT,
+        b"ri" => &INDEX_ROOT_ITEM_LAYOUT,
+        _ => return None,
+    };
+
+    // All Subkeys Lists share the same header, followed by `count` items.
+    annotations.push_struct(&SUBKEYS_LIST_HEADER_LAYOUT, "");
+    let count_offset = mem::size_of::<CellHeader>() + SUBKEYS_LIST_HEADER_LAYOUT.fields[1].1;
+    let count = cell_bytes
+        .get(count_offset..count_offset + 2)
+        .map_or(0, |count| u16::from_le_bytes(count.try_into().unwrap()));
+
+    for index in 0..count {
+        if annotations.position >= annotations.cell_size {
+            break;
+        }
+
+        annotations.push_struct(item_layout, &format!("item[{index}]."));
+    }
+
+    Some(annotations.finish("unused"))
+}
+
+fn write_line(output: &mut String, offset: usize, bytes: &[u8], label: Option<&str>) {
+    let _ = write!(output, "{offset:08x}  ");
+
+    for i in 0..BYTES_PER_LINE {
+        if i == BYTES_PER_LINE / 2 {
+            output.push(' ');
+        }
+
+        match bytes.get(i) {
+            Some(byte) => {
+                let _ = write!(output, "{byte:02x} ");
+            }
+            None => output.push_str("   "),
+        }
+    }
+
+    output.push_str(" |");
+    for byte in bytes {
+        let c = if byte.is_ascii_graphic() || *byte == b' ' {
+            *byte as char
+        } else {
+            '.'
+        };
+        output.push(c);
+    }
+    for _ in bytes.len()..BYTES_PER_LINE {
+        output.push(' ');
+    }
+    output.push('|');
+
+    if let Some(label) = label {
+        output.push_str("  ");
+        output.push_str(label);
+    }
+
+    output.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::{key_node_field, read_u32};
+    use crate::key_node::KeyNodeField;
+    use crate::*;
+
+    #[test]
+    fn test_dump_cell() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        let dump = hive.dump_cell(root_key_node.cell_offset(), true).unwrap();
+        assert_eq!(dump, include_str!("../testdata/root-key-node.dump"));
+
+        // Without annotations, each line holds 16 bytes.
+        let dump = hive.dump_cell(root_key_node.cell_offset(), false).unwrap();
+        let lines = dump.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "Cell at offset 0x00001020, 88 bytes, allocated, signature \"nk\""
+        );
+        assert_eq!(
+            lines[1],
+            "00001020  a8 ff ff ff 6e 6b 20 00  c1 e8 ac 91 31 2b d9 01  |....nk .....1+..|"
+        );
+        assert_eq!(
+            lines[6],
+            "00001070  52 4f 4f 54 00 00 00 00                           |ROOT....        |"
+        );
+        assert_eq!(lines.len(), 7);
+
+        // Subkeys Lists are annotated per item.
+        let subkey = root_key_node.subkey("data-test").unwrap().unwrap();
+        let subkeys_list_offset = read_u32(
+            &testhive,
+            key_node_field(root_key_node.cell_offset(), KeyNodeField::SubkeysListOffset),
+        );
+        let dump = hive.dump_cell(subkeys_list_offset, true).unwrap();
+        assert!(dump.contains("signature \"lh\""));
+        assert!(dump.contains("  item[4].name_hash\n"));
+        assert!(dump.contains(&format!(
+            " {:02x} {:02x} {:02x} {:02x} ",
+            subkey.cell_offset() as u8,
+            (subkey.cell_offset() >> 8) as u8,
+            (subkey.cell_offset() >> 16) as u8,
+            (subkey.cell_offset() >> 24) as u8
+        )));
+
+        // Offsets outside the hive data are rejected.
+        assert!(hive.dump_cell(0x7fff_fff0, true).is_err());
+    }
+}
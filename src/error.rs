@@ -11,6 +11,10 @@ pub type Result<T, E = NtHiveError> = core::result::Result<T, E>;
 /// Central error type of nt-hive.
 #[derive(Clone, Debug, Error, Eq, PartialEq)]
 pub enum NtHiveError {
+    #[error("The operation has been cancelled")]
+    Cancelled,
+    #[error("The Subkeys List at offset {offset:#010x} has been modified while iterating over it")]
+    ConcurrentStructuralModification { offset: usize },
     #[error("The checksum in the base block should be {expected}, but it is {actual}")]
     InvalidChecksum { expected: u32, actual: u32 },
     #[error("The data at offset {offset:#010x} should have a size of {expected} bytes, but it only has {actual} bytes")]
@@ -31,11 +35,29 @@ pub enum NtHiveError {
         expected: usize,
         actual: usize,
     },
+    #[error("The Hive Bin offset field at offset {offset:#010x} should be {expected:#010x}, but it is {actual:#010x}")]
+    InvalidHiveBinOffset {
+        offset: usize,
+        expected: u32,
+        actual: u32,
+    },
+    #[error("The blink at offset {offset:#010x} should point to the Key Security cell at {expected:#010x}, but it points to {actual:#010x}")]
+    InvalidKeySecurityBlink {
+        offset: usize,
+        expected: u32,
+        actual: u32,
+    },
     #[error("Expected one of the key value data types {expected:?}, but found {actual:?}")]
     InvalidKeyValueDataType {
         expected: &'static [KeyValueDataType],
         actual: KeyValueDataType,
     },
+    #[error("The segment count at offset {offset:#010x} should be {expected}, but it is {actual}")]
+    InvalidSegmentCount {
+        offset: usize,
+        expected: u32,
+        actual: u16,
+    },
     #[error("The size field at offset {offset:#010x} specifies {expected} bytes, but only {actual} bytes are left in the slice")]
     InvalidSizeField {
         offset: usize,
@@ -54,8 +76,34 @@ pub enum NtHiveError {
         expected: &'static [u8],
         actual: [u8; 2],
     },
+    #[error("The cell index {cell_index:#010x} at offset {offset:#010x} should refer to volatile storage, but it doesn't")]
+    InvalidVolatileCellIndex { offset: usize, cell_index: u32 },
+    #[error("The key at offset {offset:#010x} is not part of the Subkeys List of its parent at offset {parent_offset:#010x}")]
+    KeyNodeNotInParent { offset: usize, parent_offset: usize },
+    #[error(
+        "The key at offset {offset:#010x} is nested deeper than the maximum of {max_depth} levels"
+    )]
+    MaximumKeyDepthExceeded { offset: usize, max_depth: usize },
+    #[error("Resolving the symbolic link at offset {offset:#010x} exceeded the maximum of {max_depth} chained links")]
+    MaximumSymbolicLinkDepthExceeded { offset: usize, max_depth: usize },
+    #[error("The cell offset field at offset {field_offset:#010x} contains 0xFFFFFFFF, which denotes a missing cell")]
+    MissingCell { field_offset: usize },
+    #[error("The data at offset {offset:#010x} is not part of any fragment of the hive")]
+    MissingData { offset: usize },
+    #[error("The root key flags at offset {offset:#010x} do not contain KEY_HIVE_ENTRY")]
+    MissingHiveEntryFlag { offset: usize },
+    #[error("The symbolic link at offset {offset:#010x} has no SymbolicLinkValue")]
+    MissingSymbolicLinkValue { offset: usize },
+    #[error("The fragment starting at offset {offset:#010x} overlaps the previous fragment")]
+    OverlappingFragments { offset: usize },
+    #[error("The key at offset {offset:#010x} has already been visited, so the key tree contains a cycle")]
+    RepeatedKeyNode { offset: usize },
     #[error("The sequence numbers in the base block do not match ({primary} != {secondary})")]
     SequenceNumberMismatch { primary: u32, secondary: u32 },
+    #[error(
+        "The target of the symbolic link at offset {offset:#010x} does not exist in this hive"
+    )]
+    SymbolicLinkTargetNotFound { offset: usize },
     #[error("The cell at offset {offset:#010x} with a size of {size} bytes is unallocated")]
     UnallocatedCell { offset: usize, size: i32 },
     #[error(
@@ -0,0 +1,147 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+#[cfg(feature = "std")]
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "time")]
+use time::OffsetDateTime;
+
+/// Number of 100-nanosecond intervals between 1601-01-01 (FILETIME epoch) and 1970-01-01 (Unix epoch).
+const FILETIME_UNIX_EPOCH_DIFFERENCE: i128 = 116_444_736_000_000_000;
+
+/// Nanoseconds per FILETIME interval.
+const NANOSECONDS_PER_INTERVAL: i128 = 100;
+
+/// Number of FILETIME intervals per second.
+#[cfg(feature = "std")]
+const INTERVALS_PER_SECOND: u64 = 10_000_000;
+
+/// Converts a raw Windows FILETIME (100-nanosecond intervals since 1601-01-01 UTC) into an [`OffsetDateTime`].
+///
+/// Returns `None` for a zero FILETIME (which denotes "no time") and for FILETIMEs that exceed the range
+/// supported by [`OffsetDateTime`].
+#[cfg(feature = "time")]
+pub(crate) fn filetime_to_offset_date_time(filetime: u64) -> Option<OffsetDateTime> {
+    if filetime == 0 {
+        return None;
+    }
+
+    // Calculating in i128 rules out any overflow.
+    let unix_timestamp_nanos =
+        (filetime as i128 - FILETIME_UNIX_EPOCH_DIFFERENCE) * NANOSECONDS_PER_INTERVAL;
+    OffsetDateTime::from_unix_timestamp_nanos(unix_timestamp_nanos).ok()
+}
+
+/// Converts a raw Windows FILETIME (100-nanosecond intervals since 1601-01-01 UTC) into a [`SystemTime`].
+///
+/// Returns `None` for a zero FILETIME (which denotes "no time") and for FILETIMEs that exceed the range
+/// supported by [`SystemTime`] on this platform.
+#[cfg(feature = "std")]
+pub(crate) fn filetime_to_system_time(filetime: u64) -> Option<SystemTime> {
+    if filetime == 0 {
+        return None;
+    }
+
+    let epoch_difference = FILETIME_UNIX_EPOCH_DIFFERENCE as u64;
+    let (intervals, before_unix_epoch) = if filetime >= epoch_difference {
+        (filetime - epoch_difference, false)
+    } else {
+        (epoch_difference - filetime, true)
+    };
+
+    let duration = Duration::new(
+        intervals / INTERVALS_PER_SECOND,
+        (intervals % INTERVALS_PER_SECOND) as u32 * NANOSECONDS_PER_INTERVAL as u32,
+    );
+
+    if before_unix_epoch {
+        SystemTime::UNIX_EPOCH.checked_sub(duration)
+    } else {
+        SystemTime::UNIX_EPOCH.checked_add(duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "time")]
+    use time::{Date, Month, OffsetDateTime, Time};
+
+    use super::*;
+
+    #[cfg(feature = "time")]
+    fn offset_date_time_to_filetime(date_time: OffsetDateTime) -> u64 {
+        (date_time.unix_timestamp_nanos() / NANOSECONDS_PER_INTERVAL
+            + FILETIME_UNIX_EPOCH_DIFFERENCE) as u64
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_filetime_to_offset_date_time() {
+        // 2021-01-01 00:00:00 UTC
+        let filetime = 132_539_328_000_000_000;
+        let expected = Date::from_calendar_date(2021, Month::January, 1)
+            .unwrap()
+            .midnight()
+            .assume_utc();
+        let date_time = filetime_to_offset_date_time(filetime).unwrap();
+        assert_eq!(date_time, expected);
+        assert_eq!(offset_date_time_to_filetime(date_time), filetime);
+
+        // The Unix epoch.
+        let filetime = FILETIME_UNIX_EPOCH_DIFFERENCE as u64;
+        assert_eq!(
+            filetime_to_offset_date_time(filetime).unwrap(),
+            OffsetDateTime::UNIX_EPOCH
+        );
+
+        // The very first interval after the FILETIME epoch, long before the Unix epoch.
+        let expected = Date::from_calendar_date(1601, Month::January, 1)
+            .unwrap()
+            .with_time(Time::from_hms_nano(0, 0, 0, 100).unwrap())
+            .assume_utc();
+        let date_time = filetime_to_offset_date_time(1).unwrap();
+        assert_eq!(date_time, expected);
+        assert_eq!(offset_date_time_to_filetime(date_time), 1);
+
+        // The last representable value of OffsetDateTime (9999-12-31 23:59:59.9999999 UTC) and one interval later.
+        let filetime = 2_650_467_743_999_999_999;
+        let date_time = filetime_to_offset_date_time(filetime).unwrap();
+        assert_eq!(date_time.year(), 9999);
+        assert_eq!(offset_date_time_to_filetime(date_time), filetime);
+        assert_eq!(filetime_to_offset_date_time(filetime + 1), None);
+
+        // Zero denotes "no time" and u64::MAX is out of range.
+        assert_eq!(filetime_to_offset_date_time(0), None);
+        assert_eq!(filetime_to_offset_date_time(u64::MAX), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_filetime_to_system_time() {
+        use std::time::{Duration, SystemTime};
+
+        // 2021-01-01 00:00:00.0000001 UTC
+        let filetime = 132_539_328_000_000_001;
+        assert_eq!(
+            filetime_to_system_time(filetime).unwrap(),
+            SystemTime::UNIX_EPOCH + Duration::new(1_609_459_200, 100)
+        );
+
+        // The Unix epoch.
+        let filetime = FILETIME_UNIX_EPOCH_DIFFERENCE as u64;
+        assert_eq!(
+            filetime_to_system_time(filetime).unwrap(),
+            SystemTime::UNIX_EPOCH
+        );
+
+        // The very first interval after the FILETIME epoch, long before the Unix epoch.
+        assert_eq!(
+            filetime_to_system_time(1).unwrap(),
+            SystemTime::UNIX_EPOCH - Duration::new(11_644_473_599, 999_999_900)
+        );
+
+        // Zero denotes "no time".
+        assert_eq!(filetime_to_system_time(0), None);
+    }
+}
@@ -0,0 +1,393 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use zerocopy::SplitByteSlice;
+#[cfg(feature = "write")]
+use zerocopy::SplitByteSliceMut;
+
+use crate::error::{NtHiveError, Result};
+use crate::hive::{Hive, ValidationOptions, HIVE_BASE_BLOCK_SIZE};
+
+/// A single fragment of hive data, as passed to [`Hive::from_fragments`].
+struct HiveFragment<B: SplitByteSlice> {
+    data_offset: usize,
+    bytes: B,
+}
+
+impl<B> HiveFragment<B>
+where
+    B: SplitByteSlice,
+{
+    fn data_range(&self) -> Range<usize> {
+        self.data_offset..self.data_offset.saturating_add(self.bytes.len())
+    }
+}
+
+/// Base block and hive data of a hive that is scattered over multiple buffers.
+///
+/// The fragments are sorted by data offset and don't overlap.
+/// Gaps between them are allowed.
+pub(crate) struct HiveFragments<B: SplitByteSlice> {
+    base_block: Box<[u8]>,
+    fragments: Vec<HiveFragment<B>>,
+    /// Fragments without any bytes, which are only kept to give them back in [`Hive::into_fragments`].
+    empty_fragments: Vec<HiveFragment<B>>,
+}
+
+impl<B> HiveFragments<B>
+where
+    B: SplitByteSlice,
+{
+    fn new<I>(base_block: &[u8], fragments: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (u32, B)>,
+    {
+        let base_block = base_block
+            .get(..HIVE_BASE_BLOCK_SIZE)
+            .ok_or(NtHiveError::InvalidHeaderSize {
+                offset: 0,
+                expected: HIVE_BASE_BLOCK_SIZE,
+                actual: base_block.len(),
+            })?
+            .into();
+
+        let (mut fragments, empty_fragments): (Vec<_>, Vec<_>) = fragments
+            .into_iter()
+            .map(|(data_offset, bytes)| HiveFragment {
+                data_offset: data_offset as usize,
+                bytes,
+            })
+            .partition(|fragment| !fragment.bytes.is_empty());
+        fragments.sort_unstable_by_key(|fragment| fragment.data_offset);
+
+        for adjacent_fragments in fragments.windows(2) {
+            if adjacent_fragments[0].data_range().end > adjacent_fragments[1].data_offset {
+                return Err(NtHiveError::OverlappingFragments {
+                    offset: HIVE_BASE_BLOCK_SIZE + adjacent_fragments[1].data_offset,
+                });
+            }
+        }
+
+        Ok(Self {
+            base_block,
+            fragments,
+            empty_fragments,
+        })
+    }
+
+    pub(crate) fn base_block(&self) -> &[u8] {
+        &self.base_block
+    }
+
+    /// Returns the end of the last fragment, which is the size of the hive data including all gaps.
+    pub(crate) fn data_len(&self) -> usize {
+        self.fragments
+            .last()
+            .map_or(0, |fragment| fragment.data_range().end)
+    }
+
+    fn fragment_index(&self, data_offset: usize) -> Option<usize> {
+        let index = self
+            .fragments
+            .partition_point(|fragment| fragment.data_offset <= data_offset)
+            .checked_sub(1)?;
+
+        self.fragments[index]
+            .data_range()
+            .contains(&data_offset)
+            .then_some(index)
+    }
+
+    /// Calculates the offset of the given address from the very beginning of the hive bytes,
+    /// as if all fragments were laid out contiguously after the base block.
+    pub(crate) fn offset_of_address(&self, address: usize) -> usize {
+        let base_block_address = self.base_block.as_ptr() as usize;
+        if (base_block_address..base_block_address + self.base_block.len()).contains(&address) {
+            return address - base_block_address;
+        }
+
+        let fragment = self
+            .fragments
+            .iter()
+            .find(|fragment| {
+                let fragment_address = fragment.bytes.as_ptr() as usize;
+                (fragment_address..fragment_address + fragment.bytes.len()).contains(&address)
+            })
+            .expect("The address must be part of a fragment");
+
+        let fragment_address = fragment.bytes.as_ptr() as usize;
+        HIVE_BASE_BLOCK_SIZE + fragment.data_offset + (address - fragment_address)
+    }
+
+    /// Returns the range from `data_offset` up to the end of the fragment containing it.
+    ///
+    /// Returns `None` if `data_offset` lies in a gap between fragments or after the last fragment.
+    pub(crate) fn remaining_data_range(&self, data_offset: usize) -> Option<Range<usize>> {
+        let index = self.fragment_index(data_offset)?;
+        Some(data_offset..self.fragments[index].data_range().end)
+    }
+
+    /// Returns the bytes of the given data range, which must lie within a single fragment.
+    pub(crate) fn slice(&self, data_range: Range<usize>) -> &[u8] {
+        let fragment = &self.fragments[self.fragment_index(data_range.start).unwrap()];
+        let start = data_range.start - fragment.data_offset;
+        let end = data_range.end - fragment.data_offset;
+        &fragment.bytes[start..end]
+    }
+}
+
+#[cfg(feature = "write")]
+impl<B> HiveFragments<B>
+where
+    B: SplitByteSliceMut,
+{
+    pub(crate) fn base_block_mut(&mut self) -> &mut [u8] {
+        &mut self.base_block
+    }
+
+    /// Returns the bytes of the given data range mutably, which must lie within a single fragment.
+    pub(crate) fn slice_mut(&mut self, data_range: Range<usize>) -> &mut [u8] {
+        let index = self.fragment_index(data_range.start).unwrap();
+        let fragment = &mut self.fragments[index];
+        let start = data_range.start - fragment.data_offset;
+        let end = data_range.end - fragment.data_offset;
+        &mut fragment.bytes[start..end]
+    }
+}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Creates a new `Hive` from a base block and hive data scattered over multiple buffers.
+    /// Performs the same validation as [`Hive::new`].
+    ///
+    /// This is useful for hives that have been carved from disk images and reassembled from fragments,
+    /// whose original data offsets are known, but which cannot be laid out contiguously in memory.
+    /// Each fragment is passed along with the data offset of its first byte.
+    /// Data offsets are relative to the end of the 4096-byte base block, so the first Hive Bin is at
+    /// data offset 0.
+    ///
+    /// Any structure that is located in a gap between fragments is reported as [`NtHiveError::MissingData`].
+    /// A structure must not span multiple fragments, so fragments should be split at Hive Bin boundaries.
+    /// Fragments may be passed in any order, but must not overlap.
+    ///
+    /// The base block is copied, while the fragments are kept as they are.
+    /// Use [`Hive::fragments`] and [`Hive::into_fragments`] to access them, as [`Hive::as_bytes`] and
+    /// [`Hive::into_inner`] only work for contiguous hives.
+    pub fn from_fragments<I>(base_block: &[u8], fragments: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (u32, B)>,
+    {
+        Self::from_fragments_with_options(base_block, fragments, ValidationOptions::strict())
+    }
+
+    /// Creates a new `Hive` from a base block and hive data scattered over multiple buffers,
+    /// performing only the validations enabled in `options`.
+    ///
+    /// This works like [`Hive::from_fragments`] and [`Hive::with_options`].
+    /// Reassembled hives often have outdated sequence numbers or checksums, which can be tolerated
+    /// via [`ValidationOptions::relaxed`].
+    pub fn from_fragments_with_options<I>(
+        base_block: &[u8],
+        fragments: I,
+        options: ValidationOptions,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = (u32, B)>,
+    {
+        let fragments = HiveFragments::new(base_block, fragments)?;
+        Self::from_hive_fragments(fragments, options)
+    }
+
+    /// Returns the data offsets and bytes of all non-empty fragments of a hive created by
+    /// [`Hive::from_fragments`], sorted by data offset.
+    ///
+    /// Returns `None` for any other hive.
+    pub fn fragments(&self) -> Option<impl Iterator<Item = (u32, &[u8])> + '_> {
+        let fragments = self.hive_fragments()?;
+        Some(
+            fragments
+                .fragments
+                .iter()
+                .map(|fragment| (fragment.data_offset as u32, &*fragment.bytes)),
+        )
+    }
+
+    /// Returns `true` if this hive has been created by [`Hive::from_fragments`].
+    pub fn is_fragmented(&self) -> bool {
+        self.hive_fragments().is_some()
+    }
+
+    /// Consumes a hive created by [`Hive::from_fragments`] and returns its base block along with all fragments
+    /// that have been passed, including empty ones.
+    ///
+    /// The fragments are returned with their data offsets, the non-empty ones sorted by data offset.
+    /// The base block reflects any modifications made through this hive (e.g. by `Hive::repair_checksum`).
+    /// Any other hive is given back as the error.
+    #[allow(clippy::type_complexity)]
+    pub fn into_fragments(self) -> core::result::Result<(Box<[u8]>, Vec<(u32, B)>), Self> {
+        let fragments = self.into_hive_fragments()?;
+        let buffers = fragments
+            .fragments
+            .into_iter()
+            .chain(fragments.empty_fragments)
+            .map(|fragment| (fragment.data_offset as u32, fragment.bytes))
+            .collect();
+
+        Ok((fragments.base_block, buffers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use zerocopy::SplitByteSlice;
+
+    /// Path of a key along with the names and data of all its values.
+    type KeyContents = (String, Vec<(String, Vec<u8>)>);
+
+    /// Collects the path, value names, and value data of all keys below `key_node`.
+    fn collect_keys_recursively<B>(key_node: &KeyNode<B>, path: &str, output: &mut Vec<KeyContents>)
+    where
+        B: SplitByteSlice,
+    {
+        let values = match key_node.values() {
+            Some(values) => values
+                .unwrap()
+                .map(|value| {
+                    let value = value.unwrap();
+                    let name = value.name().unwrap().to_string_lossy();
+                    let data = value.data().unwrap().into_vec().unwrap();
+                    (name, data)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        output.push((path.to_owned(), values));
+
+        if let Some(subkeys) = key_node.subkeys() {
+            for subkey in subkeys.unwrap() {
+                let subkey = subkey.unwrap();
+                let subkey_path = format!("{path}\\{}", subkey.name().unwrap());
+                collect_keys_recursively(&subkey, &subkey_path, output);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_fragments() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let (base_block, data) = testhive.split_at(4096);
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let mut expected = Vec::new();
+        collect_keys_recursively(&hive.root_key_node().unwrap(), "", &mut expected);
+
+        // Split the hive data into three fragments at Hive Bin boundaries and pass them out of order.
+        let first_end = hive.bin(0).unwrap().header_info().size as usize;
+        let second_end =
+            first_end + hive.bin(first_end as u32).unwrap().header_info().size as usize;
+        let fragments = [
+            (first_end as u32, &data[first_end..second_end]),
+            (0, &data[..first_end]),
+            (second_end as u32, &data[second_end..]),
+        ];
+
+        let fragmented_hive = Hive::from_fragments(base_block, fragments).unwrap();
+        assert!(fragmented_hive.is_fragmented());
+        assert!(fragmented_hive.as_bytes().is_none());
+        assert!(!hive.is_fragmented());
+        assert!(hive.fragments().is_none());
+        let sorted_fragments = fragmented_hive.fragments().unwrap().collect::<Vec<_>>();
+        assert_eq!(sorted_fragments, [fragments[1], fragments[0], fragments[2]]);
+        let mut actual = Vec::new();
+        collect_keys_recursively(&fragmented_hive.root_key_node().unwrap(), "", &mut actual);
+        assert_eq!(actual, expected);
+
+        // Error offsets must be the same as for the contiguous hive.
+        let key_value = |hive: &Hive<&[u8]>| -> Result<u64> {
+            let root_key_node = hive.root_key_node().unwrap();
+            let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+            let key_value = key_node.value("reg-sz").unwrap().unwrap();
+            key_value.qword_data()
+        };
+        let expected_error = key_value(&hive).unwrap_err();
+        assert!(matches!(
+            expected_error,
+            NtHiveError::InvalidDataSize { .. }
+        ));
+        assert_eq!(key_value(&fragmented_hive).unwrap_err(), expected_error);
+
+        // All buffers can be taken back, including empty ones.
+        let fragmented_hive = Hive::from_fragments(
+            base_block,
+            [fragments[0], fragments[1], fragments[2], (8, &data[..0])],
+        )
+        .unwrap();
+        let (returned_base_block, returned_fragments) =
+            fragmented_hive.into_fragments().ok().unwrap();
+        assert_eq!(&*returned_base_block, base_block);
+        assert_eq!(
+            returned_fragments,
+            [fragments[1], fragments[0], fragments[2], (8, &data[..0])]
+        );
+        assert!(core::ptr::eq(returned_fragments[0].1, fragments[1].1));
+        assert!(hive.into_fragments().is_err());
+
+        // A fragmented hive has no single buffer to give back.
+        let fragmented_hive = Hive::from_fragments(base_block, fragments).unwrap();
+        let fragmented_hive = fragmented_hive.into_inner().err().unwrap();
+        assert!(fragmented_hive.into_fragments().is_ok());
+
+        // Leave out the second fragment. Everything stored in it is missing now.
+        let fragmented_hive =
+            Hive::from_fragments(base_block, [fragments[1], fragments[2]]).unwrap();
+        let root_key_node = fragmented_hive.root_key_node().unwrap();
+        let mut missing_data = false;
+        for subkey in root_key_node.subkeys().unwrap().unwrap() {
+            let result = subkey.and_then(|subkey| subkey.snapshot());
+            if let Err(NtHiveError::MissingData { offset }) = result {
+                assert!((4096 + first_end..4096 + second_end).contains(&offset));
+                missing_data = true;
+            }
+        }
+        assert!(missing_data);
+
+        // Outdated sequence numbers are only tolerated with the respective options.
+        let mut dirty_base_block = base_block.to_vec();
+        crate::helpers::tests::write_u32(&mut dirty_base_block, 4, 0);
+        assert!(matches!(
+            Hive::from_fragments(&dirty_base_block, fragments),
+            Err(NtHiveError::SequenceNumberMismatch { .. })
+        ));
+        let fragmented_hive = Hive::from_fragments_with_options(
+            &dirty_base_block,
+            fragments,
+            ValidationOptions::relaxed(),
+        )
+        .unwrap();
+        let mut actual = Vec::new();
+        collect_keys_recursively(&fragmented_hive.root_key_node().unwrap(), "", &mut actual);
+        assert_eq!(actual, expected);
+
+        // Overlapping fragments are rejected.
+        assert_eq!(
+            Hive::from_fragments(
+                base_block,
+                [
+                    (0, &data[..first_end + 8]),
+                    (first_end as u32, &data[first_end..])
+                ]
+            )
+            .err(),
+            Some(NtHiveError::OverlappingFragments {
+                offset: 4096 + first_end
+            })
+        );
+    }
+}
@@ -12,6 +12,19 @@ macro_rules! iter_try {
     };
 }
 
+/// Builds the [`StructLayout`] of an On-Disk Structure from the names of its fields.
+///
+/// [`StructLayout`]: crate::dump::StructLayout
+#[cfg(feature = "alloc")]
+macro_rules! struct_layout {
+    ($struct:ty { $($field:ident),+ $(,)? }) => {
+        crate::dump::StructLayout {
+            fields: &[$((stringify!($field), core::mem::offset_of!($struct, $field))),+],
+            size: core::mem::size_of::<$struct>(),
+        }
+    };
+}
+
 /// Return a subrange of the given `Range<usize>` encompassing `byte_count`
 /// bytes and starting at the beginning of `range`.
 ///
@@ -30,10 +43,62 @@ pub(crate) fn byte_subrange(range: &Range<usize>, byte_count: usize) -> Option<R
     Some(range.start..subrange_end)
 }
 
+/// Lookup table for calculating a CRC-32 with the reflected IEEE 802.3 polynomial.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+
+    while i < table.len() {
+        let mut crc = i as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+};
+
+/// Incremental CRC-32 calculation (the same variant as used by zlib, PNG, and Ethernet).
+///
+/// Bytes can be fed in arbitrary chunks via [`Crc32::update`] without affecting the result.
+pub(crate) struct Crc32(u32);
+
+impl Crc32 {
+    pub(crate) fn new() -> Self {
+        Self(u32::MAX)
+    }
+
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            let index = (self.0 as u8 ^ byte) as usize;
+            self.0 = (self.0 >> 8) ^ CRC32_TABLE[index];
+        }
+    }
+
+    pub(crate) fn finalize(self) -> u32 {
+        !self.0
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use std::fs::File;
     use std::io::Read;
+    use std::mem;
+
+    use crate::hive::{CellHeader, HIVE_BASE_BLOCK_SIZE};
+    use crate::key_node::KeyNodeField;
+    use crate::string::NameHash;
 
     pub fn testhive_vec() -> Vec<u8> {
         let mut buffer = Vec::new();
@@ -43,4 +108,258 @@ pub mod tests {
             .unwrap();
         buffer
     }
+
+    /// Builds a minimal hive in the format of Windows NT 3.x (version 1.2).
+    ///
+    /// Its root key has a single subkey "Sub" and the values "dword" (`REG_DWORD` 42) and "big"
+    /// (`REG_BINARY` of 20000 bytes), where the latter is stored in a single cell as there is no Big Data yet.
+    /// All Key Nodes use the short header without the statistics fields.
+    pub fn legacy_testhive_vec() -> Vec<u8> {
+        const BIN_SIZE: usize = 0x6000;
+        const BIG_DATA_LEN: usize = 20000;
+
+        fn utf16_bytes(name: &str) -> Vec<u8> {
+            name.encode_utf16().flat_map(u16::to_le_bytes).collect()
+        }
+
+        /// Writes an allocated cell of the given content size at `cell_offset` and returns the data offset
+        /// of the next cell.
+        fn write_cell(data: &mut [u8], cell_offset: usize, content: &[u8]) -> usize {
+            let cell_size = (4 + content.len()).next_multiple_of(8);
+            write_u32(data, cell_offset, (cell_size as i32).wrapping_neg() as u32);
+            data[cell_offset + 4..cell_offset + 4 + content.len()].copy_from_slice(content);
+            cell_offset + cell_size
+        }
+
+        fn key_node(
+            flags: u16,
+            parent: u32,
+            subkeys: Option<u32>,
+            values: Option<(u32, u32)>,
+            name: &str,
+        ) -> Vec<u8> {
+            let field = |field: KeyNodeField| field.offset(true);
+            let name = utf16_bytes(name);
+            let mut header = vec![0u8; field(KeyNodeField::KeyName)];
+            header[..2].copy_from_slice(b"nk");
+            write_u16(&mut header, field(KeyNodeField::Flags), flags);
+            write_u32(&mut header, field(KeyNodeField::Parent), parent);
+            write_u32(
+                &mut header,
+                field(KeyNodeField::SubkeyCount),
+                subkeys.map_or(0, |_| 1),
+            );
+            write_u32(
+                &mut header,
+                field(KeyNodeField::SubkeysListOffset),
+                subkeys.unwrap_or(u32::MAX),
+            );
+            write_u32(
+                &mut header,
+                field(KeyNodeField::VolatileSubkeysListOffset),
+                u32::MAX,
+            );
+            write_u32(
+                &mut header,
+                field(KeyNodeField::KeyValuesCount),
+                values.map_or(0, |(count, _)| count),
+            );
+            write_u32(
+                &mut header,
+                field(KeyNodeField::KeyValuesListOffset),
+                values.map_or(u32::MAX, |(_, offset)| offset),
+            );
+            write_u32(
+                &mut header,
+                field(KeyNodeField::KeySecurityOffset),
+                u32::MAX,
+            );
+            write_u32(&mut header, field(KeyNodeField::ClassNameOffset), u32::MAX);
+            write_u16(
+                &mut header,
+                field(KeyNodeField::KeyNameLength),
+                name.len() as u16,
+            );
+            header.extend_from_slice(&name);
+            header
+        }
+
+        fn key_value(name: &str, data_size: u32, data_offset: u32, data_type: u32) -> Vec<u8> {
+            let name = utf16_bytes(name);
+            let mut header = vec![0u8; 20];
+            header[0..2].copy_from_slice(b"vk");
+            write_u16(&mut header, 2, name.len() as u16);
+            write_u32(&mut header, 4, data_size);
+            write_u32(&mut header, 8, data_offset);
+            write_u32(&mut header, 12, data_type);
+            header.extend_from_slice(&name);
+            header
+        }
+
+        let mut data = vec![0u8; BIN_SIZE];
+        data[0..4].copy_from_slice(b"hbin");
+        write_u32(&mut data, 8, BIN_SIZE as u32);
+
+        let root = 0x20;
+        let sub = 0x68;
+        let subkeys_list = 0xb0;
+        let values_list = 0xc0;
+        let dword_value = 0xd0;
+        let big_value = 0xf8;
+        let big_data = 0x118;
+
+        let next = write_cell(
+            &mut data,
+            root,
+            &key_node(
+                0x4,
+                0,
+                Some(subkeys_list as u32),
+                Some((2, values_list as u32)),
+                "ROOT",
+            ),
+        );
+        assert_eq!(next, sub);
+        let next = write_cell(&mut data, sub, &key_node(0, root as u32, None, None, "Sub"));
+        assert_eq!(next, subkeys_list);
+
+        let mut list = b"li".to_vec();
+        list.extend_from_slice(&1u16.to_le_bytes());
+        list.extend_from_slice(&(sub as u32).to_le_bytes());
+        let next = write_cell(&mut data, subkeys_list, &list);
+        assert_eq!(next, values_list);
+
+        let list = [dword_value as u32, big_value as u32]
+            .iter()
+            .flat_map(|offset| offset.to_le_bytes())
+            .collect::<Vec<u8>>();
+        let next = write_cell(&mut data, values_list, &list);
+        assert_eq!(next, dword_value);
+
+        let next = write_cell(
+            &mut data,
+            dword_value,
+            &key_value("dword", 0x8000_0004, 42, 4),
+        );
+        assert_eq!(next, big_value);
+        let next = write_cell(
+            &mut data,
+            big_value,
+            &key_value("big", BIG_DATA_LEN as u32, big_data as u32, 3),
+        );
+        assert_eq!(next, big_data);
+        let next = write_cell(&mut data, big_data, &[b'B'; BIG_DATA_LEN]);
+
+        // The rest of the bin is a single free cell.
+        write_u32(&mut data, next, (BIN_SIZE - next) as u32);
+
+        let mut base_block = vec![0u8; 4096];
+        base_block[0..4].copy_from_slice(b"regf");
+        write_u32(&mut base_block, 4, 1);
+        write_u32(&mut base_block, 8, 1);
+        write_u32(&mut base_block, 20, 1);
+        write_u32(&mut base_block, 24, 2);
+        write_u32(&mut base_block, 32, 1);
+        write_u32(&mut base_block, 36, root as u32);
+        write_u32(&mut base_block, 40, BIN_SIZE as u32);
+        write_u32(&mut base_block, 44, 1);
+        let checksum = base_block[..508]
+            .chunks_exact(4)
+            .fold(0u32, |checksum, chunk| {
+                checksum ^ u32::from_le_bytes(chunk.try_into().unwrap())
+            });
+        write_u32(&mut base_block, 508, checksum);
+
+        base_block.extend_from_slice(&data);
+        base_block
+    }
+
+    /// Returns the offset of the data of the cell at the given data offset (i.e. directly after its cell header),
+    /// relative to the very beginning of the hive bytes.
+    pub fn cell_data(cell_offset: u32) -> usize {
+        HIVE_BASE_BLOCK_SIZE + cell_offset as usize + mem::size_of::<CellHeader>()
+    }
+
+    /// Returns the offset of the given field of the Key Node in the cell at the given data offset,
+    /// relative to the very beginning of the hive bytes.
+    pub fn key_node_field(cell_offset: u32, field: KeyNodeField) -> usize {
+        cell_data(cell_offset) + field.offset(false)
+    }
+
+    pub fn read_u16(buffer: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(buffer[offset..offset + 2].try_into().unwrap())
+    }
+
+    pub fn read_u32(buffer: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(buffer[offset..offset + 4].try_into().unwrap())
+    }
+
+    pub fn write_u16(buffer: &mut [u8], offset: usize, value: u16) {
+        buffer[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(buffer: &mut [u8], offset: usize, value: u32) {
+        buffer[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// Renames the Key Node at the given data offset in place and updates the name hash stored
+    /// in the Hash Leaf of its parent.
+    /// The new name must be ASCII and not be longer than the old one.
+    pub fn rename_key_node(testhive: &mut [u8], cell_offset: u32, name: &str) {
+        write_u16(
+            testhive,
+            key_node_field(cell_offset, KeyNodeField::KeyNameLength),
+            name.len() as u16,
+        );
+        let name_start = key_node_field(cell_offset, KeyNodeField::KeyName);
+        testhive[name_start..name_start + name.len()].copy_from_slice(name.as_bytes());
+
+        let parent_offset = read_u32(testhive, key_node_field(cell_offset, KeyNodeField::Parent));
+        let subkeys_list = cell_data(read_u32(
+            testhive,
+            key_node_field(parent_offset, KeyNodeField::SubkeysListOffset),
+        ));
+        let count = u16::from_le_bytes(
+            testhive[subkeys_list + 2..subkeys_list + 4]
+                .try_into()
+                .unwrap(),
+        );
+        let leafs = if &testhive[subkeys_list..subkeys_list + 2] == b"ri" {
+            (0..count as usize)
+                .map(|index| cell_data(read_u32(testhive, subkeys_list + 4 + index * 4)))
+                .collect()
+        } else {
+            vec![subkeys_list]
+        };
+
+        for leaf in leafs {
+            assert_eq!(&testhive[leaf..leaf + 2], b"lh");
+            let count = u16::from_le_bytes(testhive[leaf + 2..leaf + 4].try_into().unwrap());
+
+            for index in 0..count as usize {
+                let item = leaf + 4 + index * 8;
+                if read_u32(testhive, item) == cell_offset {
+                    testhive[item + 4..item + 8].copy_from_slice(&name.name_hash().to_le_bytes());
+                    return;
+                }
+            }
+        }
+
+        panic!("Key Node {cell_offset:#x} not found in the Subkeys List of its parent");
+    }
+
+    #[test]
+    fn test_crc32() {
+        let mut crc32 = super::Crc32::new();
+        crc32.update(b"123456789");
+        assert_eq!(crc32.finalize(), 0xcbf4_3926);
+
+        let mut crc32 = super::Crc32::new();
+        crc32.update(b"1234");
+        crc32.update(b"");
+        crc32.update(b"56789");
+        assert_eq!(crc32.finalize(), 0xcbf4_3926);
+
+        assert_eq!(super::Crc32::new().finalize(), 0);
+    }
 }
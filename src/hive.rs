@@ -4,29 +4,108 @@
 use core::mem;
 use core::ops::Range;
 
-use enumn::N;
-use memoffset::offset_of;
 use zerocopy::byteorder::LittleEndian;
 use zerocopy::{
-    FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, SplitByteSliceMut,
-    Unaligned, I32, U16, U32, U64,
+    FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, Unaligned, I32, U16, U32,
+    U64,
 };
 
 use crate::error::{NtHiveError, Result};
 use crate::helpers::byte_subrange;
-use crate::key_node::{KeyNode, KeyNodeMut};
+use crate::hive_bin::{HiveBin, HiveBins, HiveCells, HIVE_BIN_ALIGNMENT};
+use crate::key_node::{KeyId, KeyNode};
+use crate::key_value::KeyValue;
+
+#[cfg(feature = "write")]
+use {crate::key_node::KeyNodeMut, zerocopy::SplitByteSliceMut};
+
+#[cfg(feature = "alloc")]
+use {
+    crate::cancel::CancelToken, crate::fragments::HiveFragments, crate::string::NtHiveNameString,
+    crate::walker::KeyNodeWalker, alloc::string::String, alloc::vec::Vec,
+};
+
+/// Size of the base block at the beginning of every hive.
+pub(crate) const HIVE_BASE_BLOCK_SIZE: usize = mem::size_of::<HiveBaseBlock>();
 
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
-struct CellHeader {
+#[repr(C, packed)]
+pub(crate) struct CellHeader {
     size: I32<LittleEndian>,
 }
 
+/// Known values of the `boot_recover` field of the base block, as returned by [`Hive::boot_recover`].
+///
+/// You can use [`HiveBootRecover::n`] on the returned value to find out whether it is a known value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum HiveBootRecover {
+    /// The hive has been loaded without any recovery.
+    NotRecovered = 0,
+    /// The hive has been recovered from its transaction log.
+    RecoveredByHiveLog = 1,
+    /// The hive has been recovered from its alternate file (`.alt`).
+    RecoveredByAlternateHive = 2,
+}
+
+impl HiveBootRecover {
+    /// Returns the variant for the given `boot_recover` value or `None` if it is unknown.
+    pub const fn n(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::NotRecovered),
+            1 => Some(Self::RecoveredByHiveLog),
+            2 => Some(Self::RecoveredByAlternateHive),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<u32> for HiveBootRecover {
+    type Error = u32;
+
+    /// Returns the unknown value as error if it doesn't correspond to any variant.
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Self::n(value).ok_or(value)
+    }
+}
+
+/// Known values of the `boot_type` field of the base block, as returned by [`Hive::boot_type`].
+///
+/// You can use [`HiveBootType::n`] on the returned value to find out whether it is a known value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum HiveBootType {
+    /// The hive has been loaded regularly.
+    Regular = 0,
+    /// The hive has been loaded by the boot loader and mapped into memory by itself.
+    SelfMap = 1,
+}
+
+impl HiveBootType {
+    /// Returns the variant for the given `boot_type` value or `None` if it is unknown.
+    pub const fn n(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Regular),
+            1 => Some(Self::SelfMap),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<u32> for HiveBootType {
+    type Error = u32;
+
+    /// Returns the unknown value as error if it doesn't correspond to any variant.
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Self::n(value).ok_or(value)
+    }
+}
+
 /// Known hive minor versions.
 ///
 /// You can use [`HiveMinorVersion::n`] on the value returned by [`Hive::minor_version`]
 /// to find out whether a hive has a known version.
-#[derive(Clone, Copy, Debug, Eq, N, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 #[repr(u32)]
 pub enum HiveMinorVersion {
     WindowsNT3_1Beta = 0,
@@ -38,6 +117,165 @@ pub enum HiveMinorVersion {
     WindowsVista = 6,
 }
 
+impl HiveMinorVersion {
+    /// Returns the variant for the given hive minor version or `None` if it is unknown.
+    pub const fn n(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::WindowsNT3_1Beta),
+            1 => Some(Self::WindowsNT3_1),
+            2 => Some(Self::WindowsNT3_5),
+            3 => Some(Self::WindowsNT4),
+            4 => Some(Self::WindowsXPBeta),
+            5 => Some(Self::WindowsXP),
+            6 => Some(Self::WindowsVista),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<u32> for HiveMinorVersion {
+    type Error = u32;
+
+    /// Returns the unknown value as error if it doesn't correspond to any variant.
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Self::n(value).ok_or(value)
+    }
+}
+
+/// Selects one of the two sequence numbers stored in the base block of a hive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HiveSequenceNumber {
+    /// The primary sequence number, which Windows increments before writing to a hive.
+    Primary,
+    /// The secondary sequence number, which Windows sets to the primary one after writing to a hive.
+    Secondary,
+}
+
+/// Mask for the bits of the last reorganization timestamp that store the [`HiveReorganizeType`].
+const REORGANIZE_TYPE_MASK: u64 = 0b11;
+
+/// Known types of hive reorganizations, as returned by [`Hive::reorganize_type`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u64)]
+pub enum HiveReorganizeType {
+    /// The hive has been defragmented.
+    Defragmented = 1,
+    /// The access history of all keys has been cleared.
+    AccessHistoryCleared = 2,
+}
+
+impl HiveReorganizeType {
+    /// Returns the variant for the given reorganization type or `None` if it is unknown.
+    pub const fn n(value: u64) -> Option<Self> {
+        match value {
+            1 => Some(Self::Defragmented),
+            2 => Some(Self::AccessHistoryCleared),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies a specific version of a hive, as returned by [`Hive::fingerprint`].
+///
+/// The fingerprint combines the checksum, last write timestamp, and root cell offset stored in the base block.
+/// Windows updates the timestamp and sequence numbers (and hence the checksum) whenever it flushes a hive,
+/// so every flushed modification results in a different fingerprint.
+///
+/// A fingerprint is no cryptographic hash of the hive:
+/// Hives modified without updating the base block keep their fingerprint, and two unrelated hives
+/// may share the same fingerprint if their base blocks happen to match.
+/// This is likely for hives written by tools that leave the timestamp at zero.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct HiveFingerprint {
+    checksum: u32,
+    timestamp: u64,
+    root_cell_offset: u32,
+}
+
+impl HiveFingerprint {
+    /// Returns the checksum stored in the base block.
+    pub fn checksum(&self) -> u32 {
+        self.checksum
+    }
+
+    /// Returns the last write timestamp stored in the base block as a raw Windows FILETIME
+    /// (100-nanosecond intervals since 1601-01-01).
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Returns the data offset of the root key's cell stored in the base block.
+    pub fn root_cell_offset(&self) -> u32 {
+        self.root_cell_offset
+    }
+}
+
+/// Metadata stored in the base block of a hive, as returned by [`Hive::info`].
+///
+/// No field is validated beyond the checks performed by [`Hive::validate`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HiveInfo {
+    timestamp: u64,
+    file_name: [u16; 32],
+    root_cell_offset: u32,
+    data_size: u32,
+    primary_sequence_number: u32,
+    secondary_sequence_number: u32,
+}
+
+impl HiveInfo {
+    /// Returns the last write timestamp of the hive as a raw Windows FILETIME
+    /// (100-nanosecond intervals since 1601-01-01).
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Returns the UTF-16 code units of the file name embedded in the base block, up to the first NUL character.
+    ///
+    /// Windows stores the last 31 characters of the hive's path here (e.g. `\SystemRoot\System32\Config\SYSTEM`
+    /// becomes `stemRoot\System32\Config\SYSTEM`), which helps to identify a hive.
+    /// The name may be truncated or contain garbage in hives written by other tools.
+    pub fn file_name_raw(&self) -> &[u16] {
+        let length = self
+            .file_name
+            .iter()
+            .position(|code_unit| *code_unit == 0)
+            .unwrap_or(self.file_name.len());
+        &self.file_name[..length]
+    }
+
+    /// Returns the file name embedded in the base block, up to the first NUL character.
+    ///
+    /// Invalid UTF-16 is replaced by the replacement character (U+FFFD).
+    /// See [`HiveInfo::file_name_raw`] for details.
+    #[cfg(feature = "alloc")]
+    pub fn file_name(&self) -> String {
+        char::decode_utf16(self.file_name_raw().iter().copied())
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    }
+
+    /// Returns the data offset of the root key's cell.
+    pub fn root_cell_offset(&self) -> u32 {
+        self.root_cell_offset
+    }
+
+    /// Returns the size of the hive data in bytes, excluding the base block.
+    pub fn data_size(&self) -> u32 {
+        self.data_size
+    }
+
+    /// Returns the primary sequence number, which Windows increments before writing to the hive.
+    pub fn primary_sequence_number(&self) -> u32 {
+        self.primary_sequence_number
+    }
+
+    /// Returns the secondary sequence number, which Windows sets to the primary one after writing to the hive.
+    pub fn secondary_sequence_number(&self) -> u32 {
+        self.secondary_sequence_number
+    }
+}
+
 #[allow(dead_code)]
 #[repr(u32)]
 enum HiveFileTypes {
@@ -53,7 +291,7 @@ enum HiveFileFormats {
 
 #[allow(dead_code)]
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
+#[repr(C, packed)]
 struct HiveBaseBlock {
     signature: [u8; 4],
     primary_sequence_number: U32<LittleEndian>,
@@ -67,17 +305,116 @@ struct HiveBaseBlock {
     data_size: U32<LittleEndian>,
     clustering_factor: U32<LittleEndian>,
     file_name: [U16<LittleEndian>; 32],
-    padding_1: [u8; 396],
+    padding_1: [u8; 56],
+    last_reorganize_time: U64<LittleEndian>,
+    padding_2: [u8; 332],
     checksum: U32<LittleEndian>,
-    padding_2: [u8; 3576],
+    padding_3: [u8; 3576],
     boot_type: U32<LittleEndian>,
     boot_recover: U32<LittleEndian>,
 }
 
+/// Backing storage of a [`Hive`].
+enum HiveBytes<B: SplitByteSlice> {
+    /// All hive bytes in a single buffer, starting with the base block.
+    Contiguous(B),
+    /// Base block and hive data scattered over multiple buffers (see [`Hive::from_fragments`]).
+    #[cfg(feature = "alloc")]
+    Fragmented(HiveFragments<B>),
+}
+
+/// Selects the validations performed on the base block by [`Hive::with_options`].
+///
+/// The signature of the base block is always validated, as anything else cannot be a hive.
+/// Each field enables the validation of the respective part of the base block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ValidationOptions {
+    /// Validate the XOR-32 checksum of the base block.
+    pub checksum: bool,
+    /// Validate that the primary and secondary sequence numbers match.
+    pub sequence_numbers: bool,
+    /// Validate that the hive has a supported version.
+    pub version: bool,
+    /// Validate that the hive is a primary hive file.
+    pub file_type: bool,
+    /// Validate that the hive uses the direct memory load file format.
+    pub file_format: bool,
+    /// Validate that the data size is aligned and doesn't exceed the hive data.
+    pub data_size: bool,
+    /// Validate that the clustering factor is 1.
+    pub clustering_factor: bool,
+    /// Also accept hives of Windows NT 3.x (minor versions 0 to 2) when validating the version.
+    ///
+    /// These hives use a shorter Key Node header without the statistics fields (like `max_subkey_name`)
+    /// and support neither Big Data nor Fast Leafs and Hash Leafs.
+    /// Only if this option is enabled, nt-hive parses a hive with such a minor version in that format.
+    /// Otherwise, every hive is parsed in the current format, even if it has been created via
+    /// [`Hive::without_validation`] or with a disabled version validation.
+    pub legacy_versions: bool,
+    /// Also validate the headers of all Hive Bins (see [`Hive::validate_bins`]).
+    ///
+    /// In contrast to all other validations, this touches the entire hive data and not just the base block.
+    /// It is therefore not enabled by [`ValidationOptions::strict`].
+    pub bins: bool,
+    /// Validate that the `segment_count` of every Big Data header is exactly the count required for the data size.
+    ///
+    /// Windows always writes the exact count. If this validation is disabled, larger counts are tolerated
+    /// and the excess segments are ignored. Smaller counts are always rejected.
+    /// In contrast to all other validations, this one is performed whenever the data of a Key Value is read.
+    /// Hives created via [`Hive::without_validation`] tolerate larger counts.
+    pub big_data_segment_count: bool,
+}
+
+impl ValidationOptions {
+    /// Returns options enabling all validations.
+    ///
+    /// These are the validations performed by [`Hive::new`].
+    /// Hives of Windows NT 3.x are rejected.
+    pub const fn strict() -> Self {
+        Self {
+            checksum: true,
+            sequence_numbers: true,
+            version: true,
+            file_type: true,
+            file_format: true,
+            data_size: true,
+            clustering_factor: true,
+            legacy_versions: false,
+            bins: false,
+            big_data_segment_count: true,
+        }
+    }
+
+    /// Returns options skipping the checksum and sequence number validations.
+    ///
+    /// These fields are often outdated in hives that have not been fully flushed to disk
+    /// (e.g. hives recovered from memory dumps), which are otherwise perfectly readable.
+    pub const fn relaxed() -> Self {
+        Self {
+            checksum: false,
+            sequence_numbers: false,
+            ..Self::strict()
+        }
+    }
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self::strict()
+    }
+}
+
 /// Root structure describing a registry hive.
 pub struct Hive<B: SplitByteSlice> {
-    base_block: Ref<B, HiveBaseBlock>,
-    pub(crate) data: B,
+    bytes: HiveBytes<B>,
+    /// See [`ValidationOptions::big_data_segment_count`].
+    exact_big_data_segment_count: bool,
+    /// Whether this hive is parsed in the format of Windows NT 3.x (see [`ValidationOptions::legacy_versions`]).
+    legacy: bool,
+    /// See [`Hive::effective_data_len`].
+    effective_data_len: usize,
+    #[cfg(feature = "alloc")]
+    cancel_token: Option<CancelToken>,
 }
 
 impl<B> Hive<B>
@@ -87,11 +424,26 @@ where
     /// Creates a new `Hive` from any byte slice.
     /// Performs basic validation and rejects any invalid hive.
     ///
-    /// You may use [`Hive::without_validation`] if you want to accept hives that fail validation.
+    /// You may use [`Hive::with_options`] or [`Hive::without_validation`] if you want to accept hives that fail validation.
     pub fn new(bytes: B) -> Result<Self> {
-        let hive = Self::without_validation(bytes)?;
-        hive.validate()?;
-        Ok(hive)
+        Self::with_options(bytes, ValidationOptions::strict())
+    }
+
+    /// Creates a new `Hive` from any byte slice, performing only the base block validations enabled in `options`.
+    ///
+    /// This lets you tolerate specific damaged fields of the base block while still rejecting hives
+    /// that fail any other validation.
+    pub fn with_options(bytes: B, options: ValidationOptions) -> Result<Self> {
+        Self::without_validation(bytes)?.validated_with_options(options)
+    }
+
+    /// Performs the validations enabled in `options` on a freshly created `Hive` and applies the options
+    /// that affect parsing.
+    fn validated_with_options(mut self, options: ValidationOptions) -> Result<Self> {
+        self.validate_with_options(options)?;
+        self.exact_big_data_segment_count = options.big_data_segment_count;
+        self.legacy = options.legacy_versions && self.has_legacy_version();
+        Ok(self)
     }
 
     /// Creates a new `Hive` from any byte slice, without validating the header.
@@ -99,29 +451,191 @@ where
     /// You may later validate the header via [`Hive::validate`].
     /// This is a solution for accessing parts of hives that have not been fully flushed to disk
     /// (e.g. due to hibernation and mismatching sequence numbers).
+    ///
+    /// Note that even such a hive only resolves cells within [`Hive::effective_data_len`].
+    /// If the hive has grown beyond the data size specified in the base block, the cells in that growth
+    /// are only accessible after updating the data size (e.g. by replaying the transaction logs).
     pub fn without_validation(bytes: B) -> Result<Self> {
-        let length = bytes.len();
-        let (base_block, data) =
-            Ref::from_prefix(bytes).map_err(|_| NtHiveError::InvalidHeaderSize {
+        if bytes.len() < HIVE_BASE_BLOCK_SIZE {
+            return Err(NtHiveError::InvalidHeaderSize {
                 offset: 0,
-                expected: mem::size_of::<HiveBaseBlock>(),
-                actual: length,
-            })?;
+                expected: HIVE_BASE_BLOCK_SIZE,
+                actual: bytes.len(),
+            });
+        }
 
-        let hive = Self { base_block, data };
+        let data_len = bytes.len() - HIVE_BASE_BLOCK_SIZE;
+        let mut hive = Self {
+            bytes: HiveBytes::Contiguous(bytes),
+            exact_big_data_segment_count: false,
+            legacy: false,
+            effective_data_len: data_len,
+            #[cfg(feature = "alloc")]
+            cancel_token: None,
+        };
+        hive.effective_data_len = hive.last_valid_bin_end();
         Ok(hive)
     }
 
+    #[cfg(feature = "alloc")]
+    pub(crate) fn from_hive_fragments(
+        fragments: HiveFragments<B>,
+        options: ValidationOptions,
+    ) -> Result<Self> {
+        // Fragments may deliberately lie beyond the data size (e.g. volatile storage) and missing fragments
+        // interrupt the chain of Hive Bins, so everything up to the end of the last fragment is usable.
+        let data_len = fragments.data_len();
+        let hive = Self {
+            bytes: HiveBytes::Fragmented(fragments),
+            exact_big_data_segment_count: false,
+            legacy: false,
+            effective_data_len: data_len,
+            cancel_token: None,
+        };
+        hive.validated_with_options(options)
+    }
+
+    /// Returns all bytes of this hive, including the base block.
+    ///
+    /// These are exactly the bytes that have been passed to [`Hive::new`] or [`Hive::without_validation`].
+    ///
+    /// Returns `None` for a hive created by `Hive::from_fragments`, whose bytes are not contiguous.
+    /// Use `Hive::fragments` for such a hive.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match &self.bytes {
+            HiveBytes::Contiguous(bytes) => Some(bytes),
+            #[cfg(feature = "alloc")]
+            HiveBytes::Fragmented(_) => None,
+        }
+    }
+
+    fn base_block(&self) -> Ref<&[u8], HiveBaseBlock> {
+        Ref::from_bytes(self.base_block_bytes()).unwrap()
+    }
+
+    fn base_block_bytes(&self) -> &[u8] {
+        match &self.bytes {
+            HiveBytes::Contiguous(bytes) => &bytes[..HIVE_BASE_BLOCK_SIZE],
+            #[cfg(feature = "alloc")]
+            HiveBytes::Fragmented(fragments) => fragments.base_block(),
+        }
+    }
+
+    /// Returns the [`HiveBin`] starting at the given data offset.
+    ///
+    /// The data offset is relative to the start of the hive data, which directly follows the 4096-byte base block.
+    /// Consequently, the first Hive Bin is at data offset 0.
+    pub fn bin(&self, data_offset: u32) -> Result<HiveBin<'_, B>> {
+        HiveBin::new(self, data_offset)
+    }
+
+    /// Returns an iterator over all Hive Bins within the data size specified in the base block.
+    ///
+    /// The first Hive Bin starts at data offset 0 and every further one directly follows the previous one.
+    /// Bins beyond the data size (e.g. appended by a tool without updating the base block) are not returned,
+    /// but can still be accessed via [`Hive::bin`].
+    pub fn bins(&self) -> HiveBins<'_, B> {
+        let data_size = self.base_block().data_size.get() as usize;
+        HiveBins::new(self, data_size)
+    }
+
+    /// Returns an iterator over all cells of all Hive Bins returned by [`Hive::bins`], in file order.
+    ///
+    /// This includes allocated and unallocated cells and is the foundation for low-level analysis,
+    /// like carving deleted data or calculating free space statistics.
+    pub fn cells(&self) -> HiveCells<'_, B> {
+        HiveCells::new(self.bins())
+    }
+
+    /// Returns [`NtHiveError::Cancelled`] if the [`CancelToken`] of this hive has been cancelled.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn check_cancelled(&self) -> Result<()> {
+        match &self.cancel_token {
+            Some(cancel_token) if cancel_token.is_cancelled() => Err(NtHiveError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the raw `boot_recover` field of the base block.
+    ///
+    /// The kernel sets this field when loading a hive during boot, and it tells whether the hive had to be
+    /// recovered from its transaction log.
+    /// Such a hive has been dirty before, so its data deserves less trust.
+    /// You can feed this value to [`HiveBootRecover::n`] to find out whether this is a known value.
+    pub fn boot_recover(&self) -> u32 {
+        self.base_block().boot_recover.get()
+    }
+
+    /// Returns the raw `boot_type` field of the base block.
+    ///
+    /// The kernel sets this field when loading a hive during boot.
+    /// You can feed this value to [`HiveBootType::n`] to find out whether this is a known value.
+    pub fn boot_type(&self) -> u32 {
+        self.base_block().boot_type.get()
+    }
+
     pub(crate) fn cell_range_from_data_offset(&self, data_offset: u32) -> Result<Range<usize>> {
-        // Only valid data offsets are accepted here.
-        assert!(data_offset != u32::MAX);
+        self.cell_range_from_data_offset_internal(data_offset, false)
+    }
+
+    /// Like [`Hive::cell_range_from_data_offset`], but for a `data_offset` that has been read from the field
+    /// at `field_offset` (relative to the very beginning of the hive bytes).
+    ///
+    /// A `data_offset` of `u32::MAX` denotes a missing cell and is reported as [`NtHiveError::MissingCell`]
+    /// for that field.
+    pub(crate) fn cell_range_from_offset_field(
+        &self,
+        data_offset: u32,
+        field_offset: usize,
+    ) -> Result<Range<usize>> {
+        self.cell_range_from_offset_field_internal(data_offset, field_offset, false)
+    }
+
+    pub(crate) fn cell_range_from_offset_field_internal(
+        &self,
+        data_offset: u32,
+        field_offset: usize,
+        allow_unallocated: bool,
+    ) -> Result<Range<usize>> {
+        if data_offset == u32::MAX {
+            return Err(NtHiveError::MissingCell { field_offset });
+        }
+
+        self.cell_range_from_data_offset_internal(data_offset, allow_unallocated)
+    }
+
+    /// Like [`Hive::cell_range_from_data_offset`], but also accepts unallocated cells.
+    ///
+    /// This is only useful for recovering deleted data, as unallocated cells may have been
+    /// partially overwritten or merged with other unallocated cells.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn any_cell_range_from_data_offset(&self, data_offset: u32) -> Result<Range<usize>> {
+        self.cell_range_from_data_offset_internal(data_offset, true)
+    }
+
+    pub(crate) fn cell_range_from_data_offset_internal(
+        &self,
+        data_offset: u32,
+        allow_unallocated: bool,
+    ) -> Result<Range<usize>> {
+        // `u32::MAX` denotes a missing cell and is never a valid data offset.
+        // Offsets read from a field are checked by `cell_range_from_offset_field`, so this one has been
+        // passed directly. Reject it like any other offset beyond the end of the hive data, without letting
+        // the reported offset overflow.
+        if data_offset == u32::MAX {
+            return Err(NtHiveError::InvalidHeaderSize {
+                offset: self.offset_of_data_offset(self.data_len()),
+                expected: mem::size_of::<CellHeader>(),
+                actual: 0,
+            });
+        }
 
         // Accept only u32 data offsets, but convert them into usize right away for
         // slice range operations and fearless calculations.
         let data_offset = data_offset as usize;
 
         // Get the cell header.
-        let remaining_range = data_offset..self.data.len();
+        let remaining_range = self.remaining_data_range(data_offset)?;
         let header_range = byte_subrange(&remaining_range, mem::size_of::<CellHeader>())
             .ok_or_else(|| NtHiveError::InvalidHeaderSize {
                 offset: self.offset_of_data_offset(data_offset),
@@ -131,11 +645,12 @@ where
         let cell_data_offset = header_range.end;
 
         // After the check above, the following operation must succeed, so we can just `unwrap`.
-        let header = Ref::<&[u8], CellHeader>::from_bytes(&self.data[header_range]).unwrap();
+        let header = Ref::<&[u8], CellHeader>::from_bytes(self.data_slice(header_range)).unwrap();
         let cell_size = header.size.get();
 
-        // A cell with size > 0 is unallocated and shouldn't be processed any further by us.
-        if cell_size > 0 {
+        // A cell with size > 0 is unallocated and shouldn't be processed any further by us
+        // (unless we are explicitly asked to do so).
+        if cell_size > 0 && !allow_unallocated {
             return Err(NtHiveError::UnallocatedCell {
                 offset: self.offset_of_data_offset(data_offset),
                 size: cell_size,
@@ -143,9 +658,10 @@ where
         }
         let cell_size = cell_size.unsigned_abs() as usize;
 
-        // The cell size must be a multiple of 8 bytes
+        // The cell size must be a non-zero multiple of 8 bytes.
+        // This also guarantees that the cell is large enough to hold the cell header.
         let expected_alignment = 8;
-        if cell_size % expected_alignment != 0 {
+        if cell_size == 0 || cell_size % expected_alignment != 0 {
             return Err(NtHiveError::InvalidSizeFieldAlignment {
                 offset: self.offset_of_field(&header.size),
                 size: cell_size,
@@ -154,7 +670,7 @@ where
         }
 
         // Get the actual data range and verify that it's inside our hive data.
-        let remaining_range = cell_data_offset..self.data.len();
+        let remaining_range = cell_data_offset..remaining_range.end;
         let cell_data_size = cell_size - mem::size_of::<CellHeader>();
         let cell_data_range = byte_subrange(&remaining_range, cell_data_size).ok_or_else(|| {
             NtHiveError::InvalidSizeField {
@@ -167,68 +683,453 @@ where
         Ok(cell_data_range)
     }
 
+    /// Returns the size of the hive data that is actually usable, i.e. the data offset where every range check ends.
+    ///
+    /// This is the smallest of the data size specified in the base block, the length of the hive data,
+    /// and the end of the last valid Hive Bin within them.
+    /// Any bytes beyond the data size are ignored, even if they contain valid Hive Bins.
+    /// This happens when a hive has grown, but its base block hasn't been updated yet, and the growth is only
+    /// recorded in the transaction logs.
+    /// Any bytes after the last valid Hive Bin are ignored as well, e.g. the incomplete last bin of a
+    /// truncated hive.
+    ///
+    /// A damaged Hive Bin in the middle doesn't end the usable hive data: The following bins are found again
+    /// at the next 4096-byte boundary, so the damage only affects the cells inside that bin.
+    /// Such hives are only rejected if [`ValidationOptions::bins`] is enabled.
+    ///
+    /// For a hive created by `Hive::from_fragments`, this is just the end of the last fragment.
+    ///
+    /// This value is determined once when the `Hive` is created.
+    pub fn effective_data_len(&self) -> usize {
+        self.effective_data_len
+    }
+
+    /// Returns the end of the last valid Hive Bin within the data size specified in the base block
+    /// and the length of the hive data.
+    ///
+    /// Hive Bins are always aligned to 4096 bytes, so the search continues at the next 4096-byte boundary
+    /// after a damaged bin.
+    fn last_valid_bin_end(&self) -> usize {
+        let data_size = (self.base_block().data_size.get() as usize).min(self.data_len());
+        let mut bin_offset = 0;
+        let mut end = 0;
+
+        while bin_offset < data_size {
+            match HiveBin::new(self, bin_offset as u32) {
+                Ok(hive_bin) if bin_offset + hive_bin.size() as usize <= data_size => {
+                    bin_offset += hive_bin.size() as usize;
+                    end = bin_offset;
+                }
+                Ok(_) => break,
+                Err(_) => bin_offset += HIVE_BIN_ALIGNMENT,
+            }
+        }
+
+        end
+    }
+
+    /// Returns the size of the hive data following the base block.
+    ///
+    /// For a hive created by [`Hive::from_fragments`], this is the end of the last fragment.
+    pub(crate) fn data_len(&self) -> usize {
+        match &self.bytes {
+            HiveBytes::Contiguous(bytes) => bytes.len() - HIVE_BASE_BLOCK_SIZE,
+            #[cfg(feature = "alloc")]
+            HiveBytes::Fragmented(fragments) => fragments.data_len(),
+        }
+    }
+
+    /// Returns the bytes of the given range of hive data.
+    ///
+    /// All data offsets stored in the hive are relative to the end of the base block.
+    /// The range must have been checked against [`Hive::remaining_data_range`] before.
+    pub(crate) fn data_slice(&self, data_range: Range<usize>) -> &[u8] {
+        match &self.bytes {
+            HiveBytes::Contiguous(bytes) => {
+                &bytes
+                    [HIVE_BASE_BLOCK_SIZE + data_range.start..HIVE_BASE_BLOCK_SIZE + data_range.end]
+            }
+            #[cfg(feature = "alloc")]
+            HiveBytes::Fragmented(fragments) => fragments.slice(data_range),
+        }
+    }
+
+    /// Consumes this `Hive` and returns the bytes it has been created from.
+    ///
+    /// This gives back ownership of a mutable buffer, e.g. to reuse it for the next hive.
+    ///
+    /// A hive created by `Hive::from_fragments` has no single buffer and is given back as the error.
+    /// Use `Hive::into_fragments` to get back all buffers of such a hive.
+    pub fn into_inner(self) -> Result<B, Self> {
+        match self.bytes {
+            HiveBytes::Contiguous(bytes) => Ok(bytes),
+            #[cfg(feature = "alloc")]
+            HiveBytes::Fragmented(_) => Err(self),
+        }
+    }
+
+    /// Returns the fragments of a hive created by [`Hive::from_fragments`], or `None` for any other hive.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn hive_fragments(&self) -> Option<&HiveFragments<B>> {
+        match &self.bytes {
+            HiveBytes::Contiguous(_) => None,
+            HiveBytes::Fragmented(fragments) => Some(fragments),
+        }
+    }
+
+    /// Consumes a hive created by [`Hive::from_fragments`] and returns its fragments,
+    /// or gives back any other hive unchanged.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn into_hive_fragments(self) -> Result<HiveFragments<B>, Self> {
+        match self.bytes {
+            HiveBytes::Fragmented(fragments) => Ok(fragments),
+            HiveBytes::Contiguous(_) => Err(self),
+        }
+    }
+
     /// Calculate a field's offset from the very beginning of the hive bytes.
     ///
     /// Note that this function primarily exists to provide absolute hive file offsets when reporting errors.
-    /// It cannot be used to index into the hive bytes, because they are initially split into `base_block`
-    /// and `data`.
-    pub(crate) fn offset_of_field<T>(&self, field: &T) -> usize {
-        let field_address = field as *const T as usize;
-        let base_address = Ref::bytes(&self.base_block).as_ptr() as usize;
+    /// The field must be part of the hive bytes.
+    pub(crate) fn offset_of_field<T: ?Sized>(&self, field: &T) -> usize {
+        let field_address = field as *const T as *const u8 as usize;
+
+        match &self.bytes {
+            HiveBytes::Contiguous(bytes) => {
+                let base_address = bytes.as_ptr() as usize;
 
-        assert!(field_address > base_address);
-        field_address - base_address
+                assert!(field_address > base_address);
+                field_address - base_address
+            }
+            #[cfg(feature = "alloc")]
+            HiveBytes::Fragmented(fragments) => fragments.offset_of_address(field_address),
+        }
     }
 
     /// Calculate a data offset's offset from the very beginning of the hive bytes.
     pub(crate) fn offset_of_data_offset(&self, data_offset: usize) -> usize {
-        data_offset + mem::size_of::<HiveBaseBlock>()
+        data_offset + HIVE_BASE_BLOCK_SIZE
+    }
+
+    /// Returns the range from `data_offset` up to the end of the contiguous hive data containing it.
+    ///
+    /// This is the end of the usable hive data (see [`Hive::effective_data_len`]) or, for a hive created by
+    /// [`Hive::from_fragments`], the end of the fragment containing `data_offset`.
+    /// Any structure starting at `data_offset` must lie within this range.
+    pub(crate) fn remaining_data_range(&self, data_offset: usize) -> Result<Range<usize>> {
+        let remaining_range = self.remaining_raw_data_range(data_offset)?;
+        Ok(remaining_range.start..remaining_range.end.min(self.effective_data_len))
+    }
+
+    /// Like [`Hive::remaining_data_range`], but ignores [`Hive::effective_data_len`].
+    ///
+    /// This is only meant for parsing Hive Bin headers, which determine the effective data length in the first place.
+    pub(crate) fn remaining_raw_data_range(&self, data_offset: usize) -> Result<Range<usize>> {
+        match &self.bytes {
+            HiveBytes::Contiguous(_) => Ok(data_offset..self.data_len()),
+            #[cfg(feature = "alloc")]
+            HiveBytes::Fragmented(fragments) => fragments
+                .remaining_data_range(data_offset)
+                .ok_or_else(|| NtHiveError::MissingData {
+                    offset: self.offset_of_data_offset(data_offset),
+                }),
+        }
+    }
+
+    /// Calls `callback` with the path and last write timestamp of every key in this hive.
+    ///
+    /// The path is passed as a slice of key names, beginning with a subkey of the root key.
+    /// Consequently, the root key itself is passed with an empty path.
+    /// The timestamp is passed as a raw Windows FILETIME (100-nanosecond intervals since 1601-01-01).
+    ///
+    /// Names are borrowed from the hive data and no path strings are allocated per key.
+    /// The `bench` example compares this to a walk that formats the path of every key.
+    #[cfg(feature = "alloc")]
+    pub fn key_timestamps<F>(&self, mut callback: F) -> Result<()>
+    where
+        F: FnMut(&[NtHiveNameString], u64),
+    {
+        let root_key_node = self.root_key_node()?;
+        callback(&[], root_key_node.timestamp());
+
+        let mut walker = KeyNodeWalker::new(&root_key_node)?;
+        while let Some(key_node) = walker.next() {
+            let key_node = key_node?;
+            callback(walker.path(), key_node.timestamp());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the time of the last reorganization of this hive, as stored in the base block.
+    ///
+    /// Windows 8 and later periodically reorganize hives, which changes the offsets of all cells.
+    /// The timestamp is returned as a raw Windows FILETIME (100-nanosecond intervals since 1601-01-01).
+    /// Returns `None` if the hive has never been reorganized, which is always the case for hives
+    /// written by older Windows versions.
+    pub fn last_reorganize_time(&self) -> Option<u64> {
+        let last_reorganize_time = self.base_block().last_reorganize_time.get();
+        if last_reorganize_time == 0 {
+            return None;
+        }
+
+        // The lowest bits store the reorganization type and are not part of the timestamp.
+        Some(last_reorganize_time & !REORGANIZE_TYPE_MASK)
+    }
+
+    /// Returns the type of the last reorganization of this hive, as stored in the base block.
+    ///
+    /// Returns `None` if the hive has never been reorganized or the type is unknown.
+    pub fn reorganize_type(&self) -> Option<HiveReorganizeType> {
+        let last_reorganize_time = self.base_block().last_reorganize_time.get();
+        HiveReorganizeType::n(last_reorganize_time & REORGANIZE_TYPE_MASK)
+    }
+
+    /// Returns the [`HiveFingerprint`] identifying this version of the hive.
+    pub fn fingerprint(&self) -> HiveFingerprint {
+        let base_block = self.base_block();
+
+        HiveFingerprint {
+            checksum: base_block.checksum.get(),
+            timestamp: base_block.timestamp.get(),
+            root_cell_offset: base_block.root_cell_offset.get(),
+        }
+    }
+
+    /// Returns the metadata stored in the base block of this hive.
+    pub fn info(&self) -> HiveInfo {
+        let base_block = self.base_block();
+
+        HiveInfo {
+            timestamp: base_block.timestamp.get(),
+            file_name: base_block.file_name.map(|code_unit| code_unit.get()),
+            root_cell_offset: base_block.root_cell_offset.get(),
+            data_size: base_block.data_size.get(),
+            primary_sequence_number: base_block.primary_sequence_number.get(),
+            secondary_sequence_number: base_block.secondary_sequence_number.get(),
+        }
+    }
+
+    /// Returns `true` if the primary and secondary sequence numbers in the base block don't match.
+    ///
+    /// Windows increments the primary sequence number before writing to a hive and sets the secondary one
+    /// to the same value afterwards.
+    /// Mismatching sequence numbers therefore indicate that the last write was not completed, which is
+    /// common for hives copied from a live system.
+    pub fn is_dirty(&self) -> bool {
+        self.validate_sequence_numbers().is_err()
+    }
+
+    /// Returns `true` if Big Data headers must have exactly the `segment_count` required for the data size
+    /// (see [`ValidationOptions::big_data_segment_count`]).
+    pub(crate) fn requires_exact_big_data_segment_count(&self) -> bool {
+        self.exact_big_data_segment_count
+    }
+
+    /// Returns `true` if the version in the base block denotes a hive of Windows NT 3.x (minor versions 0 to 2).
+    fn has_legacy_version(&self) -> bool {
+        self.major_version() == 1 && self.minor_version() < HiveMinorVersion::WindowsNT4 as u32
+    }
+
+    /// Returns `true` if this hive is parsed in the format of Windows NT 3.x.
+    ///
+    /// This is only the case for a hive with a legacy version that has been created via [`Hive::with_options`]
+    /// with [`ValidationOptions::legacy_versions`] enabled.
+    /// Every other hive is parsed in the current format, no matter what version its base block specifies.
+    pub(crate) fn is_legacy_version(&self) -> bool {
+        self.legacy
+    }
+
+    /// Returns the [`KeyNode`] identified by the given [`KeyId`].
+    ///
+    /// This is the counterpart to [`KeyNode::id`].
+    /// Returns `Ok(None)` if the [`HiveFingerprint`] of the [`KeyId`] doesn't match the one of this hive,
+    /// which means that the id has been taken from a different hive or a different version of this hive.
+    /// Otherwise, this behaves like [`Hive::key_node_from_offset`].
+    pub fn key_by_id(&self, id: &KeyId) -> Result<Option<KeyNode<'_, B>>> {
+        if id.fingerprint() != self.fingerprint() {
+            return Ok(None);
+        }
+
+        self.key_node_from_offset(id.cell_offset()).map(Some)
+    }
+
+    /// Returns the [`KeyNode`] stored in the cell at the given data offset.
+    ///
+    /// This is the counterpart to [`KeyNode::cell_offset`] and lets you resume navigating a hive at a
+    /// previously discovered key without walking there from the root key.
+    /// The cell must be allocated and contain a Key Node (signature `nk`), otherwise an error is returned.
+    /// This includes the offset `u32::MAX`, which denotes a missing cell.
+    #[doc(alias = "key_node_at_offset")]
+    pub fn key_node_from_offset(&self, cell_offset: u32) -> Result<KeyNode<'_, B>> {
+        let cell_range = self.cell_range_from_data_offset(cell_offset)?;
+        KeyNode::from_cell_range(self, cell_range)
+    }
+
+    /// Returns the [`KeyValue`] stored in the cell at the given data offset.
+    ///
+    /// This is the counterpart to [`KeyValue::cell_offset`] and lets you reopen a previously discovered
+    /// value or inspect a value referenced from a Key Values List that has been found elsewhere.
+    /// The cell must be allocated and contain a Key Value (signature `vk`), otherwise an error is returned.
+    /// This includes the offset `u32::MAX`, which denotes a missing cell.
+    #[doc(alias = "key_value_at_offset")]
+    pub fn key_value_from_offset(&self, cell_offset: u32) -> Result<KeyValue<'_, B>> {
+        let cell_range = self.cell_range_from_data_offset(cell_offset)?;
+        KeyValue::new(self, cell_range)
     }
 
     /// Returns the major version of this hive.
     ///
     /// The only known value is `1`.
     pub fn major_version(&self) -> u32 {
-        self.base_block.major_version.get()
+        self.base_block().major_version.get()
     }
 
     /// Returns the minor version of this hive.
     ///
     /// You can feed this value to [`HiveMinorVersion::n`] to find out whether this is a known version.
     pub fn minor_version(&self) -> u32 {
-        self.base_block.minor_version.get()
+        self.base_block().minor_version.get()
+    }
+
+    /// Returns the data offset of the root key's cell, as stored in the base block.
+    pub(crate) fn root_cell_offset(&self) -> u32 {
+        self.base_block().root_cell_offset.get()
+    }
+
+    /// Returns the cell range of the root Key Node.
+    pub(crate) fn root_cell_range(&self) -> Result<Range<usize>> {
+        self.cell_range_from_offset_field(
+            self.root_cell_offset(),
+            mem::offset_of!(HiveBaseBlock, root_cell_offset),
+        )
     }
 
     /// Returns the root [`KeyNode`] of this hive.
-    pub fn root_key_node(&self) -> Result<KeyNode<B>> {
-        let root_cell_offset = self.base_block.root_cell_offset.get();
-        let cell_range = self.cell_range_from_data_offset(root_cell_offset)?;
+    pub fn root_key_node(&self) -> Result<KeyNode<'_, B>> {
+        let cell_range = self.root_cell_range()?;
         KeyNode::from_cell_range(self, cell_range)
     }
 
+    /// Sets a [`CancelToken`] to cooperatively cancel long-running operations on this hive.
+    ///
+    /// This affects every operation that walks all keys below a key or all Hive Bins of the hive,
+    /// e.g. [`KeyNode::descendants`], [`Hive::key_timestamps`], [`Hive::search_value_data`],
+    /// [`Hive::verify`], `Hive::export_bodyfile`, and [`Hive::deleted_keys_report`].
+    /// Once the token has been cancelled, they return [`NtHiveError::Cancelled`] or, if they return a
+    /// report, add it to the report collected so far.
+    /// Lookups of single keys and values are not affected.
+    #[cfg(feature = "alloc")]
+    pub fn set_cancel_token(&mut self, cancel_token: CancelToken) {
+        self.cancel_token = Some(cancel_token);
+    }
+
+    /// All validations of the base block, in the order they are performed by [`Hive::validate`],
+    /// along with a function returning whether the respective validation is enabled in [`ValidationOptions`].
+    #[allow(clippy::type_complexity)]
+    const BASE_BLOCK_VALIDATIONS: [(fn(&Self) -> Result<()>, fn(&ValidationOptions) -> bool); 9] = [
+        (Self::validate_signature, |_| true),
+        (Self::validate_sequence_numbers, |o| o.sequence_numbers),
+        (Self::validate_version, |o| o.version && !o.legacy_versions),
+        (Self::validate_legacy_version, |o| {
+            o.version && o.legacy_versions
+        }),
+        (Self::validate_file_type, |o| o.file_type),
+        (Self::validate_file_format, |o| o.file_format),
+        (Self::validate_data_size, |o| o.data_size),
+        (Self::validate_clustering_factor, |o| o.clustering_factor),
+        (Self::validate_checksum, |o| o.checksum),
+    ];
+
     /// Performs basic validations on the header of this hive.
     ///
     /// If you read the hive via [`Hive::new`], these validations have already been performed.
     /// This function is only relevant for hives opened via [`Hive::without_validation`].
     pub fn validate(&self) -> Result<()> {
-        self.validate_signature()?;
-        self.validate_sequence_numbers()?;
-        self.validate_version()?;
-        self.validate_file_type()?;
-        self.validate_file_format()?;
-        self.validate_data_size()?;
-        self.validate_clustering_factor()?;
-        self.validate_checksum()?;
+        self.validate_with_options(ValidationOptions::strict())
+    }
+
+    /// Performs the validations of [`Hive::validate`] that are enabled in `options`.
+    ///
+    /// If you read the hive via [`Hive::with_options`], these validations have already been performed.
+    pub fn validate_with_options(&self, options: ValidationOptions) -> Result<()> {
+        for (validation, is_enabled) in Self::BASE_BLOCK_VALIDATIONS {
+            if is_enabled(&options) {
+                validation(self)?;
+            }
+        }
+
+        if options.bins {
+            self.validate_bins()?;
+        }
+
         Ok(())
     }
 
-    fn validate_checksum(&self) -> Result<()> {
-        let checksum_offset = offset_of!(HiveBaseBlock, checksum);
+    /// Performs the same validations as [`Hive::validate`], but doesn't stop at the first failing one.
+    ///
+    /// Returns the errors of all failing validations, which is empty for a valid hive.
+    /// This helps to assess the damage of a corrupted base block at once.
+    /// Like [`Hive::validate`], this uses [`ValidationOptions::strict`], so hives of Windows NT 3.x fail the
+    /// version validation.
+    /// Use [`Hive::validate_all_with_options`] to select the validations.
+    #[cfg(feature = "alloc")]
+    pub fn validate_all(&self) -> Vec<NtHiveError> {
+        self.validate_all_with_options(ValidationOptions::strict())
+    }
+
+    /// Performs the base block validations of [`Hive::validate_with_options`] that are enabled in `options`,
+    /// but doesn't stop at the first failing one.
+    ///
+    /// Returns the errors of all failing validations, which is empty for a valid hive.
+    /// [`ValidationOptions::bins`] is ignored here, as it is no validation of the base block.
+    #[cfg(feature = "alloc")]
+    pub fn validate_all_with_options(&self, options: ValidationOptions) -> Vec<NtHiveError> {
+        Self::BASE_BLOCK_VALIDATIONS
+            .iter()
+            .filter(|(_, is_enabled)| is_enabled(&options))
+            .filter_map(|(validation, _)| validation(self).err())
+            .collect()
+    }
+
+    /// Validates the chain of Hive Bin headers within the data size specified in the base block.
+    ///
+    /// This checks that every bin has the `hbin` signature, that the `offset` field of its header matches
+    /// its actual position, that its size is a multiple of 4096 bytes, and that all bins together cover
+    /// exactly the data size.
+    /// Without this validation, garbage in the middle of the hive data only shows up when accessing a cell
+    /// in the damaged region.
+    ///
+    /// This is not part of [`Hive::validate`], but can be enabled via [`ValidationOptions::bins`].
+    /// `Hive::verify` always performs it.
+    pub fn validate_bins(&self) -> Result<()> {
+        let data_size = self.base_block().data_size.get();
+
+        for hive_bin in self.bins() {
+            let hive_bin = hive_bin?;
+            hive_bin.validate_offset()?;
+            hive_bin.validate_end(data_size)?;
+        }
+
+        Ok(())
+    }
+
+    /// Validates that the root key of this hive has the `KEY_HIVE_ENTRY` flag set.
+    ///
+    /// This catches hives whose root cell offset has been corrupted to point at an ordinary key.
+    /// It is not part of [`Hive::validate`], because hives generated by the Offline Registry Library
+    /// and some other tools don't set this flag for the root key.
+    /// Hives written by Windows itself always have this flag set for the root key.
+    pub fn validate_root_key_node(&self) -> Result<()> {
+        self.root_key_node()?.validate_hive_entry_flag()
+    }
+
+    /// Calculates the XOR-32 checksum of all base block bytes preceding the checksum field.
+    fn calculate_checksum(&self) -> u32 {
+        let checksum_offset = mem::offset_of!(HiveBaseBlock, checksum);
 
-        // Calculate the XOR-32 checksum of all bytes preceding the checksum field.
         let mut calculated_checksum = 0;
-        for dword_bytes in
-            Ref::bytes(&self.base_block)[..checksum_offset].chunks(mem::size_of::<u32>())
+        for dword_bytes in self.base_block_bytes()[..checksum_offset].chunks(mem::size_of::<u32>())
         {
             let dword = u32::from_le_bytes(dword_bytes.try_into().unwrap());
             calculated_checksum ^= dword;
@@ -240,8 +1141,14 @@ where
             calculated_checksum -= 1;
         }
 
+        calculated_checksum
+    }
+
+    fn validate_checksum(&self) -> Result<()> {
+        let calculated_checksum = self.calculate_checksum();
+
         // Compare the calculated checksum with the stored one.
-        let checksum = self.base_block.checksum.get();
+        let checksum = self.base_block().checksum.get();
         if checksum == calculated_checksum {
             Ok(())
         } else {
@@ -253,7 +1160,7 @@ where
     }
 
     fn validate_clustering_factor(&self) -> Result<()> {
-        let clustering_factor = self.base_block.clustering_factor.get();
+        let clustering_factor = self.base_block().clustering_factor.get();
         let expected_clustering_factor = 1;
 
         if clustering_factor == expected_clustering_factor {
@@ -267,24 +1174,25 @@ where
     }
 
     fn validate_data_size(&self) -> Result<()> {
-        let data_size = self.base_block.data_size.get() as usize;
+        let base_block = self.base_block();
+        let data_size = base_block.data_size.get() as usize;
         let expected_alignment = 4096;
 
         // The data size must be a multiple of 4096 bytes
         if data_size % expected_alignment != 0 {
             return Err(NtHiveError::InvalidSizeFieldAlignment {
-                offset: self.offset_of_field(&self.base_block.data_size),
+                offset: self.offset_of_field(&base_block.data_size),
                 size: data_size,
                 expected_alignment,
             });
         }
 
         // Does the size go beyond our hive data?
-        if data_size > self.data.len() {
+        if data_size > self.data_len() {
             return Err(NtHiveError::InvalidSizeField {
-                offset: self.offset_of_field(&self.base_block.data_size),
+                offset: self.offset_of_field(&base_block.data_size),
                 expected: data_size,
-                actual: self.data.len(),
+                actual: self.data_len(),
             });
         }
 
@@ -292,7 +1200,7 @@ where
     }
 
     fn validate_file_format(&self) -> Result<()> {
-        let file_format = self.base_block.file_format.get();
+        let file_format = self.base_block().file_format.get();
         let expected_file_format = HiveFileFormats::Memory as u32;
 
         if file_format == expected_file_format {
@@ -306,7 +1214,7 @@ where
     }
 
     fn validate_file_type(&self) -> Result<()> {
-        let file_type = self.base_block.file_type.get();
+        let file_type = self.base_block().file_type.get();
         let expected_file_type = HiveFileTypes::Primary as u32;
 
         if file_type == expected_file_type {
@@ -320,8 +1228,9 @@ where
     }
 
     fn validate_sequence_numbers(&self) -> Result<()> {
-        let primary_sequence_number = self.base_block.primary_sequence_number.get();
-        let secondary_sequence_number = self.base_block.secondary_sequence_number.get();
+        let base_block = self.base_block();
+        let primary_sequence_number = base_block.primary_sequence_number.get();
+        let secondary_sequence_number = base_block.secondary_sequence_number.get();
 
         if primary_sequence_number == secondary_sequence_number {
             Ok(())
@@ -334,7 +1243,8 @@ where
     }
 
     fn validate_signature(&self) -> Result<()> {
-        let signature = &self.base_block.signature;
+        let base_block = self.base_block();
+        let signature = &base_block.signature;
         let expected_signature = b"regf";
 
         if signature == expected_signature {
@@ -348,6 +1258,14 @@ where
         }
     }
 
+    fn validate_legacy_version(&self) -> Result<()> {
+        if self.has_legacy_version() {
+            Ok(())
+        } else {
+            self.validate_version()
+        }
+    }
+
     fn validate_version(&self) -> Result<()> {
         let major = self.major_version();
         let minor = self.minor_version();
@@ -360,6 +1278,7 @@ where
     }
 }
 
+#[cfg(feature = "write")]
 impl<B> Hive<B>
 where
     B: SplitByteSliceMut,
@@ -373,17 +1292,144 @@ where
         root_key_node.clear_volatile_subkeys()
     }
 
-    pub(crate) fn root_key_node_mut(&mut self) -> Result<KeyNodeMut<B>> {
-        let root_cell_offset = self.base_block.root_cell_offset.get();
-        let cell_range = self.cell_range_from_data_offset(root_cell_offset)?;
+    fn base_block_mut(&mut self) -> Ref<&mut [u8], HiveBaseBlock> {
+        let base_block_bytes = match &mut self.bytes {
+            HiveBytes::Contiguous(bytes) => &mut bytes[..HIVE_BASE_BLOCK_SIZE],
+            #[cfg(feature = "alloc")]
+            HiveBytes::Fragmented(fragments) => fragments.base_block_mut(),
+        };
+
+        Ref::from_bytes(base_block_bytes).unwrap()
+    }
+
+    /// Recalculates the checksum of the base block and stores it in the checksum field.
+    ///
+    /// Any modification of the base block invalidates its checksum, and Windows refuses to load
+    /// hives with an invalid checksum.
+    /// The checksum is calculated in the same way as by [`Hive::validate`].
+    ///
+    /// Returns the new checksum.
+    pub fn repair_checksum(&mut self) -> u32 {
+        let checksum = self.calculate_checksum();
+        self.base_block_mut().checksum.set(checksum);
+        checksum
+    }
+
+    /// Sets both sequence numbers in the base block to the value of the one selected by `source`
+    /// and repairs the checksum afterwards.
+    ///
+    /// This normalizes a hive with mismatching sequence numbers (see [`Hive::is_dirty`]) for tools
+    /// that refuse such hives.
+    ///
+    /// **This is dangerous:** Mismatching sequence numbers mean that a write to the hive has been interrupted.
+    /// Its data may therefore be partially written and inconsistent, even if the base block and all keys
+    /// can still be read.
+    /// Only replaying the transaction logs restores a consistent state.
+    /// The hive file alone doesn't tell whether such logs exist or still need to be replayed, so this function
+    /// can't check that and resets the sequence numbers unconditionally.
+    /// Only call it if you know that the hive data has been completely written or that the logs have been
+    /// replayed already.
+    pub fn reset_sequence_numbers(&mut self, source: HiveSequenceNumber) {
+        let mut base_block = self.base_block_mut();
+        let sequence_number = match source {
+            HiveSequenceNumber::Primary => base_block.primary_sequence_number.get(),
+            HiveSequenceNumber::Secondary => base_block.secondary_sequence_number.get(),
+        };
+        base_block.primary_sequence_number.set(sequence_number);
+        base_block.secondary_sequence_number.set(sequence_number);
+
+        self.repair_checksum();
+    }
+
+    /// Returns the bytes of the given range of hive data mutably.
+    ///
+    /// The range must have been checked against [`Hive::remaining_data_range`] before.
+    pub(crate) fn data_slice_mut(&mut self, data_range: Range<usize>) -> &mut [u8] {
+        match &mut self.bytes {
+            HiveBytes::Contiguous(bytes) => {
+                &mut bytes
+                    [HIVE_BASE_BLOCK_SIZE + data_range.start..HIVE_BASE_BLOCK_SIZE + data_range.end]
+            }
+            #[cfg(feature = "alloc")]
+            HiveBytes::Fragmented(fragments) => fragments.slice_mut(data_range),
+        }
+    }
+
+    pub(crate) fn root_key_node_mut(&mut self) -> Result<KeyNodeMut<'_, B>> {
+        let cell_range = self.root_cell_range()?;
         KeyNodeMut::from_cell_range(self, cell_range)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::helpers::tests::{cell_data, key_node_field};
+    use crate::key_node::KeyNodeField;
     use crate::*;
+    use zerocopy::SplitByteSlice;
+
+    fn collect_key_timestamps_recursively<B>(
+        key_node: &KeyNode<B>,
+        path: &str,
+        output: &mut Vec<(String, u64)>,
+    ) where
+        B: SplitByteSlice,
+    {
+        output.push((path.to_owned(), key_node.timestamp()));
+
+        if let Some(subkeys) = key_node.subkeys() {
+            for subkey in subkeys.unwrap() {
+                let subkey = subkey.unwrap();
+                let name = subkey.name().unwrap().to_string_lossy();
+                let subkey_path = if path.is_empty() {
+                    name
+                } else {
+                    format!("{path}\\{name}")
+                };
+
+                collect_key_timestamps_recursively(&subkey, &subkey_path, output);
+            }
+        }
+    }
+
+    #[test]
+    fn test_boot_type_and_recover() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(hive.boot_type(), 0);
+        assert_eq!(hive.boot_recover(), 0);
+        assert_eq!(
+            HiveBootType::n(hive.boot_type()),
+            Some(HiveBootType::Regular)
+        );
+        assert_eq!(
+            HiveBootRecover::n(hive.boot_recover()),
+            Some(HiveBootRecover::NotRecovered)
+        );
+
+        // Both fields are outside the checksummed part of the base block.
+        testhive[4088..4092].copy_from_slice(&1u32.to_le_bytes());
+        testhive[4092..4096].copy_from_slice(&1u32.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(hive.boot_type(), 1);
+        assert_eq!(hive.boot_recover(), 1);
+        assert_eq!(HiveBootType::try_from(1), Ok(HiveBootType::SelfMap));
+        assert_eq!(
+            HiveBootRecover::try_from(1),
+            Ok(HiveBootRecover::RecoveredByHiveLog)
+        );
 
+        testhive[4088..4092].copy_from_slice(&7u32.to_le_bytes());
+        testhive[4092..4096].copy_from_slice(&2u32.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(HiveBootType::try_from(hive.boot_type()), Err(7));
+        assert_eq!(
+            HiveBootRecover::n(hive.boot_recover()),
+            Some(HiveBootRecover::RecoveredByAlternateHive)
+        );
+    }
+
+    #[cfg(feature = "write")]
     #[test]
     fn test_clear_volatile_subkeys() {
         // clear_volatile_subkeys traverses all subkeys, so this test just checks
@@ -392,4 +1438,685 @@ mod tests {
         let mut hive = Hive::new(testhive.as_mut()).unwrap();
         assert!(hive.clear_volatile_subkeys().is_ok());
     }
+
+    #[test]
+    fn test_key_by_id() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let fingerprint = hive.fingerprint();
+        assert_eq!(fingerprint.timestamp(), 0);
+        assert_eq!(fingerprint.root_cell_offset(), 0x20);
+        assert_eq!(
+            fingerprint.checksum(),
+            u32::from_le_bytes(testhive[508..512].try_into().unwrap())
+        );
+
+        // Ids round-trip for every key.
+        let root_key_node = hive.root_key_node().unwrap();
+        let root_id = root_key_node.id();
+        assert_eq!(root_id.fingerprint(), fingerprint);
+        assert_eq!(root_id.cell_offset(), root_key_node.cell_offset());
+
+        let ids = core::iter::once(root_id)
+            .chain(
+                root_key_node
+                    .descendants()
+                    .unwrap()
+                    .map(|key_node| key_node.unwrap().id()),
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(ids.len(), 528);
+        for id in &ids {
+            let key_node = hive.key_by_id(id).unwrap().unwrap();
+            assert_eq!(key_node.id(), *id);
+        }
+
+        // Ids are unique within a hive.
+        let unique_ids = ids.iter().collect::<alloc::collections::BTreeSet<_>>();
+        assert_eq!(unique_ids.len(), ids.len());
+
+        // Bump the timestamp and fix up the checksum to get a newer version of the hive.
+        let mut newer_testhive = testhive.clone();
+        newer_testhive[12] ^= 1;
+        newer_testhive[508] ^= 1;
+        let newer_hive = Hive::new(newer_testhive.as_ref()).unwrap();
+        assert_ne!(newer_hive.fingerprint(), fingerprint);
+        assert!(newer_hive.key_by_id(&root_id).unwrap().is_none());
+        assert!(newer_hive.key_by_id(&ids[1]).unwrap().is_none());
+
+        // Ids of the newer hive only resolve there.
+        let newer_root_id = newer_hive.root_key_node().unwrap().id();
+        assert!(newer_hive.key_by_id(&newer_root_id).unwrap().is_some());
+        assert!(hive.key_by_id(&newer_root_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_info() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let info = hive.info();
+        assert_eq!(info.timestamp(), 0);
+        assert_eq!(info.file_name(), "");
+        assert_eq!(info.root_cell_offset(), 0x20);
+        assert_eq!(info.data_size(), 155648);
+        assert_eq!(info.primary_sequence_number(), 1);
+        assert_eq!(info.secondary_sequence_number(), 1);
+
+        fn set_file_name(testhive: &mut [u8], code_units: &[u16]) {
+            testhive[48..112].fill(0);
+            for (i, code_unit) in code_units.iter().enumerate() {
+                testhive[48 + i * 2..48 + i * 2 + 2].copy_from_slice(&code_unit.to_le_bytes());
+            }
+        }
+
+        // A short name ends at the first NUL character.
+        set_file_name(&mut testhive, &"SYSTEM".encode_utf16().collect::<Vec<_>>());
+        testhive[12..20].copy_from_slice(&0x01da_3c45_7689_c000u64.to_le_bytes());
+        let hive = Hive::without_validation(testhive.as_ref()).unwrap();
+        let info = hive.info();
+        assert_eq!(info.file_name(), "SYSTEM");
+        assert_eq!(info.file_name_raw().len(), 6);
+        assert_eq!(info.timestamp(), 0x01da_3c45_7689_c000);
+
+        // A full-length name has no terminating NUL character.
+        let full_name = "tRoot\\System32\\Config\\SYSTEM.LOG";
+        let full_name_utf16 = full_name.encode_utf16().collect::<Vec<_>>();
+        assert_eq!(full_name_utf16.len(), 32);
+        set_file_name(&mut testhive, &full_name_utf16);
+        let hive = Hive::without_validation(testhive.as_ref()).unwrap();
+        assert_eq!(hive.info().file_name(), full_name);
+
+        // Garbage is decoded lossily.
+        set_file_name(&mut testhive, &[u16::from(b'A'), 0xd800, u16::from(b'B')]);
+        let hive = Hive::without_validation(testhive.as_ref()).unwrap();
+        assert_eq!(hive.info().file_name(), "A\u{fffd}B");
+    }
+
+    #[test]
+    fn test_into_inner() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let testhive_ptr = testhive.as_ptr();
+        let testhive_len = testhive.len();
+
+        let hive = Hive::new(testhive.as_mut_slice()).unwrap();
+        let bytes = hive.as_bytes().unwrap();
+        assert_eq!(bytes.as_ptr(), testhive_ptr);
+        assert_eq!(bytes.len(), testhive_len);
+        assert_eq!(&bytes[..4], b"regf");
+
+        #[cfg(feature = "write")]
+        let hive = {
+            let mut hive = hive;
+            hive.clear_volatile_subkeys().unwrap();
+            hive
+        };
+
+        // We must get back the very same buffer.
+        let bytes = hive.into_inner().ok().unwrap();
+        assert_eq!(bytes.as_ptr(), testhive_ptr);
+        assert_eq!(bytes.len(), testhive_len);
+
+        // The buffer can be reused for the next hive.
+        let hive = Hive::new(bytes).unwrap();
+        assert!(hive.root_key_node().is_ok());
+
+        // A buffer too small for the base block is rejected.
+        assert!(matches!(
+            Hive::new(&testhive[..4095]),
+            Err(NtHiveError::InvalidHeaderSize {
+                offset: 0,
+                expected: 4096,
+                actual: 4095,
+            })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_cell_sizes() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_cell = 4096 + hive.root_key_node().unwrap().cell_offset() as usize;
+
+        // A zero cell size must be rejected instead of underflowing.
+        testhive[root_cell..root_cell + 4].copy_from_slice(&0i32.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert!(matches!(
+            hive.root_key_node(),
+            Err(NtHiveError::InvalidSizeFieldAlignment { size: 0, .. })
+        ));
+
+        // The largest possible cell size must be rejected, because it exceeds the hive data.
+        testhive[root_cell..root_cell + 4].copy_from_slice(&(i32::MIN + 8).to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert!(matches!(
+            hive.root_key_node(),
+            Err(NtHiveError::InvalidSizeField { .. })
+        ));
+        testhive[root_cell..root_cell + 4].copy_from_slice(&i32::MIN.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert!(matches!(
+            hive.root_key_node(),
+            Err(NtHiveError::InvalidSizeField { .. })
+        ));
+    }
+
+    #[test]
+    fn test_key_timestamps() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        let mut key_timestamps = Vec::new();
+        hive.key_timestamps(|path, timestamp| {
+            let path = path
+                .iter()
+                .map(|name| name.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("\\");
+            key_timestamps.push((path, timestamp));
+        })
+        .unwrap();
+
+        // Compare against the slow path of recursively iterating all subkeys and building strings.
+        let root_key_node = hive.root_key_node().unwrap();
+        let mut expected_key_timestamps = Vec::new();
+        collect_key_timestamps_recursively(&root_key_node, "", &mut expected_key_timestamps);
+
+        assert_eq!(key_timestamps, expected_key_timestamps);
+        assert!(key_timestamps
+            .iter()
+            .any(|(path, _)| path == "subpath-test\\with-two-levels-of-subkeys\\subkey1\\subkey2"));
+    }
+
+    #[test]
+    fn test_key_node_from_offset() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node
+            .subpath("subpath-test\\with-two-levels-of-subkeys\\subkey1")
+            .unwrap()
+            .unwrap();
+
+        let resumed_key_node = hive.key_node_from_offset(key_node.cell_offset()).unwrap();
+        assert_eq!(resumed_key_node, key_node);
+        assert_eq!(resumed_key_node.file_offset(), key_node.file_offset());
+        assert_eq!(
+            key_node.file_offset(),
+            4096 + key_node.cell_offset() as usize
+        );
+        let signature = key_node_field(key_node.cell_offset(), KeyNodeField::Signature);
+        assert_eq!(&testhive[signature..signature + 2], b"nk");
+        assert_eq!(resumed_key_node.name().unwrap(), "subkey1");
+        assert_eq!(
+            resumed_key_node.parent().unwrap().unwrap().name().unwrap(),
+            "with-two-levels-of-subkeys"
+        );
+
+        // The Key Security cell of the root key is no Key Node.
+        let key_security = root_key_node.key_security().unwrap().unwrap();
+        assert!(matches!(
+            hive.key_node_from_offset(key_security.cell_offset()),
+            Err(NtHiveError::InvalidTwoByteSignature {
+                expected: b"nk",
+                actual: [b's', b'k'],
+                ..
+            })
+        ));
+
+        // Offsets outside the hive data are rejected.
+        assert!(matches!(
+            hive.key_node_from_offset(0x7fff_fff0),
+            Err(NtHiveError::InvalidHeaderSize { .. })
+        ));
+        assert!(matches!(
+            hive.key_node_from_offset(u32::MAX),
+            Err(NtHiveError::InvalidHeaderSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_key_value_from_offset() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("reg-sz").unwrap().unwrap();
+
+        let reopened_key_value = hive.key_value_from_offset(key_value.cell_offset()).unwrap();
+        assert_eq!(reopened_key_value, key_value);
+        assert_eq!(reopened_key_value.name().unwrap(), "reg-sz");
+        assert_eq!(
+            reopened_key_value.data_cow().unwrap(),
+            key_value.data_cow().unwrap()
+        );
+        assert_eq!(
+            reopened_key_value.string_data().unwrap(),
+            key_value.string_data().unwrap()
+        );
+
+        // A Key Node is no Key Value.
+        assert!(matches!(
+            hive.key_value_from_offset(key_node.cell_offset()),
+            Err(NtHiveError::InvalidTwoByteSignature {
+                expected: b"vk",
+                actual: [b'n', b'k'],
+                ..
+            })
+        ));
+
+        // Offsets outside the hive data are rejected.
+        assert!(matches!(
+            hive.key_value_from_offset(0x7fff_fff0),
+            Err(NtHiveError::InvalidHeaderSize { .. })
+        ));
+        assert!(matches!(
+            hive.key_value_from_offset(u32::MAX),
+            Err(NtHiveError::InvalidHeaderSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_last_reorganize_time() {
+        // The testhive has been written by the Offline Registry Library, which doesn't reorganize.
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(hive.last_reorganize_time(), None);
+        assert_eq!(hive.reorganize_type(), None);
+
+        // Store a defragmentation on 2024-01-01 00:00:00 UTC and fix up the checksum.
+        let timestamp = 0x01da_3c45_7689_c000u64;
+        let last_reorganize_time = timestamp | HiveReorganizeType::Defragmented as u64;
+        testhive[0xa8..0xb0].copy_from_slice(&last_reorganize_time.to_le_bytes());
+
+        let mut checksum = u32::from_le_bytes(testhive[508..512].try_into().unwrap());
+        checksum ^= last_reorganize_time as u32 ^ (last_reorganize_time >> 32) as u32;
+        testhive[508..512].copy_from_slice(&checksum.to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(hive.last_reorganize_time(), Some(timestamp));
+        assert_eq!(
+            hive.reorganize_type(),
+            Some(HiveReorganizeType::Defragmented)
+        );
+    }
+
+    #[test]
+    fn test_legacy_versions() {
+        let testhive = crate::helpers::tests::legacy_testhive_vec();
+        assert_eq!(
+            Hive::new(testhive.as_ref()).err(),
+            Some(NtHiveError::UnsupportedVersion { major: 1, minor: 2 })
+        );
+
+        let options = ValidationOptions {
+            legacy_versions: true,
+            ..ValidationOptions::strict()
+        };
+        let hive = Hive::with_options(testhive.as_ref(), options).unwrap();
+        assert_eq!(hive.minor_version(), 2);
+
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(root_key_node.name().unwrap(), "ROOT");
+        assert_eq!(root_key_node.max_subkey_name(), 0);
+        assert_eq!(root_key_node.max_value_data(), 0);
+
+        let subkey = root_key_node.subkey("Sub").unwrap().unwrap();
+        assert_eq!(
+            subkey.parent().unwrap().unwrap().cell_offset(),
+            root_key_node.cell_offset()
+        );
+        assert_eq!(root_key_node.subkeys().unwrap().unwrap().count(), 1);
+
+        let key_value = root_key_node.value("dword").unwrap().unwrap();
+        assert_eq!(key_value.dword_data().unwrap(), 42);
+
+        // Data beyond the size of a Big Data segment is still stored in a single cell.
+        let key_value = root_key_node.value("big").unwrap().unwrap();
+        let data = key_value.data().unwrap();
+        assert!(matches!(data, KeyValueData::Small(_)));
+        assert_eq!(data.into_vec().unwrap(), vec![b'B'; 20000]);
+
+        assert_eq!(hive.validate_all_with_options(options), []);
+        assert_eq!(
+            hive.validate_all(),
+            [NtHiveError::UnsupportedVersion { major: 1, minor: 2 }]
+        );
+
+        // Windows NT 3.x only knows Index Leafs, so a Hash Leaf indicates corruption.
+        let mut legacy_testhive = testhive.clone();
+        let subkeys_list = cell_data(0xb0);
+        legacy_testhive[subkeys_list..subkeys_list + 2].copy_from_slice(b"lh");
+        let hive = Hive::with_options(legacy_testhive.as_ref(), options).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert!(matches!(
+            root_key_node.subkeys().unwrap(),
+            Err(NtHiveError::InvalidTwoByteSignature {
+                expected: b"li|ri",
+                actual: [b'l', b'h'],
+                ..
+            })
+        ));
+
+        // Without the option, the legacy format is never used, not even for unvalidated hives.
+        let hive = Hive::without_validation(testhive.as_ref()).unwrap();
+        assert!(matches!(
+            hive.root_key_node(),
+            Err(NtHiveError::InvalidHeaderSize { expected: 76, .. })
+        ));
+
+        // The legacy option still accepts current versions.
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::with_options(testhive.as_ref(), options).unwrap();
+        assert_eq!(hive.root_key_node().unwrap().name().unwrap(), "ROOT");
+
+        // A current hive with a corrupted minor version is still parsed in the current format.
+        let mut testhive = testhive;
+        let minor_version = core::mem::offset_of!(super::HiveBaseBlock, minor_version);
+        crate::helpers::tests::write_u32(&mut testhive, minor_version, 0);
+        let hive = Hive::without_validation(testhive.as_ref()).unwrap();
+        assert_eq!(hive.root_key_node().unwrap().name().unwrap(), "ROOT");
+
+        let options = ValidationOptions {
+            checksum: false,
+            version: false,
+            ..ValidationOptions::strict()
+        };
+        let hive = Hive::with_options(testhive.as_ref(), options).unwrap();
+        assert_eq!(hive.root_key_node().unwrap().name().unwrap(), "ROOT");
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_reset_sequence_numbers() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let primary = u32::from_le_bytes(testhive[4..8].try_into().unwrap());
+
+        // Simulate an interrupted write by incrementing the primary sequence number.
+        testhive[4..8].copy_from_slice(&(primary + 1).to_le_bytes());
+        let mut hive = Hive::without_validation(testhive.as_mut()).unwrap();
+        hive.repair_checksum();
+        assert!(hive.is_dirty());
+        assert!(matches!(
+            hive.validate_sequence_numbers(),
+            Err(NtHiveError::SequenceNumberMismatch { .. })
+        ));
+
+        // Copy the primary sequence number into the secondary one.
+        hive.reset_sequence_numbers(HiveSequenceNumber::Primary);
+        assert!(!hive.is_dirty());
+        assert_eq!(hive.validate_sequence_numbers(), Ok(()));
+        assert_eq!(hive.validate(), Ok(()));
+        assert_eq!(testhive[4..8], (primary + 1).to_le_bytes());
+        assert_eq!(testhive[8..12], (primary + 1).to_le_bytes());
+
+        // Copy the secondary sequence number instead.
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        testhive[4..8].copy_from_slice(&(primary + 1).to_le_bytes());
+        let mut hive = Hive::without_validation(testhive.as_mut()).unwrap();
+        hive.repair_checksum();
+        hive.reset_sequence_numbers(HiveSequenceNumber::Secondary);
+        assert!(!hive.is_dirty());
+        assert_eq!(hive.validate(), Ok(()));
+        assert_eq!(testhive[4..8], primary.to_le_bytes());
+        assert_eq!(testhive[8..12], primary.to_le_bytes());
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_repair_checksum() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let checksum = u32::from_le_bytes(testhive[508..512].try_into().unwrap());
+
+        // Modify the last written timestamp, which breaks the checksum.
+        testhive[12..16].copy_from_slice(&42u32.to_le_bytes());
+        let mut hive = Hive::without_validation(testhive.as_mut()).unwrap();
+        assert!(matches!(
+            hive.validate(),
+            Err(NtHiveError::InvalidChecksum { .. })
+        ));
+
+        let new_checksum = hive.repair_checksum();
+        assert_ne!(new_checksum, checksum);
+        assert_eq!(hive.validate(), Ok(()));
+        assert_eq!(testhive[508..512], new_checksum.to_le_bytes());
+
+        // Repairing a valid checksum doesn't change it.
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        testhive[508..512].copy_from_slice(&0u32.to_le_bytes());
+        let mut hive = Hive::without_validation(testhive.as_mut()).unwrap();
+        assert_eq!(hive.repair_checksum(), checksum);
+        assert_eq!(hive.repair_checksum(), checksum);
+        assert_eq!(hive.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_all() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(hive.validate_all(), []);
+
+        // Break the sequence numbers and the clustering factor.
+        // This also breaks the checksum.
+        testhive[8..12].copy_from_slice(&0u32.to_le_bytes());
+        testhive[44..48].copy_from_slice(&2u32.to_le_bytes());
+
+        let hive = Hive::without_validation(testhive.as_ref()).unwrap();
+        let errors = hive.validate_all();
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            errors[0],
+            NtHiveError::SequenceNumberMismatch { secondary: 0, .. }
+        ));
+        assert_eq!(
+            errors[1],
+            NtHiveError::UnsupportedClusteringFactor {
+                expected: 1,
+                actual: 2
+            }
+        );
+        assert!(matches!(errors[2], NtHiveError::InvalidChecksum { .. }));
+        assert_eq!(hive.validate().err(), Some(errors[0].clone()));
+    }
+
+    #[test]
+    fn test_validate_bins() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(hive.validate_bins(), Ok(()));
+        let last_bin_offset = hive.bins().last().unwrap().unwrap().offset() as usize;
+        let options = ValidationOptions {
+            bins: true,
+            ..ValidationOptions::strict()
+        };
+
+        // A broken signature in the middle of the hive data is only found when validating the bins.
+        let mut corrupted = testhive.clone();
+        corrupted[4096 + 0x1000..4096 + 0x1004].copy_from_slice(b"xbin");
+        assert!(Hive::new(corrupted.as_ref()).is_ok());
+        assert!(matches!(
+            Hive::with_options(corrupted.as_ref(), options),
+            Err(NtHiveError::InvalidFourByteSignature { offset, .. }) if offset == 4096 + 0x1000
+        ));
+
+        // So is an offset field that doesn't match the position of the bin.
+        let mut corrupted = testhive.clone();
+        corrupted[4096 + 0x1004..4096 + 0x1008].copy_from_slice(&0x2000u32.to_le_bytes());
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        assert_eq!(
+            hive.validate_bins(),
+            Err(NtHiveError::InvalidHiveBinOffset {
+                offset: 4096 + 0x1004,
+                expected: 0x1000,
+                actual: 0x2000,
+            })
+        );
+
+        // A truncated file leaves a short final bin.
+        let size_field = 4096 + last_bin_offset + 8;
+        let size = u32::from_le_bytes(testhive[size_field..size_field + 4].try_into().unwrap());
+        let truncated = &testhive[..testhive.len() - 2048];
+        let options = ValidationOptions {
+            data_size: false,
+            ..options
+        };
+        assert_eq!(
+            Hive::with_options(truncated, options).err(),
+            Some(NtHiveError::InvalidSizeField {
+                offset: size_field,
+                expected: size as usize,
+                actual: size as usize - 2048,
+            })
+        );
+
+        // A final bin extending beyond the data size is rejected as well, even if the file has more data.
+        let mut corrupted = testhive.clone();
+        corrupted[size_field..size_field + 4].copy_from_slice(&(size + 4096).to_le_bytes());
+        corrupted.extend_from_slice(&[0; 4096]);
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        assert_eq!(
+            hive.validate_bins(),
+            Err(NtHiveError::InvalidSizeField {
+                offset: size_field,
+                expected: size as usize + 4096,
+                actual: size as usize,
+            })
+        );
+    }
+
+    #[test]
+    fn test_effective_data_len() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let data_size = hive.info().data_size() as usize;
+        let bin_count = hive.bins().count();
+        let last_bin_offset = hive.bins().last().unwrap().unwrap().offset() as usize;
+        assert_eq!(hive.effective_data_len(), data_size);
+
+        // Append a Hive Bin holding a copy of the root Key Node, as if the hive has grown,
+        // but the base block hasn't been updated yet.
+        let root_cell_start = 4096 + hive.root_cell_offset() as usize;
+        let root_cell = &testhive[root_cell_start..root_cell_start + 88];
+        let mut bin = vec![0u8; 4096];
+        bin[0..4].copy_from_slice(b"hbin");
+        bin[4..8].copy_from_slice(&(data_size as u32).to_le_bytes());
+        bin[8..12].copy_from_slice(&4096u32.to_le_bytes());
+        bin[32..120].copy_from_slice(root_cell);
+        bin[120..124].copy_from_slice(&(4096i32 - 120).to_le_bytes());
+        let mut grown = testhive.clone();
+        grown.extend_from_slice(&bin);
+
+        // The tail is ignored as long as the data size doesn't cover it.
+        let tail_cell_offset = data_size as u32 + 32;
+        let hive = Hive::new(grown.as_ref()).unwrap();
+        assert_eq!(hive.effective_data_len(), data_size);
+        assert_eq!(hive.bins().count(), bin_count);
+        assert!(matches!(
+            hive.key_node_from_offset(tail_cell_offset),
+            Err(NtHiveError::InvalidHeaderSize { offset, .. }) if offset == 4096 + tail_cell_offset as usize
+        ));
+
+        // It is honored after the data size has been extended (e.g. by replaying the transaction logs).
+        grown[40..44].copy_from_slice(&((data_size + 4096) as u32).to_le_bytes());
+        let hive = Hive::with_options(grown.as_ref(), ValidationOptions::relaxed()).unwrap();
+        assert_eq!(hive.effective_data_len(), data_size + 4096);
+        assert_eq!(hive.bins().count(), bin_count + 1);
+        let key_node = hive.key_node_from_offset(tail_cell_offset).unwrap();
+        assert_eq!(key_node.name().unwrap(), "ROOT");
+
+        // A data size beyond the hive bytes only covers the complete Hive Bins.
+        let truncated = &testhive[..testhive.len() - 1];
+        let hive = Hive::without_validation(truncated).unwrap();
+        assert_eq!(hive.effective_data_len(), last_bin_offset);
+
+        // So does a data size covering garbage.
+        let mut garbage = testhive.clone();
+        garbage[4096 + last_bin_offset..4096 + last_bin_offset + 4].copy_from_slice(b"xbin");
+        let hive = Hive::new(garbage.as_ref()).unwrap();
+        assert_eq!(hive.effective_data_len(), last_bin_offset);
+
+        // A damaged Hive Bin doesn't make the cells after it unreachable, unless bins are validated.
+        let mut garbage = testhive.clone();
+        garbage[4096..4096 + 4].copy_from_slice(b"xbin");
+        let hive = Hive::new(garbage.as_ref()).unwrap();
+        assert_eq!(hive.effective_data_len(), data_size);
+        assert_eq!(hive.root_key_node().unwrap().name().unwrap(), "ROOT");
+
+        let options = ValidationOptions {
+            bins: true,
+            ..ValidationOptions::strict()
+        };
+        assert!(matches!(
+            Hive::with_options(garbage.as_ref(), options),
+            Err(NtHiveError::InvalidFourByteSignature { offset: 4096, .. })
+        ));
+    }
+
+    #[test]
+    fn test_with_options() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        assert!(Hive::with_options(testhive.as_ref(), ValidationOptions::strict()).is_ok());
+
+        // Break the checksum.
+        testhive[508..512].copy_from_slice(&0u32.to_le_bytes());
+        assert!(matches!(
+            Hive::with_options(testhive.as_ref(), ValidationOptions::strict()),
+            Err(NtHiveError::InvalidChecksum { .. })
+        ));
+        assert!(Hive::new(testhive.as_ref()).is_err());
+
+        let hive = Hive::with_options(testhive.as_ref(), ValidationOptions::relaxed()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert!(root_key_node.subkey("data-test").unwrap().is_ok());
+
+        // Relaxed options still reject other damaged fields.
+        testhive[44..48].copy_from_slice(&2u32.to_le_bytes());
+        assert!(matches!(
+            Hive::with_options(testhive.as_ref(), ValidationOptions::relaxed()),
+            Err(NtHiveError::UnsupportedClusteringFactor { .. })
+        ));
+
+        let options = ValidationOptions {
+            clustering_factor: false,
+            ..ValidationOptions::relaxed()
+        };
+        assert!(Hive::with_options(testhive.as_ref(), options).is_ok());
+    }
+
+    #[test]
+    fn test_validate_root_key_node() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let root_cell_offset = root_key_node.cell_offset();
+        let subpath_test_offset = root_key_node
+            .subkey("subpath-test")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+
+        // The Offline Registry Library doesn't set the KEY_HIVE_ENTRY flag for the root key.
+        let root_flags_offset = key_node_field(root_cell_offset, KeyNodeField::Flags);
+        assert_eq!(
+            hive.validate_root_key_node(),
+            Err(NtHiveError::MissingHiveEntryFlag {
+                offset: root_flags_offset
+            })
+        );
+
+        testhive[root_flags_offset] |= 0x04;
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(hive.validate_root_key_node(), Ok(()));
+
+        // Let the root cell offset point at an ordinary key.
+        testhive[36..40].copy_from_slice(&subpath_test_offset.to_le_bytes());
+        let hive = Hive::without_validation(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(root_key_node.name().unwrap(), "subpath-test");
+        assert_eq!(
+            hive.validate_root_key_node(),
+            Err(NtHiveError::MissingHiveEntryFlag {
+                offset: key_node_field(subpath_test_offset, KeyNodeField::Flags)
+            })
+        );
+    }
 }
@@ -0,0 +1,608 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::Range;
+
+use zerocopy::byteorder::LittleEndian;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, Unaligned, U32, U64,
+};
+
+use zerocopy::I32;
+
+use crate::error::{NtHiveError, Result};
+use crate::helpers::byte_subrange;
+use crate::hive::{CellHeader, Hive};
+
+/// The size of every Hive Bin is a multiple of this value.
+pub(crate) const HIVE_BIN_ALIGNMENT: usize = 4096;
+
+/// On-Disk Structure of a Hive Bin header.
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct HiveBinHeader {
+    signature: [u8; 4],
+    offset: U32<LittleEndian>,
+    size: U32<LittleEndian>,
+    reserved: [U32<LittleEndian>; 2],
+    timestamp: U64<LittleEndian>,
+    spare: U32<LittleEndian>,
+}
+
+/// The raw fields of a Hive Bin header, as returned by [`HiveBin::header_info`].
+///
+/// No field is interpreted or validated beyond the signature and size checks performed by [`Hive::bin`].
+/// This makes it possible to detect inconsistencies like an `offset` that doesn't match the actual position of the bin.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HiveBinHeaderInfo {
+    /// Signature of the bin, always `hbin`.
+    pub signature: [u8; 4],
+    /// Offset of this bin relative to the start of the hive data (i.e. excluding the base block).
+    pub offset: u32,
+    /// Size of this bin in bytes, including this header.
+    pub size: u32,
+    /// Reserved fields, usually zero.
+    pub reserved: [u32; 2],
+    /// Raw Windows FILETIME (100-nanosecond intervals since 1601-01-01).
+    /// Usually only set for the first bin of a hive.
+    pub timestamp: u64,
+    /// Spare field, used by Windows for in-memory bookkeeping and usually zero on disk.
+    pub spare: u32,
+}
+
+/// A single Hive Bin, the unit of allocation for the cells of a hive.
+///
+/// On-Disk Signature: `hbin`
+#[derive(Clone)]
+pub struct HiveBin<'h, B: SplitByteSlice> {
+    hive: &'h Hive<B>,
+    header_range: Range<usize>,
+}
+
+impl<'h, B> HiveBin<'h, B>
+where
+    B: SplitByteSlice,
+{
+    pub(crate) fn new(hive: &'h Hive<B>, data_offset: u32) -> Result<Self> {
+        let data_offset = data_offset as usize;
+
+        let remaining_range = hive.remaining_raw_data_range(data_offset)?;
+        let header_range = byte_subrange(&remaining_range, mem::size_of::<HiveBinHeader>())
+            .ok_or_else(|| NtHiveError::InvalidHeaderSize {
+                offset: hive.offset_of_data_offset(data_offset),
+                expected: mem::size_of::<HiveBinHeader>(),
+                actual: remaining_range.len(),
+            })?;
+
+        let hive_bin = Self { hive, header_range };
+        hive_bin.validate_signature()?;
+        hive_bin.validate_size()?;
+
+        Ok(hive_bin)
+    }
+
+    /// Returns an iterator over all cells of this Hive Bin, allocated and unallocated ones.
+    pub fn cells(&self) -> HiveBinCells<'h, B> {
+        let bin_size = self.header().size.get() as usize;
+        let cells_range = self.header_range.end..self.header_range.start + bin_size;
+
+        HiveBinCells {
+            hive: self.hive,
+            cells_range,
+        }
+    }
+
+    fn header(&self) -> Ref<&[u8], HiveBinHeader> {
+        Ref::from_bytes(self.hive.data_slice(self.header_range.clone())).unwrap()
+    }
+
+    /// Returns the data offset of this Hive Bin, i.e. its actual position relative to the start of the hive data.
+    ///
+    /// This is the offset the bin has been found at and not the `offset` field of its header.
+    /// Compare it with [`HiveBinHeaderInfo::offset`] to detect inconsistent bins.
+    pub fn offset(&self) -> u32 {
+        self.header_range.start as u32
+    }
+
+    /// Returns the size of this Hive Bin in bytes, including its header.
+    ///
+    /// The size is guaranteed to be a non-zero multiple of 4096 bytes.
+    pub fn size(&self) -> u32 {
+        self.header().size.get()
+    }
+
+    /// Returns the timestamp of this Hive Bin as a raw Windows FILETIME (100-nanosecond intervals since 1601-01-01).
+    ///
+    /// Windows usually only sets it for the first bin of a hive and for bins appended later.
+    pub fn timestamp(&self) -> u64 {
+        self.header().timestamp.get()
+    }
+
+    /// Returns the raw fields of the header of this Hive Bin.
+    pub fn header_info(&self) -> HiveBinHeaderInfo {
+        let header = self.header();
+
+        HiveBinHeaderInfo {
+            signature: header.signature,
+            offset: header.offset.get(),
+            size: header.size.get(),
+            reserved: [header.reserved[0].get(), header.reserved[1].get()],
+            timestamp: header.timestamp.get(),
+            spare: header.spare.get(),
+        }
+    }
+
+    /// Validates that this Hive Bin doesn't extend beyond the given data size.
+    pub(crate) fn validate_end(&self, data_size: u32) -> Result<()> {
+        let header = self.header();
+        let size = header.size.get();
+        let available = data_size.saturating_sub(self.offset());
+
+        if size <= available {
+            Ok(())
+        } else {
+            Err(NtHiveError::InvalidSizeField {
+                offset: self.hive.offset_of_field(&header.size),
+                expected: size as usize,
+                actual: available as usize,
+            })
+        }
+    }
+
+    /// Validates that the `offset` field of the header matches the actual position of this Hive Bin.
+    pub(crate) fn validate_offset(&self) -> Result<()> {
+        let header = self.header();
+        let expected = self.offset();
+        let actual = header.offset.get();
+
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(NtHiveError::InvalidHiveBinOffset {
+                offset: self.hive.offset_of_field(&header.offset),
+                expected,
+                actual,
+            })
+        }
+    }
+
+    fn validate_signature(&self) -> Result<()> {
+        let header = self.header();
+        let signature = &header.signature;
+        let expected_signature = b"hbin";
+
+        if signature == expected_signature {
+            Ok(())
+        } else {
+            Err(NtHiveError::InvalidFourByteSignature {
+                offset: self.hive.offset_of_field(signature),
+                expected: expected_signature,
+                actual: *signature,
+            })
+        }
+    }
+
+    fn validate_size(&self) -> Result<()> {
+        let header = self.header();
+        let size = header.size.get() as usize;
+
+        // The size must be a non-zero multiple of 4096 bytes.
+        if size == 0 || size % HIVE_BIN_ALIGNMENT != 0 {
+            return Err(NtHiveError::InvalidSizeFieldAlignment {
+                offset: self.hive.offset_of_field(&header.size),
+                size,
+                expected_alignment: HIVE_BIN_ALIGNMENT,
+            });
+        }
+
+        // Does the size go beyond our hive data?
+        let remaining_range = self
+            .hive
+            .remaining_raw_data_range(self.header_range.start)?;
+        if byte_subrange(&remaining_range, size).is_none() {
+            return Err(NtHiveError::InvalidSizeField {
+                offset: self.hive.offset_of_field(&header.size),
+                expected: size,
+                actual: remaining_range.len(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Iterator over
+///   all Hive Bins of a hive,
+///   returning a [`HiveBin`] for each bin.
+///
+/// Every bin is validated before it is returned.
+/// Iteration stops after the first bin that fails validation or when the `CancelToken` of the hive
+/// has been cancelled (see `Hive::set_cancel_token`).
+///
+/// Created by [`Hive::bins`].
+pub struct HiveBins<'h, B: SplitByteSlice> {
+    hive: &'h Hive<B>,
+    bins_range: Range<usize>,
+}
+
+impl<'h, B> HiveBins<'h, B>
+where
+    B: SplitByteSlice,
+{
+    pub(crate) fn new(hive: &'h Hive<B>, data_size: usize) -> Self {
+        Self {
+            hive,
+            bins_range: 0..data_size,
+        }
+    }
+
+    fn next_bin(&self) -> Result<HiveBin<'h, B>> {
+        #[cfg(feature = "alloc")]
+        self.hive.check_cancelled()?;
+
+        HiveBin::new(self.hive, self.bins_range.start as u32)
+    }
+}
+
+impl<'h, B> Iterator for HiveBins<'h, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<HiveBin<'h, B>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bins_range.is_empty() {
+            return None;
+        }
+
+        let hive_bin = match self.next_bin() {
+            Ok(hive_bin) => hive_bin,
+            Err(e) => {
+                self.bins_range.start = self.bins_range.end;
+                return Some(Err(e));
+            }
+        };
+
+        let bin_size = hive_bin.header().size.get() as usize;
+        self.bins_range.start += bin_size;
+
+        Some(Ok(hive_bin))
+    }
+}
+
+impl<B> FusedIterator for HiveBins<'_, B> where B: SplitByteSlice {}
+
+/// A single cell returned by [`HiveBinCells`].
+///
+/// The cell is not interpreted in any way, so it may contain any structure or no valid data at all.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HiveBinCell {
+    /// Data offset of the cell header.
+    data_offset: usize,
+    /// Offset of the cell header relative to the very beginning of the hive bytes.
+    offset: usize,
+    /// Raw size of the cell. A negative size denotes an allocated cell.
+    size: i32,
+}
+
+impl HiveBinCell {
+    /// Returns the data offset of this cell (relative to the start of the hive data).
+    ///
+    /// This is the same kind of offset as returned by [`KeyNode::cell_offset`] and accepted by
+    /// [`Hive::key_node_from_offset`].
+    ///
+    /// [`KeyNode::cell_offset`]: crate::key_node::KeyNode::cell_offset
+    pub fn data_offset(&self) -> u32 {
+        self.data_offset as u32
+    }
+
+    /// Returns the offset of this cell relative to the very beginning of the hive bytes.
+    ///
+    /// This is the kind of offset reported by [`NtHiveError`], so it can be used to look up the cell
+    /// mentioned in an error message.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns `true` if this cell is allocated, and `false` if it is free space.
+    pub fn is_allocated(&self) -> bool {
+        self.size < 0
+    }
+
+    /// Returns the size of this cell in bytes, including its 4-byte header.
+    ///
+    /// The size is guaranteed to be a non-zero multiple of 8 bytes.
+    pub fn size(&self) -> u32 {
+        self.size.unsigned_abs()
+    }
+}
+
+/// Iterator over
+///   all cells of a Hive Bin,
+///   returning a [`HiveBinCell`] for each cell.
+///
+/// Iteration stops after the first cell with an invalid size.
+///
+/// Created by [`HiveBin::cells`].
+pub struct HiveBinCells<'h, B: SplitByteSlice> {
+    hive: &'h Hive<B>,
+    cells_range: Range<usize>,
+}
+
+impl<B> HiveBinCells<'_, B>
+where
+    B: SplitByteSlice,
+{
+    fn next_cell(&self) -> Result<HiveBinCell> {
+        let header_range = byte_subrange(&self.cells_range, mem::size_of::<CellHeader>())
+            .ok_or_else(|| NtHiveError::InvalidHeaderSize {
+                offset: self.hive.offset_of_data_offset(self.cells_range.start),
+                expected: mem::size_of::<CellHeader>(),
+                actual: self.cells_range.len(),
+            })?;
+        let size_field =
+            Ref::<&[u8], I32<LittleEndian>>::from_bytes(self.hive.data_slice(header_range))
+                .unwrap();
+        let size = size_field.get();
+        let cell_size = size.unsigned_abs() as usize;
+
+        // The cell size must be a non-zero multiple of 8 bytes.
+        let expected_alignment = 8;
+        if cell_size == 0 || cell_size % expected_alignment != 0 {
+            return Err(NtHiveError::InvalidSizeFieldAlignment {
+                offset: self.hive.offset_of_field(&*size_field),
+                size: cell_size,
+                expected_alignment,
+            });
+        }
+
+        // The cell must not go beyond the Hive Bin.
+        if byte_subrange(&self.cells_range, cell_size).is_none() {
+            return Err(NtHiveError::InvalidSizeField {
+                offset: self.hive.offset_of_field(&*size_field),
+                expected: cell_size,
+                actual: self.cells_range.len(),
+            });
+        }
+
+        Ok(HiveBinCell {
+            data_offset: self.cells_range.start,
+            offset: self.hive.offset_of_data_offset(self.cells_range.start),
+            size,
+        })
+    }
+}
+
+impl<B> Iterator for HiveBinCells<'_, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<HiveBinCell>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cells_range.is_empty() {
+            return None;
+        }
+
+        let cell = match self.next_cell() {
+            Ok(cell) => cell,
+            Err(e) => {
+                self.cells_range.start = self.cells_range.end;
+                return Some(Err(e));
+            }
+        };
+
+        self.cells_range.start += cell.size.unsigned_abs() as usize;
+        Some(Ok(cell))
+    }
+}
+
+impl<B> FusedIterator for HiveBinCells<'_, B> where B: SplitByteSlice {}
+
+/// Iterator over
+///   all cells of all Hive Bins of a hive,
+///   returning a [`HiveBinCell`] for each cell.
+///
+/// Cells are returned in file order, allocated and unallocated ones.
+/// Iteration stops after the first invalid Hive Bin or cell, so a corrupted size never leads to an endless loop.
+///
+/// Created by [`Hive::cells`].
+pub struct HiveCells<'h, B: SplitByteSlice> {
+    bins: HiveBins<'h, B>,
+    cells: Option<HiveBinCells<'h, B>>,
+    finished: bool,
+}
+
+impl<'h, B> HiveCells<'h, B>
+where
+    B: SplitByteSlice,
+{
+    pub(crate) fn new(bins: HiveBins<'h, B>) -> Self {
+        Self {
+            bins,
+            cells: None,
+            finished: false,
+        }
+    }
+}
+
+impl<B> Iterator for HiveCells<'_, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<HiveBinCell>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        loop {
+            if let Some(cell) = self.cells.as_mut().and_then(|cells| cells.next()) {
+                if cell.is_err() {
+                    self.finished = true;
+                }
+
+                return Some(cell);
+            }
+
+            match self.bins.next()? {
+                Ok(hive_bin) => self.cells = Some(hive_bin.cells()),
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl<B> FusedIterator for HiveCells<'_, B> where B: SplitByteSlice {}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_bins() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        let bins = hive.bins().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(bins[0].offset(), 0);
+        assert_eq!(bins[0].size(), 4096);
+        assert_eq!(bins[0].timestamp(), 0);
+        assert_eq!(bins[1].offset(), 0x1000);
+        assert_eq!(bins[1].size(), 16384);
+
+        // The bins are contiguous and cover the entire hive data.
+        let mut expected_offset = 0;
+        for hive_bin in &bins {
+            assert_eq!(hive_bin.offset(), expected_offset);
+            assert_eq!(hive_bin.header_info().offset, expected_offset);
+            expected_offset += hive_bin.size();
+        }
+        assert_eq!(expected_offset, 155648);
+
+        // The cells of a bin directly follow its 32-byte header and fill the entire bin.
+        let cells = bins[0].cells().collect::<Result<Vec<_>>>().unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(cells[0].data_offset(), root_key_node.cell_offset());
+        assert!(cells[0].is_allocated());
+        assert_eq!(cells[0].size(), 88);
+        assert_eq!(cells.iter().map(|cell| cell.size()).sum::<u32>(), 4096 - 32);
+        assert!(cells.iter().all(|cell| cell.size() % 8 == 0));
+
+        // Invalid bins are returned as errors and end the iteration.
+        testhive[4096 + 0x1000..4096 + 0x1004].copy_from_slice(b"xbin");
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let mut bins = hive.bins();
+        assert!(bins.next().unwrap().is_ok());
+        assert!(matches!(
+            bins.next(),
+            Some(Err(NtHiveError::InvalidFourByteSignature { offset, .. })) if offset == 4096 + 0x1000
+        ));
+        assert!(bins.next().is_none());
+
+        testhive[4096 + 0x1000..4096 + 0x1004].copy_from_slice(b"hbin");
+        testhive[4096 + 0x1008..4096 + 0x100c].copy_from_slice(&100u32.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let mut bins = hive.bins();
+        assert!(bins.next().unwrap().is_ok());
+        assert!(matches!(
+            bins.next(),
+            Some(Err(NtHiveError::InvalidSizeFieldAlignment {
+                size: 100,
+                expected_alignment: 4096,
+                ..
+            }))
+        ));
+        assert!(bins.next().is_none());
+    }
+
+    #[test]
+    fn test_cells() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        // The cells fill the payload of all bins, i.e. everything except for the 32-byte bin headers.
+        let cells = hive.cells().collect::<Result<Vec<_>>>().unwrap();
+        let bins = hive.bins().collect::<Result<Vec<_>>>().unwrap();
+        let payload_size = bins
+            .iter()
+            .map(|hive_bin| hive_bin.size() - 32)
+            .sum::<u32>();
+        assert_eq!(
+            cells.iter().map(|cell| cell.size()).sum::<u32>(),
+            payload_size
+        );
+
+        // Cells are returned in file order without gaps within a bin.
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(cells[0].data_offset(), root_key_node.cell_offset());
+        assert_eq!(cells[0].offset(), 4096 + 0x20);
+        assert!(cells
+            .windows(2)
+            .all(|pair| pair[0].offset() < pair[1].offset()));
+        assert!(cells.iter().any(|cell| !cell.is_allocated()));
+
+        // A zero cell size ends the iteration with an error instead of looping forever.
+        let second_cell = cells[1].offset();
+        testhive[second_cell..second_cell + 4].copy_from_slice(&0i32.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let mut cells = hive.cells();
+        assert!(cells.next().unwrap().is_ok());
+        assert!(matches!(
+            cells.next(),
+            Some(Err(NtHiveError::InvalidSizeFieldAlignment { offset, size: 0, .. })) if offset == second_cell
+        ));
+        assert!(cells.next().is_none());
+
+        // So does a cell going beyond the end of its bin.
+        testhive[second_cell..second_cell + 4].copy_from_slice(&(-8192i32).to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let mut cells = hive.cells();
+        assert!(cells.next().unwrap().is_ok());
+        assert!(matches!(
+            cells.next(),
+            Some(Err(NtHiveError::InvalidSizeField { offset, expected: 8192, .. })) if offset == second_cell
+        ));
+        assert!(cells.next().is_none());
+    }
+
+    #[test]
+    fn test_header_info() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        let header_info = hive.bin(0).unwrap().header_info();
+        assert_eq!(&header_info.signature, b"hbin");
+        assert_eq!(header_info.offset, 0);
+        assert_eq!(header_info.size, 4096);
+        assert_eq!(header_info.reserved, [0, 0]);
+        assert_eq!(header_info.timestamp, 0);
+        assert_eq!(header_info.spare, 0);
+
+        let header_info = hive.bin(0x1000).unwrap().header_info();
+        assert_eq!(header_info.offset, 0x1000);
+        assert_eq!(header_info.size, 16384);
+
+        // Offsets that are not at the beginning of a bin must be rejected.
+        assert!(matches!(
+            hive.bin(0x800),
+            Err(NtHiveError::InvalidFourByteSignature { .. })
+        ));
+        assert!(matches!(
+            hive.bin(0x2000),
+            Err(NtHiveError::InvalidFourByteSignature { .. })
+        ));
+
+        // A tampered offset field must be reported as-is.
+        testhive[4096 + 0x1000 + 4] = 0x42;
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let header_info = hive.bin(0x1000).unwrap().header_info();
+        assert_eq!(header_info.offset, 0x1042);
+    }
+}
@@ -6,24 +6,35 @@ use core::mem;
 use core::ops::{Deref, Range};
 
 use zerocopy::byteorder::LittleEndian;
-use zerocopy::{
-    FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, SplitByteSliceMut,
-    Unaligned, U32,
-};
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, Unaligned, U32};
 
 use crate::error::{NtHiveError, Result};
 use crate::helpers::byte_subrange;
 use crate::hive::Hive;
-use crate::key_node::{KeyNode, KeyNodeMut};
+use crate::key_node::KeyNode;
 use crate::leaf::LeafItemRanges;
 
+#[cfg(feature = "alloc")]
+use crate::dump::StructLayout;
+
+#[cfg(feature = "write")]
+use {
+    crate::key_node::KeyNodeMut, crate::subkeys_list::SubkeysListSnapshot,
+    zerocopy::SplitByteSliceMut,
+};
+
 /// On-Disk Structure of a single Index Root item.
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
+#[repr(C, packed)]
 struct IndexRootItem {
     subkeys_list_offset: U32<LittleEndian>,
 }
 
+#[cfg(feature = "alloc")]
+pub(crate) const INDEX_ROOT_ITEM_LAYOUT: StructLayout = struct_layout!(IndexRootItem {
+    subkeys_list_offset
+});
+
 /// Byte range of a single Index Root item returned by [`IndexRootItemRanges`].
 pub(crate) struct IndexRootItemRange(Range<usize>);
 
@@ -32,7 +43,8 @@ impl IndexRootItemRange {
     where
         B: SplitByteSlice,
     {
-        let item = Ref::<&[u8], IndexRootItem>::from_bytes(&hive.data[self.0.clone()]).unwrap();
+        let item =
+            Ref::<&[u8], IndexRootItem>::from_bytes(hive.data_slice(self.0.clone())).unwrap();
         item.subkeys_list_offset.get()
     }
 }
@@ -56,7 +68,11 @@ pub(crate) struct IndexRootItemRanges {
 }
 
 impl IndexRootItemRanges {
-    fn new(count: u16, count_field_offset: usize, data_range: Range<usize>) -> Result<Self> {
+    pub(crate) fn new(
+        count: u16,
+        count_field_offset: usize,
+        data_range: Range<usize>,
+    ) -> Result<Self> {
         let byte_count = count as usize * mem::size_of::<IndexRootItem>();
 
         let items_range = byte_subrange(&data_range, byte_count).ok_or_else(|| {
@@ -69,6 +85,18 @@ impl IndexRootItemRanges {
 
         Ok(Self { items_range })
     }
+
+    /// Returns the ranges of the items that follow the declared items up to the end of the data.
+    fn stale(self, data_range: &Range<usize>) -> Self {
+        Self {
+            items_range: self.items_range.end..data_range.end,
+        }
+    }
+
+    /// Returns the number of data bytes used by the declared items.
+    pub(crate) fn used_size(&self, data_range: &Range<usize>) -> usize {
+        self.items_range.end - data_range.start
+    }
 }
 
 impl Iterator for IndexRootItemRanges {
@@ -151,6 +179,25 @@ where
             leaf_item_ranges: None,
         })
     }
+
+    /// Like [`IndexRootKeyNodes::new`], but iterates the subkeys referenced by the stale items
+    /// following the declared `count` items.
+    pub(crate) fn new_stale(
+        hive: &'h Hive<B>,
+        count: u16,
+        count_field_offset: usize,
+        data_range: Range<usize>,
+    ) -> Result<Self> {
+        let index_root_item_ranges =
+            IndexRootItemRanges::new(count, count_field_offset, data_range.clone())?
+                .stale(&data_range);
+
+        Ok(Self {
+            hive,
+            index_root_item_ranges,
+            leaf_item_ranges: None,
+        })
+    }
 }
 
 impl<'h, B> Iterator for IndexRootKeyNodes<'h, B>
@@ -179,6 +226,32 @@ where
             self.leaf_item_ranges = Some(leaf_item_ranges);
         }
     }
+
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        loop {
+            if let Some(leaf_item_ranges) = self.leaf_item_ranges.as_mut() {
+                // Skip entire leaves without looking at their Key Nodes until we reach the leaf containing
+                // the n-th item.
+                let remaining = leaf_item_ranges.len();
+                if n < remaining {
+                    let leaf_item_range = leaf_item_ranges.nth(n)?;
+                    let key_node =
+                        iter_try!(KeyNode::from_leaf_item_range(self.hive, leaf_item_range));
+                    return Some(Ok(key_node));
+                }
+
+                n -= remaining;
+                self.leaf_item_ranges = None;
+            }
+
+            let index_root_item_range = self.index_root_item_ranges.next()?;
+            let leaf_item_ranges = iter_try!(LeafItemRanges::from_index_root_item_range(
+                self.hive,
+                index_root_item_range
+            ));
+            self.leaf_item_ranges = Some(leaf_item_ranges);
+        }
+    }
 }
 
 impl<B> FusedIterator for IndexRootKeyNodes<'_, B> where B: SplitByteSlice {}
@@ -191,12 +264,43 @@ impl<B> FusedIterator for IndexRootKeyNodes<'_, B> where B: SplitByteSlice {}
 /// On-Disk Signature: `ri`
 ///
 /// [`SubKeyNodesMut`]: crate::subkeys_list::SubKeyNodesMut
+#[cfg(feature = "write")]
 pub(crate) struct IndexRootKeyNodesMut<'h, B: SplitByteSliceMut> {
     hive: &'h mut Hive<B>,
     index_root_item_ranges: IndexRootItemRanges,
-    leaf_item_ranges: Option<LeafItemRanges>,
+    leaf: Option<IndexRootLeafMut>,
+    snapshot: SubkeysListSnapshot,
 }
 
+/// The Leaf currently iterated by [`IndexRootKeyNodesMut`].
+///
+/// Along with the Index Root header, the Index Root item referencing the Leaf and the Leaf header
+/// are checked before every step.
+#[cfg(feature = "write")]
+struct IndexRootLeafMut {
+    index_root_item_range: IndexRootItemRange,
+    subkeys_list_offset: u32,
+    leaf_item_ranges: LeafItemRanges,
+    snapshot: SubkeysListSnapshot,
+}
+
+#[cfg(feature = "write")]
+impl IndexRootLeafMut {
+    fn verify<B>(&self, hive: &Hive<B>) -> Result<()>
+    where
+        B: SplitByteSlice,
+    {
+        if self.index_root_item_range.subkeys_list_offset(hive) != self.subkeys_list_offset {
+            return Err(NtHiveError::ConcurrentStructuralModification {
+                offset: hive.offset_of_data_offset(self.index_root_item_range.start),
+            });
+        }
+
+        self.snapshot.verify(hive)
+    }
+}
+
+#[cfg(feature = "write")]
 impl<'h, B> IndexRootKeyNodesMut<'h, B>
 where
     B: SplitByteSliceMut,
@@ -206,6 +310,7 @@ where
         count: u16,
         count_field_offset: usize,
         data_range: Range<usize>,
+        snapshot: SubkeysListSnapshot,
     ) -> Result<Self> {
         let index_root_item_ranges =
             IndexRootItemRanges::new(count, count_field_offset, data_range)?;
@@ -213,7 +318,8 @@ where
         Ok(Self {
             hive,
             index_root_item_ranges,
-            leaf_item_ranges: None,
+            leaf: None,
+            snapshot,
         })
     }
 
@@ -221,23 +327,99 @@ where
     where
         'h: 'a,
     {
+        iter_try!(self.snapshot.verify(self.hive));
+
         loop {
-            if let Some(leaf_item_ranges) = self.leaf_item_ranges.as_mut() {
-                if let Some(leaf_item_range) = leaf_item_ranges.next() {
+            if let Some(leaf) = self.leaf.as_mut() {
+                iter_try!(leaf.verify(self.hive));
+
+                if let Some(leaf_item_range) = leaf.leaf_item_ranges.next() {
                     let key_node =
                         iter_try!(KeyNodeMut::from_leaf_item_range(self.hive, leaf_item_range));
                     return Some(Ok(key_node));
                 }
             }
 
-            // No leaf_item_ranges or the last one has been fully iterated.
-            // So get the next Index Root item and build leaf_item_ranges out of that.
+            // No Leaf or the last one has been fully iterated.
+            // So get the next Index Root item and build the Leaf out of that.
             let index_root_item_range = self.index_root_item_ranges.next()?;
-            let leaf_item_ranges = iter_try!(LeafItemRanges::from_index_root_item_range(
-                self.hive,
-                index_root_item_range
+            let subkeys_list_offset = index_root_item_range.subkeys_list_offset(self.hive);
+            let (leaf_item_ranges, snapshot) =
+                iter_try!(LeafItemRanges::from_index_root_item_range_with_snapshot(
+                    self.hive,
+                    &index_root_item_range
+                ));
+            self.leaf = Some(IndexRootLeafMut {
+                index_root_item_range,
+                subkeys_list_offset,
+                leaf_item_ranges,
+                snapshot,
+            });
+        }
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use crate::helpers::tests::{cell_data, key_node_field, read_u32};
+    use crate::hive::HIVE_BASE_BLOCK_SIZE;
+    use crate::key_node::{KeyNodeField, KeyNodeMut};
+    use crate::subkeys_list::SubKeyNodesMut;
+    use crate::*;
+
+    #[test]
+    fn test_concurrent_structural_modification() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("subkey-test").unwrap().unwrap();
+        let cell_range = hive
+            .cell_range_from_data_offset(key_node.cell_offset())
+            .unwrap();
+
+        // "subkey-test" has an Index Root, whose first item references the first Leaf.
+        let index_root = cell_data(read_u32(
+            &testhive,
+            key_node_field(key_node.cell_offset(), KeyNodeField::SubkeysListOffset),
+        ));
+        assert_eq!(&testhive[index_root..index_root + 2], b"ri");
+        let first_leaf = cell_data(read_u32(&testhive, index_root + 4));
+        let second_leaf = read_u32(&testhive, index_root + 8);
+
+        for modify_leaf in [true, false] {
+            let mut testhive = testhive.clone();
+            let mut hive = Hive::new(testhive.as_mut()).unwrap();
+            let mut key_node = KeyNodeMut::from_cell_range(&mut hive, cell_range.clone()).unwrap();
+            let SubKeyNodesMut::IndexRoot(mut iter) = key_node.subkeys_mut().unwrap().unwrap()
+            else {
+                panic!("\"subkey-test\" should have an Index Root as its Subkeys List");
+            };
+            assert!(iter.next().unwrap().is_ok());
+
+            // Shrink the Leaf or let the Index Root item reference another Leaf while iterating over it.
+            // The Index Root header is left untouched.
+            let expected_offset = if modify_leaf {
+                iter.hive
+                    .data_slice_mut(
+                        first_leaf - HIVE_BASE_BLOCK_SIZE + 2
+                            ..first_leaf - HIVE_BASE_BLOCK_SIZE + 4,
+                    )
+                    .copy_from_slice(&1u16.to_le_bytes());
+                first_leaf
+            } else {
+                iter.hive
+                    .data_slice_mut(
+                        index_root - HIVE_BASE_BLOCK_SIZE + 4
+                            ..index_root - HIVE_BASE_BLOCK_SIZE + 8,
+                    )
+                    .copy_from_slice(&second_leaf.to_le_bytes());
+                index_root + 4
+            };
+            assert!(matches!(
+                iter.next(),
+                Some(Err(NtHiveError::ConcurrentStructuralModification { offset }))
+                    if offset == expected_offset
             ));
-            self.leaf_item_ranges = Some(leaf_item_ranges);
         }
     }
 }
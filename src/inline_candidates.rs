@@ -0,0 +1,197 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+use zerocopy::SplitByteSlice;
+
+use crate::error::{NtHiveError, Result};
+use crate::hive::{CellHeader, Hive};
+use crate::key_node::KeyNode;
+use crate::key_value::KeyValue;
+use crate::walker::KeyNodeWalker;
+
+/// Maximum data size that fits into the `data_offset` field of a Key Value.
+const MAX_INLINE_DATA_SIZE: u32 = mem::size_of::<u32>() as u32;
+
+/// A Key Value whose data is stored in a separate cell, although it would fit into the Key Value itself.
+///
+/// Returned as part of an [`InlineCandidatesReport`] by [`Hive::inline_candidates`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InlineCandidate {
+    /// Path of the key containing the Key Value (without the root key name).
+    pub key_path: String,
+    /// Name of the Key Value.
+    pub value_name: String,
+    /// Size of the data of the Key Value.
+    pub data_size: u32,
+    /// Data offset of the cell holding the data.
+    pub data_cell_offset: u32,
+    /// Total size of the cell holding the data, including its header.
+    pub data_cell_size: u32,
+    /// Estimated number of bytes that could be saved by inlining the data (cell size minus 4).
+    pub wasted_bytes: u32,
+}
+
+/// Result of [`Hive::inline_candidates`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InlineCandidatesReport {
+    /// All Key Values whose data could be inlined, in depth-first order of their keys.
+    pub candidates: Vec<InlineCandidate>,
+    /// Sum of [`InlineCandidate::wasted_bytes`] over all candidates.
+    pub total_wasted_bytes: u64,
+    /// Errors of Key Values (or Key Values Lists) that could not be checked.
+    /// They don't stop the search for further candidates.
+    pub errors: Vec<NtHiveError>,
+}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Finds all Key Values whose data is at most 4 bytes long, but stored in a separate cell.
+    ///
+    /// Windows stores such small data directly in the `data_offset` field of the Key Value.
+    /// Some tools don't, wasting an entire cell for each of these Key Values.
+    /// The returned report lists every such Key Value along with an estimate of the wasted bytes.
+    /// Key Values that cannot be checked are recorded in [`InlineCandidatesReport::errors`], while
+    /// an error is only returned if the keys themselves cannot be traversed.
+    pub fn inline_candidates(&self) -> Result<InlineCandidatesReport> {
+        let mut report = InlineCandidatesReport::default();
+
+        let root_key_node = self.root_key_node()?;
+        self.add_inline_candidates(&root_key_node, String::new(), &mut report)?;
+
+        let mut walker = KeyNodeWalker::new(&root_key_node)?;
+        while let Some(key_node) = walker.next() {
+            let key_node = key_node?;
+            self.add_inline_candidates(&key_node, walker.path_string(), &mut report)?;
+        }
+
+        Ok(report)
+    }
+
+    fn add_inline_candidates(
+        &self,
+        key_node: &KeyNode<B>,
+        key_path: String,
+        report: &mut InlineCandidatesReport,
+    ) -> Result<()> {
+        let key_values = match key_node.values() {
+            Some(Ok(key_values)) => key_values,
+            Some(Err(e)) => {
+                report.errors.push(e);
+                return Ok(());
+            }
+            None => return Ok(()),
+        };
+
+        for key_value in key_values {
+            match key_value.and_then(|key_value| self.inline_candidate(&key_value, &key_path)) {
+                Ok(Some(candidate)) => {
+                    report.total_wasted_bytes += u64::from(candidate.wasted_bytes);
+                    report.candidates.push(candidate);
+                }
+                Ok(None) => {}
+                Err(e) => report.errors.push(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn inline_candidate(
+        &self,
+        key_value: &KeyValue<B>,
+        key_path: &str,
+    ) -> Result<Option<InlineCandidate>> {
+        let data_size = key_value.data_size();
+        if data_size > MAX_INLINE_DATA_SIZE {
+            return Ok(None);
+        }
+
+        // Empty data is often not stored in any cell at all.
+        let Some(data_cell_offset) = key_value
+            .data_cell_offset()
+            .filter(|data_cell_offset| *data_cell_offset != u32::MAX)
+        else {
+            return Ok(None);
+        };
+
+        let cell_range = self.cell_range_from_data_offset(data_cell_offset)?;
+        let data_cell_size = (cell_range.len() + mem::size_of::<CellHeader>()) as u32;
+        let wasted_bytes = data_cell_size - MAX_INLINE_DATA_SIZE;
+
+        Ok(Some(InlineCandidate {
+            key_path: String::from(key_path),
+            value_name: key_value.name_lossy()?,
+            data_size,
+            data_cell_offset,
+            data_cell_size,
+            wasted_bytes,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::cell_data;
+    use crate::*;
+
+    #[test]
+    fn test_inline_candidates() {
+        use crate::helpers::tests::write_u32;
+
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        // offreg-testhive-writer inlines all small data.
+        let report = hive.inline_candidates().unwrap();
+        assert!(report.candidates.is_empty());
+        assert_eq!(report.total_wasted_bytes, 0);
+
+        // Craft a 2-byte value stored in a cell by shrinking the data size of "reg-sz".
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = |name: &str| key_node.value(name).unwrap().unwrap();
+        let data_cell_offset = key_value("reg-sz").data_cell_offset().unwrap();
+        let [reg_sz, qword, binary] =
+            ["reg-sz", "qword", "binary"].map(|name| cell_data(key_value(name).cell_offset()));
+        write_u32(&mut testhive, reg_sz + 4, 2);
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let report = hive.inline_candidates().unwrap();
+        assert_eq!(
+            report.candidates,
+            [InlineCandidate {
+                key_path: "data-test".to_owned(),
+                value_name: "reg-sz".to_owned(),
+                data_size: 2,
+                data_cell_offset,
+                data_cell_size: 24,
+                wasted_bytes: 20,
+            }]
+        );
+        assert_eq!(report.total_wasted_bytes, 20);
+        assert!(report.errors.is_empty());
+
+        // An empty value without a data cell is no candidate, and a value with a broken data offset
+        // is recorded without aborting the report.
+        write_u32(&mut testhive, qword + 4, 0);
+        write_u32(&mut testhive, qword + 8, u32::MAX);
+        write_u32(&mut testhive, binary + 4, 3);
+        write_u32(&mut testhive, binary + 8, 0x7fff_fff0);
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let report = hive.inline_candidates().unwrap();
+        assert_eq!(report.candidates.len(), 1);
+        assert_eq!(report.candidates[0].value_name, "reg-sz");
+        assert_eq!(report.errors.len(), 1);
+        assert!(matches!(
+            report.errors[0],
+            NtHiveError::InvalidHeaderSize { .. }
+        ));
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: GPL-2.0-or-later
 
 use core::cmp::Ordering;
+use core::fmt;
 use core::mem;
 use core::ops::Range;
 use core::ptr;
@@ -9,22 +10,51 @@ use core::ptr;
 use bitflags::bitflags;
 use zerocopy::byteorder::LittleEndian;
 use zerocopy::{
-    FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, SplitByteSliceMut,
-    Unaligned, U16, U32, U64,
+    FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, Unaligned, U16, U32, U64,
 };
 
 use crate::error::{NtHiveError, Result};
 use crate::helpers::byte_subrange;
-use crate::hive::Hive;
+use crate::hive::{CellHeader, Hive, HiveFingerprint};
 use crate::index_root::IndexRootItemRanges;
+use crate::key_security::KeySecurity;
 use crate::key_value::KeyValue;
-use crate::key_values_list::KeyValues;
-use crate::leaf::{LeafItemRange, LeafItemRanges};
-use crate::string::NtHiveNameString;
-use crate::subkeys_list::{SubKeyNodes, SubKeyNodesMut};
+use crate::key_values_list::{key_values_list_capacity, KeyValues};
+use crate::leaf::{LeafItemRange, LeafItemRanges, LeafType};
+use crate::list_capacity::ListCapacity;
+use crate::security_descriptor::SecurityDescriptor;
+use crate::string::{NameHash, NtHiveNameString};
+use crate::subkeys_list::{SubKeyNodes, SubKeyNodesWithPrefix, SubkeysList};
+
+#[cfg(feature = "alloc")]
+use {
+    crate::dump::StructLayout,
+    crate::walker::{join_path, MAX_KEY_DEPTH},
+    alloc::{string::String, vec::Vec},
+};
+
+#[cfg(feature = "write")]
+use {crate::subkeys_list::SubKeyNodesMut, zerocopy::SplitByteSliceMut};
+
+#[cfg(feature = "time")]
+use {crate::filetime::filetime_to_offset_date_time, time::OffsetDateTime};
+
+/// Name of the Key Value storing the target of a symbolic link.
+#[cfg(feature = "alloc")]
+const SYMBOLIC_LINK_VALUE_NAME: &str = "SymbolicLinkValue";
+
+/// Maximum number of chained symbolic links we follow before giving up.
+/// This protects against symbolic links pointing to each other.
+#[cfg(feature = "alloc")]
+const MAX_SYMBOLIC_LINK_DEPTH: usize = 16;
 
 bitflags! {
-    struct KeyNodeFlags: u16 {
+    /// Flags of a [`KeyNode`], returned by [`KeyNode::flags`].
+    ///
+    /// Only the named flags are part of the public interface.
+    /// Unknown bits stored in the hive are ignored.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct KeyNodeFlags: u16 {
         /// This is a volatile key (not stored on disk).
         const KEY_IS_VOLATILE = 0x0001;
         /// This is the mount point of another hive (not stored on disk).
@@ -48,10 +78,13 @@ bitflags! {
     }
 }
 
-/// On-Disk Structure of a Key Node header.
+/// On-Disk Structure of the fields at the beginning of every Key Node header.
+///
+/// In hives of Windows NT 3.x (minor versions 0 to 2), this is directly followed by a [`KeyNodeNameLengths`].
+/// All later versions have a [`KeyNodeStatistics`] in between.
 #[allow(dead_code)]
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
+#[repr(C, packed)]
 struct KeyNodeHeader {
     signature: [u8; 2],
     flags: U16<LittleEndian>,
@@ -66,20 +99,214 @@ struct KeyNodeHeader {
     key_values_list_offset: U32<LittleEndian>,
     key_security_offset: U32<LittleEndian>,
     class_name_offset: U32<LittleEndian>,
+}
+
+/// On-Disk Structure of the statistics fields of a Key Node header, which are missing in hives of Windows NT 3.x.
+#[allow(dead_code)]
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct KeyNodeStatistics {
     max_subkey_name: U32<LittleEndian>,
     max_subkey_class_name: U32<LittleEndian>,
     max_value_name: U32<LittleEndian>,
     max_value_data: U32<LittleEndian>,
     work_var: U32<LittleEndian>,
+}
+
+/// On-Disk Structure of the name length fields at the end of every Key Node header.
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct KeyNodeNameLengths {
     key_name_length: U16<LittleEndian>,
     class_name_length: U16<LittleEndian>,
 }
 
+/// Returns the total size of a Key Node header, depending on whether the hive is from Windows NT 3.x.
+pub(crate) const fn key_node_header_size(legacy: bool) -> usize {
+    let statistics_size = if legacy {
+        0
+    } else {
+        mem::size_of::<KeyNodeStatistics>()
+    };
+
+    mem::size_of::<KeyNodeHeader>() + statistics_size + mem::size_of::<KeyNodeNameLengths>()
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) const KEY_NODE_HEADER_LAYOUT: StructLayout = struct_layout!(KeyNodeHeader {
+    signature,
+    flags,
+    timestamp,
+    spare,
+    parent,
+    subkey_count,
+    volatile_subkey_count,
+    subkeys_list_offset,
+    volatile_subkeys_list_offset,
+    key_values_count,
+    key_values_list_offset,
+    key_security_offset,
+    class_name_offset,
+});
+
+#[cfg(feature = "alloc")]
+pub(crate) const KEY_NODE_STATISTICS_LAYOUT: StructLayout = struct_layout!(KeyNodeStatistics {
+    max_subkey_name,
+    max_subkey_class_name,
+    max_value_name,
+    max_value_data,
+    work_var,
+});
+
+#[cfg(feature = "alloc")]
+pub(crate) const KEY_NODE_NAME_LENGTHS_LAYOUT: StructLayout = struct_layout!(KeyNodeNameLengths {
+    key_name_length,
+    class_name_length,
+});
+
+/// Fields of a Key Node header, for tests that need to craft hives with modified Key Nodes.
+#[cfg(test)]
+#[allow(dead_code)]
+#[derive(Clone, Copy)]
+pub(crate) enum KeyNodeField {
+    Signature,
+    Flags,
+    Timestamp,
+    Parent,
+    SubkeyCount,
+    VolatileSubkeyCount,
+    SubkeysListOffset,
+    VolatileSubkeysListOffset,
+    KeyValuesCount,
+    KeyValuesListOffset,
+    KeySecurityOffset,
+    ClassNameOffset,
+    KeyNameLength,
+    ClassNameLength,
+    /// The key name directly following the header.
+    KeyName,
+}
+
+#[cfg(test)]
+impl KeyNodeField {
+    /// Returns the offset of this field relative to the start of a Key Node header,
+    /// depending on whether the hive is from Windows NT 3.x.
+    pub(crate) const fn offset(self, legacy: bool) -> usize {
+        let name_lengths = key_node_header_size(legacy) - mem::size_of::<KeyNodeNameLengths>();
+
+        match self {
+            Self::Signature => mem::offset_of!(KeyNodeHeader, signature),
+            Self::Flags => mem::offset_of!(KeyNodeHeader, flags),
+            Self::Timestamp => mem::offset_of!(KeyNodeHeader, timestamp),
+            Self::Parent => mem::offset_of!(KeyNodeHeader, parent),
+            Self::SubkeyCount => mem::offset_of!(KeyNodeHeader, subkey_count),
+            Self::VolatileSubkeyCount => mem::offset_of!(KeyNodeHeader, volatile_subkey_count),
+            Self::SubkeysListOffset => mem::offset_of!(KeyNodeHeader, subkeys_list_offset),
+            Self::VolatileSubkeysListOffset => {
+                mem::offset_of!(KeyNodeHeader, volatile_subkeys_list_offset)
+            }
+            Self::KeyValuesCount => mem::offset_of!(KeyNodeHeader, key_values_count),
+            Self::KeyValuesListOffset => mem::offset_of!(KeyNodeHeader, key_values_list_offset),
+            Self::KeySecurityOffset => mem::offset_of!(KeyNodeHeader, key_security_offset),
+            Self::ClassNameOffset => mem::offset_of!(KeyNodeHeader, class_name_offset),
+            Self::KeyNameLength => {
+                name_lengths + mem::offset_of!(KeyNodeNameLengths, key_name_length)
+            }
+            Self::ClassNameLength => {
+                name_lengths + mem::offset_of!(KeyNodeNameLengths, class_name_length)
+            }
+            Self::KeyName => key_node_header_size(legacy),
+        }
+    }
+}
+
+/// This bit in a cell index indicates that the cell is located in volatile storage.
+const VOLATILE_CELL_INDEX: u32 = 0x8000_0000;
+
+/// Direction for [`KeyNode::next_sibling`] and [`KeyNode::prev_sibling`].
+#[derive(Clone, Copy)]
+enum SiblingDirection {
+    Next,
+    Previous,
+}
+
+impl SiblingDirection {
+    fn step(self, index: usize) -> Option<usize> {
+        match self {
+            Self::Next => index.checked_add(1),
+            Self::Previous => index.checked_sub(1),
+        }
+    }
+}
+
+/// Position of a subkey in the Subkeys List of its parent, as determined by binary search.
+struct SubkeyPosition {
+    /// All Index Root items and the index of the one containing the subkey,
+    /// if the Subkeys List is an Index Root.
+    index_root: Option<(IndexRootItemRanges, usize)>,
+    /// All items of the Leaf containing the subkey.
+    leaf_item_ranges: LeafItemRanges,
+    /// Index of the subkey in `leaf_item_ranges`.
+    leaf_index: usize,
+}
+
+impl SubkeyPosition {
+    /// Returns the position of the adjacent subkey in the given direction,
+    /// continuing with the adjacent Index Root item (if any) at the end of a Leaf.
+    fn step<B>(&self, hive: &Hive<B>, direction: SiblingDirection) -> Option<Result<Self>>
+    where
+        B: SplitByteSlice,
+    {
+        // Try the adjacent item in the same Leaf first.
+        if let Some(leaf_index) = direction
+            .step(self.leaf_index)
+            .filter(|&leaf_index| leaf_index < self.leaf_item_ranges.len())
+        {
+            return Some(Ok(Self {
+                index_root: self.index_root.clone(),
+                leaf_item_ranges: self.leaf_item_ranges.clone(),
+                leaf_index,
+            }));
+        }
+
+        // Otherwise, continue with the adjacent Index Root item (if any).
+        let (index_root_item_ranges, index_root_index) = self.index_root.as_ref()?;
+        let index_root_index = direction.step(*index_root_index)?;
+        let index_root_item_range = index_root_item_ranges.clone().nth(index_root_index)?;
+        let leaf_item_ranges = iter_try!(LeafItemRanges::from_index_root_item_range(
+            hive,
+            index_root_item_range
+        ));
+
+        // `from_index_root_item_range` guarantees that the Leaf has at least one item.
+        let leaf_index = match direction {
+            SiblingDirection::Next => 0,
+            SiblingDirection::Previous => leaf_item_ranges.len() - 1,
+        };
+
+        Some(Ok(Self {
+            index_root: Some((index_root_item_ranges.clone(), index_root_index)),
+            leaf_item_ranges,
+            leaf_index,
+        }))
+    }
+
+    fn key_node_item_range<B>(&self, hive: &Hive<B>) -> Result<KeyNodeItemRange>
+    where
+        B: SplitByteSlice,
+    {
+        let leaf_item_range = self.leaf_item_ranges.clone().nth(self.leaf_index).unwrap();
+        KeyNodeItemRange::from_leaf_item_range(hive, leaf_item_range)
+    }
+}
+
 /// Byte range of a single Key Node item.
 #[derive(Clone, Eq, PartialEq)]
 struct KeyNodeItemRange {
     header_range: Range<usize>,
     data_range: Range<usize>,
+    /// Whether the header has the shorter layout of Windows NT 3.x hives (see [`KeyNodeStatistics`]).
+    legacy: bool,
 }
 
 impl KeyNodeItemRange {
@@ -87,19 +314,21 @@ impl KeyNodeItemRange {
     where
         B: SplitByteSlice,
     {
-        let header_range =
-            byte_subrange(&cell_range, mem::size_of::<KeyNodeHeader>()).ok_or_else(|| {
-                NtHiveError::InvalidHeaderSize {
-                    offset: hive.offset_of_data_offset(cell_range.start),
-                    expected: mem::size_of::<KeyNodeHeader>(),
-                    actual: cell_range.len(),
-                }
-            })?;
+        let legacy = hive.is_legacy_version();
+        let header_size = key_node_header_size(legacy);
+        let header_range = byte_subrange(&cell_range, header_size).ok_or_else(|| {
+            NtHiveError::InvalidHeaderSize {
+                offset: hive.offset_of_data_offset(cell_range.start),
+                expected: header_size,
+                actual: cell_range.len(),
+            }
+        })?;
         let data_range = header_range.end..cell_range.end;
 
         let key_node_item_range = Self {
             header_range,
             data_range,
+            legacy,
         };
         key_node_item_range.validate_signature(hive)?;
 
@@ -111,19 +340,24 @@ impl KeyNodeItemRange {
         B: SplitByteSlice,
     {
         let key_node_offset = leaf_item_range.key_node_offset(hive);
-        let cell_range = hive.cell_range_from_data_offset(key_node_offset)?;
+        let cell_range = hive.cell_range_from_offset_field(
+            key_node_offset,
+            hive.offset_of_data_offset(leaf_item_range.start),
+        )?;
         let key_node = Self::from_cell_range(hive, cell_range)?;
         Ok(key_node)
     }
 
-    fn binary_search_subkey_in_index_root<B>(
+    fn binary_search_subkey_in_index_root<'h, B, N>(
         &self,
-        hive: &Hive<B>,
-        name: &str,
+        hive: &'h Hive<B>,
+        name: &N,
         index_root_item_ranges: IndexRootItemRanges,
-    ) -> Option<Result<Self>>
+    ) -> Option<Result<(SubkeyPosition, Self)>>
     where
         B: SplitByteSlice,
+        N: NameHash + ?Sized,
+        NtHiveNameString<'h>: PartialOrd<N>,
     {
         // The following textbook binary search algorithm requires signed math.
         // Fortunately, Index Roots have a u16 `count` field, hence we should be able to convert to i32.
@@ -142,13 +376,19 @@ impl KeyNodeItemRange {
                 index_root_item_range
             ));
 
+            let position = |leaf_index| SubkeyPosition {
+                index_root: Some((index_root_item_ranges.clone(), mid as usize)),
+                leaf_item_ranges: leaf_item_ranges.clone(),
+                leaf_index,
+            };
+
             // Check the name of the FIRST Key Node of the selected Index Root item.
             let leaf_item_range = leaf_item_ranges.clone().next().unwrap();
             let key_node_item_range = iter_try!(Self::from_leaf_item_range(hive, leaf_item_range));
             let key_node_name = iter_try!(key_node_item_range.name(hive));
 
             match key_node_name.partial_cmp(name).unwrap() {
-                Ordering::Equal => return Some(Ok(key_node_item_range)),
+                Ordering::Equal => return Some(Ok((position(0), key_node_item_range))),
                 Ordering::Less => (),
                 Ordering::Greater => {
                     // The FIRST Key Node of the selected Index Root item has a name that comes
@@ -165,7 +405,12 @@ impl KeyNodeItemRange {
             let key_node_name = iter_try!(key_node_item_range.name(hive));
 
             match key_node_name.partial_cmp(name).unwrap() {
-                Ordering::Equal => return Some(Ok(key_node_item_range)),
+                Ordering::Equal => {
+                    return Some(Ok((
+                        position(leaf_item_ranges.len() - 1),
+                        key_node_item_range,
+                    )))
+                }
                 Ordering::Less => {
                     // The LAST Key Node of the selected Index Root item has a name that comes
                     // BEFORE the name we are looking for.
@@ -177,21 +422,41 @@ impl KeyNodeItemRange {
             }
 
             // If the searched Key Node exists at all, it must be in this Leaf.
-            return self.binary_search_subkey_in_leaf(hive, name, leaf_item_ranges);
+            let (mut position, key_node_item_range) =
+                iter_try!(self.binary_search_subkey_in_leaf(hive, name, leaf_item_ranges)?);
+            position.index_root = Some((index_root_item_ranges, mid as usize));
+            return Some(Ok((position, key_node_item_range)));
         }
 
         None
     }
 
-    fn binary_search_subkey_in_leaf<B>(
+    fn binary_search_subkey_in_leaf<'h, B, N>(
         &self,
-        hive: &Hive<B>,
-        name: &str,
+        hive: &'h Hive<B>,
+        name: &N,
         leaf_item_ranges: LeafItemRanges,
-    ) -> Option<Result<Self>>
+    ) -> Option<Result<(SubkeyPosition, Self)>>
     where
         B: SplitByteSlice,
+        N: NameHash + ?Sized,
+        NtHiveNameString<'h>: PartialOrd<N>,
     {
+        if let LeafType::Hash = leaf_item_ranges.leaf_type() {
+            if let Some(result) =
+                self.hash_search_subkey_in_leaf(hive, name, leaf_item_ranges.clone())
+            {
+                return Some(result);
+            }
+
+            // No stored hash matched. Usually, the subkey just doesn't exist, but the stored hash may
+            // also be stale or zero (e.g. in hives written by other tools).
+            // Hence, confirm the miss with the binary search by name.
+            // This makes a miss in a Hash Leaf more expensive than a plain binary search: All hashes have
+            // been compared in vain before decoding the names of up to log2(n) Key Nodes.
+            // Hits remain cheap, and we accept the extra cost of misses to never miss a subkey with a bad hash.
+        }
+
         // The following textbook binary search algorithm requires signed math.
         // Fortunately, Leafs have a u16 `count` field, hence we should be able to convert to i32.
         assert!(leaf_item_ranges.len() <= u16::MAX as usize);
@@ -208,7 +473,14 @@ impl KeyNodeItemRange {
 
             // Check if it's the name we are looking for, otherwise adjust the boundaries accordingly.
             match key_node_name.partial_cmp(name).unwrap() {
-                Ordering::Equal => return Some(Ok(key_node_item_range)),
+                Ordering::Equal => {
+                    let position = SubkeyPosition {
+                        index_root: None,
+                        leaf_item_ranges,
+                        leaf_index: mid as usize,
+                    };
+                    return Some(Ok((position, key_node_item_range)));
+                }
                 Ordering::Less => left = mid + 1,
                 Ordering::Greater => right = mid - 1,
             }
@@ -217,7 +489,51 @@ impl KeyNodeItemRange {
         None
     }
 
-    fn class_name<'h, B>(&self, hive: &'h Hive<B>) -> Option<Result<NtHiveNameString<'h>>>
+    fn hash_search_subkey_in_leaf<'h, B, N>(
+        &self,
+        hive: &'h Hive<B>,
+        name: &N,
+        leaf_item_ranges: LeafItemRanges,
+    ) -> Option<Result<(SubkeyPosition, Self)>>
+    where
+        B: SplitByteSlice,
+        N: NameHash + ?Sized,
+        NtHiveNameString<'h>: PartialOrd<N>,
+    {
+        // Comparing the hashes of all Hash Leaf items is cheaper than decoding and comparing
+        // the names of a few Key Nodes during a binary search.
+        // Only the names of Key Nodes with matching hashes need to be compared.
+        // This is just a fast path for hits: If no hash matches, the caller still performs a binary search,
+        // so misses cost this linear scan on top of it.
+        let name_hash = name.name_hash();
+
+        for (leaf_index, leaf_item_range) in leaf_item_ranges.clone().enumerate() {
+            if leaf_item_range.name_hash(hive) != name_hash {
+                continue;
+            }
+
+            let key_node_item_range = iter_try!(Self::from_leaf_item_range(hive, leaf_item_range));
+            let key_node_name = iter_try!(key_node_item_range.name(hive));
+
+            if key_node_name.partial_cmp(name) == Some(Ordering::Equal) {
+                let position = SubkeyPosition {
+                    index_root: None,
+                    leaf_item_ranges,
+                    leaf_index,
+                };
+                return Some(Ok((position, key_node_item_range)));
+            }
+        }
+
+        None
+    }
+
+    fn cell_offset(&self) -> u32 {
+        // Every Key Node header directly follows the header of the cell containing it.
+        (self.header_range.start - mem::size_of::<CellHeader>()) as u32
+    }
+
+    fn class_name_raw<'h, B>(&self, hive: &'h Hive<B>) -> Option<Result<&'h [u8]>>
     where
         B: SplitByteSlice,
     {
@@ -228,7 +544,8 @@ impl KeyNodeItemRange {
             return None;
         }
 
-        let class_name_length = header.class_name_length.get() as usize;
+        let name_lengths = self.name_lengths(hive);
+        let class_name_length = name_lengths.class_name_length.get() as usize;
         let class_name_offset_range =
             iter_try!(hive.cell_range_from_data_offset(class_name_offset));
 
@@ -237,45 +554,111 @@ impl KeyNodeItemRange {
             class_name_length
         )
         .ok_or_else(|| NtHiveError::InvalidSizeField {
-            offset: hive.offset_of_field(&header.class_name_length),
+            offset: hive.offset_of_field(&name_lengths.class_name_length),
             expected: class_name_length,
             actual: class_name_offset_range.len(),
         }));
-        let class_name_bytes = &hive.data[class_name_range];
 
-        Some(Ok(NtHiveNameString::Utf16LE(class_name_bytes)))
+        Some(Ok(hive.data_slice(class_name_range)))
     }
 
     fn header<'h, B>(&self, hive: &'h Hive<B>) -> Ref<&'h [u8], KeyNodeHeader>
     where
         B: SplitByteSlice,
     {
-        Ref::from_bytes(&hive.data[self.header_range.clone()]).unwrap()
+        Ref::from_bytes(hive.data_slice(self.header_fields_range())).unwrap()
+    }
+
+    /// Returns the byte range of the [`KeyNodeHeader`] at the beginning of the entire header.
+    fn header_fields_range(&self) -> Range<usize> {
+        self.header_range.start..self.header_range.start + mem::size_of::<KeyNodeHeader>()
     }
 
+    #[cfg(feature = "write")]
     fn header_mut<'h, B>(&self, hive: &'h mut Hive<B>) -> Ref<&'h mut [u8], KeyNodeHeader>
     where
         B: SplitByteSliceMut,
     {
-        Ref::from_bytes(&mut hive.data[self.header_range.clone()]).unwrap()
+        Ref::from_bytes(hive.data_slice_mut(self.header_fields_range())).unwrap()
     }
 
-    fn name<'h, B>(&self, hive: &'h Hive<B>) -> Result<NtHiveNameString<'h>>
+    fn flags<B>(&self, hive: &Hive<B>) -> KeyNodeFlags
     where
         B: SplitByteSlice,
     {
         let header = self.header(hive);
-        let flags = KeyNodeFlags::from_bits_truncate(header.flags.get());
-        let key_name_length = header.key_name_length.get() as usize;
+        KeyNodeFlags::from_bits_truncate(header.flags.get())
+    }
+
+    fn key_security<'h, B>(&self, hive: &'h Hive<B>) -> Option<Result<KeySecurity<'h, B>>>
+    where
+        B: SplitByteSlice,
+    {
+        let key_security_offset = self.security_offset(hive);
+        if key_security_offset == u32::MAX {
+            // This Key Node has no Key Security cell.
+            return None;
+        }
+
+        let cell_range = iter_try!(hive.cell_range_from_data_offset(key_security_offset));
+        Some(KeySecurity::from_cell_range(hive, cell_range))
+    }
+
+    fn max_subkey_class_name<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        self.statistics(hive)
+            .map_or(0, |statistics| statistics.max_subkey_class_name.get())
+    }
+
+    fn max_subkey_name<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        self.statistics(hive)
+            .map_or(0, |statistics| statistics.max_subkey_name.get())
+    }
+
+    fn max_value_data<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        self.statistics(hive)
+            .map_or(0, |statistics| statistics.max_value_data.get())
+    }
+
+    fn max_value_name<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        self.statistics(hive)
+            .map_or(0, |statistics| statistics.max_value_name.get())
+    }
+
+    fn key_name_range<B>(&self, hive: &Hive<B>) -> Result<Range<usize>>
+    where
+        B: SplitByteSlice,
+    {
+        let name_lengths = self.name_lengths(hive);
+        let key_name_length = name_lengths.key_name_length.get() as usize;
 
-        let key_name_range = byte_subrange(&self.data_range, key_name_length).ok_or_else(|| {
+        byte_subrange(&self.data_range, key_name_length).ok_or_else(|| {
             NtHiveError::InvalidSizeField {
-                offset: hive.offset_of_field(&header.key_name_length),
+                offset: hive.offset_of_field(&name_lengths.key_name_length),
                 expected: key_name_length,
                 actual: self.data_range.len(),
             }
-        })?;
-        let key_name_bytes = &hive.data[key_name_range];
+        })
+    }
+
+    fn name<'h, B>(&self, hive: &'h Hive<B>) -> Result<NtHiveNameString<'h>>
+    where
+        B: SplitByteSlice,
+    {
+        let flags = self.flags(hive);
+        let key_name_range = self.key_name_range(hive)?;
+        let key_name_bytes = hive.data_slice(key_name_range);
 
         if flags.contains(KeyNodeFlags::KEY_COMP_NAME) {
             Ok(NtHiveNameString::Latin1(key_name_bytes))
@@ -284,9 +667,114 @@ impl KeyNodeItemRange {
         }
     }
 
+    fn name_lengths<'h, B>(&self, hive: &'h Hive<B>) -> Ref<&'h [u8], KeyNodeNameLengths>
+    where
+        B: SplitByteSlice,
+    {
+        let start = self.header_range.end - mem::size_of::<KeyNodeNameLengths>();
+        Ref::from_bytes(hive.data_slice(start..self.header_range.end)).unwrap()
+    }
+
+    fn parent_offset<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        header.parent.get()
+    }
+
+    fn slack<'h, B>(&self, hive: &'h Hive<B>) -> Result<&'h [u8]>
+    where
+        B: SplitByteSlice,
+    {
+        let key_name_range = self.key_name_range(hive)?;
+        Ok(hive.data_slice(key_name_range.end..self.data_range.end))
+    }
+
+    fn security_offset<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        header.key_security_offset.get()
+    }
+
+    /// Returns the statistics fields of the header or `None` for hives of Windows NT 3.x, which lack them.
+    fn statistics<'h, B>(&self, hive: &'h Hive<B>) -> Option<Ref<&'h [u8], KeyNodeStatistics>>
+    where
+        B: SplitByteSlice,
+    {
+        if self.legacy {
+            return None;
+        }
+
+        let start = self.header_range.start + mem::size_of::<KeyNodeHeader>();
+        let end = start + mem::size_of::<KeyNodeStatistics>();
+        Some(Ref::from_bytes(hive.data_slice(start..end)).unwrap())
+    }
+
+    fn sibling<B>(
+        &self,
+        hive: &Hive<B>,
+        parent: &Self,
+        direction: SiblingDirection,
+    ) -> Option<Result<Self>>
+    where
+        B: SplitByteSlice,
+    {
+        let name = iter_try!(self.name(hive));
+        let (position, _) =
+            match parent.subkey_with_position_matching(hive, &name, |item, _| item == self) {
+                Some(result) => iter_try!(result),
+                None => {
+                    return Some(Err(NtHiveError::KeyNodeNotInParent {
+                        offset: hive.offset_of_data_offset(self.header_range.start),
+                        parent_offset: hive.offset_of_data_offset(parent.header_range.start),
+                    }))
+                }
+            };
+
+        let position = iter_try!(position.step(hive, direction)?);
+        Some(position.key_node_item_range(hive))
+    }
+
     fn subkey<B>(&self, hive: &Hive<B>, name: &str) -> Option<Result<Self>>
     where
         B: SplitByteSlice,
+    {
+        let (_, key_node_item_range) = iter_try!(self.subkey_with_position(hive, name)?);
+        Some(Ok(key_node_item_range))
+    }
+
+    fn subkey_case_sensitive<B>(&self, hive: &Hive<B>, name: &str) -> Option<Result<Self>>
+    where
+        B: SplitByteSlice,
+    {
+        let (_, key_node_item_range) = iter_try!(self.subkey_with_position_matching(
+            hive,
+            name,
+            |_, key_node_name| { key_node_name.eq_case_sensitive(name) }
+        )?);
+        Some(Ok(key_node_item_range))
+    }
+
+    fn subkey_count<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        header.subkey_count.get()
+    }
+
+    fn subkey_with_position<'h, B, N>(
+        &self,
+        hive: &'h Hive<B>,
+        name: &N,
+    ) -> Option<Result<(SubkeyPosition, Self)>>
+    where
+        B: SplitByteSlice,
+        N: NameHash + ?Sized,
+        NtHiveNameString<'h>: PartialOrd<N>,
     {
         let cell_range = iter_try!(self.subkeys_cell_range(hive)?);
         let subkeys = iter_try!(SubKeyNodes::new(hive, cell_range));
@@ -303,6 +791,83 @@ impl KeyNodeItemRange {
         }
     }
 
+    /// Finds the subkey named `name` (compared case-insensitively) for which `matches` returns `true`.
+    fn subkey_with_position_matching<'h, B, N, F>(
+        &self,
+        hive: &'h Hive<B>,
+        name: &N,
+        matches: F,
+    ) -> Option<Result<(SubkeyPosition, Self)>>
+    where
+        B: SplitByteSlice,
+        N: NameHash + ?Sized,
+        NtHiveNameString<'h>: PartialOrd<N>,
+        F: Fn(&Self, &NtHiveNameString<'h>) -> bool,
+    {
+        let (position, key_node_item_range) = iter_try!(self.subkey_with_position(hive, name)?);
+        if matches(
+            &key_node_item_range,
+            &iter_try!(key_node_item_range.name(hive)),
+        ) {
+            return Some(Ok((position, key_node_item_range)));
+        }
+
+        // Binary search has found another subkey with the same case-insensitive name.
+        // Such subkeys are sorted next to each other, but that run may begin before the found
+        // subkey and even span multiple Leafs of an Index Root.
+        // Hence, look for a match in both directions until the names stop being equal.
+        for direction in [SiblingDirection::Previous, SiblingDirection::Next] {
+            let mut next_position = position.step(hive, direction);
+
+            while let Some(result) = next_position {
+                let position = iter_try!(result);
+                let key_node_item_range = iter_try!(position.key_node_item_range(hive));
+                let key_node_name = iter_try!(key_node_item_range.name(hive));
+                if key_node_name.partial_cmp(name) != Some(Ordering::Equal) {
+                    break;
+                }
+
+                if matches(&key_node_item_range, &key_node_name) {
+                    return Some(Ok((position, key_node_item_range)));
+                }
+
+                next_position = position.step(hive, direction);
+            }
+        }
+
+        None
+    }
+
+    fn volatile_subkeys_cell_range<B>(&self, hive: &Hive<B>) -> Option<Result<Range<usize>>>
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        let volatile_subkeys_list_offset = header.volatile_subkeys_list_offset.get();
+        if volatile_subkeys_list_offset == u32::MAX {
+            // This Key Node has no volatile subkeys.
+            return None;
+        }
+
+        if volatile_subkeys_list_offset & VOLATILE_CELL_INDEX == 0 {
+            return Some(Err(NtHiveError::InvalidVolatileCellIndex {
+                offset: hive.offset_of_field(&header.volatile_subkeys_list_offset),
+                cell_index: volatile_subkeys_list_offset,
+            }));
+        }
+
+        // Volatile storage is only available if it has been passed as a fragment.
+        let data_offset = volatile_subkeys_list_offset as usize;
+        if iter_try!(hive.remaining_data_range(data_offset)).is_empty() {
+            return Some(Err(NtHiveError::MissingData {
+                offset: hive.offset_of_data_offset(data_offset),
+            }));
+        }
+
+        let cell_range = iter_try!(hive.cell_range_from_data_offset(volatile_subkeys_list_offset));
+        Some(Ok(cell_range))
+    }
+
     fn subkeys_cell_range<B>(&self, hive: &Hive<B>) -> Option<Result<Range<usize>>>
     where
         B: SplitByteSlice,
@@ -318,6 +883,15 @@ impl KeyNodeItemRange {
         Some(Ok(cell_range))
     }
 
+    fn subkeys_list_capacity<B>(&self, hive: &Hive<B>) -> Option<Result<ListCapacity>>
+    where
+        B: SplitByteSlice,
+    {
+        let cell_range = iter_try!(self.subkeys_cell_range(hive)?);
+        let subkeys_list = iter_try!(SubkeysList::new(hive, cell_range));
+        Some(subkeys_list.capacity())
+    }
+
     fn subpath<B>(&self, hive: &Hive<B>, path: &str) -> Option<Result<Self>>
     where
         B: SplitByteSlice,
@@ -334,6 +908,30 @@ impl KeyNodeItemRange {
         Some(Ok(key_node_item_range))
     }
 
+    fn timestamp<B>(&self, hive: &Hive<B>) -> u64
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        header.timestamp.get()
+    }
+
+    fn validate_hive_entry_flag<B>(&self, hive: &Hive<B>) -> Result<()>
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        let flags = KeyNodeFlags::from_bits_truncate(header.flags.get());
+
+        if flags.contains(KeyNodeFlags::KEY_HIVE_ENTRY) {
+            Ok(())
+        } else {
+            Err(NtHiveError::MissingHiveEntryFlag {
+                offset: hive.offset_of_field(&header.flags),
+            })
+        }
+    }
+
     fn validate_signature<B>(&self, hive: &Hive<B>) -> Result<()>
     where
         B: SplitByteSlice,
@@ -357,24 +955,82 @@ impl KeyNodeItemRange {
     where
         B: SplitByteSlice,
     {
-        let mut values = iter_try!(self.values(hive)?);
+        let mut values = iter_try!(self.values(hive, false)?);
 
         // Key Values are not sorted, so we can only iterate until we find a match.
+        // A value whose name cannot be read is no match, but must not hide the other values either.
         values.find(|key_value| {
             let key_value = match key_value {
                 Ok(key_value) => key_value,
                 Err(_) => return true,
             };
-            let key_value_name = match key_value.name() {
-                Ok(name) => name,
-                Err(_) => return true,
-            };
 
-            key_value_name == name
+            key_value
+                .name()
+                .is_ok_and(|key_value_name| key_value_name == name)
         })
     }
 
-    fn values<'h, B>(&self, hive: &'h Hive<B>) -> Option<Result<KeyValues<'h, B>>>
+    fn volatile_subkey_count<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        header.volatile_subkey_count.get()
+    }
+
+    fn value_count<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        header.key_values_count.get()
+    }
+
+    #[cfg(feature = "std")]
+    fn subkeys_list_offset<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        header.subkeys_list_offset.get()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn values_list_info<B>(&self, hive: &Hive<B>) -> (u32, u32)
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        (
+            header.key_values_count.get(),
+            header.key_values_list_offset.get(),
+        )
+    }
+
+    fn values_list_capacity<B>(&self, hive: &Hive<B>) -> Option<Result<ListCapacity>>
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        let key_values_list_offset = header.key_values_list_offset.get();
+        if key_values_list_offset == u32::MAX {
+            // This Key Node has no values.
+            return None;
+        }
+
+        let cell_range = iter_try!(hive.cell_range_from_data_offset(key_values_list_offset));
+        let count = header.key_values_count.get();
+        let count_field_offset = hive.offset_of_field(&header.key_values_count);
+
+        Some(key_values_list_capacity(
+            count,
+            count_field_offset,
+            cell_range,
+        ))
+    }
+
+    fn values<'h, B>(&self, hive: &'h Hive<B>, stale: bool) -> Option<Result<KeyValues<'h, B>>>
     where
         B: SplitByteSlice,
     {
@@ -389,7 +1045,42 @@ impl KeyNodeItemRange {
         let count = header.key_values_count.get();
         let count_field_offset = hive.offset_of_field(&header.key_values_count);
 
-        Some(KeyValues::new(hive, count, count_field_offset, cell_range))
+        if stale {
+            Some(KeyValues::new_stale(
+                hive,
+                count,
+                count_field_offset,
+                cell_range,
+            ))
+        } else {
+            Some(KeyValues::new(hive, count, count_field_offset, cell_range))
+        }
+    }
+}
+
+/// Identifies a key across all hives, as returned by [`KeyNode::id`].
+///
+/// In contrast to a [`KeyNode`], this is not bound to the lifetime of a [`Hive`] and can be copied,
+/// compared, hashed, and stored freely.
+/// It combines the [`HiveFingerprint`] of the hive with the cell offset of the key.
+/// [`Hive::key_by_id`] compares the fingerprint before resolving the cell offset, so an id taken from
+/// a different version of a hive is rejected instead of resolving to an unrelated key.
+/// The collision characteristics of [`HiveFingerprint`] apply.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct KeyId {
+    fingerprint: HiveFingerprint,
+    cell_offset: u32,
+}
+
+impl KeyId {
+    /// Returns the data offset of the cell containing the key (see [`KeyNode::cell_offset`]).
+    pub fn cell_offset(&self) -> u32 {
+        self.cell_offset
+    }
+
+    /// Returns the fingerprint of the hive containing the key.
+    pub fn fingerprint(&self) -> HiveFingerprint {
+        self.fingerprint
     }
 }
 
@@ -399,12 +1090,24 @@ impl KeyNodeItemRange {
 /// On-Disk Signature: `nk`
 ///
 /// [`KeyValue`]: crate::key_value::KeyValue
-#[derive(Clone)]
 pub struct KeyNode<'h, B: SplitByteSlice> {
     hive: &'h Hive<B>,
     item_range: KeyNodeItemRange,
 }
 
+// Implemented manually, because `#[derive(Clone)]` would needlessly require `B: Clone`.
+impl<B> Clone for KeyNode<'_, B>
+where
+    B: SplitByteSlice,
+{
+    fn clone(&self) -> Self {
+        Self {
+            hive: self.hive,
+            item_range: self.item_range.clone(),
+        }
+    }
+}
+
 impl<'h, B> KeyNode<'h, B>
 where
     B: SplitByteSlice,
@@ -422,197 +1125,1696 @@ where
         Ok(Self { hive, item_range })
     }
 
-    /// Returns the class name of this Key Node (if any).
-    pub fn class_name(&self) -> Option<Result<NtHiveNameString>> {
-        self.item_range.class_name(self.hive)
+    /// Returns the offset of this Key Node's header from the very beginning of the hive bytes.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn header_offset(&self) -> usize {
+        self.hive
+            .offset_of_data_offset(self.item_range.header_range.start)
     }
 
-    /// Returns the name of this Key Node.
-    pub fn name(&self) -> Result<NtHiveNameString> {
-        self.item_range.name(self.hive)
+    /// Returns the [`Hive`] this Key Node belongs to.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn hive(&self) -> &'h Hive<B> {
+        self.hive
     }
 
-    /// Finds a single subkey by name using efficient binary search.
-    pub fn subkey(&self, name: &str) -> Option<Result<KeyNode<'h, B>>> {
-        let item_range = iter_try!(self.item_range.subkey(self.hive, name)?);
+    /// Returns the data offset of the cell containing this Key Node.
+    ///
+    /// This offset uniquely identifies the key within its hive.
+    /// It can be stored and later passed to [`Hive::key_node_from_offset`] to get the same key again.
+    pub fn cell_offset(&self) -> u32 {
+        self.item_range.cell_offset()
+    }
 
-        Some(Ok(KeyNode {
-            hive: self.hive,
-            item_range,
-        }))
+    /// Returns the offset of the cell containing this Key Node relative to the very beginning of the hive bytes.
+    ///
+    /// In contrast to [`KeyNode::cell_offset`], this includes the base block.
+    /// It is the kind of offset reported by [`NtHiveError`] and by tools working on the entire hive file.
+    pub fn file_offset(&self) -> usize {
+        self.hive
+            .offset_of_data_offset(self.item_range.cell_offset() as usize)
     }
 
-    /// Returns an iterator over the subkeys of this Key Node.
-    pub fn subkeys(&self) -> Option<Result<SubKeyNodes<'h, B>>> {
-        let cell_range = iter_try!(self.item_range.subkeys_cell_range(self.hive)?);
-        Some(SubKeyNodes::new(self.hive, cell_range))
+    /// Returns the last write timestamp of this Key Node as an [`OffsetDateTime`] in UTC.
+    ///
+    /// Returns `None` if the timestamp is zero or cannot be represented by [`OffsetDateTime`].
+    /// Use [`KeyNode::timestamp`] to get the raw FILETIME in these cases.
+    #[cfg(feature = "time")]
+    pub fn last_written(&self) -> Option<OffsetDateTime> {
+        filetime_to_offset_date_time(self.timestamp())
     }
 
-    /// Traverses the given subpath and returns the [`KeyNode`] of the last path element.
+    /// Returns the class name of this Key Node (if any).
+    pub fn class_name(&self) -> Option<Result<NtHiveNameString<'h>>> {
+        let class_name_bytes = iter_try!(self.class_name_raw()?);
+        Some(Ok(NtHiveNameString::Utf16LE(class_name_bytes)))
+    }
+
+    /// Returns the raw bytes of the class name of this Key Node (if any).
     ///
-    /// Path elements must be separated by backslashes.
-    pub fn subpath(&self, path: &str) -> Option<Result<KeyNode<'h, B>>> {
-        let item_range = iter_try!(self.item_range.subpath(self.hive, path)?);
+    /// Class names are usually UTF-16LE strings, but some hives (like SAM) store binary data in them.
+    /// The `class_name_length` field is validated against the size of the cell referenced by `class_name_offset`.
+    pub fn class_name_raw(&self) -> Option<Result<&'h [u8]>> {
+        self.item_range.class_name_raw(self.hive)
+    }
 
-        Some(Ok(KeyNode {
-            hive: self.hive,
-            item_range,
-        }))
+    /// Returns the flags of this Key Node.
+    pub fn flags(&self) -> KeyNodeFlags {
+        self.item_range.flags(self.hive)
     }
 
-    /// Finds a single value by name.
-    pub fn value(&self, name: &str) -> Option<Result<KeyValue<'h, B>>> {
-        self.item_range.value(self.hive, name)
+    /// Returns `true` if this key is marked as not deletable (`KEY_NO_DELETE`).
+    pub fn cannot_be_deleted(&self) -> bool {
+        self.flags().contains(KeyNodeFlags::KEY_NO_DELETE)
     }
 
-    /// Returns an iterator over the values of this Key Node.
-    pub fn values(&self) -> Option<Result<KeyValues<'h, B>>> {
-        self.item_range.values(self.hive)
+    /// Returns `true` if the name of this key is stored in (extended) ASCII instead of UTF-16LE.
+    #[doc(alias = "name_is_compressed")]
+    pub fn has_compressed_name(&self) -> bool {
+        self.flags().contains(KeyNodeFlags::KEY_COMP_NAME)
     }
-}
 
-impl<B> PartialEq for KeyNode<'_, B>
-where
-    B: SplitByteSlice,
-{
-    fn eq(&self, other: &Self) -> bool {
-        ptr::eq(self.hive, other.hive) && self.item_range == other.item_range
+    /// Returns a [`KeyId`] identifying this key, which can later be passed to [`Hive::key_by_id`].
+    pub fn id(&self) -> KeyId {
+        KeyId {
+            fingerprint: self.hive.fingerprint(),
+            cell_offset: self.cell_offset(),
+        }
     }
-}
 
-impl<B> Eq for KeyNode<'_, B> where B: SplitByteSlice {}
+    /// Returns `true` if the `KEY_HIVE_ENTRY` flag is set for this key.
+    ///
+    /// Windows sets this flag for the root key of a hive, but not every tool does.
+    /// Use [`KeyNode::is_root`] to reliably check for the root key.
+    pub fn is_hive_entry(&self) -> bool {
+        self.flags().contains(KeyNodeFlags::KEY_HIVE_ENTRY)
+    }
 
-pub(crate) struct KeyNodeMut<'h, B: SplitByteSliceMut> {
-    hive: &'h mut Hive<B>,
-    item_range: KeyNodeItemRange,
-}
+    /// Returns `true` if this key is a symbolic link to another key.
+    ///
+    /// Use `KeyNode::resolve_symlink` to get the target key.
+    pub fn is_symlink(&self) -> bool {
+        self.flags().contains(KeyNodeFlags::KEY_SYM_LINK)
+    }
 
-impl<'h, B> KeyNodeMut<'h, B>
-where
-    B: SplitByteSliceMut,
-{
-    pub(crate) fn from_cell_range(hive: &'h mut Hive<B>, cell_range: Range<usize>) -> Result<Self> {
-        let item_range = KeyNodeItemRange::from_cell_range(hive, cell_range)?;
-        Ok(Self { hive, item_range })
+    /// Returns `true` if this key is volatile.
+    ///
+    /// Volatile keys only exist in memory and are usually not found in hive files.
+    pub fn is_volatile(&self) -> bool {
+        self.flags().contains(KeyNodeFlags::KEY_IS_VOLATILE)
     }
 
-    pub(crate) fn from_leaf_item_range(
-        hive: &'h mut Hive<B>,
-        leaf_item_range: LeafItemRange,
-    ) -> Result<Self> {
-        let item_range = KeyNodeItemRange::from_leaf_item_range(hive, leaf_item_range)?;
-        Ok(Self { hive, item_range })
+    /// Returns `true` if this is the root key of the hive.
+    ///
+    /// This is determined by comparing the offset of this Key Node with the root cell offset
+    /// stored in the base block.
+    /// The `KEY_HIVE_ENTRY` flag is not consulted, because tool-generated hives (e.g. by
+    /// `RegSaveKey`) may have a root key without that flag.
+    pub fn is_root(&self) -> bool {
+        self.cell_offset() == self.hive.root_cell_offset()
     }
 
-    pub(crate) fn clear_volatile_subkeys(&mut self) -> Result<()> {
-        let mut header = self.item_range.header_mut(self.hive);
-        header.volatile_subkey_count.set(0);
+    /// Returns the predefined handle value of this key if the `KEY_PREDEF_HANDLE` flag is set.
+    ///
+    /// Such a key has no real values or subkeys and must not be descended into.
+    /// Windows stores the handle value in the field usually holding the number of values.
+    /// The known values are the `HKEY_*` constants of the Windows API:
+    ///
+    /// | Value        | Constant                   |
+    /// |--------------|----------------------------|
+    /// | `0x80000000` | `HKEY_CLASSES_ROOT`        |
+    /// | `0x80000001` | `HKEY_CURRENT_USER`        |
+    /// | `0x80000002` | `HKEY_LOCAL_MACHINE`       |
+    /// | `0x80000003` | `HKEY_USERS`               |
+    /// | `0x80000004` | `HKEY_PERFORMANCE_DATA`    |
+    /// | `0x80000005` | `HKEY_CURRENT_CONFIG`      |
+    /// | `0x80000006` | `HKEY_DYN_DATA`            |
+    /// | `0x80000050` | `HKEY_PERFORMANCE_TEXT`    |
+    /// | `0x80000060` | `HKEY_PERFORMANCE_NLSTEXT` |
+    ///
+    /// Returns `None` if the flag is not set.
+    pub fn predefined_handle(&self) -> Option<u32> {
+        self.flags()
+            .contains(KeyNodeFlags::KEY_PREDEF_HANDLE)
+            .then(|| self.item_range.value_count(self.hive))
+    }
 
-        if let Some(subkeys) = self.subkeys_mut() {
-            let mut subkeys = subkeys?;
-            while let Some(subkey) = subkeys.next() {
-                subkey?.clear_volatile_subkeys()?;
-            }
-        }
+    /// Returns the Key Security cell (signature `sk`) holding the security descriptor of this key.
+    ///
+    /// Key Security cells are shared between all keys with the same security descriptor.
+    /// Returns `None` if this key has no Key Security cell, which only happens for volatile keys.
+    #[doc(alias = "security_key")]
+    pub fn key_security(&self) -> Option<Result<KeySecurity<'h, B>>> {
+        self.item_range.key_security(self.hive)
+    }
 
-        Ok(())
+    /// Returns the raw `max_subkey_class_name` field of the header.
+    ///
+    /// This is the length in bytes of the longest class name of all subkeys, as reported by `RegQueryInfoKey`.
+    /// Hives of Windows NT 3.x don't store this field, so `0` is returned for them.
+    pub fn max_subkey_class_name(&self) -> u32 {
+        self.item_range.max_subkey_class_name(self.hive)
     }
 
-    pub(crate) fn subkeys_mut(&mut self) -> Option<Result<SubKeyNodesMut<B>>> {
-        let cell_range = iter_try!(self.item_range.subkeys_cell_range(self.hive)?);
-        Some(SubKeyNodesMut::new(self.hive, cell_range))
+    /// Returns the raw `max_subkey_name` field of the header.
+    ///
+    /// This is the length in UTF-16 bytes of the longest name of all subkeys, as reported by `RegQueryInfoKey`.
+    /// Windows Vista and later use only the lower 16 bits for the length and store additional
+    /// flags in the upper 16 bits, which are returned unchanged.
+    /// Hives of Windows NT 3.x don't store this field, so `0` is returned for them.
+    pub fn max_subkey_name(&self) -> u32 {
+        self.item_range.max_subkey_name(self.hive)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::*;
+    /// Returns the raw `max_value_data` field of the header.
+    ///
+    /// This is the size in bytes of the largest data of all values, as reported by `RegQueryInfoKey`.
+    /// Hives of Windows NT 3.x don't store this field, so `0` is returned for them.
+    pub fn max_value_data(&self) -> u32 {
+        self.item_range.max_value_data(self.hive)
+    }
 
-    #[test]
-    fn test_character_encoding() {
-        let testhive = crate::helpers::tests::testhive_vec();
-        let hive = Hive::new(testhive.as_ref()).unwrap();
-        let root_key_node = hive.root_key_node().unwrap();
-        let key_node = root_key_node
-            .subkey("character-encoding-test")
-            .unwrap()
-            .unwrap();
+    /// Returns the raw `max_value_name` field of the header.
+    ///
+    /// This is the length in UTF-16 bytes of the longest name of all values, as reported by `RegQueryInfoKey`.
+    /// Hives of Windows NT 3.x don't store this field, so `0` is returned for them.
+    pub fn max_value_name(&self) -> u32 {
+        self.item_range.max_value_name(self.hive)
+    }
 
-        // Prove that Latin1 characters are always stored with 1 byte per character.
-        let subkey = key_node.subkey("äöü").unwrap().unwrap();
-        assert!(matches!(
-            subkey.name().unwrap(),
-            NtHiveNameString::Latin1(&[0xe4, 0xf6, 0xfc])
+    /// Returns the name of this Key Node.
+    pub fn name(&self) -> Result<NtHiveNameString<'h>> {
+        self.item_range.name(self.hive)
+    }
+
+    /// Returns the subkey of the parent key that directly follows this key in sorted order.
+    ///
+    /// Returns `None` if this is the last subkey of its parent or has no parent (see [`KeyNode::parent`]).
+    /// Returns [`NtHiveError::KeyNodeNotInParent`] if the Subkeys List of the parent doesn't contain this key.
+    /// This key is located in the Subkeys List of its parent via binary search, so no subkeys need
+    /// to be enumerated.
+    pub fn next_sibling(&self) -> Option<Result<KeyNode<'h, B>>> {
+        self.sibling(SiblingDirection::Next)
+    }
+
+    /// Returns the parent [`KeyNode`] of this key.
+    ///
+    /// Returns `None` if this is the root key of the hive (see [`KeyNode::is_root`]) or has the
+    /// `KEY_HIVE_ENTRY` flag set (see [`KeyNode::is_hive_entry`]).
+    /// The parent offset of such a key may point anywhere and is therefore never followed.
+    /// For any other key, the referenced cell must contain a valid Key Node, otherwise an error is returned.
+    pub fn parent(&self) -> Option<Result<KeyNode<'h, B>>> {
+        if self.is_root() || self.is_hive_entry() {
+            return None;
+        }
+
+        let cell_range = iter_try!(self.hive.cell_range_from_offset_field(
+            self.parent_offset(),
+            self.hive.offset_of_data_offset(
+                self.item_range.header_range.start + mem::offset_of!(KeyNodeHeader, parent),
+            ),
         ));
+        Some(KeyNode::from_cell_range(self.hive, cell_range))
+    }
 
-        // Prove that all characters of the Unicode Basic Multilingual Plane are compared case-insensitively
-        // by trying to find both "Full-Width Uppercase A" (U+FF21) and "Full-Width Lowercase A" (U+FF41),
-        // and ending up with the same subkeys.
-        let subkey1 = key_node.subkey("Ａ").unwrap().unwrap();
-        let subkey2 = key_node.subkey("ａ").unwrap().unwrap();
-        assert!(subkey1 == subkey2);
+    /// Returns the data offset of the parent Key Node, as stored in the header.
+    pub(crate) fn parent_offset(&self) -> u32 {
+        self.item_range.parent_offset(self.hive)
+    }
 
-        // Prove that this isn't the case outside the Unicode Basic Multilingual Plane
-        // by trying the same for "Deseret Uppercase H" (U+10410) and "Deseret Lowercase H" (U+10438).
-        let subkey1 = key_node.subkey("𐐐").unwrap().unwrap();
-        let subkey2 = key_node.subkey("𐐸").unwrap().unwrap();
-        assert!(subkey1 != subkey2);
+    pub(crate) fn validate_hive_entry_flag(&self) -> Result<()> {
+        self.item_range.validate_hive_entry_flag(self.hive)
     }
 
-    #[test]
-    fn test_subkey() {
-        // Prove that our binary search algorithm finds every subkey of "subkey-test".
-        let testhive = crate::helpers::tests::testhive_vec();
-        let hive = Hive::new(testhive.as_ref()).unwrap();
-        let root_key_node = hive.root_key_node().unwrap();
-        let key_node = root_key_node.subkey("subkey-test").unwrap().unwrap();
+    /// Returns the absolute path of this key, starting with a backslash (e.g. `\ControlSet001\Services\Foo`).
+    ///
+    /// This is [`KeyNode::path`] with a leading backslash, which is how tools like regedit display keys
+    /// relative to the mount point of a hive.
+    /// The root key itself has the path `\`.
+    #[cfg(feature = "alloc")]
+    pub fn full_path(&self) -> Result<String> {
+        let path = self.path()?;
+
+        let mut full_path = String::with_capacity(path.len() + 1);
+        full_path.push('\\');
+        full_path.push_str(&path);
+        Ok(full_path)
+    }
 
-        for i in 0..512 {
-            let subkey_name = format!("key{i}");
-            assert!(
-                matches!(key_node.subkey(&subkey_name), Some(Ok(_))),
-                "Could not find subkey \"{subkey_name}\""
-            );
-        }
+    /// Returns the full path of this key, with the key names separated by backslashes.
+    ///
+    /// The path is relative to the root key of the hive, i.e. the root key name is not part of it
+    /// and the root key itself has an empty path.
+    /// It is built by following the parent keys (see [`KeyNode::parent`]), so this also works for keys
+    /// that have been found without traversing the tree.
+    /// Names that aren't valid UTF-16 are converted lossily.
+    #[cfg(feature = "alloc")]
+    pub fn path(&self) -> Result<String> {
+        let mut components = Vec::new();
+        self.path_components(&mut components)?;
+        Ok(join_path(&components))
     }
 
-    #[test]
-    fn test_subkeys() {
-        // Keep in mind that subkeys in the hive are sorted like key0, key1, key10, key11, ...
-        // We can create the same order by adding them to a vector and sorting that vector.
-        let mut key_names = Vec::with_capacity(512);
-        for i in 0..512 {
-            key_names.push(format!("key{}", i));
+    /// Fills `components` with the names of all keys from below the root key down to this key.
+    ///
+    /// This is the building block of [`KeyNode::path`].
+    /// Any previous content of `components` is cleared, so the same vector can be reused for many keys
+    /// without allocating again.
+    ///
+    /// The parent chain is followed for at most 512 levels.
+    /// A longer chain can only come from a corrupted hive with a cycle in it and results in
+    /// [`NtHiveError::MaximumKeyDepthExceeded`].
+    #[cfg(feature = "alloc")]
+    pub fn path_components(&self, components: &mut Vec<NtHiveNameString<'h>>) -> Result<()> {
+        components.clear();
+
+        let mut key_node = self.clone();
+        while let Some(parent) = key_node.parent() {
+            if components.len() >= MAX_KEY_DEPTH {
+                return Err(NtHiveError::MaximumKeyDepthExceeded {
+                    offset: self.header_offset(),
+                    max_depth: MAX_KEY_DEPTH,
+                });
+            }
+
+            components.push(key_node.name()?);
+            key_node = parent?;
         }
 
-        key_names.sort_unstable();
+        components.reverse();
+        Ok(())
+    }
 
-        // Iterate through subkeys of "subkey-test" and prove that they are sorted just like our vector.
-        let testhive = crate::helpers::tests::testhive_vec();
-        let hive = Hive::new(testhive.as_ref()).unwrap();
-        let root_key_node = hive.root_key_node().unwrap();
-        let key_node = root_key_node.subkey("subkey-test").unwrap().unwrap();
+    /// Returns the security descriptor of this key, parsed as a [`SecurityDescriptor`].
+    ///
+    /// Returns `None` if this key has no Key Security cell, which only happens for volatile keys.
+    pub fn security_descriptor(&self) -> Option<Result<SecurityDescriptor<'h>>> {
+        let key_security = iter_try!(self.key_security()?);
+        Some(key_security.descriptor())
+    }
 
-        let subkeys = key_node.subkeys().unwrap().unwrap();
+    /// Returns the raw self-relative SECURITY_DESCRIPTOR of this key.
+    ///
+    /// The descriptor is stored in a Key Security cell (signature `sk`), which may be shared between many keys.
+    /// The returned bytes can be passed to any library that parses Windows security descriptors.
+    /// Returns `None` if this key has no Key Security cell, which only happens for volatile keys.
+    pub fn security_descriptor_raw(&self) -> Option<Result<&'h [u8]>> {
+        let key_security = iter_try!(self.key_security()?);
+        Some(key_security.descriptor_raw())
+    }
 
-        for (subkey, expected_key_name) in subkeys.zip(key_names.iter()) {
-            let subkey = subkey.unwrap();
-            assert_eq!(subkey.name().unwrap(), expected_key_name.as_str());
-        }
+    /// Returns the raw `key_security_offset` field of the header.
+    ///
+    /// This is the data offset of the Key Security cell (signature `sk`) holding the security descriptor
+    /// of this key, or `u32::MAX` if the key has none.
+    /// Use [`KeyNode::key_security`] or [`KeyNode::security_descriptor_raw`] to resolve it.
+    pub fn security_offset(&self) -> u32 {
+        self.item_range.security_offset(self.hive)
     }
 
-    #[test]
-    fn test_subpath() {
-        let testhive = crate::helpers::tests::testhive_vec();
-        let hive = Hive::new(testhive.as_ref()).unwrap();
-        let root_key_node = hive.root_key_node().unwrap();
-        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+    /// Returns the slack space of this Key Node, i.e. the bytes between the end of the key name
+    /// and the end of its cell.
+    ///
+    /// Cells are 8-byte aligned and often reused for a shorter name, so these bytes may contain remnants
+    /// of previous data.
+    /// Returns an error if the key name doesn't fit into the cell (just like [`KeyNode::name`]).
+    pub fn slack(&self) -> Result<&'h [u8]> {
+        self.item_range.slack(self.hive)
+    }
 
-        assert!(matches!(key_node.subpath("no-subkeys"), Some(Ok(_))));
-        assert!(matches!(key_node.subpath("\\no-subkeys"), Some(Ok(_))));
-        assert!(matches!(key_node.subpath("no-subkeys\\"), Some(Ok(_))));
-        assert!(matches!(key_node.subpath("\\no-subkeys\\"), Some(Ok(_))));
-        assert!(key_node.subpath("no-subkeys\\non-existing").is_none());
+    /// Returns the subkey of the parent key that directly precedes this key in sorted order.
+    ///
+    /// Returns `None` if this is the first subkey of its parent or has no parent (see [`KeyNode::parent`]).
+    /// Returns [`NtHiveError::KeyNodeNotInParent`] if the Subkeys List of the parent doesn't contain this key.
+    /// This key is located in the Subkeys List of its parent via binary search, so no subkeys need
+    /// to be enumerated.
+    pub fn prev_sibling(&self) -> Option<Result<KeyNode<'h, B>>> {
+        self.sibling(SiblingDirection::Previous)
+    }
+
+    /// Follows the symbolic link of this key and returns the target [`KeyNode`].
+    ///
+    /// Returns `None` if this key is no symbolic link (see [`KeyNode::is_symlink`]).
+    /// If the target is a symbolic link itself, it is followed as well, up to a maximum depth.
+    ///
+    /// The target is read from the `REG_LINK` value named `SymbolicLinkValue`.
+    /// Absolute paths like `\Registry\Machine\System\ControlSet001` are resolved relative to the root of
+    /// this hive by skipping the `\Registry`, `Machine`, and `System` components, as a hive doesn't know
+    /// where it is mounted.
+    /// Any other path is resolved relative to the root key of this hive.
+    /// Links to other hives can't be resolved and result in [`NtHiveError::SymbolicLinkTargetNotFound`].
+    #[cfg(feature = "alloc")]
+    pub fn resolve_symlink(&self) -> Option<Result<KeyNode<'h, B>>> {
+        if !self.is_symlink() {
+            return None;
+        }
+
+        let mut key_node = self.clone();
+
+        for _ in 0..MAX_SYMBOLIC_LINK_DEPTH {
+            key_node = iter_try!(key_node.symlink_target());
+
+            if !key_node.is_symlink() {
+                return Some(Ok(key_node));
+            }
+        }
+
+        Some(Err(NtHiveError::MaximumSymbolicLinkDepthExceeded {
+            offset: self.header_offset(),
+            max_depth: MAX_SYMBOLIC_LINK_DEPTH,
+        }))
+    }
+
+    /// Finds a single subkey by name using efficient binary search.
+    ///
+    /// Hash Leafs are first scanned for the hash of the name, which makes finding an existing subkey cheap.
+    /// As the stored hashes may be stale, a miss is only reported after an additional binary search by name,
+    /// so looking up a non-existing subkey costs slightly more than looking up an existing one.
+    ///
+    /// Returns `None` if there is no subkey with that name, and `Some(Err(..))` if the subkey
+    /// structures are corrupted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let nt_hive::DoctestHive { intact: testhive, broken_subkeys_list: corrupted, .. } = nt_hive::doctest_hive();
+    /// use nt_hive::Hive;
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    ///
+    /// // The subkey exists.
+    /// let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+    /// assert_eq!(key_node.name().unwrap(), "data-test");
+    ///
+    /// // The subkey doesn't exist.
+    /// assert!(root_key_node.subkey("non-existing").is_none());
+    ///
+    /// // The Subkeys List of the root key has been overwritten.
+    /// let hive = Hive::new(corrupted.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    /// assert!(matches!(root_key_node.subkey("data-test"), Some(Err(_))));
+    /// ```
+    pub fn subkey(&self, name: &str) -> Option<Result<KeyNode<'h, B>>> {
+        let item_range = iter_try!(self.item_range.subkey(self.hive, name)?);
+
+        Some(Ok(KeyNode {
+            hive: self.hive,
+            item_range,
+        }))
+    }
+
+    /// Checks whether this Key Node has a subkey with the given name.
+    ///
+    /// Returns `Ok(false)` if this Key Node has no subkeys at all, and an error if the Subkeys List is corrupted.
+    /// The name comparison is case-insensitive, just like in [`KeyNode::subkey`].
+    pub fn has_subkey(&self, name: &str) -> Result<bool> {
+        let exists = self
+            .item_range
+            .subkey(self.hive, name)
+            .transpose()?
+            .is_some();
+        Ok(exists)
+    }
+
+    /// Finds a single subkey by name using a case-sensitive comparison.
+    ///
+    /// Windows compares key names case-insensitively and doesn't allow two subkeys whose names only
+    /// differ in case.
+    /// A crafted hive may still contain such subkeys, and [`KeyNode::subkey`] returns any of them.
+    /// This function only returns a subkey whose name matches `name` exactly.
+    /// It performs the same binary search as [`KeyNode::subkey`], and then scans the neighbors of
+    /// the found subkey (across Leaf boundaries of an Index Root) for an exact match.
+    pub fn subkey_case_sensitive(&self, name: &str) -> Option<Result<KeyNode<'h, B>>> {
+        let item_range = iter_try!(self.item_range.subkey_case_sensitive(self.hive, name)?);
+
+        Some(Ok(KeyNode {
+            hive: self.hive,
+            item_range,
+        }))
+    }
+
+    /// Returns the subkey at the given position in the sorted list of subkeys.
+    ///
+    /// Returns `None` if this key has no subkeys or `index` is out of range.
+    /// Whole leaves of an Index Root are skipped at once, so this is much faster than iterating
+    /// [`KeyNode::subkeys`] up to the requested position.
+    pub fn subkey_at(&self, index: u32) -> Option<Result<KeyNode<'h, B>>> {
+        let mut subkeys = iter_try!(self.subkeys()?);
+        subkeys.nth(index as usize)
+    }
+
+    fn sibling(&self, direction: SiblingDirection) -> Option<Result<KeyNode<'h, B>>> {
+        let parent = iter_try!(self.parent()?);
+        let item_range =
+            iter_try!(self
+                .item_range
+                .sibling(self.hive, &parent.item_range, direction)?);
+
+        Some(Ok(KeyNode {
+            hive: self.hive,
+            item_range,
+        }))
+    }
+
+    /// Returns the number of subkeys of this Key Node, as stored in its header.
+    ///
+    /// This doesn't iterate the subkeys.
+    /// In a corrupted hive, the actual number of subkeys returned by [`KeyNode::subkeys`] may differ.
+    pub fn subkey_count(&self) -> u32 {
+        self.item_range.subkey_count(self.hive)
+    }
+
+    /// Returns an iterator over the subkeys of this Key Node.
+    ///
+    /// Returns `None` if this Key Node has no subkeys, and `Some(Err(..))` if the Subkeys List is corrupted.
+    /// Each item of the iterator may still be an error if a single subkey is corrupted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let nt_hive::DoctestHive { intact: testhive, broken_subkeys_list: corrupted, .. } = nt_hive::doctest_hive();
+    /// use nt_hive::Hive;
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    ///
+    /// // The root key has subkeys.
+    /// let subkeys = root_key_node.subkeys().unwrap().unwrap();
+    /// assert!(subkeys.count() > 0);
+    ///
+    /// // A key without subkeys.
+    /// let key_node = root_key_node.subpath("subpath-test\\no-subkeys").unwrap().unwrap();
+    /// assert!(key_node.subkeys().is_none());
+    ///
+    /// // The Subkeys List of the root key has been overwritten.
+    /// let hive = Hive::new(corrupted.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    /// assert!(matches!(root_key_node.subkeys(), Some(Err(_))));
+    /// ```
+    pub fn subkeys(&self) -> Option<Result<SubKeyNodes<'h, B>>> {
+        let cell_range = iter_try!(self.item_range.subkeys_cell_range(self.hive)?);
+        Some(SubKeyNodes::new(self.hive, cell_range))
+    }
+
+    /// Returns an iterator over the subkeys of this Key Node whose names start with `prefix`.
+    ///
+    /// Names are compared case-insensitively, like [`KeyNode::subkey`] does.
+    /// The first matching subkey is located via binary search, so this is much faster than filtering
+    /// the result of [`KeyNode::subkeys`] when only a few subkeys match.
+    ///
+    /// Returns `None` if this Key Node has no subkeys, and `Some(Err(..))` if the Subkeys List is corrupted.
+    pub fn subkeys_with_prefix<'p>(
+        &self,
+        prefix: &'p str,
+    ) -> Option<Result<SubKeyNodesWithPrefix<'h, 'p, B>>> {
+        let cell_range = iter_try!(self.item_range.subkeys_cell_range(self.hive)?);
+        Some(SubKeyNodesWithPrefix::new(self.hive, cell_range, prefix))
+    }
+
+    /// Returns how much of the Subkeys List cell of this Key Node is used by its declared items.
+    ///
+    /// For an Index Root, this only covers the Index Root cell itself and not the Leafs it references.
+    /// Returns `None` if this Key Node has no subkeys, and `Some(Err(..))` if the Subkeys List is corrupted.
+    pub fn subkeys_list_capacity(&self) -> Option<Result<ListCapacity>> {
+        self.item_range.subkeys_list_capacity(self.hive)
+    }
+
+    /// Returns an iterator over the subkeys referenced by the stale items of the Subkeys List of this Key Node.
+    ///
+    /// Stale items are the items following the declared number of items up to the end of the Subkeys List cell
+    /// (see [`KeyNode::subkeys_list_capacity`]).
+    /// They usually reference subkeys that have been deleted or moved and are not part of [`KeyNode::subkeys`].
+    /// As their cells may have been freed or reused, expect many of them to return errors.
+    ///
+    /// Returns `None` if this Key Node has no subkeys, and `Some(Err(..))` if the Subkeys List is corrupted.
+    pub fn stale_subkeys(&self) -> Option<Result<SubKeyNodes<'h, B>>> {
+        let cell_range = iter_try!(self.item_range.subkeys_cell_range(self.hive)?);
+        Some(SubKeyNodes::new_stale(self.hive, cell_range))
+    }
+
+    /// Traverses the given subpath and returns the [`KeyNode`] of the last path element.
+    ///
+    /// Path elements must be separated by backslashes.
+    /// Returns `None` if any path element doesn't exist, and `Some(Err(..))` if the subkey structures
+    /// along the path are corrupted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let nt_hive::DoctestHive { intact: testhive, broken_subkeys_list: corrupted, .. } = nt_hive::doctest_hive();
+    /// use nt_hive::Hive;
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    ///
+    /// // The path exists.
+    /// let key_node = root_key_node
+    ///     .subpath("subpath-test\\with-single-level-subkey\\subkey")
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert_eq!(key_node.name().unwrap(), "subkey");
+    ///
+    /// // The last path element doesn't exist.
+    /// assert!(root_key_node.subpath("subpath-test\\non-existing").is_none());
+    ///
+    /// // The Subkeys List of the root key has been overwritten.
+    /// let hive = Hive::new(corrupted.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    /// assert!(matches!(root_key_node.subpath("subpath-test\\no-subkeys"), Some(Err(_))));
+    /// ```
+    pub fn subpath(&self, path: &str) -> Option<Result<KeyNode<'h, B>>> {
+        let item_range = iter_try!(self.item_range.subpath(self.hive, path)?);
+
+        Some(Ok(KeyNode {
+            hive: self.hive,
+            item_range,
+        }))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn symlink_target(&self) -> Result<KeyNode<'h, B>> {
+        let key_value = self.value(SYMBOLIC_LINK_VALUE_NAME).ok_or_else(|| {
+            NtHiveError::MissingSymbolicLinkValue {
+                offset: self.header_offset(),
+            }
+        })??;
+        let target = key_value.link_data()?;
+
+        // Skip the `\Registry\<Root>\<Hive>` components of an absolute path.
+        let mut components = target.split('\\').filter(|component| !component.is_empty());
+        let mut path = Vec::new();
+        if target.starts_with('\\') {
+            let first_component = components.next();
+            if first_component.is_some_and(|component| component.eq_ignore_ascii_case("Registry")) {
+                components.nth(1);
+            } else {
+                path.extend(first_component);
+            }
+        }
+        path.extend(components);
+
+        let root_key_node = self.hive.root_key_node()?;
+        root_key_node.subpath(&path.join("\\")).ok_or_else(|| {
+            NtHiveError::SymbolicLinkTargetNotFound {
+                offset: self.header_offset(),
+            }
+        })?
+    }
+
+    /// Returns the last write timestamp of this Key Node as a raw Windows FILETIME
+    /// (100-nanosecond intervals since 1601-01-01 UTC).
+    ///
+    /// This is the time when the key, one of its values, or its list of subkeys has last been modified.
+    /// It is always available, even in `no_std` builds.
+    #[doc(alias = "last_written_timestamp")]
+    pub fn timestamp(&self) -> u64 {
+        self.item_range.timestamp(self.hive)
+    }
+
+    /// Returns how much of the Key Values List cell of this Key Node is used by its declared items.
+    ///
+    /// Returns `None` if this Key Node has no values, and `Some(Err(..))` if the Key Values List is corrupted.
+    pub fn values_list_capacity(&self) -> Option<Result<ListCapacity>> {
+        self.item_range.values_list_capacity(self.hive)
+    }
+
+    /// Returns an iterator over the values referenced by the stale items of the Key Values List of this Key Node.
+    ///
+    /// Stale items are the items following the declared number of items up to the end of the Key Values List cell
+    /// (see [`KeyNode::values_list_capacity`]).
+    /// They usually reference values that have been deleted and are not part of [`KeyNode::values`].
+    /// As their cells may have been freed or reused, expect many of them to return errors.
+    ///
+    /// Returns `None` if this Key Node has no values, and `Some(Err(..))` if the Key Values List is corrupted.
+    pub fn stale_values(&self) -> Option<Result<KeyValues<'h, B>>> {
+        self.item_range.values(self.hive, true)
+    }
+
+    /// Returns the data offset of the Subkeys List, as stored in the header.
+    ///
+    /// In contrast to [`KeyNode::subkeys`], this doesn't follow the offset.
+    #[cfg(feature = "std")]
+    pub(crate) fn subkeys_list_offset(&self) -> u32 {
+        self.item_range.subkeys_list_offset(self.hive)
+    }
+
+    /// Returns the raw value count and the data offset of the Key Values List, as stored in the header.
+    ///
+    /// In contrast to [`KeyNode::values`], this doesn't follow the offset.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn values_list_info(&self) -> (u32, u32) {
+        self.item_range.values_list_info(self.hive)
+    }
+
+    /// Returns the number of values of this Key Node, as stored in its header.
+    ///
+    /// This doesn't iterate the values.
+    /// In a corrupted hive, the actual number of values returned by [`KeyNode::values`] may differ.
+    #[doc(alias = "values_count")]
+    pub fn value_count(&self) -> u32 {
+        self.item_range.value_count(self.hive)
+    }
+
+    /// Returns the number of volatile subkeys of this Key Node, as stored in its header.
+    ///
+    /// Volatile subkeys only exist in memory, so this is usually zero for hive files.
+    /// A non-zero count may be found in hives extracted from memory.
+    /// See [`KeyNode::volatile_subkeys`] for accessing the volatile subkeys themselves.
+    pub fn volatile_subkey_count(&self) -> u32 {
+        self.item_range.volatile_subkey_count(self.hive)
+    }
+
+    /// Returns an iterator over the volatile subkeys of this Key Node.
+    ///
+    /// Volatile subkeys are referenced by cell indices with the highest bit set, which refer to
+    /// volatile storage instead of the hive data.
+    /// Hive files don't contain volatile storage.
+    /// To inspect the volatile subkeys of a hive captured from memory, pass its volatile storage
+    /// as a fragment at data offset `0x8000_0000` to `Hive::from_fragments`.
+    ///
+    /// Returns `None` if this Key Node has no volatile subkeys.
+    /// Returns [`NtHiveError::InvalidVolatileCellIndex`] if the stored cell index doesn't refer to
+    /// volatile storage and [`NtHiveError::MissingData`] if the volatile storage isn't available.
+    pub fn volatile_subkeys(&self) -> Option<Result<SubKeyNodes<'h, B>>> {
+        let cell_range = iter_try!(self.item_range.volatile_subkeys_cell_range(self.hive)?);
+        Some(SubKeyNodes::new(self.hive, cell_range))
+    }
+
+    /// Finds a single value by name.
+    ///
+    /// Returns `None` if there is no value with that name, and `Some(Err(..))` if the value
+    /// structures are corrupted.
+    /// Values whose names cannot be read are skipped, so they don't prevent finding any other value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let nt_hive::DoctestHive { intact: testhive, freed_values_list: corrupted, .. } = nt_hive::doctest_hive();
+    /// use nt_hive::Hive;
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    /// let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+    ///
+    /// // The value exists.
+    /// let key_value = key_node.value("dword").unwrap().unwrap();
+    /// assert_eq!(key_value.dword_data().unwrap(), 42);
+    ///
+    /// // The value doesn't exist.
+    /// assert!(key_node.value("non-existing").is_none());
+    ///
+    /// // The Key Values List of "data-test" has been freed.
+    /// let hive = Hive::new(corrupted.as_ref()).unwrap();
+    /// let key_node = hive.root_key_node().unwrap().subkey("data-test").unwrap().unwrap();
+    /// assert!(matches!(key_node.value("dword"), Some(Err(_))));
+    /// ```
+    pub fn value(&self, name: &str) -> Option<Result<KeyValue<'h, B>>> {
+        self.item_range.value(self.hive, name)
+    }
+
+    /// Checks whether this Key Node has a value with the given name.
+    ///
+    /// Returns `Ok(false)` if this Key Node has no values at all, and an error if the Key Values List is corrupted.
+    /// The name comparison is case-insensitive, just like in [`KeyNode::value`].
+    pub fn has_value(&self, name: &str) -> Result<bool> {
+        let exists = self.value(name).transpose()?.is_some();
+        Ok(exists)
+    }
+
+    /// Returns an iterator over the values of this Key Node.
+    ///
+    /// Returns `None` if this Key Node has no values, and `Some(Err(..))` if the Key Values List is corrupted.
+    /// Each item of the iterator may still be an error if a single value is corrupted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let nt_hive::DoctestHive { intact: testhive, freed_values_list: corrupted, .. } = nt_hive::doctest_hive();
+    /// use nt_hive::Hive;
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    ///
+    /// // A key with values.
+    /// let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+    /// let key_values = key_node.values().unwrap().unwrap();
+    /// assert_eq!(key_values.count(), key_node.value_count() as usize);
+    ///
+    /// // The root key has no values.
+    /// assert!(root_key_node.values().is_none());
+    ///
+    /// // The Key Values List of "data-test" has been freed.
+    /// let hive = Hive::new(corrupted.as_ref()).unwrap();
+    /// let key_node = hive.root_key_node().unwrap().subkey("data-test").unwrap().unwrap();
+    /// assert!(matches!(key_node.values(), Some(Err(_))));
+    /// ```
+    pub fn values(&self) -> Option<Result<KeyValues<'h, B>>> {
+        self.item_range.values(self.hive, false)
+    }
+}
+
+impl<B> fmt::Debug for KeyNode<'_, B>
+where
+    B: SplitByteSlice,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_struct = f.debug_struct("KeyNode");
+
+        match self.name() {
+            Ok(name) => debug_struct.field("name", &format_args!("\"{name}\"")),
+            Err(e) => debug_struct.field("name", &e),
+        };
+
+        debug_struct
+            .field("cell_offset", &format_args!("{:#010x}", self.cell_offset()))
+            .finish()
+    }
+}
+
+impl<B> PartialEq for KeyNode<'_, B>
+where
+    B: SplitByteSlice,
+{
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.hive, other.hive) && self.item_range == other.item_range
+    }
+}
+
+impl<B> Eq for KeyNode<'_, B> where B: SplitByteSlice {}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns the name of the root key of this hive.
+    ///
+    /// This only reads the header and name of the root Key Node and is the cheapest way to label a hive
+    /// file (e.g. `CMI-CreateHive{...}`).
+    /// Returns an error if the root cell offset is invalid or doesn't point to a Key Node.
+    pub fn root_key_name(&self) -> Result<NtHiveNameString<'_>> {
+        self.root_key_item_range()?.name(self)
+    }
+
+    /// Returns the last write timestamp of the root key of this hive as a raw Windows FILETIME.
+    ///
+    /// Like [`Hive::root_key_name`], this only reads the header of the root Key Node.
+    pub fn root_key_timestamp(&self) -> Result<u64> {
+        Ok(self.root_key_item_range()?.timestamp(self))
+    }
+
+    fn root_key_item_range(&self) -> Result<KeyNodeItemRange> {
+        let cell_range = self.root_cell_range()?;
+        KeyNodeItemRange::from_cell_range(self, cell_range)
+    }
+}
+
+#[cfg(feature = "write")]
+pub(crate) struct KeyNodeMut<'h, B: SplitByteSliceMut> {
+    hive: &'h mut Hive<B>,
+    item_range: KeyNodeItemRange,
+}
+
+#[cfg(feature = "write")]
+impl<'h, B> KeyNodeMut<'h, B>
+where
+    B: SplitByteSliceMut,
+{
+    pub(crate) fn from_cell_range(hive: &'h mut Hive<B>, cell_range: Range<usize>) -> Result<Self> {
+        let item_range = KeyNodeItemRange::from_cell_range(hive, cell_range)?;
+        Ok(Self { hive, item_range })
+    }
+
+    pub(crate) fn from_leaf_item_range(
+        hive: &'h mut Hive<B>,
+        leaf_item_range: LeafItemRange,
+    ) -> Result<Self> {
+        let item_range = KeyNodeItemRange::from_leaf_item_range(hive, leaf_item_range)?;
+        Ok(Self { hive, item_range })
+    }
+
+    pub(crate) fn clear_volatile_subkeys(&mut self) -> Result<()> {
+        let mut header = self.item_range.header_mut(self.hive);
+        header.volatile_subkey_count.set(0);
+
+        if let Some(subkeys) = self.subkeys_mut() {
+            let mut subkeys = subkeys?;
+            while let Some(subkey) = subkeys.next() {
+                subkey?.clear_volatile_subkeys()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn subkeys_mut(&mut self) -> Option<Result<SubKeyNodesMut<'_, B>>> {
+        let cell_range = iter_try!(self.item_range.subkeys_cell_range(self.hive)?);
+        Some(SubKeyNodesMut::new(self.hive, cell_range))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyNodeField;
+    use crate::helpers::tests::{cell_data, key_node_field, read_u32, write_u32};
+    use crate::*;
+
+    #[test]
+    fn test_character_encoding() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node
+            .subkey("character-encoding-test")
+            .unwrap()
+            .unwrap();
+
+        // Prove that Latin1 characters are always stored with 1 byte per character.
+        let subkey = key_node.subkey("äöü").unwrap().unwrap();
+        assert!(matches!(
+            subkey.name().unwrap(),
+            NtHiveNameString::Latin1(&[0xe4, 0xf6, 0xfc])
+        ));
+
+        // Prove that all characters of the Unicode Basic Multilingual Plane are compared case-insensitively
+        // by trying to find both "Full-Width Uppercase A" (U+FF21) and "Full-Width Lowercase A" (U+FF41),
+        // and ending up with the same subkeys.
+        let subkey1 = key_node.subkey("Ａ").unwrap().unwrap();
+        let subkey2 = key_node.subkey("ａ").unwrap().unwrap();
+        assert!(subkey1 == subkey2);
+
+        // Prove that this isn't the case outside the Unicode Basic Multilingual Plane
+        // by trying the same for "Deseret Uppercase H" (U+10410) and "Deseret Lowercase H" (U+10438).
+        let subkey1 = key_node.subkey("𐐐").unwrap().unwrap();
+        let subkey2 = key_node.subkey("𐐸").unwrap().unwrap();
+        assert!(subkey1 != subkey2);
+    }
+
+    #[test]
+    fn test_subkey() {
+        // Prove that our binary search algorithm finds every subkey of "subkey-test".
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("subkey-test").unwrap().unwrap();
+
+        for i in 0..512 {
+            let subkey_name = format!("key{i}");
+            assert!(
+                matches!(key_node.subkey(&subkey_name), Some(Ok(_))),
+                "Could not find subkey \"{subkey_name}\""
+            );
+        }
+    }
+
+    #[test]
+    fn test_has_subkey_and_value() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(root_key_node.has_subkey("data-test"), Ok(true));
+        assert_eq!(root_key_node.has_subkey("DATA-TEST"), Ok(true));
+        assert_eq!(root_key_node.has_subkey("non-existing"), Ok(false));
+        assert_eq!(root_key_node.has_value("dword"), Ok(false));
+
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert_eq!(key_node.has_subkey("data-test"), Ok(false));
+        assert_eq!(key_node.has_value("dword"), Ok(true));
+        assert_eq!(key_node.has_value("non-existing"), Ok(false));
+
+        // A corrupted Subkeys List is an error and not a miss.
+        let mut corrupted = testhive.clone();
+        let subkeys_list = cell_data(read_u32(
+            &corrupted,
+            key_node_field(root_key_node.cell_offset(), KeyNodeField::SubkeysListOffset),
+        ));
+        corrupted[subkeys_list..subkeys_list + 2].copy_from_slice(b"xx");
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert!(root_key_node.has_subkey("data-test").is_err());
+    }
+
+    #[test]
+    fn test_subkey_case_sensitive() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        let key_node = root_key_node
+            .subkey_case_sensitive("data-test")
+            .unwrap()
+            .unwrap();
+        assert_eq!(key_node.name().unwrap(), "data-test");
+        assert!(root_key_node.subkey_case_sensitive("Data-Test").is_none());
+        assert!(root_key_node.subkey("Data-Test").is_some());
+
+        // Rename "with-single-level-subkey" to "NO-SUBKEYS", so that it only differs in case
+        // from its predecessor "no-subkeys".
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        let subkey_offset = key_node
+            .subkey("with-single-level-subkey")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+        crate::helpers::tests::rename_key_node(&mut testhive, subkey_offset, "NO-SUBKEYS");
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+
+        // Case-insensitive lookups always find the same subkey, no matter which case is requested.
+        let subkey = key_node.subkey("no-subkeys").unwrap().unwrap();
+        let other_subkey = key_node.subkey("NO-SUBKEYS").unwrap().unwrap();
+        assert_eq!(subkey, other_subkey);
+
+        // Case-sensitive lookups tell both subkeys apart.
+        let subkey = key_node
+            .subkey_case_sensitive("NO-SUBKEYS")
+            .unwrap()
+            .unwrap();
+        assert_eq!(subkey.cell_offset(), subkey_offset);
+        let subkey = key_node
+            .subkey_case_sensitive("no-subkeys")
+            .unwrap()
+            .unwrap();
+        assert_ne!(subkey.cell_offset(), subkey_offset);
+        assert!(subkey.name().unwrap().eq_case_sensitive("no-subkeys"));
+        assert!(key_node.subkey_case_sensitive("No-Subkeys").is_none());
+
+        // Do the same across the boundary of the first two Leafs of the Index Root of "subkey-test".
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let key_node = hive
+            .root_key_node()
+            .unwrap()
+            .subkey("subkey-test")
+            .unwrap()
+            .unwrap();
+        let index_root = cell_data(read_u32(
+            &testhive,
+            key_node_field(key_node.cell_offset(), KeyNodeField::SubkeysListOffset),
+        ));
+        assert_eq!(&testhive[index_root..index_root + 2], b"ri");
+        let first_leaf = cell_data(read_u32(&testhive, index_root + 4));
+        let first_leaf_count =
+            u16::from_le_bytes(testhive[first_leaf + 2..first_leaf + 4].try_into().unwrap());
+
+        let subkeys = key_node
+            .subkeys()
+            .unwrap()
+            .unwrap()
+            .skip(first_leaf_count as usize - 1)
+            .take(2)
+            .map(|subkey| subkey.unwrap())
+            .collect::<Vec<_>>();
+        let last_name = subkeys[0].name().unwrap().to_string_lossy();
+        let upper_name = last_name.to_uppercase();
+        assert_ne!(last_name, upper_name);
+        let last_offset = subkeys[0].cell_offset();
+        let first_offset = subkeys[1].cell_offset();
+        crate::helpers::tests::rename_key_node(&mut testhive, first_offset, &upper_name);
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let key_node = hive
+            .root_key_node()
+            .unwrap()
+            .subkey("subkey-test")
+            .unwrap()
+            .unwrap();
+
+        // Whichever Leaf the binary search lands in, the exact match is found in the other one.
+        let subkey = key_node.subkey_case_sensitive(&last_name).unwrap().unwrap();
+        assert_eq!(subkey.cell_offset(), last_offset);
+        let subkey = key_node
+            .subkey_case_sensitive(&upper_name)
+            .unwrap()
+            .unwrap();
+        assert_eq!(subkey.cell_offset(), first_offset);
+    }
+
+    #[test]
+    fn test_subkey_name_hash() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        // All Leafs of the testhive are Hash Leafs.
+        // Every key must still be found by looking it up in its parent.
+        for key_node in root_key_node.descendants().unwrap() {
+            let key_node = key_node.unwrap();
+            let parent = key_node.parent().unwrap().unwrap();
+            let name = key_node.name().unwrap().to_string_lossy();
+            assert_eq!(parent.subkey(&name).unwrap().unwrap(), key_node);
+            assert_eq!(
+                parent.subkey(&name.to_ascii_uppercase()).unwrap().unwrap(),
+                key_node
+            );
+        }
+
+        // A key whose stored hash doesn't match its name is still found by its name.
+        let subkeys_list_offset = read_u32(
+            &testhive,
+            key_node_field(root_key_node.cell_offset(), KeyNodeField::SubkeysListOffset),
+        );
+        let first_hash_field = cell_data(subkeys_list_offset) + 4 + 4;
+        assert_eq!(
+            testhive[first_hash_field..first_hash_field + 4],
+            "big-data-test".name_hash().to_le_bytes()
+        );
+        testhive[first_hash_field..first_hash_field + 4].copy_from_slice(&0u32.to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        assert_eq!(key_node.name().unwrap(), "big-data-test");
+        assert!(root_key_node.subkey("data-test").is_some());
+        assert!(root_key_node.subkey("no-such-key").is_none());
+    }
+
+    #[test]
+    fn test_subkey_at() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        // "subkey-test" has its 512 subkeys spread over multiple leaves of an Index Root.
+        let key_node = root_key_node.subkey("subkey-test").unwrap().unwrap();
+        assert!(matches!(
+            key_node.subkeys(),
+            Some(Ok(SubKeyNodes::IndexRoot(_)))
+        ));
+
+        let subkey_names = key_node
+            .subkeys()
+            .unwrap()
+            .unwrap()
+            .map(|subkey| subkey.unwrap().name().unwrap().to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(subkey_names.len(), 512);
+
+        for (index, subkey_name) in subkey_names.iter().enumerate() {
+            let subkey = key_node.subkey_at(index as u32).unwrap().unwrap();
+            assert_eq!(subkey.name().unwrap(), subkey_name.as_str());
+        }
+        assert!(key_node.subkey_at(512).is_none());
+        assert!(key_node.subkey_at(u32::MAX).is_none());
+
+        // "subpath-test" has a single leaf.
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        let subkey = key_node.subkey_at(2).unwrap().unwrap();
+        assert_eq!(subkey.name().unwrap(), "with-two-levels-of-subkeys");
+        assert!(key_node.subkey_at(3).is_none());
+
+        let key_node = key_node.subkey("no-subkeys").unwrap().unwrap();
+        assert!(key_node.subkey_at(0).is_none());
+    }
+
+    #[test]
+    fn test_subkeys() {
+        // Keep in mind that subkeys in the hive are sorted like key0, key1, key10, key11, ...
+        // We can create the same order by adding them to a vector and sorting that vector.
+        let mut key_names = Vec::with_capacity(512);
+        for i in 0..512 {
+            key_names.push(format!("key{}", i));
+        }
+
+        key_names.sort_unstable();
+
+        // Iterate through subkeys of "subkey-test" and prove that they are sorted just like our vector.
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("subkey-test").unwrap().unwrap();
+
+        let subkeys = key_node.subkeys().unwrap().unwrap();
+
+        for (subkey, expected_key_name) in subkeys.zip(key_names.iter()) {
+            let subkey = subkey.unwrap();
+            assert_eq!(subkey.name().unwrap(), expected_key_name.as_str());
+        }
+    }
+
+    #[test]
+    fn test_subkeys_with_prefix() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        let names = |key_node: &KeyNode<&[u8]>, prefix: &str| {
+            key_node
+                .subkeys_with_prefix(prefix)
+                .unwrap()
+                .unwrap()
+                .map(|subkey| subkey.unwrap().name().unwrap().to_string_lossy())
+                .collect::<Vec<String>>()
+        };
+
+        // The root key has a single Leaf.
+        assert_eq!(
+            names(&root_key_node, "SUB"),
+            ["subkey-test", "subpath-test"]
+        );
+        assert!(names(&root_key_node, "x").is_empty());
+
+        // "subkey-test" has an Index Root.
+        // Compare with filtering the result of `subkeys`.
+        let key_node = root_key_node.subkey("subkey-test").unwrap().unwrap();
+        let all_names = key_node
+            .subkeys()
+            .unwrap()
+            .unwrap()
+            .map(|subkey| subkey.unwrap().name().unwrap().to_string_lossy())
+            .collect::<Vec<String>>();
+
+        for prefix in ["", "key", "Key1", "KEY25", "key511", "key5110", "a", "kez"] {
+            let expected = all_names
+                .iter()
+                .filter(|name| name.to_lowercase().starts_with(&prefix.to_lowercase()))
+                .cloned()
+                .collect::<Vec<String>>();
+            assert_eq!(names(&key_node, prefix), expected, "prefix {prefix}");
+        }
+        assert_eq!(names(&key_node, "key25").len(), 11);
+
+        // Latin1 names are compared case-insensitively as well.
+        let key_node = root_key_node
+            .subkey("character-encoding-test")
+            .unwrap()
+            .unwrap();
+        assert_eq!(names(&key_node, "ÄÖ"), ["äöü"]);
+
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert!(key_node.subkeys_with_prefix("").is_none());
+    }
+
+    #[test]
+    fn test_list_capacity() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        // All lists of the testhive are allocated tightly.
+        let capacity = root_key_node.subkeys_list_capacity().unwrap().unwrap();
+        assert_eq!(capacity.allocated_size, 44);
+        assert_eq!(capacity.used_size, 44);
+        assert!(!capacity.is_oversized());
+        assert_eq!(root_key_node.stale_subkeys().unwrap().unwrap().count(), 0);
+        assert!(root_key_node.values_list_capacity().is_none());
+        assert!(root_key_node.stale_values().is_none());
+
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let capacity = key_node.values_list_capacity().unwrap().unwrap();
+        assert_eq!(capacity.excess_capacity(), 0);
+        assert!(key_node.subkeys_list_capacity().is_none());
+        let value_names = key_node
+            .values()
+            .unwrap()
+            .unwrap()
+            .map(|value| value.unwrap().name().unwrap().to_string_lossy())
+            .collect::<Vec<String>>();
+
+        let subkeys_list_offset = read_u32(
+            &testhive,
+            key_node_field(root_key_node.cell_offset(), KeyNodeField::SubkeysListOffset),
+        );
+        let subkeys_count_field = cell_data(subkeys_list_offset) + 2;
+        let values_count_field =
+            key_node_field(key_node.cell_offset(), KeyNodeField::KeyValuesCount);
+
+        // Shrink the lists without shrinking their cells.
+        testhive[subkeys_count_field..subkeys_count_field + 2].copy_from_slice(&2u16.to_le_bytes());
+        testhive[values_count_field..values_count_field + 4].copy_from_slice(&2u32.to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let capacity = root_key_node.subkeys_list_capacity().unwrap().unwrap();
+        assert_eq!(capacity.used_size, 20);
+        assert_eq!(capacity.excess_capacity(), 24);
+        assert!(capacity.is_oversized());
+
+        let stale_subkeys = root_key_node
+            .stale_subkeys()
+            .unwrap()
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let stale_names = stale_subkeys
+            .iter()
+            .map(|subkey| subkey.name().unwrap().to_string_lossy())
+            .collect::<Vec<String>>();
+        assert_eq!(stale_names, ["data-test", "subkey-test", "subpath-test"]);
+        assert!(root_key_node.subkey("data-test").is_none());
+
+        let key_node = &stale_subkeys[0];
+        let capacity = key_node.values_list_capacity().unwrap().unwrap();
+        assert_eq!(capacity.used_size, 8);
+        assert_eq!(capacity.excess_capacity(), 28);
+        assert!(capacity.is_oversized());
+
+        let stale_names = key_node
+            .stale_values()
+            .unwrap()
+            .unwrap()
+            .map(|value| value.unwrap().name().unwrap().to_string_lossy())
+            .collect::<Vec<String>>();
+        assert_eq!(stale_names, value_names[2..]);
+
+        // An excess of a single allocation granule is tolerated.
+        testhive[values_count_field..values_count_field + 4].copy_from_slice(&7u32.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node
+            .stale_subkeys()
+            .unwrap()
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let capacity = key_node.values_list_capacity().unwrap().unwrap();
+        assert_eq!(capacity.excess_capacity(), 8);
+        assert!(!capacity.is_oversized());
+        assert_eq!(key_node.stale_values().unwrap().unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_debug() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert_eq!(
+            format!("{key_node:?}"),
+            format!(
+                "KeyNode {{ name: \"data-test\", cell_offset: {:#010x} }}",
+                key_node.cell_offset()
+            )
+        );
+    }
+
+    #[test]
+    fn test_path() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(root_key_node.path().unwrap(), "");
+        assert_eq!(root_key_node.full_path().unwrap(), "\\");
+
+        let path = "subpath-test\\with-single-level-subkey\\subkey";
+        let key_node = root_key_node.subpath(path).unwrap().unwrap();
+        assert_eq!(key_node.path().unwrap(), path);
+        assert_eq!(
+            key_node.full_path().unwrap(),
+            "\\subpath-test\\with-single-level-subkey\\subkey"
+        );
+
+        let path = "character-encoding-test\\äöü";
+        let key_node = root_key_node.subpath(path).unwrap().unwrap();
+        assert_eq!(key_node.path().unwrap(), path);
+
+        let mut components = vec![root_key_node.name().unwrap()];
+        key_node.path_components(&mut components).unwrap();
+        assert_eq!(components, ["character-encoding-test", "äöü"]);
+
+        // Let the parent offset of "subpath-test" point to its own subkey "no-subkeys" to create a cycle.
+        let subpath_test = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        let subpath_test_offset = subpath_test.cell_offset();
+        let no_subkeys = subpath_test.subkey("no-subkeys").unwrap().unwrap();
+        let no_subkeys_offset = no_subkeys.cell_offset();
+        let parent_field = key_node_field(subpath_test_offset, KeyNodeField::Parent);
+        testhive[parent_field..parent_field + 4].copy_from_slice(&no_subkeys_offset.to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node
+            .subpath("subpath-test\\no-subkeys")
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            key_node.path(),
+            Err(NtHiveError::MaximumKeyDepthExceeded { .. })
+        ));
+        assert!(matches!(
+            key_node.full_path(),
+            Err(NtHiveError::MaximumKeyDepthExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn test_security_descriptor_raw() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        // Every self-relative SECURITY_DESCRIPTOR starts with revision 1 and has the SE_SELF_RELATIVE control flag.
+        let descriptor = root_key_node.security_descriptor_raw().unwrap().unwrap();
+        assert!(descriptor.len() >= 20);
+        assert_eq!(descriptor[0], 1);
+        assert_ne!(
+            u16::from_le_bytes([descriptor[2], descriptor[3]]) & 0x8000,
+            0
+        );
+
+        let key_security_offset = read_u32(
+            &testhive,
+            key_node_field(root_key_node.cell_offset(), KeyNodeField::KeySecurityOffset),
+        );
+        assert_eq!(root_key_node.security_offset(), key_security_offset);
+        assert_eq!(
+            root_key_node.key_security().unwrap().unwrap().cell_offset(),
+            key_security_offset
+        );
+        let key_security_header = cell_data(key_security_offset);
+        let cell_size =
+            (read_u32(&testhive, key_security_header - 4) as i32).unsigned_abs() as usize;
+        let descriptor_length_field = key_security_header + 16;
+
+        // A descriptor length exceeding the cell.
+        let mut corrupted_testhive = testhive.clone();
+        corrupted_testhive[descriptor_length_field..descriptor_length_field + 4]
+            .copy_from_slice(&u32::MAX.to_le_bytes());
+        let hive = Hive::new(corrupted_testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(
+            root_key_node.security_descriptor_raw(),
+            Some(Err(NtHiveError::InvalidSizeField {
+                offset: descriptor_length_field,
+                expected: u32::MAX as usize,
+                actual: cell_size - 4 - 20,
+            }))
+        );
+
+        // A descriptor length too small for a SECURITY_DESCRIPTOR.
+        let mut corrupted_testhive = testhive.clone();
+        corrupted_testhive[descriptor_length_field..descriptor_length_field + 4]
+            .copy_from_slice(&4u32.to_le_bytes());
+        let hive = Hive::new(corrupted_testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert!(matches!(
+            root_key_node.security_descriptor_raw(),
+            Some(Err(NtHiveError::InvalidDataSize {
+                expected: 20,
+                actual: 4,
+                ..
+            }))
+        ));
+
+        // A Key Security offset pointing to a cell with a different signature.
+        testhive[key_security_header..key_security_header + 2].copy_from_slice(b"xx");
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert!(matches!(
+            root_key_node.security_descriptor_raw(),
+            Some(Err(NtHiveError::InvalidTwoByteSignature { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_siblings() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert!(root_key_node.next_sibling().is_none());
+        assert!(root_key_node.prev_sibling().is_none());
+
+        // "subkey-test" has 512 subkeys spread over an Index Root.
+        // Walk through all of them in both directions and compare with the iterator.
+        let key_node = root_key_node.subkey("subkey-test").unwrap().unwrap();
+        let subkeys = key_node
+            .subkeys()
+            .unwrap()
+            .unwrap()
+            .map(|subkey| subkey.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(subkeys.len(), 512);
+
+        assert!(subkeys[0].prev_sibling().is_none());
+        assert!(subkeys[511].next_sibling().is_none());
+
+        for pair in subkeys.windows(2) {
+            let next_sibling = pair[0].next_sibling().unwrap().unwrap();
+            assert_eq!(next_sibling.cell_offset(), pair[1].cell_offset());
+
+            let prev_sibling = pair[1].prev_sibling().unwrap().unwrap();
+            assert_eq!(prev_sibling.cell_offset(), pair[0].cell_offset());
+        }
+
+        // Siblings of a key in a simple Leaf.
+        let subkey_test = root_key_node.subkey("subkey-test").unwrap().unwrap();
+        let next_sibling = subkey_test.next_sibling().unwrap().unwrap();
+        assert_eq!(next_sibling.name().unwrap(), "subpath-test");
+        assert!(next_sibling.next_sibling().is_none());
+        let prev_sibling = next_sibling.prev_sibling().unwrap().unwrap();
+        assert_eq!(prev_sibling.cell_offset(), subkey_test.cell_offset());
+
+        // Let "data-test" claim "subpath-test" as its parent, which doesn't list it as a subkey.
+        let data_test_offset = root_key_node
+            .subkey("data-test")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+        let subpath_test_offset = next_sibling.cell_offset();
+        let mut testhive = testhive;
+        write_u32(
+            &mut testhive,
+            key_node_field(data_test_offset, KeyNodeField::Parent),
+            subpath_test_offset,
+        );
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let key_node = hive
+            .root_key_node()
+            .unwrap()
+            .subkey("data-test")
+            .unwrap()
+            .unwrap();
+        for sibling in [key_node.next_sibling(), key_node.prev_sibling()] {
+            assert!(matches!(
+                sibling,
+                Some(Err(NtHiveError::KeyNodeNotInParent { offset, parent_offset }))
+                    if offset == cell_data(data_test_offset)
+                        && parent_offset == cell_data(subpath_test_offset)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_class_name() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+
+        // offreg-testhive-writer doesn't write class names.
+        assert!(key_node.class_name().is_none());
+        assert!(key_node.class_name_raw().is_none());
+
+        // Let the class name of "data-test" point to the "sz-test" data of "reg-sz".
+        let data_cell_offset = key_node
+            .value("reg-sz")
+            .unwrap()
+            .unwrap()
+            .data_cell_offset()
+            .unwrap();
+        let key_node_offset = key_node.cell_offset();
+        let class_name_length_field =
+            key_node_field(key_node_offset, KeyNodeField::ClassNameLength);
+        write_u32(
+            &mut testhive,
+            key_node_field(key_node_offset, KeyNodeField::ClassNameOffset),
+            data_cell_offset,
+        );
+        testhive[class_name_length_field..class_name_length_field + 2]
+            .copy_from_slice(&14u16.to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert_eq!(key_node.class_name().unwrap().unwrap(), "sz-test");
+        assert_eq!(
+            key_node.class_name_raw().unwrap().unwrap(),
+            b"s\0z\0-\0t\0e\0s\0t\0"
+        );
+
+        // The data cell has a size of 24 bytes, leaving 20 bytes for the class name.
+        testhive[class_name_length_field..class_name_length_field + 2]
+            .copy_from_slice(&22u16.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert_eq!(
+            key_node.class_name_raw(),
+            Some(Err(NtHiveError::InvalidSizeField {
+                offset: class_name_length_field,
+                expected: 22,
+                actual: 20,
+            }))
+        );
+        assert!(matches!(
+            key_node.class_name(),
+            Some(Err(NtHiveError::InvalidSizeField { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(
+            root_key_node.subkey_count() as usize,
+            root_key_node.subkeys().unwrap().unwrap().count()
+        );
+        assert_eq!(root_key_node.value_count(), 0);
+
+        let key_node = root_key_node.subkey("subkey-test").unwrap().unwrap();
+        assert_eq!(key_node.subkey_count(), 512);
+        assert_eq!(
+            key_node.subkey_count() as usize,
+            key_node.subkeys().unwrap().unwrap().count()
+        );
+        assert_eq!(key_node.volatile_subkey_count(), 0);
+
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert_eq!(key_node.subkey_count(), 0);
+        assert!(key_node.subkeys().is_none());
+        assert_eq!(
+            key_node.value_count() as usize,
+            key_node.values().unwrap().unwrap().count()
+        );
+
+        // Volatile subkeys are counted separately.
+        let volatile_subkey_count_offset =
+            key_node_field(key_node.cell_offset(), KeyNodeField::VolatileSubkeyCount);
+        testhive[volatile_subkey_count_offset..volatile_subkey_count_offset + 4]
+            .copy_from_slice(&3u32.to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert_eq!(key_node.volatile_subkey_count(), 3);
+        assert_eq!(key_node.subkey_count(), 0);
+        assert!(key_node.subkeys().is_none());
+    }
+
+    #[test]
+    fn test_volatile_subkeys() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert!(root_key_node.volatile_subkeys().is_none());
+
+        let root_volatile_subkeys_list_offset = key_node_field(
+            root_key_node.cell_offset(),
+            KeyNodeField::VolatileSubkeysListOffset,
+        );
+        let subkeys_list_offset = {
+            let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+            read_u32(
+                &testhive,
+                key_node_field(key_node.cell_offset(), KeyNodeField::SubkeysListOffset),
+            )
+        };
+        let set_volatile_subkeys_list_offset = |testhive: &mut [u8], cell_index: u32| {
+            testhive[root_volatile_subkeys_list_offset..root_volatile_subkeys_list_offset + 4]
+                .copy_from_slice(&cell_index.to_le_bytes());
+        };
+
+        // A cell index without the volatile bit doesn't refer to volatile storage.
+        set_volatile_subkeys_list_offset(&mut testhive, subkeys_list_offset);
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(
+            root_key_node.volatile_subkeys().unwrap().err(),
+            Some(NtHiveError::InvalidVolatileCellIndex {
+                offset: root_volatile_subkeys_list_offset,
+                cell_index: subkeys_list_offset,
+            })
+        );
+
+        // Hive files have no volatile storage.
+        let volatile_cell_index = 0x8000_0000 | subkeys_list_offset;
+        set_volatile_subkeys_list_offset(&mut testhive, volatile_cell_index);
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(
+            root_key_node.volatile_subkeys().unwrap().err(),
+            Some(NtHiveError::MissingData {
+                offset: 4096 + volatile_cell_index as usize,
+            })
+        );
+
+        // Pass a copy of the hive data as volatile storage, so that the volatile cell index refers
+        // to the subkeys list of "subpath-test".
+        let (base_block, data) = testhive.split_at(4096);
+        let hive = Hive::from_fragments(base_block, [(0, data), (0x8000_0000, data)]).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let names = root_key_node
+            .volatile_subkeys()
+            .unwrap()
+            .unwrap()
+            .map(|key_node| key_node.unwrap().name().unwrap().to_string_lossy())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            names,
+            [
+                "no-subkeys",
+                "with-single-level-subkey",
+                "with-two-levels-of-subkeys"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_statistics() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        // The longest subkey name is "character-encoding-test" (23 characters, stored as UTF-16 bytes).
+        assert_eq!(root_key_node.max_subkey_name(), 46);
+        assert_eq!(root_key_node.max_subkey_class_name(), 0);
+        assert_eq!(root_key_node.max_value_name(), 0);
+        assert_eq!(root_key_node.max_value_data(), 0);
+
+        // The longest subkey names of "subkey-test" are "key100" to "key511".
+        let key_node = root_key_node.subkey("subkey-test").unwrap().unwrap();
+        assert_eq!(key_node.max_subkey_name(), 12);
+
+        // The numbers must match the actual values of "data-test".
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_values = key_node
+            .values()
+            .unwrap()
+            .unwrap()
+            .map(|key_value| key_value.unwrap())
+            .collect::<Vec<_>>();
+        let max_value_name = key_values
+            .iter()
+            .map(|key_value| key_value.name().unwrap().to_string_lossy().len() * 2)
+            .max()
+            .unwrap();
+        let max_value_data = key_values
+            .iter()
+            .map(|key_value| key_value.data_size())
+            .max()
+            .unwrap();
+        assert_eq!(key_node.max_subkey_name(), 0);
+        assert_eq!(key_node.max_value_name() as usize, max_value_name);
+        assert_eq!(key_node.max_value_data(), max_value_data);
+    }
+
+    #[test]
+    fn test_subpath() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+
+        assert!(matches!(key_node.subpath("no-subkeys"), Some(Ok(_))));
+        assert!(matches!(key_node.subpath("\\no-subkeys"), Some(Ok(_))));
+        assert!(matches!(key_node.subpath("no-subkeys\\"), Some(Ok(_))));
+        assert!(matches!(key_node.subpath("\\no-subkeys\\"), Some(Ok(_))));
+        assert!(key_node.subpath("no-subkeys\\non-existing").is_none());
 
         assert!(matches!(
             key_node.subpath("with-single-level-subkey"),
@@ -646,4 +2848,297 @@ mod tests {
         assert!(key_node.subpath("non-existing").is_none());
         assert!(key_node.subpath("non-existing\\sub").is_none());
     }
+
+    #[test]
+    fn test_symlink() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let find_value_offset = |testhive: &[u8], key_node: &KeyNode<&[u8]>, name: &[u8]| {
+            let (count, values_list_offset) = key_node.values_list_info();
+            (0..count as usize)
+                .map(|i| read_u32(testhive, cell_data(values_list_offset) + i * 4))
+                .find(|&offset| {
+                    let name_start = cell_data(offset) + 20;
+                    &testhive[name_start..name_start + name.len()] == name
+                })
+                .unwrap()
+        };
+
+        // Turn "data-test" into a symbolic link by setting KEY_SYM_LINK and turning one of its values into
+        // a REG_LINK value named "SymbolicLinkValue".
+        // The link target is stored in the data cell of "big-data-test\A", which is large enough for any path.
+        let data_test = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert!(!data_test.is_symlink());
+        assert!(data_test.resolve_symlink().is_none());
+
+        let data_test_offset = data_test.cell_offset();
+        let link_value_offset =
+            find_value_offset(&testhive, &data_test, b"reg-sz-with-terminating-nul");
+        let big_data_test = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let a_value_offset = find_value_offset(&testhive, &big_data_test, b"A");
+        let link_data_offset = read_u32(&testhive, cell_data(a_value_offset) + 8);
+        let subpath_test_offset = root_key_node
+            .subkey("subpath-test")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+        let target_offset = root_key_node
+            .subpath("subpath-test\\with-single-level-subkey")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+
+        let set_link_target = |testhive: &mut [u8], target: &str| {
+            let target = target
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect::<Vec<u8>>();
+            let data_start = cell_data(link_data_offset);
+            testhive[data_start..data_start + target.len()].copy_from_slice(&target);
+
+            let name = b"SymbolicLinkValue";
+            let header = cell_data(link_value_offset);
+            testhive[header + 2..header + 4].copy_from_slice(&(name.len() as u16).to_le_bytes());
+            testhive[header + 4..header + 8].copy_from_slice(&(target.len() as u32).to_le_bytes());
+            testhive[header + 8..header + 12].copy_from_slice(&link_data_offset.to_le_bytes());
+            testhive[header + 12..header + 16].copy_from_slice(&6u32.to_le_bytes());
+            testhive[header + 20..header + 20 + name.len()].copy_from_slice(name);
+        };
+        testhive[key_node_field(data_test_offset, KeyNodeField::Flags)] |= 0x10;
+
+        let resolve = |testhive: &[u8]| {
+            let hive = Hive::new(testhive).unwrap();
+            let root_key_node = hive.root_key_node().unwrap();
+            let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+            assert!(key_node.is_symlink());
+            key_node
+                .resolve_symlink()
+                .unwrap()
+                .map(|key_node| key_node.cell_offset())
+        };
+
+        // Absolute path
+        set_link_target(
+            &mut testhive,
+            "\\Registry\\Machine\\TestHive\\subpath-test\\with-single-level-subkey",
+        );
+        assert_eq!(resolve(&testhive), Ok(target_offset));
+
+        // Path relative to the root of the hive
+        set_link_target(&mut testhive, "subpath-test\\with-single-level-subkey");
+        assert_eq!(resolve(&testhive), Ok(target_offset));
+
+        // Non-existing target
+        set_link_target(&mut testhive, "\\Registry\\Machine\\Other\\non-existing");
+        assert!(matches!(
+            resolve(&testhive),
+            Err(NtHiveError::SymbolicLinkTargetNotFound { .. })
+        ));
+
+        // Symbolic link pointing to itself
+        set_link_target(&mut testhive, "data-test");
+        assert!(matches!(
+            resolve(&testhive),
+            Err(NtHiveError::MaximumSymbolicLinkDepthExceeded { .. })
+        ));
+
+        // Symbolic link without a SymbolicLinkValue
+        testhive[key_node_field(subpath_test_offset, KeyNodeField::Flags)] |= 0x10;
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        assert!(matches!(
+            key_node.resolve_symlink(),
+            Some(Err(NtHiveError::MissingSymbolicLinkValue { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+
+        // 2023-01-18 11:39:48 UTC, when testhive was last regenerated.
+        assert_eq!(key_node.timestamp(), 0x01d9_2b31_91ac_e8c1);
+
+        #[cfg(feature = "time")]
+        {
+            let last_written = key_node.last_written().unwrap();
+            assert_eq!(last_written.date().to_string(), "2023-01-18");
+            assert_eq!(
+                (
+                    last_written.hour(),
+                    last_written.minute(),
+                    last_written.second()
+                ),
+                (11, 39, 48)
+            );
+        }
+    }
+
+    #[test]
+    fn test_root_key_name() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(hive.root_key_name().unwrap(), "ROOT");
+        assert_eq!(hive.root_key_timestamp().unwrap(), 0x01d9_2b31_91ac_e8c1);
+        assert_eq!(
+            hive.root_key_timestamp().unwrap(),
+            root_key_node.timestamp()
+        );
+
+        // A broken signature of the root cell is an error.
+        let root_header = cell_data(root_key_node.cell_offset());
+        testhive[root_header..root_header + 2].copy_from_slice(b"xx");
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert!(matches!(
+            hive.root_key_name(),
+            Err(NtHiveError::InvalidTwoByteSignature { offset, .. }) if offset == root_header
+        ));
+        assert!(hive.root_key_timestamp().is_err());
+    }
+
+    #[test]
+    fn test_flags() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert_eq!(root_key_node.flags(), KeyNodeFlags::KEY_COMP_NAME);
+
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        let flags_offset = key_node_field(key_node.cell_offset(), KeyNodeField::Flags);
+
+        // Set KEY_SYM_LINK and an unknown bit.
+        testhive[flags_offset] |= 0x10;
+        testhive[flags_offset + 1] |= 0x80;
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        assert_eq!(
+            key_node.flags(),
+            KeyNodeFlags::KEY_COMP_NAME | KeyNodeFlags::KEY_SYM_LINK
+        );
+        assert!(key_node.is_symlink());
+        assert!(key_node.has_compressed_name());
+        assert!(!key_node.is_volatile());
+        assert!(!key_node.is_hive_entry());
+        assert!(!key_node.cannot_be_deleted());
+
+        // Replace all flags by KEY_IS_VOLATILE, KEY_HIVE_ENTRY, and KEY_NO_DELETE.
+        // KEY_COMP_NAME must be kept for the name lookup to succeed.
+        testhive[flags_offset] = 0x2d;
+        testhive[flags_offset + 1] = 0;
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        assert!(key_node.is_volatile());
+        assert!(key_node.is_hive_entry());
+        assert!(key_node.cannot_be_deleted());
+        assert!(!key_node.is_symlink());
+    }
+
+    #[test]
+    fn test_predefined_handle() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        assert_eq!(key_node.predefined_handle(), None);
+
+        // Turn the key into a predefined handle for HKEY_LOCAL_MACHINE.
+        let key_node_offset = key_node.cell_offset();
+        testhive[key_node_field(key_node_offset, KeyNodeField::Flags)] |= 0x40;
+        write_u32(
+            &mut testhive,
+            key_node_field(key_node_offset, KeyNodeField::KeyValuesCount),
+            0x8000_0002,
+        );
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        assert!(key_node.flags().contains(KeyNodeFlags::KEY_PREDEF_HANDLE));
+        assert_eq!(key_node.predefined_handle(), Some(0x8000_0002));
+    }
+
+    #[test]
+    fn test_parent() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert!(root_key_node.parent().is_none());
+
+        let subpath_test = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        let no_subkeys = subpath_test.subkey("no-subkeys").unwrap().unwrap();
+
+        let parent = no_subkeys.parent().unwrap().unwrap();
+        assert_eq!(parent.cell_offset(), subpath_test.cell_offset());
+        assert_eq!(parent.name().unwrap(), "subpath-test");
+
+        let parent = parent.parent().unwrap().unwrap();
+        assert_eq!(parent.cell_offset(), root_key_node.cell_offset());
+        assert!(parent.parent().is_none());
+
+        // Destroy the signature of "subpath-test" and ask "no-subkeys" for its parent again.
+        let no_subkeys_offset = no_subkeys.cell_offset();
+        let subpath_test_offset = subpath_test.cell_offset();
+        let signature_offset = key_node_field(subpath_test_offset, KeyNodeField::Signature);
+        testhive[signature_offset..signature_offset + 2].copy_from_slice(b"xx");
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let cell_range = hive.cell_range_from_data_offset(no_subkeys_offset).unwrap();
+        let no_subkeys = KeyNode::from_cell_range(&hive, cell_range).unwrap();
+        assert!(matches!(
+            no_subkeys.parent(),
+            Some(Err(NtHiveError::InvalidTwoByteSignature { .. }))
+        ));
+        testhive[signature_offset..signature_offset + 2].copy_from_slice(b"nk");
+
+        // The parent of a key with the KEY_HIVE_ENTRY flag is never followed.
+        let flags_offset = key_node_field(subpath_test_offset, KeyNodeField::Flags);
+        testhive[flags_offset] |= 0x04;
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let subpath_test = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        assert!(subpath_test.parent().is_none());
+
+        // The parent offset of the root key is never followed, even if it is bogus.
+        let root_parent_field = key_node_field(root_key_node.cell_offset(), KeyNodeField::Parent);
+        testhive[root_parent_field..root_parent_field + 4].copy_from_slice(&[0xff; 4]);
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert!(root_key_node.parent().is_none());
+    }
+
+    #[test]
+    fn test_is_root() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let subpath_test_offset = root_key_node
+            .subkey("subpath-test")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+
+        // The Offline Registry Library doesn't set the KEY_HIVE_ENTRY flag for the root key.
+        // Set it for another key instead and prove that this doesn't make that key a root key.
+        let flags_offset = key_node_field(subpath_test_offset, KeyNodeField::Flags);
+        testhive[flags_offset] |= 0x04;
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert!(root_key_node.is_root());
+
+        let key_node = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        assert!(!key_node.is_root());
+    }
 }
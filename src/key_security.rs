@@ -0,0 +1,777 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::Range;
+
+use zerocopy::byteorder::LittleEndian;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, Unaligned, U16, U32,
+};
+
+use crate::error::{NtHiveError, Result};
+use crate::helpers::byte_subrange;
+use crate::hive::{CellHeader, Hive};
+use crate::security_descriptor::SecurityDescriptor;
+
+#[cfg(feature = "alloc")]
+use {
+    crate::dump::StructLayout,
+    crate::walker::KeyNodeWalker,
+    alloc::{collections::BTreeMap, vec::Vec},
+    core::iter,
+};
+
+/// Size of the fixed part of a self-relative SECURITY_DESCRIPTOR
+/// (Revision, Sbz1, Control, and the offsets of Owner, Group, Sacl, Dacl).
+const SECURITY_DESCRIPTOR_MIN_LENGTH: usize = 20;
+
+/// On-Disk Structure of a Key Security header.
+#[allow(dead_code)]
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct KeySecurityHeader {
+    signature: [u8; 2],
+    reserved: U16<LittleEndian>,
+    flink: U32<LittleEndian>,
+    blink: U32<LittleEndian>,
+    reference_count: U32<LittleEndian>,
+    descriptor_length: U32<LittleEndian>,
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) const KEY_SECURITY_HEADER_LAYOUT: StructLayout = struct_layout!(KeySecurityHeader {
+    signature,
+    reserved,
+    flink,
+    blink,
+    reference_count,
+    descriptor_length,
+});
+
+/// Byte range of a single Key Security cell.
+/// Key Security cells are shared between all Key Nodes with the same security descriptor.
+///
+/// On-Disk Signature: `sk`
+#[derive(Clone)]
+pub(crate) struct KeySecurityItemRange {
+    header_range: Range<usize>,
+    data_range: Range<usize>,
+}
+
+impl KeySecurityItemRange {
+    pub(crate) fn from_cell_range<B>(hive: &Hive<B>, cell_range: Range<usize>) -> Result<Self>
+    where
+        B: SplitByteSlice,
+    {
+        let header_range = byte_subrange(&cell_range, mem::size_of::<KeySecurityHeader>())
+            .ok_or_else(|| NtHiveError::InvalidHeaderSize {
+                offset: hive.offset_of_data_offset(cell_range.start),
+                expected: mem::size_of::<KeySecurityHeader>(),
+                actual: cell_range.len(),
+            })?;
+        let data_range = header_range.end..cell_range.end;
+
+        let key_security_item_range = Self {
+            header_range,
+            data_range,
+        };
+        key_security_item_range.validate_signature(hive)?;
+
+        Ok(key_security_item_range)
+    }
+
+    fn blink<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        header.blink.get()
+    }
+
+    fn cell_offset(&self) -> u32 {
+        // Every Key Security header directly follows the header of the cell containing it.
+        (self.header_range.start - mem::size_of::<CellHeader>()) as u32
+    }
+
+    /// Returns the range of the self-relative SECURITY_DESCRIPTOR stored in this Key Security cell.
+    pub(crate) fn descriptor_range<B>(&self, hive: &Hive<B>) -> Result<Range<usize>>
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        let descriptor_length = header.descriptor_length.get() as usize;
+
+        let descriptor_range =
+            byte_subrange(&self.data_range, descriptor_length).ok_or_else(|| {
+                NtHiveError::InvalidSizeField {
+                    offset: hive.offset_of_field(&header.descriptor_length),
+                    expected: descriptor_length,
+                    actual: self.data_range.len(),
+                }
+            })?;
+
+        if descriptor_length < SECURITY_DESCRIPTOR_MIN_LENGTH {
+            return Err(NtHiveError::InvalidDataSize {
+                offset: hive.offset_of_data_offset(descriptor_range.start),
+                expected: SECURITY_DESCRIPTOR_MIN_LENGTH,
+                actual: descriptor_length,
+            });
+        }
+
+        Ok(descriptor_range)
+    }
+
+    fn slack<'h, B>(&self, hive: &'h Hive<B>) -> Result<&'h [u8]>
+    where
+        B: SplitByteSlice,
+    {
+        let descriptor_range = self.descriptor_range(hive)?;
+        Ok(hive.data_slice(descriptor_range.end..self.data_range.end))
+    }
+
+    fn descriptor_length<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        header.descriptor_length.get()
+    }
+
+    fn flink<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        header.flink.get()
+    }
+
+    fn header<'h, B>(&self, hive: &'h Hive<B>) -> Ref<&'h [u8], KeySecurityHeader>
+    where
+        B: SplitByteSlice,
+    {
+        Ref::from_bytes(hive.data_slice(self.header_range.clone())).unwrap()
+    }
+
+    fn reference_count<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        header.reference_count.get()
+    }
+
+    fn validate_signature<B>(&self, hive: &Hive<B>) -> Result<()>
+    where
+        B: SplitByteSlice,
+    {
+        let header = self.header(hive);
+        let signature = &header.signature;
+        let expected_signature = b"sk";
+
+        if signature == expected_signature {
+            Ok(())
+        } else {
+            Err(NtHiveError::InvalidTwoByteSignature {
+                offset: hive.offset_of_field(signature),
+                expected: expected_signature,
+                actual: *signature,
+            })
+        }
+    }
+}
+
+/// A security descriptor of a [`Hive`], shared between all keys that reference it.
+///
+/// All Key Security cells of a hive form a circular doubly-linked list.
+/// [`KeySecurity::next`] and [`KeySecurity::prev`] follow this list by a single step,
+/// [`KeySecurity::list`] iterates over it.
+///
+/// On-Disk Signature: `sk`
+#[doc(alias = "SecurityKey")]
+pub struct KeySecurity<'h, B: SplitByteSlice> {
+    hive: &'h Hive<B>,
+    item_range: KeySecurityItemRange,
+}
+
+// Implemented manually, because `#[derive(Clone)]` would needlessly require `B: Clone`.
+impl<B> Clone for KeySecurity<'_, B>
+where
+    B: SplitByteSlice,
+{
+    fn clone(&self) -> Self {
+        Self {
+            hive: self.hive,
+            item_range: self.item_range.clone(),
+        }
+    }
+}
+
+impl<'h, B> KeySecurity<'h, B>
+where
+    B: SplitByteSlice,
+{
+    pub(crate) fn from_cell_range(hive: &'h Hive<B>, cell_range: Range<usize>) -> Result<Self> {
+        let item_range = KeySecurityItemRange::from_cell_range(hive, cell_range)?;
+        Ok(Self { hive, item_range })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn from_data_offset(hive: &'h Hive<B>, data_offset: u32) -> Result<Self> {
+        let cell_range = hive.cell_range_from_data_offset(data_offset)?;
+        Self::from_cell_range(hive, cell_range)
+    }
+
+    /// Returns the data offset of the previous Key Security cell, as stored in the header.
+    pub fn blink(&self) -> u32 {
+        self.item_range.blink(self.hive)
+    }
+
+    /// Returns the data offset of the cell containing this Key Security header.
+    pub fn cell_offset(&self) -> u32 {
+        self.item_range.cell_offset()
+    }
+
+    /// Returns the length of the security descriptor in bytes, as stored in the header.
+    pub fn descriptor_length(&self) -> u32 {
+        self.item_range.descriptor_length(self.hive)
+    }
+
+    /// Returns the self-relative SECURITY_DESCRIPTOR, parsed as a [`SecurityDescriptor`].
+    pub fn descriptor(&self) -> Result<SecurityDescriptor<'h>> {
+        let descriptor_range = self.item_range.descriptor_range(self.hive)?;
+        let offset = self.hive.offset_of_data_offset(descriptor_range.start);
+        SecurityDescriptor::with_offset(self.hive.data_slice(descriptor_range), offset)
+    }
+
+    /// Returns the raw self-relative SECURITY_DESCRIPTOR.
+    ///
+    /// The returned bytes can be passed to any library that parses Windows security descriptors.
+    #[doc(alias = "descriptor_bytes")]
+    pub fn descriptor_raw(&self) -> Result<&'h [u8]> {
+        let descriptor_range = self.item_range.descriptor_range(self.hive)?;
+        Ok(self.hive.data_slice(descriptor_range))
+    }
+
+    /// Returns the data offset of the next Key Security cell, as stored in the header.
+    pub fn flink(&self) -> u32 {
+        self.item_range.flink(self.hive)
+    }
+
+    /// Returns an iterator over all Key Security cells of the hive, beginning with this one.
+    ///
+    /// The list is followed in forward direction until it leads back to this Key Security cell.
+    pub fn list(&self) -> KeySecurities<'h, B> {
+        KeySecurities::new(self.clone())
+    }
+
+    /// Returns the next Key Security cell in the list, as referenced by [`KeySecurity::flink`].
+    ///
+    /// The list is circular, so this is the first Key Security cell of the hive if this is the last one.
+    pub fn next(&self) -> Result<Self> {
+        let header = self.item_range.header(self.hive);
+        let cell_range = self.hive.cell_range_from_offset_field(
+            header.flink.get(),
+            self.hive.offset_of_field(&header.flink),
+        )?;
+        Self::from_cell_range(self.hive, cell_range)
+    }
+
+    /// Returns the previous Key Security cell in the list, as referenced by [`KeySecurity::blink`].
+    ///
+    /// The list is circular, so this is the last Key Security cell of the hive if this is the first one.
+    pub fn prev(&self) -> Result<Self> {
+        let header = self.item_range.header(self.hive);
+        let cell_range = self.hive.cell_range_from_offset_field(
+            header.blink.get(),
+            self.hive.offset_of_field(&header.blink),
+        )?;
+        Self::from_cell_range(self.hive, cell_range)
+    }
+
+    /// Returns the number of keys referencing this Key Security cell, as stored in the header.
+    pub fn reference_count(&self) -> u32 {
+        self.item_range.reference_count(self.hive)
+    }
+
+    /// Returns the bytes between the end of the security descriptor and the end of the Key Security cell.
+    pub(crate) fn slack(&self) -> Result<&'h [u8]> {
+        self.item_range.slack(self.hive)
+    }
+}
+
+/// Iterator over
+///   all Key Security cells of a hive,
+///   returning a [`KeySecurity`] for each cell.
+///
+/// This iterator is returned by [`KeySecurity::list`].
+/// The blink of every Key Security cell must point back to the Key Security cell preceding it.
+/// This also guarantees that the list leads back to its start, so a list corrupted into an endless
+/// loop results in [`NtHiveError::InvalidKeySecurityBlink`].
+/// Iteration stops after the first error.
+pub struct KeySecurities<'h, B: SplitByteSlice> {
+    start: KeySecurity<'h, B>,
+    previous: Option<KeySecurity<'h, B>>,
+    finished: bool,
+}
+
+impl<'h, B> KeySecurities<'h, B>
+where
+    B: SplitByteSlice,
+{
+    fn new(start: KeySecurity<'h, B>) -> Self {
+        Self {
+            start,
+            previous: None,
+            finished: false,
+        }
+    }
+}
+
+impl<'h, B> Iterator for KeySecurities<'h, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<KeySecurity<'h, B>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let Some(previous) = &self.previous else {
+            self.previous = Some(self.start.clone());
+            return Some(Ok(self.start.clone()));
+        };
+
+        if previous.flink() == self.start.cell_offset() {
+            // We are back at the start of the list.
+            self.finished = true;
+            return None;
+        }
+
+        let result = previous.next().and_then(|key_security| {
+            let header = key_security.item_range.header(key_security.hive);
+            let blink = header.blink.get();
+
+            if blink == previous.cell_offset() {
+                Ok(key_security)
+            } else {
+                Err(NtHiveError::InvalidKeySecurityBlink {
+                    offset: key_security.hive.offset_of_field(&header.blink),
+                    expected: previous.cell_offset(),
+                    actual: blink,
+                })
+            }
+        });
+
+        match result {
+            Ok(key_security) => {
+                self.previous = Some(key_security.clone());
+                Some(Ok(key_security))
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<B> FusedIterator for KeySecurities<'_, B> where B: SplitByteSlice {}
+
+/// Iterator over
+///   all security descriptors of a hive,
+///   returning the [`KeySecurity`] cell containing each security descriptor.
+///
+/// Created by [`Hive::security_descriptors`].
+/// This follows the list of Key Security cells like [`KeySecurities`] does, but additionally validates
+/// the descriptor length of each Key Security cell.
+/// A Key Security cell whose descriptor exceeds the cell is returned as an error, and iteration
+/// continues with the next one.
+pub struct SecurityDescriptors<'h, B: SplitByteSlice> {
+    key_securities: Option<KeySecurities<'h, B>>,
+}
+
+impl<'h, B> Iterator for SecurityDescriptors<'h, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<KeySecurity<'h, B>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key_security = iter_try!(self.key_securities.as_mut()?.next()?);
+        iter_try!(key_security.descriptor_raw());
+        Some(Ok(key_security))
+    }
+}
+
+impl<B> FusedIterator for SecurityDescriptors<'_, B> where B: SplitByteSlice {}
+
+/// A Key Security cell whose reference count doesn't match the number of keys referencing it,
+/// as returned by [`Hive::verify_security_references`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SecurityReferenceMismatch {
+    /// Data offset of the Key Security cell.
+    pub offset: u32,
+    /// Reference count stored in the Key Security cell.
+    pub claimed_count: u32,
+    /// Number of keys actually referencing the Key Security cell.
+    pub actual_count: u32,
+}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns an iterator over all security descriptors of this hive.
+    ///
+    /// Every security descriptor is stored only once in a Key Security cell, which is shared between
+    /// all keys with that security descriptor.
+    /// This iterator follows the list of Key Security cells, starting at the one of the root key,
+    /// so no keys need to be visited.
+    pub fn security_descriptors(&self) -> Result<SecurityDescriptors<'_, B>> {
+        let key_security = self.root_key_node()?.key_security().transpose()?;
+        let key_securities = key_security.map(|key_security| key_security.list());
+
+        Ok(SecurityDescriptors { key_securities })
+    }
+
+    /// Compares the reference count of every Key Security cell with the number of keys referencing it.
+    ///
+    /// This visits all keys of the hive and returns a [`SecurityReferenceMismatch`] for every Key Security cell
+    /// with a wrong reference count.
+    /// Key Security cells that are not referenced by any key are found by following the list of Key Security
+    /// cells (see [`Hive::security_descriptors`]).
+    /// Conversely, Key Security cells that are referenced by keys, but missing from that list, are reported too.
+    /// An empty result means that all reference counts are consistent.
+    #[cfg(feature = "alloc")]
+    pub fn verify_security_references(&self) -> Result<Vec<SecurityReferenceMismatch>> {
+        let root_key_node = self.root_key_node()?;
+        let mut actual_counts = BTreeMap::<u32, u32>::new();
+
+        let walker = KeyNodeWalker::new(&root_key_node)?;
+        for key_node in iter::once(Ok(root_key_node.clone())).chain(walker) {
+            if let Some(key_security) = key_node?.key_security() {
+                let count = actual_counts
+                    .entry(key_security?.cell_offset())
+                    .or_default();
+                *count = count.saturating_add(1);
+            }
+        }
+
+        let mut mismatches = Vec::new();
+        let mut check = |key_security: &KeySecurity<B>, actual_count: u32| {
+            let claimed_count = key_security.reference_count();
+            if claimed_count != actual_count {
+                mismatches.push(SecurityReferenceMismatch {
+                    offset: key_security.cell_offset(),
+                    claimed_count,
+                    actual_count,
+                });
+            }
+        };
+
+        if let Some(key_security) = root_key_node.key_security() {
+            for key_security in key_security?.list() {
+                let key_security = key_security?;
+                let actual_count = actual_counts
+                    .remove(&key_security.cell_offset())
+                    .unwrap_or(0);
+                check(&key_security, actual_count);
+            }
+        }
+
+        // Everything left has been referenced by a key, but is not part of the list.
+        for (offset, actual_count) in actual_counts {
+            let key_security = KeySecurity::from_data_offset(self, offset)?;
+            check(&key_security, actual_count);
+        }
+
+        Ok(mismatches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::{cell_data, key_node_field};
+    use crate::key_node::KeyNodeField;
+    use crate::*;
+
+    /// Data offset of a free cell in the testhive, which is large enough for a Key Security cell.
+    const FREE_CELL_OFFSET: u32 = 0xf20;
+
+    /// Turns the free cell at [`FREE_CELL_OFFSET`] into a second Key Security cell with a copy of
+    /// the root key's descriptor and links it with the Key Security cell of the root key.
+    fn insert_key_security(testhive: &mut [u8], reference_count: u32) {
+        let hive = Hive::new(&*testhive).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_security = root_key_node.key_security().unwrap().unwrap();
+        let root_key_security_offset = key_security.cell_offset();
+        let descriptor = key_security.descriptor_raw().unwrap().to_vec();
+
+        let cell = 4096 + FREE_CELL_OFFSET as usize;
+        assert_eq!(testhive[cell..cell + 4], 224i32.to_le_bytes());
+        testhive[cell..cell + 4].copy_from_slice(&(-224i32).to_le_bytes());
+
+        let header = cell + 4;
+        testhive[header..header + 4].copy_from_slice(b"sk\0\0");
+        testhive[header + 4..header + 8].copy_from_slice(&root_key_security_offset.to_le_bytes());
+        testhive[header + 8..header + 12].copy_from_slice(&root_key_security_offset.to_le_bytes());
+        testhive[header + 12..header + 16].copy_from_slice(&reference_count.to_le_bytes());
+        testhive[header + 16..header + 20]
+            .copy_from_slice(&(descriptor.len() as u32).to_le_bytes());
+        testhive[header + 20..header + 20 + descriptor.len()].copy_from_slice(&descriptor);
+
+        let root_header = cell_data(root_key_security_offset);
+        testhive[root_header + 4..root_header + 8].copy_from_slice(&FREE_CELL_OFFSET.to_le_bytes());
+        testhive[root_header + 8..root_header + 12]
+            .copy_from_slice(&FREE_CELL_OFFSET.to_le_bytes());
+    }
+
+    #[test]
+    fn test_key_security() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        // The Offline Registry Library shares a single Key Security cell between all keys.
+        let key_security = root_key_node.key_security().unwrap().unwrap();
+        let root_key_security_offset = key_security.cell_offset();
+        let reference_count = key_security.reference_count();
+        let descriptor = key_security.descriptor_raw().unwrap().to_vec();
+        assert_eq!(key_security.descriptor_length() as usize, descriptor.len());
+        assert_eq!(key_security.flink(), root_key_security_offset);
+        assert_eq!(key_security.blink(), root_key_security_offset);
+        assert_eq!(key_security.list().count(), 1);
+
+        let key_security_field = |key_node: KeyNode<&[u8]>| {
+            key_node_field(key_node.cell_offset(), KeyNodeField::KeySecurityOffset)
+        };
+        let data_test_field =
+            key_security_field(root_key_node.subkey("data-test").unwrap().unwrap());
+        let subkey_test_field =
+            key_security_field(root_key_node.subkey("subkey-test").unwrap().unwrap());
+
+        // Add a second Key Security cell with the same descriptor and let "data-test" and
+        // "subkey-test" reference it.
+        insert_key_security(&mut testhive, 2);
+        let new_key_security_offset = FREE_CELL_OFFSET;
+        let header = cell_data(new_key_security_offset);
+
+        let root_header = cell_data(root_key_security_offset);
+        testhive[root_header + 12..root_header + 16]
+            .copy_from_slice(&(reference_count - 2).to_le_bytes());
+
+        for field in [data_test_field, subkey_test_field] {
+            testhive[field..field + 4].copy_from_slice(&new_key_security_offset.to_le_bytes());
+        }
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_security = root_key_node.key_security().unwrap().unwrap();
+        assert_eq!(key_security.cell_offset(), root_key_security_offset);
+        assert_eq!(key_security.reference_count(), reference_count - 2);
+        assert_eq!(
+            key_security.next().unwrap().cell_offset(),
+            new_key_security_offset
+        );
+        assert_eq!(
+            key_security.prev().unwrap().cell_offset(),
+            new_key_security_offset
+        );
+
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_security = key_node.key_security().unwrap().unwrap();
+        assert_eq!(key_security.cell_offset(), new_key_security_offset);
+        assert_eq!(key_security.reference_count(), 2);
+        assert_eq!(key_security.descriptor_raw().unwrap(), descriptor);
+        assert_eq!(
+            key_node.security_descriptor_raw().unwrap().unwrap(),
+            descriptor
+        );
+
+        // The list can be walked starting from any Key Security cell.
+        let key_node = root_key_node.subkey("subkey-test").unwrap().unwrap();
+        let key_security = key_node.key_security().unwrap().unwrap();
+        let offsets = key_security
+            .list()
+            .map(|key_security| key_security.unwrap().cell_offset())
+            .collect::<Vec<u32>>();
+        assert_eq!(offsets, [new_key_security_offset, root_key_security_offset]);
+
+        // Let the second Key Security cell point to itself, so that the list never leads back
+        // to the first one.
+        testhive[header + 4..header + 8].copy_from_slice(&new_key_security_offset.to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_security = root_key_node.key_security().unwrap().unwrap();
+        let mut list = key_security.list();
+        assert_eq!(
+            list.next().unwrap().unwrap().cell_offset(),
+            root_key_security_offset
+        );
+        assert_eq!(
+            list.next().unwrap().unwrap().cell_offset(),
+            new_key_security_offset
+        );
+        assert_eq!(
+            list.next().unwrap().err(),
+            Some(NtHiveError::InvalidKeySecurityBlink {
+                offset: header + 8,
+                expected: new_key_security_offset,
+                actual: root_key_security_offset,
+            })
+        );
+        assert!(list.next().is_none());
+    }
+
+    #[test]
+    fn test_security_descriptors() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let root_key_security = root_key_node.key_security().unwrap().unwrap();
+        let root_key_security_offset = root_key_security.cell_offset();
+        let reference_count = root_key_security.reference_count();
+        let descriptor = root_key_security.descriptor_raw().unwrap().to_vec();
+
+        let collect = |hive: &Hive<&[u8]>| {
+            hive.security_descriptors()
+                .unwrap()
+                .map(|key_security| {
+                    key_security.map(|key_security| {
+                        (
+                            key_security.cell_offset(),
+                            key_security.reference_count(),
+                            key_security.descriptor_raw().unwrap().to_vec(),
+                        )
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            collect(&hive),
+            [Ok((
+                root_key_security_offset,
+                reference_count,
+                descriptor.clone()
+            ))]
+        );
+
+        insert_key_security(&mut testhive, 0);
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(
+            collect(&hive),
+            [
+                Ok((
+                    root_key_security_offset,
+                    reference_count,
+                    descriptor.clone()
+                )),
+                Ok((FREE_CELL_OFFSET, 0, descriptor.clone())),
+            ]
+        );
+
+        // A descriptor exceeding its cell is reported, but iteration continues.
+        let root_descriptor_length_field = cell_data(root_key_security_offset) + 16;
+        testhive[root_descriptor_length_field..root_descriptor_length_field + 4]
+            .copy_from_slice(&1000u32.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let descriptors = collect(&hive);
+        assert_eq!(descriptors.len(), 2);
+        assert!(matches!(
+            descriptors[0],
+            Err(NtHiveError::InvalidSizeField { offset, expected: 1000, .. })
+                if offset == root_descriptor_length_field
+        ));
+        assert_eq!(descriptors[1], Ok((FREE_CELL_OFFSET, 0, descriptor)));
+
+        // A cycle ends the iteration.
+        let header = cell_data(FREE_CELL_OFFSET);
+        testhive[header + 4..header + 8].copy_from_slice(&FREE_CELL_OFFSET.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let descriptors = collect(&hive);
+        assert_eq!(descriptors.len(), 3);
+        assert!(matches!(
+            descriptors[2],
+            Err(NtHiveError::InvalidKeySecurityBlink { .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_security_references() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let root_key_security = root_key_node.key_security().unwrap().unwrap();
+        let root_key_security_offset = root_key_security.cell_offset();
+        let reference_count = root_key_security.reference_count();
+        let data_test_key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let data_test_field = key_node_field(
+            data_test_key_node.cell_offset(),
+            KeyNodeField::KeySecurityOffset,
+        );
+        assert_eq!(hive.verify_security_references().unwrap(), []);
+
+        // Claim one reference less than there are keys.
+        let root_reference_count_field = cell_data(root_key_security_offset) + 12;
+        testhive[root_reference_count_field..root_reference_count_field + 4]
+            .copy_from_slice(&(reference_count - 1).to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(
+            hive.verify_security_references().unwrap(),
+            [SecurityReferenceMismatch {
+                offset: root_key_security_offset,
+                claimed_count: reference_count - 1,
+                actual_count: reference_count,
+            }]
+        );
+
+        // An unreferenced Key Security cell claiming references is reported as well.
+        testhive[root_reference_count_field..root_reference_count_field + 4]
+            .copy_from_slice(&reference_count.to_le_bytes());
+        insert_key_security(&mut testhive, 2);
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(
+            hive.verify_security_references().unwrap(),
+            [SecurityReferenceMismatch {
+                offset: FREE_CELL_OFFSET,
+                claimed_count: 2,
+                actual_count: 0,
+            }]
+        );
+
+        // So is a Key Security cell that is referenced by a key, but missing from the list.
+        let header = cell_data(FREE_CELL_OFFSET);
+        for field in [header + 4, header + 8] {
+            testhive[field..field + 4].copy_from_slice(&FREE_CELL_OFFSET.to_le_bytes());
+        }
+        let root_header = cell_data(root_key_security_offset);
+        for field in [root_header + 4, root_header + 8] {
+            testhive[field..field + 4].copy_from_slice(&root_key_security_offset.to_le_bytes());
+        }
+        testhive[data_test_field..data_test_field + 4]
+            .copy_from_slice(&FREE_CELL_OFFSET.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(
+            hive.verify_security_references().unwrap(),
+            [
+                SecurityReferenceMismatch {
+                    offset: root_key_security_offset,
+                    claimed_count: reference_count,
+                    actual_count: reference_count - 1,
+                },
+                SecurityReferenceMismatch {
+                    offset: FREE_CELL_OFFSET,
+                    claimed_count: 2,
+                    actual_count: 1,
+                },
+            ]
+        );
+    }
+}
@@ -1,13 +1,15 @@
 // Copyright 2020-2025 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: GPL-2.0-or-later
 
+use core::cmp;
+use core::fmt;
 use core::mem;
 use core::ops::Range;
 use core::ptr;
+use core::str::FromStr;
 
 use bitflags::bitflags;
-use enumn::N;
-use memoffset::offset_of;
+use thiserror::Error;
 use zerocopy::byteorder::LittleEndian;
 use zerocopy::{
     FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, Unaligned, U16, U32,
@@ -15,13 +17,25 @@ use zerocopy::{
 
 use crate::big_data::{BigDataSlices, BIG_DATA_SEGMENT_SIZE};
 use crate::error::{NtHiveError, Result};
-use crate::helpers::byte_subrange;
-use crate::hive::Hive;
+use crate::helpers::{byte_subrange, Crc32};
+use crate::hive::{CellHeader, Hive};
 use crate::string::NtHiveNameString;
 
+#[cfg(feature = "std")]
+use {
+    crate::filetime::filetime_to_system_time,
+    std::io::{self, Read},
+    std::time::SystemTime,
+};
+
+#[cfg(feature = "time")]
+use {crate::filetime::filetime_to_offset_date_time, time::OffsetDateTime};
+
 #[cfg(feature = "alloc")]
 use {
-    alloc::{string::String, vec::Vec},
+    crate::big_data::BigDataGap,
+    crate::dump::StructLayout,
+    alloc::{borrow::Cow, string::String, vec::Vec},
     core::{
         char::{self, DecodeUtf16, DecodeUtf16Error},
         iter::{self, FusedIterator, Map},
@@ -33,6 +47,7 @@ use {
 const DATA_STORED_IN_DATA_OFFSET: u32 = 0x8000_0000;
 
 bitflags! {
+    #[derive(Clone, Copy)]
     struct KeyValueFlags: u16 {
         /// The name is in (extended) ASCII instead of UTF-16LE.
         const VALUE_COMP_NAME = 0x0001;
@@ -50,16 +65,176 @@ pub enum KeyValueData<'h, B: SplitByteSlice> {
     Big(BigDataSlices<'h, B>),
 }
 
-impl<B> KeyValueData<'_, B>
+impl<'h, B> KeyValueData<'h, B>
 where
     B: SplitByteSlice,
 {
+    /// Compares the data bytes with `expected` without copying any data.
+    pub fn eq_bytes(&self, expected: &[u8]) -> Result<bool> {
+        if self.len() != expected.len() {
+            return Ok(false);
+        }
+
+        let mut remaining = expected;
+
+        for slice_data in self.slices() {
+            let slice_data = slice_data?;
+            let (expected_slice_data, rest) = remaining.split_at(slice_data.len());
+            if slice_data != expected_slice_data {
+                return Ok(false);
+            }
+
+            remaining = rest;
+        }
+
+        Ok(true)
+    }
+
+    /// Compares the data bytes with those of `other` without copying any data.
+    ///
+    /// This works for any combination of Small and Big data.
+    /// `other` may even come from a different hive.
+    pub fn eq_data<B2>(&self, other: &KeyValueData<'_, B2>) -> Result<bool>
+    where
+        B2: SplitByteSlice,
+    {
+        if self.len() != other.len() {
+            return Ok(false);
+        }
+
+        // The segment boundaries of both sides don't need to match.
+        // Hence, we always compare the largest window that is available on both sides
+        // and fetch the next segment from the side that has been exhausted.
+        let mut left_slices = self.slices();
+        let mut right_slices = other.slices();
+        let mut left: &[u8] = &[];
+        let mut right: &[u8] = &[];
+
+        loop {
+            while left.is_empty() {
+                match left_slices.next() {
+                    Some(slice_data) => left = slice_data?,
+                    None => break,
+                }
+            }
+
+            while right.is_empty() {
+                match right_slices.next() {
+                    Some(slice_data) => right = slice_data?,
+                    None => break,
+                }
+            }
+
+            if left.is_empty() || right.is_empty() {
+                // We have checked that both sides have the same length,
+                // so they must be exhausted at the same time.
+                return Ok(left.is_empty() && right.is_empty());
+            }
+
+            let window_size = cmp::min(left.len(), right.len());
+            if left[..window_size] != right[..window_size] {
+                return Ok(false);
+            }
+
+            left = &left[window_size..];
+            right = &right[window_size..];
+        }
+    }
+
+    /// Returns `true` if the data bytes contain `needle` anywhere.
+    ///
+    /// Big Data is searched segment by segment, including matches that span segment boundaries.
+    /// Only the last `needle.len() - 1` bytes of the previous segment are kept around for that.
+    /// An empty `needle` is contained in any data.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn contains_bytes(&self, needle: &[u8]) -> Result<bool> {
+        if needle.is_empty() {
+            return Ok(true);
+        }
+
+        let overlap_size = needle.len() - 1;
+        let mut boundary = Vec::with_capacity(2 * overlap_size);
+
+        for slice_data in self.slices() {
+            let slice_data = slice_data?;
+
+            // Check for a match spanning the boundary between the previous segments and this one.
+            boundary.extend_from_slice(&slice_data[..cmp::min(overlap_size, slice_data.len())]);
+            if contains_subslice(&boundary, needle) || contains_subslice(slice_data, needle) {
+                return Ok(true);
+            }
+
+            // Keep only the bytes that may still be part of a match.
+            if slice_data.len() >= overlap_size {
+                boundary.clear();
+                boundary.extend_from_slice(&slice_data[slice_data.len() - overlap_size..]);
+            } else {
+                let excess = boundary.len().saturating_sub(overlap_size);
+                boundary.drain(..excess);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            KeyValueData::Small(data) => data.len(),
+            KeyValueData::Big(iter) => iter.bytes_left(),
+        }
+    }
+
+    /// Returns an iterator over the data slices, no matter if this is Small or Big data.
+    fn slices(&self) -> impl Iterator<Item = Result<&'h [u8]>> {
+        let (small, big) = match self {
+            KeyValueData::Small(data) => (Some(Ok(*data)), None),
+            KeyValueData::Big(iter) => (None, Some(iter.clone())),
+        };
+
+        small.into_iter().chain(big.into_iter().flatten())
+    }
+
+    /// Copies all readable data bytes into a vector, filling unreadable Big Data segments with zeros.
+    ///
+    /// Returns the data along with a [`BigDataGap`] for every segment that could not be read.
+    /// The data always has the full length, so the ranges of the gaps can be used to index it.
+    /// See [`BigDataSlicesLossy`] for details.
+    ///
+    /// [`BigDataSlicesLossy`]: crate::big_data::BigDataSlicesLossy
+    #[cfg(feature = "alloc")]
+    pub fn into_vec_lossy(self) -> (Vec<u8>, Vec<BigDataGap>) {
+        match self {
+            KeyValueData::Small(data) => (data.to_vec(), Vec::new()),
+            KeyValueData::Big(iter) => {
+                let data_size = iter.bytes_left();
+                let mut data = Vec::with_capacity(data_size);
+                let mut gaps = Vec::new();
+
+                for slice_data in iter.into_lossy() {
+                    match slice_data {
+                        Ok(slice_data) => data.extend_from_slice(slice_data),
+                        Err(gap) => {
+                            data.resize(gap.data_range.end, 0);
+                            gaps.push(gap);
+                        }
+                    }
+                }
+
+                // Every byte up to the declared data size must either have been read or be part of a gap.
+                debug_assert_eq!(data.len(), data_size);
+                data.resize(data_size, 0);
+
+                (data, gaps)
+            }
+        }
+    }
+
     #[cfg(feature = "alloc")]
     pub fn into_vec(self) -> Result<Vec<u8>> {
         match self {
             KeyValueData::Small(data) => Ok(data.to_vec()),
             KeyValueData::Big(iter) => {
-                let mut data = Vec::new();
+                let mut data = Vec::with_capacity(iter.bytes_left());
 
                 for slice_data in iter {
                     let slice_data = slice_data?;
@@ -70,10 +245,72 @@ where
             }
         }
     }
+
+    /// Turns this data into a [`KeyValueDataReader`] implementing [`std::io::Read`].
+    ///
+    /// This streams the data bytes segment by segment without copying the entire data into memory first.
+    /// Use it to pipe a large Big Data value into a hasher or a file via [`std::io::copy`].
+    #[cfg(feature = "std")]
+    pub fn into_reader(self) -> KeyValueDataReader<'h, B> {
+        match self {
+            KeyValueData::Small(data) => KeyValueDataReader {
+                current: data,
+                segments: None,
+            },
+            KeyValueData::Big(iter) => KeyValueDataReader {
+                current: &[],
+                segments: Some(iter),
+            },
+        }
+    }
+}
+
+/// Reader over the data bytes of a Key Value, implementing [`std::io::Read`].
+///
+/// Big Data segments are read one after another, and only when the previous one has been consumed.
+/// A segment that cannot be read is reported as an I/O error of kind [`io::ErrorKind::Other`] wrapping
+/// the respective [`NtHiveError`].
+///
+/// Created by [`KeyValueData::into_reader`].
+#[cfg(feature = "std")]
+pub struct KeyValueDataReader<'h, B: SplitByteSlice> {
+    current: &'h [u8],
+    segments: Option<BigDataSlices<'h, B>>,
+}
+
+#[cfg(feature = "std")]
+impl<B> Read for KeyValueDataReader<'_, B>
+where
+    B: SplitByteSlice,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.current.is_empty() {
+            // `BigDataSlices` ends after all `bytes_left` have been returned, so this is where we report EOF.
+            let Some(segment) = self.segments.as_mut().and_then(|segments| segments.next()) else {
+                return Ok(0);
+            };
+
+            self.current = segment.map_err(io::Error::other)?;
+        }
+
+        let bytes_to_copy = cmp::min(buf.len(), self.current.len());
+        let (data, rest) = self.current.split_at(bytes_to_copy);
+        buf[..bytes_to_copy].copy_from_slice(data);
+        self.current = rest;
+
+        Ok(bytes_to_copy)
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn contains_subslice(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
 }
 
 /// Possible data types of the data belonging to a [`KeyValue`].
-#[derive(Clone, Copy, Debug, Eq, N, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[repr(u32)]
 pub enum KeyValueDataType {
     RegNone = 0x0000_0000,
@@ -90,10 +327,132 @@ pub enum KeyValueDataType {
     RegQWord = 0x0000_000b,
 }
 
+impl KeyValueDataType {
+    /// Returns the variant for the given numeric data type code or `None` if it is unknown.
+    pub const fn n(value: u32) -> Option<Self> {
+        match value {
+            0x0000_0000 => Some(Self::RegNone),
+            0x0000_0001 => Some(Self::RegSZ),
+            0x0000_0002 => Some(Self::RegExpandSZ),
+            0x0000_0003 => Some(Self::RegBinary),
+            0x0000_0004 => Some(Self::RegDWord),
+            0x0000_0005 => Some(Self::RegDWordBigEndian),
+            0x0000_0006 => Some(Self::RegLink),
+            0x0000_0007 => Some(Self::RegMultiSZ),
+            0x0000_0008 => Some(Self::RegResourceList),
+            0x0000_0009 => Some(Self::RegFullResourceDescriptor),
+            0x0000_000a => Some(Self::RegResourceRequirementsList),
+            0x0000_000b => Some(Self::RegQWord),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::RegNone => "REG_NONE",
+            Self::RegSZ => "REG_SZ",
+            Self::RegExpandSZ => "REG_EXPAND_SZ",
+            Self::RegBinary => "REG_BINARY",
+            Self::RegDWord => "REG_DWORD",
+            Self::RegDWordBigEndian => "REG_DWORD_BIG_ENDIAN",
+            Self::RegLink => "REG_LINK",
+            Self::RegMultiSZ => "REG_MULTI_SZ",
+            Self::RegResourceList => "REG_RESOURCE_LIST",
+            Self::RegFullResourceDescriptor => "REG_FULL_RESOURCE_DESCRIPTOR",
+            Self::RegResourceRequirementsList => "REG_RESOURCE_REQUIREMENTS_LIST",
+            Self::RegQWord => "REG_QWORD",
+        }
+    }
+}
+
+impl TryFrom<u32> for KeyValueDataType {
+    type Error = u32;
+
+    /// Returns the unknown value as error if it doesn't correspond to any variant.
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Self::n(value).ok_or(value)
+    }
+}
+
+/// Formats the data type using its Windows API constant name (e.g. `REG_SZ`).
+impl fmt::Display for KeyValueDataType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses a Windows API constant name (e.g. `REG_SZ`) into a data type.
+///
+/// The aliases `REG_DWORD_LITTLE_ENDIAN` and `REG_QWORD_LITTLE_ENDIAN` are accepted as well.
+impl FromStr for KeyValueDataType {
+    type Err = ParseKeyValueDataTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "REG_NONE" => Ok(Self::RegNone),
+            "REG_SZ" => Ok(Self::RegSZ),
+            "REG_EXPAND_SZ" => Ok(Self::RegExpandSZ),
+            "REG_BINARY" => Ok(Self::RegBinary),
+            "REG_DWORD" | "REG_DWORD_LITTLE_ENDIAN" => Ok(Self::RegDWord),
+            "REG_DWORD_BIG_ENDIAN" => Ok(Self::RegDWordBigEndian),
+            "REG_LINK" => Ok(Self::RegLink),
+            "REG_MULTI_SZ" => Ok(Self::RegMultiSZ),
+            "REG_RESOURCE_LIST" => Ok(Self::RegResourceList),
+            "REG_FULL_RESOURCE_DESCRIPTOR" => Ok(Self::RegFullResourceDescriptor),
+            "REG_RESOURCE_REQUIREMENTS_LIST" => Ok(Self::RegResourceRequirementsList),
+            "REG_QWORD" | "REG_QWORD_LITTLE_ENDIAN" => Ok(Self::RegQWord),
+            _ => Err(ParseKeyValueDataTypeError),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for KeyValueDataType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for KeyValueDataType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct KeyValueDataTypeVisitor;
+
+        impl serde::de::Visitor<'_> for KeyValueDataTypeVisitor {
+            type Value = KeyValueDataType;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a registry data type name like \"REG_SZ\"")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                v.parse()
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_str(KeyValueDataTypeVisitor)
+    }
+}
+
+/// Error returned when parsing an unknown name into a [`KeyValueDataType`].
+#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
+#[error("The string is not a known registry data type name")]
+pub struct ParseKeyValueDataTypeError;
+
 /// On-Disk Structure of a Key Value header.
 #[allow(dead_code)]
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
+#[repr(C, packed)]
 struct KeyValueHeader {
     signature: [u8; 2],
     name_length: U16<LittleEndian>,
@@ -104,17 +463,39 @@ struct KeyValueHeader {
     spare: U16<LittleEndian>,
 }
 
+#[cfg(feature = "alloc")]
+pub(crate) const KEY_VALUE_HEADER_LAYOUT: StructLayout = struct_layout!(KeyValueHeader {
+    signature,
+    name_length,
+    data_size,
+    data_offset,
+    data_type,
+    flags,
+    spare,
+});
+
 /// A single value that belongs to a [`KeyNode`].
 /// It has a name and attached data.
 ///
 /// On-Disk Signature: `vk`
 ///
+/// The header fields are parsed once when the Key Value is created (e.g. by [`KeyNode::value`]
+/// or [`KeyNode::values`]), so an invalid header is reported at that point.
+/// All accessors afterwards work on copies of these fields.
+///
 /// [`KeyNode`]: crate::key_node::KeyNode
+/// [`KeyNode::value`]: crate::key_node::KeyNode::value
+/// [`KeyNode::values`]: crate::key_node::KeyNode::values
 #[derive(Clone)]
 pub struct KeyValue<'h, B: SplitByteSlice> {
     hive: &'h Hive<B>,
     header_range: Range<usize>,
     data_range: Range<usize>,
+    name_length: u16,
+    data_size: u32,
+    data_offset: u32,
+    data_type: u32,
+    flags: KeyValueFlags,
 }
 
 impl<'h, B> KeyValue<'h, B>
@@ -130,25 +511,88 @@ where
             })?;
         let data_range = header_range.end..cell_range.end;
 
-        let key_value = Self {
+        let header = Ref::from_bytes(hive.data_slice(header_range.clone())).unwrap();
+        Self::validate_signature(hive, &header)?;
+
+        Ok(Self {
             hive,
             header_range,
             data_range,
-        };
-        key_value.validate_signature()?;
+            name_length: header.name_length.get(),
+            data_size: header.data_size.get(),
+            data_offset: header.data_offset.get(),
+            data_type: header.data_type.get(),
+            flags: KeyValueFlags::from_bits_truncate(header.flags.get()),
+        })
+    }
+
+    /// Returns the offset of the given header field from the very beginning of the hive bytes.
+    fn offset_of_header_field(&self, field_offset: usize) -> usize {
+        self.hive
+            .offset_of_data_offset(self.header_range.start + field_offset)
+    }
 
-        Ok(key_value)
+    /// Returns the range of the cell referenced by the `data_offset` field.
+    fn data_offset_cell_range(&self, allow_unallocated: bool) -> Result<Range<usize>> {
+        self.hive.cell_range_from_offset_field_internal(
+            self.data_offset,
+            self.offset_of_header_field(mem::offset_of!(KeyValueHeader, data_offset)),
+            allow_unallocated,
+        )
     }
 
-    fn header(&self) -> Ref<&[u8], KeyValueHeader> {
-        Ref::from_bytes(&self.hive.data[self.header_range.clone()]).unwrap()
+    /// Returns the data offset of the cell containing this Key Value.
+    ///
+    /// It can be stored and later passed to [`Hive::key_value_from_offset`] to get the same value again.
+    pub fn cell_offset(&self) -> u32 {
+        // Every Key Value header directly follows the header of the cell containing it.
+        (self.header_range.start - mem::size_of::<CellHeader>()) as u32
     }
 
     /// Returns the raw data bytes as [`KeyValueData`].
+    ///
+    /// This returns an error if the data cannot be found where the Key Value says it is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let testhive = nt_hive::doctest_hive().intact;
+    /// use nt_hive::Hive;
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    /// let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+    ///
+    /// // Data of up to 16344 bytes is stored in a single cell.
+    /// let key_value = key_node.value("binary").unwrap().unwrap();
+    /// let data = key_value.data().unwrap();
+    /// assert!(matches!(data, KeyValueData::Small(_)));
+    /// assert_eq!(data.into_vec().unwrap(), [1, 2, 3, 4, 5]);
+    ///
+    /// // Anything larger is split into multiple segments.
+    /// let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+    /// let key_value = key_node.value("C").unwrap().unwrap();
+    /// let data = key_value.data().unwrap();
+    /// assert!(matches!(data, KeyValueData::Big(_)));
+    /// assert_eq!(data.into_vec().unwrap(), vec![b'C'; 16345]);
+    /// # use nt_hive::KeyValueData;
+    /// ```
     pub fn data(&self) -> Result<KeyValueData<'h, B>> {
-        let header = self.header();
+        self.data_internal(false)
+    }
+
+    /// Like [`KeyValue::data`], but also accepts an unallocated data cell or Big Data header.
+    ///
+    /// This is only useful for recovering deleted Key Values, whose data cells have usually been freed
+    /// along with them.
+    /// The Big Data segment list and segments are still required to be allocated.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn data_including_unallocated(&self) -> Result<KeyValueData<'h, B>> {
+        self.data_internal(true)
+    }
 
-        let data_size = header.data_size.get();
+    fn data_internal(&self, allow_unallocated: bool) -> Result<KeyValueData<'h, B>> {
+        let data_size = self.data_size;
         let data_stored_in_data_offset = data_size & DATA_STORED_IN_DATA_OFFSET > 0;
         let data_size = (data_size & !DATA_STORED_IN_DATA_OFFSET) as usize;
 
@@ -157,21 +601,21 @@ where
             // exceed the 4 bytes we have.
             if data_size > mem::size_of::<u32>() {
                 return Err(NtHiveError::InvalidSizeField {
-                    offset: self.hive.offset_of_field(&header.data_size),
+                    offset: self.offset_of_header_field(mem::offset_of!(KeyValueHeader, data_size)),
                     expected: mem::size_of::<u32>(),
                     actual: data_size,
                 });
             }
 
-            let data_start = self.header_range.start + offset_of!(KeyValueHeader, data_offset);
+            let data_start = self.header_range.start + mem::offset_of!(KeyValueHeader, data_offset);
             let data_end = data_start + data_size;
 
-            Ok(KeyValueData::Small(&self.hive.data[data_start..data_end]))
-        } else if data_size <= BIG_DATA_SEGMENT_SIZE {
+            Ok(KeyValueData::Small(
+                self.hive.data_slice(data_start..data_end),
+            ))
+        } else if !self.is_big_data(data_size) {
             // The entire data is stored in a single cell referenced by `data_offset`.
-            let cell_range = self
-                .hive
-                .cell_range_from_data_offset(header.data_offset.get())?;
+            let cell_range = self.data_offset_cell_range(allow_unallocated)?;
             if cell_range.len() < data_size {
                 return Err(NtHiveError::InvalidDataSize {
                     offset: self.hive.offset_of_data_offset(cell_range.start),
@@ -183,26 +627,248 @@ where
             let data_start = cell_range.start;
             let data_end = data_start + data_size;
 
-            Ok(KeyValueData::Small(&self.hive.data[data_start..data_end]))
+            Ok(KeyValueData::Small(
+                self.hive.data_slice(data_start..data_end),
+            ))
         } else {
             // The data size exceeds what can be stored in a single cell.
             // It's therefore stored in a Big Data structure referencing multiple cells.
-            let cell_range = self
-                .hive
-                .cell_range_from_data_offset(header.data_offset.get())?;
-            let iter = BigDataSlices::new(
-                self.hive,
-                data_size as u32,
-                self.hive.offset_of_field(&header.data_size),
-                cell_range,
-            )?;
+            let cell_range = self.data_offset_cell_range(allow_unallocated)?;
+            let iter = BigDataSlices::new(self.hive, data_size as u32, cell_range)?;
 
             Ok(KeyValueData::Big(iter))
         }
     }
 
+    /// Returns the raw data bytes as a single contiguous slice, no matter how they are stored.
+    ///
+    /// Data stored inline or in a single cell is borrowed from the hive without copying anything.
+    /// Big Data is concatenated into a vector, which is allocated once with the final size.
+    /// This spares callers that just want the bytes from matching on [`KeyValueData`].
+    #[cfg(feature = "alloc")]
+    pub fn data_cow(&self) -> Result<Cow<'h, [u8]>> {
+        match self.data()? {
+            KeyValueData::Small(data) => Ok(Cow::Borrowed(data)),
+            data @ KeyValueData::Big(_) => data.into_vec().map(Cow::Owned),
+        }
+    }
+
+    /// Returns the slack space of the data cell, i.e. the bytes between the end of the data and the end of the cell.
+    ///
+    /// Cells are 8-byte aligned and often reused for shorter data, so these bytes may contain remnants
+    /// of previous data.
+    /// Returns `None` if the data is stored inline in the `data_offset` field or in a Big Data structure.
+    /// Returns an error if the data cannot be found where the Key Value says it is (just like [`KeyValue::data`]).
+    pub fn data_slack(&self) -> Option<Result<&'h [u8]>> {
+        let data_size = self.data_size() as usize;
+        if self.is_inline() || self.is_big_data(data_size) {
+            return None;
+        }
+
+        let cell_range = iter_try!(self.data_offset_cell_range(false));
+        let Some(data_range) = byte_subrange(&cell_range, data_size) else {
+            return Some(Err(NtHiveError::InvalidDataSize {
+                offset: self.hive.offset_of_data_offset(cell_range.start),
+                expected: data_size,
+                actual: cell_range.len(),
+            }));
+        };
+
+        Some(Ok(self.hive.data_slice(data_range.end..cell_range.end)))
+    }
+
+    /// Returns the data offset of the cell holding the data of this Key Value, if the data is stored
+    /// in a single separate cell.
+    ///
+    /// Returns `None` if the data is stored inline in the `data_offset` field or in a Big Data structure.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn data_cell_offset(&self) -> Option<u32> {
+        let data_size = self.data_size() as usize;
+        if self.is_inline() || self.is_big_data(data_size) {
+            None
+        } else {
+            Some(self.data_offset)
+        }
+    }
+
+    /// Returns the data offset of the cell referenced by the `data_offset` field of this Key Value,
+    /// which is either the data cell or the Big Data header.
+    ///
+    /// Returns `None` if the data is stored inline in the `data_offset` field.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn referenced_cell_offset(&self) -> Option<u32> {
+        if self.is_inline() {
+            None
+        } else {
+            Some(self.data_offset)
+        }
+    }
+
+    /// Returns whether data of the given size is stored in a Big Data structure.
+    ///
+    /// Hives of Windows NT 3.x predate Big Data and always store the data in a single cell.
+    fn is_big_data(&self, data_size: usize) -> bool {
+        data_size > BIG_DATA_SEGMENT_SIZE && !self.hive.is_legacy_version()
+    }
+
+    /// Checks if this is a `REG_BINARY` or `REG_NONE` Key Value and copies the beginning of its data into a `T`.
+    ///
+    /// This is meant for values storing fixed-size C structures, which can be declared via the zerocopy derives.
+    /// Returns [`NtHiveError::InvalidDataSize`] if the data is shorter than `T`.
+    /// Any further data is ignored (use [`KeyValue::data_as_exact`] to reject it).
+    ///
+    /// For Big Data, `T` is read directly from the first segment if it fits in there.
+    /// Otherwise, the data is concatenated first, which requires the `alloc` feature.
+    /// Without it, such a `T` is rejected with [`NtHiveError::InvalidDataSize`] for the size of the first segment.
+    pub fn data_as<T>(&self) -> Result<T>
+    where
+        T: FromBytes + Unaligned,
+    {
+        self.data_as_internal(false)
+    }
+
+    /// Like [`KeyValue::data_as`], but returns [`NtHiveError::InvalidDataSize`] unless the data has exactly
+    /// the size of `T`.
+    pub fn data_as_exact<T>(&self) -> Result<T>
+    where
+        T: FromBytes + Unaligned,
+    {
+        self.data_as_internal(true)
+    }
+
+    fn data_as_internal<T>(&self, exact: bool) -> Result<T>
+    where
+        T: FromBytes + Unaligned,
+    {
+        self.validate_binary_data_type()?;
+
+        let data = self.data()?;
+        let data_len = data.len();
+        let expected = mem::size_of::<T>();
+        let invalid_data_size = |actual| NtHiveError::InvalidDataSize {
+            offset: self.offset_of_header_field(mem::offset_of!(KeyValueHeader, data_size)),
+            expected,
+            actual,
+        };
+
+        if data_len < expected || (exact && data_len != expected) {
+            return Err(invalid_data_size(data_len));
+        }
+
+        // Only the first Big Data segment is required here, unless `T` is larger than that.
+        let first_slice = match data {
+            KeyValueData::Small(data) => data,
+            KeyValueData::Big(mut iter) => iter.next().unwrap_or(Ok(&[]))?,
+        };
+        if let Ok((value, _)) = T::read_from_prefix(first_slice) {
+            return Ok(value);
+        }
+
+        #[cfg(feature = "alloc")]
+        {
+            let data = self.data_cow()?;
+            let (value, _) =
+                T::read_from_prefix(&data).map_err(|_| invalid_data_size(data.len()))?;
+            Ok(value)
+        }
+
+        #[cfg(not(feature = "alloc"))]
+        {
+            Err(invalid_data_size(first_slice.len()))
+        }
+    }
+
+    /// Checks if this is a `REG_BINARY` or `REG_NONE` Key Value and copies its data into a vector of `T`.
+    ///
+    /// This is meant for values storing arrays of fixed-size C structures.
+    /// Returns [`NtHiveError::InvalidSizeFieldAlignment`] if the data size is not a multiple of the size of `T`.
+    #[cfg(feature = "alloc")]
+    pub fn data_as_slice_of<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromBytes + Unaligned,
+    {
+        self.validate_binary_data_type()?;
+
+        let data = self.data_cow()?;
+        let element_size = mem::size_of::<T>();
+        if element_size == 0 {
+            return Ok(Vec::new());
+        }
+
+        if data.len() % element_size != 0 {
+            return Err(NtHiveError::InvalidSizeFieldAlignment {
+                offset: self.offset_of_header_field(mem::offset_of!(KeyValueHeader, data_size)),
+                size: data.len(),
+                expected_alignment: element_size,
+            });
+        }
+
+        let elements = data
+            .chunks_exact(element_size)
+            .map(|element| T::read_from_bytes(element).unwrap())
+            .collect();
+
+        Ok(elements)
+    }
+
+    fn validate_binary_data_type(&self) -> Result<()> {
+        match self.data_type()? {
+            KeyValueDataType::RegBinary | KeyValueDataType::RegNone => Ok(()),
+            data_type => Err(NtHiveError::InvalidKeyValueDataType {
+                expected: &[KeyValueDataType::RegBinary, KeyValueDataType::RegNone],
+                actual: data_type,
+            }),
+        }
+    }
+
+    /// Returns the total length of the raw data bytes after validating that all of them are actually available.
+    ///
+    /// In contrast to [`KeyValue::data_size`], which just returns the size field of the header,
+    /// this performs the same checks as reading the data, but without copying anything.
+    /// For Big Data, every segment referenced by the segment list is checked to be an allocated cell
+    /// holding enough bytes.
+    ///
+    /// Use this to decide whether a value is small enough to be loaded into memory via `KeyValueData::into_vec`.
+    pub fn data_len_checked(&self) -> Result<usize> {
+        match self.data()? {
+            KeyValueData::Small(data) => Ok(data.len()),
+            KeyValueData::Big(iter) => {
+                let mut data_len = 0;
+
+                for slice_data in iter {
+                    data_len += slice_data?.len();
+                }
+
+                Ok(data_len)
+            }
+        }
+    }
+
+    /// Calculates the CRC-32 of the raw data bytes.
+    ///
+    /// This streams over the data returned by [`KeyValue::data`] and never copies it,
+    /// even if the data is split over multiple Big Data segments.
+    /// It is useful as a cheap fingerprint for comparing values before doing a full byte comparison.
+    pub fn data_crc32(&self) -> Result<u32> {
+        let mut crc32 = Crc32::new();
+
+        match self.data()? {
+            KeyValueData::Small(data) => crc32.update(data),
+            KeyValueData::Big(iter) => {
+                for slice_data in iter {
+                    crc32.update(slice_data?);
+                }
+            }
+        }
+
+        Ok(crc32.finalize())
+    }
+
+    /// Decodes UTF-16LE data up to the first NUL character.
+    /// Also returns whether such a NUL character terminated the string, as opposed to the string simply ending
+    /// at the data boundary.
     #[cfg(feature = "alloc")]
-    fn utf16le_to_string_lossy<I>(iter: I) -> Result<String>
+    fn utf16le_to_string_lossy<I>(iter: I) -> Result<(String, bool)>
     where
         I: Iterator<Item = Result<&'h [u8]>>,
     {
@@ -229,20 +895,93 @@ where
                 // Some applications erroneously store NUL-terminated strings in the registry.
                 // To cope with that, we either stop at the first NUL character or when no more characters are left, whatever comes first.
                 if c == '\0' {
-                    return Ok(string);
+                    return Ok((string, true));
                 } else {
                     string.push(c);
                 }
             }
         }
 
-        Ok(string)
+        Ok((string, false))
     }
 
     /// Checks if this is a `REG_SZ` or `REG_EXPAND_SZ` Key Value
     /// and returns the data as a [`String`] in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let testhive = nt_hive::doctest_hive().intact;
+    /// use nt_hive::Hive;
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    /// let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+    ///
+    /// let key_value = key_node.value("reg-sz").unwrap().unwrap();
+    /// assert_eq!(key_value.string_data().unwrap(), "sz-test");
+    ///
+    /// // Any other data type is an error.
+    /// let key_value = key_node.value("dword").unwrap().unwrap();
+    /// assert!(matches!(
+    ///     key_value.string_data(),
+    ///     Err(NtHiveError::InvalidKeyValueDataType { .. })
+    /// ));
+    /// # use nt_hive::NtHiveError;
+    /// ```
     #[cfg(feature = "alloc")]
     pub fn string_data(&'h self) -> Result<String> {
+        let (string, _terminated) = self.string_data_with_terminator()?;
+        Ok(string)
+    }
+
+    /// Checks if this is a `REG_SZ` or `REG_EXPAND_SZ` Key Value
+    /// and returns the data as a [`RegSZData`] in that case.
+    ///
+    /// Unlike [`KeyValue::string_data`], this also tells whether the string was terminated by a NUL character
+    /// or simply ended at the data boundary, which hints at truncated data.
+    #[cfg(feature = "alloc")]
+    pub fn string_data_strict(&'h self) -> Result<RegSZData> {
+        let (string, terminated) = self.string_data_with_terminator()?;
+        Ok(RegSZData { string, terminated })
+    }
+
+    /// Checks if this is a `REG_SZ` or `REG_EXPAND_SZ` Key Value
+    /// and returns the data as a [`String`] with all environment variables expanded in that case.
+    ///
+    /// Every `%NAME%` token is replaced by the result of `lookup("NAME")`.
+    /// Tokens for which `lookup` returns `None` are left untouched, just like a `%` without a closing one.
+    /// Expanded values are not expanded again.
+    ///
+    /// Use [`KeyValue::string_data`] to get the unexpanded string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let testhive = nt_hive::doctest_hive().intact;
+    /// use nt_hive::Hive;
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    /// let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+    ///
+    /// let key_value = key_node.value("reg-expand-sz").unwrap().unwrap();
+    /// let expanded = key_value
+    ///     .expand_string_data(|name| std::env::var(name).ok())
+    ///     .unwrap();
+    /// assert_eq!(expanded, "sz-test");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn expand_string_data<F>(&'h self, lookup: F) -> Result<String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let string = self.string_data()?;
+        Ok(expand_environment_variables(&string, lookup))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn string_data_with_terminator(&'h self) -> Result<(String, bool)> {
         match self.data_type()? {
             KeyValueDataType::RegSZ | KeyValueDataType::RegExpandSZ => (),
             data_type => {
@@ -259,15 +998,59 @@ where
         }
     }
 
+    /// Checks if this is a `REG_LINK` Key Value
+    /// and returns the link target as a [`String`] in that case.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn link_data(&'h self) -> Result<String> {
+        match self.data_type()? {
+            KeyValueDataType::RegLink => (),
+            data_type => {
+                return Err(NtHiveError::InvalidKeyValueDataType {
+                    expected: &[KeyValueDataType::RegLink],
+                    actual: data_type,
+                });
+            }
+        }
+
+        let (string, _terminated) = match self.data()? {
+            KeyValueData::Small(data) => Self::utf16le_to_string_lossy(iter::once(Ok(data)))?,
+            KeyValueData::Big(iter) => Self::utf16le_to_string_lossy(iter)?,
+        };
+
+        Ok(string)
+    }
+
     /// Checks if this is a `REG_DWORD` or `REG_DWORD_BIG_ENDIAN` Key Value
     /// and returns the data as a [`u32`] in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let testhive = nt_hive::doctest_hive().intact;
+    /// use nt_hive::Hive;
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    /// let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+    ///
+    /// let key_value = key_node.value("dword").unwrap().unwrap();
+    /// assert_eq!(key_value.dword_data().unwrap(), 42);
+    ///
+    /// // Data of any other size or type is an error.
+    /// let key_value = key_node.value("qword").unwrap().unwrap();
+    /// assert!(matches!(
+    ///     key_value.dword_data(),
+    ///     Err(NtHiveError::InvalidDataSize { expected: 4, actual: 8, .. })
+    /// ));
+    /// # use nt_hive::NtHiveError;
+    /// ```
     pub fn dword_data(&self) -> Result<u32> {
         // DWORD data never needs a Big Data structure.
         if let KeyValueData::Small(data) = self.data()? {
             // DWORD data must be exactly 4 bytes long.
             if data.len() != mem::size_of::<u32>() {
                 return Err(NtHiveError::InvalidDataSize {
-                    offset: self.hive.offset_of_field(&data),
+                    offset: self.hive.offset_of_field(data),
                     expected: mem::size_of::<u32>(),
                     actual: data.len(),
                 });
@@ -291,23 +1074,103 @@ where
             // We got a Big Data structure and this can only happen if the data
             // is much longer than a single DWORD.
             Err(NtHiveError::InvalidDataSize {
-                offset: self
-                    .hive
-                    .offset_of_data_offset(self.header().data_offset.get() as usize),
+                offset: self.hive.offset_of_data_offset(self.data_offset as usize),
                 expected: mem::size_of::<u32>(),
                 actual: self.data_size() as usize,
             })
         }
     }
 
-    /// Checks if this is a `REG_MULTI_SZ` Key Value
-    /// and returns an iterator over [`String`]s for each line in that case.
-    #[cfg(feature = "alloc")]
-    pub fn multi_string_data(&self) -> Result<RegMultiSZStrings<'h, B>> {
-        // Ensure that this is a REG_MULTI_SZ data type.
-        match self.data_type()? {
-            KeyValueDataType::RegMultiSZ => (),
-            data_type => {
+    /// Checks if this is a `REG_QWORD` or `REG_BINARY` Key Value with exactly 8 bytes of data
+    /// and returns the data as a raw Windows FILETIME (100-nanosecond intervals since 1601-01-01 UTC).
+    ///
+    /// Many values storing timestamps (like installation dates) use this format.
+    pub fn filetime_data(&self) -> Result<u64> {
+        // FILETIME data never needs a Big Data structure.
+        if let KeyValueData::Small(data) = self.data()? {
+            // FILETIME data must be exactly 8 bytes long.
+            if data.len() != mem::size_of::<u64>() {
+                return Err(NtHiveError::InvalidDataSize {
+                    offset: self.hive.offset_of_field(data),
+                    expected: mem::size_of::<u64>(),
+                    actual: data.len(),
+                });
+            }
+
+            // Ensure that this is a REG_QWORD or REG_BINARY data type.
+            match self.data_type()? {
+                KeyValueDataType::RegQWord | KeyValueDataType::RegBinary => {
+                    Ok(u64::from_le_bytes(data.try_into().unwrap()))
+                }
+                data_type => Err(NtHiveError::InvalidKeyValueDataType {
+                    expected: &[KeyValueDataType::RegQWord, KeyValueDataType::RegBinary],
+                    actual: data_type,
+                }),
+            }
+        } else {
+            // We got a Big Data structure and this can only happen if the data
+            // is much longer than a single FILETIME.
+            Err(NtHiveError::InvalidDataSize {
+                offset: self.hive.offset_of_data_offset(self.data_offset as usize),
+                expected: mem::size_of::<u64>(),
+                actual: self.data_size() as usize,
+            })
+        }
+    }
+
+    /// Reads the data like [`KeyValue::filetime_data`] and converts it into an [`OffsetDateTime`] in UTC.
+    ///
+    /// Returns `None` if the FILETIME is zero (i.e. "no time") or cannot be represented by [`OffsetDateTime`].
+    #[cfg(feature = "time")]
+    pub fn offset_date_time_data(&self) -> Result<Option<OffsetDateTime>> {
+        let filetime = self.filetime_data()?;
+        Ok(filetime_to_offset_date_time(filetime))
+    }
+
+    /// Reads the data like [`KeyValue::filetime_data`] and converts it into a [`SystemTime`].
+    ///
+    /// Returns `None` if the FILETIME is zero (i.e. "no time") or cannot be represented by [`SystemTime`].
+    #[cfg(feature = "std")]
+    pub fn system_time_data(&self) -> Result<Option<SystemTime>> {
+        let filetime = self.filetime_data()?;
+        Ok(filetime_to_system_time(filetime))
+    }
+
+    /// Checks if this is a `REG_MULTI_SZ` Key Value
+    /// and returns an iterator over [`String`]s for each line in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let testhive = nt_hive::doctest_hive().intact;
+    /// use nt_hive::Hive;
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    /// let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+    ///
+    /// let key_value = key_node.value("reg-multi-sz").unwrap().unwrap();
+    /// let lines = key_value
+    ///     .multi_string_data()
+    ///     .unwrap()
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(lines, ["multi-sz-test", "line2"]);
+    ///
+    /// // Any other data type is an error.
+    /// let key_value = key_node.value("reg-sz").unwrap().unwrap();
+    /// assert!(matches!(
+    ///     key_value.multi_string_data(),
+    ///     Err(NtHiveError::InvalidKeyValueDataType { .. })
+    /// ));
+    /// # use nt_hive::NtHiveError;
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn multi_string_data(&self) -> Result<RegMultiSZStrings<'h, B>> {
+        // Ensure that this is a REG_MULTI_SZ data type.
+        match self.data_type()? {
+            KeyValueDataType::RegMultiSZ => (),
+            data_type => {
                 return Err(NtHiveError::InvalidKeyValueDataType {
                     expected: &[KeyValueDataType::RegMultiSZ],
                     actual: data_type,
@@ -321,15 +1184,120 @@ where
         }
     }
 
+    /// Checks if this is a `REG_MULTI_SZ` Key Value
+    /// and returns all lines as a [`RegMultiSZData`] in that case.
+    ///
+    /// Unlike [`KeyValue::multi_string_data`], this also tells whether the list of strings was properly terminated
+    /// by a double-NUL.
+    /// A missing terminator hints at data that has been truncated, possibly in the middle of the final string.
+    #[cfg(feature = "alloc")]
+    pub fn multi_string_data_strict(&self) -> Result<RegMultiSZData> {
+        let mut iter = self.multi_string_data()?;
+        let strings = iter.by_ref().collect::<Result<Vec<String>>>()?;
+
+        Ok(RegMultiSZData {
+            strings,
+            terminated: iter.terminated,
+        })
+    }
+
+    /// Decodes the data of this Key Value according to its data type.
+    ///
+    /// This calls [`KeyValue::string_data`], [`KeyValue::multi_string_data`], [`KeyValue::dword_data`],
+    /// or [`KeyValue::qword_data`] depending on the data type and returns the result as a
+    /// [`KeyValueParsedData`].
+    /// Data of all other types is returned as raw bytes.
+    /// Errors of the respective decoder (e.g. a `REG_DWORD` Key Value with 8 bytes of data) are returned as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let testhive = nt_hive::doctest_hive().intact;
+    /// use nt_hive::{Hive, KeyValueParsedData};
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    /// let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+    ///
+    /// for key_value in key_node.values().unwrap().unwrap() {
+    ///     let key_value = key_value.unwrap();
+    ///     match key_value.parse_data().unwrap() {
+    ///         KeyValueParsedData::U32(dword) => println!("{dword}"),
+    ///         KeyValueParsedData::String(string) => println!("{string}"),
+    ///         other => println!("{other:?}"),
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn parse_data(&'h self) -> Result<KeyValueParsedData> {
+        let Some(data_type) = KeyValueDataType::n(self.data_type) else {
+            return Ok(KeyValueParsedData::Unknown {
+                data_type: self.data_type,
+                data: self.data()?.into_vec()?,
+            });
+        };
+
+        let parsed_data = match data_type {
+            KeyValueDataType::RegNone => {
+                let data = self.data()?.into_vec()?;
+                if data.is_empty() {
+                    KeyValueParsedData::None
+                } else {
+                    KeyValueParsedData::Binary(data)
+                }
+            }
+            KeyValueDataType::RegSZ => KeyValueParsedData::String(self.string_data()?),
+            KeyValueDataType::RegExpandSZ => KeyValueParsedData::ExpandString(self.string_data()?),
+            KeyValueDataType::RegMultiSZ => KeyValueParsedData::MultiString(
+                self.multi_string_data()?.collect::<Result<Vec<_>>>()?,
+            ),
+            KeyValueDataType::RegDWord | KeyValueDataType::RegDWordBigEndian => {
+                KeyValueParsedData::U32(self.dword_data()?)
+            }
+            KeyValueDataType::RegQWord => KeyValueParsedData::U64(self.qword_data()?),
+            KeyValueDataType::RegLink => KeyValueParsedData::Link(self.link_data()?),
+            KeyValueDataType::RegBinary
+            | KeyValueDataType::RegResourceList
+            | KeyValueDataType::RegFullResourceDescriptor
+            | KeyValueDataType::RegResourceRequirementsList => {
+                KeyValueParsedData::Binary(self.data()?.into_vec()?)
+            }
+        };
+
+        Ok(parsed_data)
+    }
+
     /// Checks if this is a `REG_QWORD` Key Value
     /// and returns the data as a [`u64`] in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let testhive = nt_hive::doctest_hive().intact;
+    /// use nt_hive::Hive;
+    ///
+    /// let hive = Hive::new(testhive.as_ref()).unwrap();
+    /// let root_key_node = hive.root_key_node().unwrap();
+    /// let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+    ///
+    /// let key_value = key_node.value("qword").unwrap().unwrap();
+    /// assert_eq!(key_value.qword_data().unwrap(), u64::MAX);
+    ///
+    /// // Data of any other size or type is an error.
+    /// let key_value = key_node.value("dword").unwrap().unwrap();
+    /// assert!(matches!(
+    ///     key_value.qword_data(),
+    ///     Err(NtHiveError::InvalidDataSize { expected: 8, actual: 4, .. })
+    /// ));
+    /// # use nt_hive::NtHiveError;
+    /// ```
     pub fn qword_data(&self) -> Result<u64> {
         // QWORD data never needs a Big Data structure.
         if let KeyValueData::Small(data) = self.data()? {
             // QWORD data must be exactly 8 bytes long.
             if data.len() != mem::size_of::<u64>() {
                 return Err(NtHiveError::InvalidDataSize {
-                    offset: self.hive.offset_of_field(&data),
+                    offset: self.hive.offset_of_field(data),
                     expected: mem::size_of::<u64>(),
                     actual: data.len(),
                 });
@@ -347,58 +1315,115 @@ where
             // We got a Big Data structure and this can only happen if the data
             // is much longer than a single QWORD.
             Err(NtHiveError::InvalidDataSize {
-                offset: self
-                    .hive
-                    .offset_of_data_offset(self.header().data_offset.get() as usize),
+                offset: self.hive.offset_of_data_offset(self.data_offset as usize),
                 expected: mem::size_of::<u64>(),
                 actual: self.data_size() as usize,
             })
         }
     }
 
+    /// Returns the raw `data_offset` field of the header.
+    pub(crate) fn data_offset(&self) -> u32 {
+        self.data_offset
+    }
+
     /// Returns the size of the raw data.
     pub fn data_size(&self) -> u32 {
-        let header = self.header();
-        header.data_size.get() & !DATA_STORED_IN_DATA_OFFSET
+        self.data_size & !DATA_STORED_IN_DATA_OFFSET
+    }
+
+    /// Returns `true` if the data of this Key Value is stored inline in the `data_offset` field of its header.
+    ///
+    /// Windows does this for data of up to 4 bytes (like a `REG_DWORD`), which then needs no separate cell.
+    /// Otherwise, the `data_offset` field refers to a data cell or a Big Data structure.
+    #[doc(alias = "DATA_STORED_IN_DATA_OFFSET")]
+    pub fn is_inline(&self) -> bool {
+        self.data_size & DATA_STORED_IN_DATA_OFFSET != 0
+    }
+
+    /// Returns the raw numeric data type of this Key Value, as stored in the header.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn data_type_code(&self) -> u32 {
+        self.data_type
     }
 
     /// Returns the data type of this Key Value.
     pub fn data_type(&self) -> Result<KeyValueDataType> {
-        let header = self.header();
-        let data_type_code = header.data_type.get();
-
-        KeyValueDataType::n(data_type_code).ok_or_else(|| {
+        KeyValueDataType::n(self.data_type).ok_or_else(|| {
             NtHiveError::UnsupportedKeyValueDataType {
-                offset: self.hive.offset_of_field(&header.data_type),
-                actual: data_type_code,
+                offset: self.offset_of_header_field(mem::offset_of!(KeyValueHeader, data_type)),
+                actual: self.data_type,
             }
         })
     }
 
+    /// Returns `true` if the name of this Key Value is stored in (extended) ASCII instead of UTF-16LE.
+    #[doc(alias = "name_is_compressed")]
+    pub fn has_compressed_name(&self) -> bool {
+        self.flags.contains(KeyValueFlags::VALUE_COMP_NAME)
+    }
+
     /// Returns the name of this Key Value.
+    ///
+    /// A UTF-16LE name must have an even length, otherwise [`NtHiveError::InvalidSizeFieldAlignment`]
+    /// is returned.
+    /// Latin1 names (see [`KeyValue::has_compressed_name`]) may have any length.
+    /// If the `VALUE_COMP_NAME` flag has been set wrongly for a UTF-16LE name,
+    /// [`NtHiveNameString::miscoded_utf16le`] detects this and returns the correctly decoded name.
     pub fn name(&self) -> Result<NtHiveNameString<'h>> {
-        let header = self.header();
-        let flags = KeyValueFlags::from_bits_truncate(header.flags.get());
-        let name_length = header.name_length.get() as usize;
+        let name_range = self.name_range()?;
+        let name_bytes = self.hive.data_slice(name_range);
 
-        let name_range = byte_subrange(&self.data_range, name_length).ok_or_else(|| {
-            NtHiveError::InvalidSizeField {
-                offset: self.hive.offset_of_field(&header.name_length),
-                expected: name_length,
-                actual: self.data_range.len(),
-            }
-        })?;
-        let name_bytes = &self.hive.data[name_range];
-
-        if flags.contains(KeyValueFlags::VALUE_COMP_NAME) {
+        if self.has_compressed_name() {
             Ok(NtHiveNameString::Latin1(name_bytes))
+        } else if name_bytes.len() % mem::size_of::<u16>() != 0 {
+            Err(NtHiveError::InvalidSizeFieldAlignment {
+                offset: self.offset_of_header_field(mem::offset_of!(KeyValueHeader, name_length)),
+                size: name_bytes.len(),
+                expected_alignment: mem::size_of::<u16>(),
+            })
         } else {
             Ok(NtHiveNameString::Utf16LE(name_bytes))
         }
     }
 
-    fn validate_signature(&self) -> Result<()> {
-        let header = self.header();
+    /// Returns the name of this Key Value as a [`String`], converting it lossily.
+    ///
+    /// In contrast to [`KeyValue::name`], this also accepts a UTF-16LE name with an odd length and replaces
+    /// its last byte by [`char::REPLACEMENT_CHARACTER`].
+    #[cfg(feature = "alloc")]
+    pub(crate) fn name_lossy(&self) -> Result<String> {
+        match self.name() {
+            Ok(name) => Ok(name.to_string_lossy()),
+            Err(NtHiveError::InvalidSizeFieldAlignment { .. }) => {
+                let name_bytes = self.hive.data_slice(self.name_range()?);
+                let even_length = name_bytes.len() - 1;
+                let mut name =
+                    NtHiveNameString::Utf16LE(&name_bytes[..even_length]).to_string_lossy();
+                name.push(char::REPLACEMENT_CHARACTER);
+                Ok(name)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn name_range(&self) -> Result<Range<usize>> {
+        let name_length = self.name_length as usize;
+
+        byte_subrange(&self.data_range, name_length).ok_or_else(|| NtHiveError::InvalidSizeField {
+            offset: self.offset_of_header_field(mem::offset_of!(KeyValueHeader, name_length)),
+            expected: name_length,
+            actual: self.data_range.len(),
+        })
+    }
+
+    /// Returns the bytes between the end of the name and the end of the Key Value cell.
+    pub(crate) fn name_slack(&self) -> Result<&'h [u8]> {
+        let name_range = self.name_range()?;
+        Ok(self.hive.data_slice(name_range.end..self.data_range.end))
+    }
+
+    fn validate_signature(hive: &Hive<B>, header: &Ref<&[u8], KeyValueHeader>) -> Result<()> {
         let signature = &header.signature;
         let expected_signature = b"vk";
 
@@ -406,7 +1431,7 @@ where
             Ok(())
         } else {
             Err(NtHiveError::InvalidTwoByteSignature {
-                offset: self.hive.offset_of_field(signature),
+                offset: hive.offset_of_field(signature),
                 expected: expected_signature,
                 actual: *signature,
             })
@@ -414,6 +1439,30 @@ where
     }
 }
 
+impl<B> fmt::Debug for KeyValue<'_, B>
+where
+    B: SplitByteSlice,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_struct = f.debug_struct("KeyValue");
+
+        match self.name() {
+            Ok(name) => debug_struct.field("name", &format_args!("\"{name}\"")),
+            Err(e) => debug_struct.field("name", &e),
+        };
+
+        let cell_offset = self.header_range.start - mem::size_of::<CellHeader>();
+        debug_struct.field("cell_offset", &format_args!("{cell_offset:#010x}"));
+
+        match self.data_type() {
+            Ok(data_type) => debug_struct.field("data_type", &data_type),
+            Err(_) => debug_struct.field("data_type", &format_args!("{:#010x}", self.data_type)),
+        };
+
+        debug_struct.field("data_size", &self.data_size()).finish()
+    }
+}
+
 impl<B> PartialEq for KeyValue<'_, B>
 where
     B: SplitByteSlice,
@@ -427,6 +1476,51 @@ where
 
 impl<B> Eq for KeyValue<'_, B> where B: SplitByteSlice {}
 
+/// String data of a `REG_SZ` or `REG_EXPAND_SZ` Key Value, as returned by [`KeyValue::string_data_strict`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegSZData {
+    /// The string up to the first NUL character.
+    pub string: String,
+    /// Whether the string was terminated by a NUL character (`true`) or ended at the data boundary (`false`).
+    pub terminated: bool,
+}
+
+/// String data of a `REG_MULTI_SZ` Key Value, as returned by [`KeyValue::multi_string_data_strict`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegMultiSZData {
+    /// The strings up to the terminating empty string.
+    pub strings: Vec<String>,
+    /// Whether the list of strings was terminated by a double-NUL (`true`) or ended at the data boundary (`false`).
+    pub terminated: bool,
+}
+
+/// Data of a [`KeyValue`] decoded according to its data type, as returned by [`KeyValue::parse_data`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KeyValueParsedData {
+    /// A `REG_NONE` Key Value without any data.
+    None,
+    /// A `REG_SZ` Key Value.
+    String(String),
+    /// A `REG_EXPAND_SZ` Key Value, whose string may contain unexpanded environment variables.
+    ExpandString(String),
+    /// A `REG_MULTI_SZ` Key Value.
+    MultiString(Vec<String>),
+    /// A `REG_DWORD` or `REG_DWORD_BIG_ENDIAN` Key Value, already converted to native byte order.
+    U32(u32),
+    /// A `REG_QWORD` Key Value.
+    U64(u64),
+    /// A `REG_LINK` Key Value with the target path of a symbolic link.
+    Link(String),
+    /// A `REG_BINARY` Key Value, a Key Value with one of the resource list data types,
+    /// or a `REG_NONE` Key Value with data.
+    Binary(Vec<u8>),
+    /// A Key Value with an unknown data type, returned along with its raw numeric data type.
+    Unknown { data_type: u32, data: Vec<u8> },
+}
+
 #[cfg(feature = "alloc")]
 type RegMultiSZCharIter<'h> = Map<
     DecodeUtf16<Map<ChunksExact<'h, u8>, fn(&'h [u8]) -> u16>>,
@@ -441,6 +1535,7 @@ where
 {
     char_iter: Option<RegMultiSZCharIter<'h>>,
     big_iter: Option<BigDataSlices<'h, B>>,
+    terminated: bool,
 }
 
 #[cfg(feature = "alloc")]
@@ -452,6 +1547,7 @@ where
         Self {
             char_iter: Some(Self::make_char_iter(data)),
             big_iter: None,
+            terminated: false,
         }
     }
 
@@ -459,6 +1555,7 @@ where
         Self {
             char_iter: None,
             big_iter: Some(iter),
+            terminated: false,
         }
     }
 
@@ -488,7 +1585,12 @@ where
     type Item = Result<String>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.terminated {
+            return None;
+        }
+
         let mut string = String::new();
+        let mut found_nul = false;
 
         'outer_loop: loop {
             let char_iter = match self.char_iter.as_mut() {
@@ -515,6 +1617,7 @@ where
                 //
                 // However, we will happily accept data without terminating NUL characters as well.
                 if c == '\0' {
+                    found_nul = true;
                     break 'outer_loop;
                 } else {
                     string.push(c);
@@ -528,6 +1631,8 @@ where
         }
 
         if string.is_empty() {
+            // An empty string after a NUL character is the final double-NUL termination.
+            self.terminated = found_nul;
             None
         } else {
             Some(Ok(string))
@@ -538,8 +1643,49 @@ where
 #[cfg(feature = "alloc")]
 impl<'h, B> FusedIterator for RegMultiSZStrings<'h, B> where B: SplitByteSlice + 'h {}
 
+/// Replaces every `%NAME%` token in `string` for which `lookup` returns a value.
+#[cfg(feature = "alloc")]
+fn expand_environment_variables<F>(string: &str, lookup: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::with_capacity(string.len());
+    let mut remaining = string;
+
+    while let Some(start) = remaining.find('%') {
+        output.push_str(&remaining[..start]);
+        let after_start = &remaining[start + 1..];
+
+        let Some(end) = after_start.find('%') else {
+            // A lone `%` without a closing one is copied as-is.
+            output.push_str(&remaining[start..]);
+            return output;
+        };
+
+        let name = &after_start[..end];
+        let value = if name.is_empty() { None } else { lookup(name) };
+        match value {
+            Some(value) => {
+                output.push_str(&value);
+                remaining = &after_start[end + 1..];
+            }
+            None => {
+                // Keep the unknown token, but let its closing `%` start the next one.
+                // This way, `100%%SystemRoot%` still expands the variable.
+                output.push('%');
+                output.push_str(name);
+                remaining = &after_start[end..];
+            }
+        }
+    }
+
+    output.push_str(remaining);
+    output
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::helpers::tests::{cell_data, read_u32};
     use crate::*;
 
     #[test]
@@ -610,4 +1756,818 @@ mod tests {
         assert!(matches!(key_value_data, KeyValueData::Small(_)));
         assert_eq!(key_value_data.into_vec().unwrap(), vec![1, 2, 3, 4, 5]);
     }
+
+    #[test]
+    fn test_parse_data() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+
+        let parse_data = |name: &str| {
+            let key_value = key_node.value(name).unwrap().unwrap();
+            key_value.parse_data().unwrap()
+        };
+        assert_eq!(
+            parse_data("reg-sz"),
+            KeyValueParsedData::String("sz-test".to_owned())
+        );
+        assert_eq!(
+            parse_data("reg-expand-sz"),
+            KeyValueParsedData::ExpandString("sz-test".to_owned())
+        );
+        assert_eq!(
+            parse_data("reg-multi-sz"),
+            KeyValueParsedData::MultiString(vec!["multi-sz-test".to_owned(), "line2".to_owned()])
+        );
+        assert_eq!(parse_data("dword"), KeyValueParsedData::U32(42));
+        assert_eq!(
+            parse_data("dword-big-endian"),
+            KeyValueParsedData::U32(42 << 24)
+        );
+        assert_eq!(parse_data("qword"), KeyValueParsedData::U64(u64::MAX));
+        assert_eq!(
+            parse_data("binary"),
+            KeyValueParsedData::Binary(vec![1, 2, 3, 4, 5])
+        );
+
+        // Change the data types of some Key Values.
+        let data_type_field = |testhive: &[u8], name: &str| {
+            testhive
+                .windows(2)
+                .enumerate()
+                .position(|(offset, signature)| {
+                    signature == b"vk"
+                        && usize::from(u16::from_le_bytes([
+                            testhive[offset + 2],
+                            testhive[offset + 3],
+                        ])) == name.len()
+                        && &testhive[offset + 20..offset + 20 + name.len()] == name.as_bytes()
+                })
+                .unwrap()
+                + 12
+        };
+        let binary_field = data_type_field(&testhive, "binary");
+        let reg_sz_field = data_type_field(&testhive, "reg-sz");
+        let dword_field = data_type_field(&testhive, "dword");
+        testhive[binary_field..binary_field + 4].copy_from_slice(&0x1234u32.to_le_bytes());
+        testhive[reg_sz_field..reg_sz_field + 4]
+            .copy_from_slice(&(KeyValueDataType::RegLink as u32).to_le_bytes());
+        testhive[dword_field..dword_field + 4]
+            .copy_from_slice(&(KeyValueDataType::RegNone as u32).to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let parse_data = |name: &str| {
+            let key_value = key_node.value(name).unwrap().unwrap();
+            key_value.parse_data().unwrap()
+        };
+        assert_eq!(
+            parse_data("binary"),
+            KeyValueParsedData::Unknown {
+                data_type: 0x1234,
+                data: vec![1, 2, 3, 4, 5]
+            }
+        );
+        assert_eq!(
+            parse_data("reg-sz"),
+            KeyValueParsedData::Link("sz-test".to_owned())
+        );
+        assert_eq!(
+            parse_data("dword"),
+            KeyValueParsedData::Binary(42u32.to_le_bytes().to_vec())
+        );
+    }
+
+    #[test]
+    fn test_string_terminators() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+
+        let key_value = key_node.value("reg-sz").unwrap().unwrap();
+        assert_eq!(
+            key_value.string_data_strict().unwrap(),
+            RegSZData {
+                string: "sz-test".to_owned(),
+                terminated: true,
+            }
+        );
+        let reg_sz_data_size = 4096 + key_value.header_range.start + 4;
+
+        let key_value = key_node.value("reg-multi-sz").unwrap().unwrap();
+        assert_eq!(
+            key_value.multi_string_data_strict().unwrap(),
+            RegMultiSZData {
+                strings: vec!["multi-sz-test".to_owned(), "line2".to_owned()],
+                terminated: true,
+            }
+        );
+        let reg_multi_sz_data_size = 4096 + key_value.header_range.start + 4;
+
+        let key_value = key_node.value("reg-multi-sz-big").unwrap().unwrap();
+        assert!(key_value.multi_string_data_strict().unwrap().terminated);
+
+        // Cut off the data at various points by shrinking the data sizes.
+        // The data of both values is stored in separate cells, so we don't need to worry about the flag bit.
+        let truncate = |data_size_offset: usize, data_size: u32| {
+            let mut testhive = testhive.clone();
+            testhive[data_size_offset..data_size_offset + 4]
+                .copy_from_slice(&data_size.to_le_bytes());
+            testhive
+        };
+
+        // "sz-test" without the terminating NUL.
+        let truncated_testhive = truncate(reg_sz_data_size, 14);
+        let hive = Hive::new(truncated_testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("reg-sz").unwrap().unwrap();
+        assert_eq!(key_value.string_data().unwrap(), "sz-test");
+        assert_eq!(
+            key_value.string_data_strict().unwrap(),
+            RegSZData {
+                string: "sz-test".to_owned(),
+                terminated: false,
+            }
+        );
+
+        // "multi-sz-test\0line2\0" is singly terminated, "multi-sz-test\0line2" and "multi-sz-test\0lin" are unterminated.
+        for (data_size, last_string) in [(40, "line2"), (38, "line2"), (34, "lin")] {
+            let truncated_testhive = truncate(reg_multi_sz_data_size, data_size);
+            let hive = Hive::new(truncated_testhive.as_ref()).unwrap();
+            let root_key_node = hive.root_key_node().unwrap();
+            let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+            let key_value = key_node.value("reg-multi-sz").unwrap().unwrap();
+            assert_eq!(
+                key_value.multi_string_data_strict().unwrap(),
+                RegMultiSZData {
+                    strings: vec!["multi-sz-test".to_owned(), last_string.to_owned()],
+                    terminated: false,
+                }
+            );
+        }
+
+        // Data following the double-NUL termination must be ignored.
+        testhive[reg_multi_sz_data_size..reg_multi_sz_data_size + 4]
+            .copy_from_slice(&16u32.to_le_bytes());
+        let data_cell = 4096
+            + u32::from_le_bytes(
+                testhive[reg_multi_sz_data_size + 4..reg_multi_sz_data_size + 8]
+                    .try_into()
+                    .unwrap(),
+            ) as usize
+            + 4;
+        testhive[data_cell..data_cell + 16].copy_from_slice(b"a\0\0\0\0\0b\0c\0\0\0d\0\0\0");
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("reg-multi-sz").unwrap().unwrap();
+        assert_eq!(
+            key_value.multi_string_data_strict().unwrap(),
+            RegMultiSZData {
+                strings: vec!["a".to_owned()],
+                terminated: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_expand_string_data() {
+        let lookup = |name: &str| match name.to_ascii_uppercase().as_str() {
+            "SYSTEMROOT" => Some("C:\\Windows".to_owned()),
+            "LOOP" => Some("%LOOP%".to_owned()),
+            _ => None,
+        };
+        let expand = |string| super::expand_environment_variables(string, lookup);
+
+        assert_eq!(
+            expand("%SystemRoot%\\system32\\drivers\\null.sys"),
+            "C:\\Windows\\system32\\drivers\\null.sys"
+        );
+        assert_eq!(expand("no variables"), "no variables");
+        assert_eq!(expand("%UNKNOWN%\\%SystemRoot%"), "%UNKNOWN%\\C:\\Windows");
+        assert_eq!(expand("%LOOP%"), "%LOOP%");
+
+        // Nested and lone percent signs
+        assert_eq!(expand("100%%SystemRoot%"), "100%C:\\Windows");
+        assert_eq!(expand("%%"), "%%");
+        assert_eq!(expand("%%%"), "%%%");
+        assert_eq!(expand("trailing %"), "trailing %");
+        assert_eq!(expand("%SystemRoot%%"), "C:\\Windows%");
+        assert_eq!(expand("%"), "%");
+        assert_eq!(expand("%Sys%tem%Root%"), "%Sys%tem%Root%");
+
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+
+        let key_value = key_node.value("reg-expand-sz").unwrap().unwrap();
+        assert_eq!(key_value.expand_string_data(lookup).unwrap(), "sz-test");
+
+        let key_value = key_node.value("dword").unwrap().unwrap();
+        assert!(matches!(
+            key_value.expand_string_data(lookup),
+            Err(NtHiveError::InvalidKeyValueDataType { .. })
+        ));
+    }
+
+    #[test]
+    fn test_into_reader() {
+        use std::io::{self, Read};
+
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+
+        // "C" has two Big Data segments, the second one with only a single byte.
+        // Reading in chunks that don't match the segment size must still return all bytes.
+        let key_value = key_node.value("C").unwrap().unwrap();
+        let mut reader = key_value.data().unwrap().into_reader();
+        let mut data = Vec::new();
+        let mut buf = [0; 1000];
+        loop {
+            let bytes_read = reader.read(&mut buf).unwrap();
+            if bytes_read == 0 {
+                break;
+            }
+
+            data.extend_from_slice(&buf[..bytes_read]);
+        }
+        assert_eq!(data, vec![b'C'; 16345]);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+
+        let mut data = Vec::new();
+        let bytes_copied =
+            io::copy(&mut key_value.data().unwrap().into_reader(), &mut data).unwrap();
+        assert_eq!(bytes_copied, 16345);
+        assert_eq!(data, vec![b'C'; 16345]);
+
+        // Small data works as well.
+        let key_value = key_node.value("A").unwrap().unwrap();
+        let mut data = Vec::new();
+        key_value
+            .data()
+            .unwrap()
+            .into_reader()
+            .read_to_end(&mut data)
+            .unwrap();
+        assert_eq!(data, vec![b'A'; 16343]);
+
+        // An unreadable segment is reported as an I/O error after the readable ones.
+        let (values_count, values_list_offset) = key_node.values_list_info();
+        let values_list = cell_data(values_list_offset);
+        let key_value_header = (0..values_count as usize)
+            .map(|i| {
+                let offset = values_list + i * 4;
+                4096 + u32::from_le_bytes(testhive[offset..offset + 4].try_into().unwrap()) as usize
+                    + 4
+            })
+            .find(|&header| testhive[header + 2] == 1 && testhive[header + 20] == b'C')
+            .unwrap();
+        let big_data_header = 4096
+            + u32::from_le_bytes(
+                testhive[key_value_header + 8..key_value_header + 12]
+                    .try_into()
+                    .unwrap(),
+            ) as usize
+            + 4;
+        let segment_list = 4096
+            + u32::from_le_bytes(
+                testhive[big_data_header + 4..big_data_header + 8]
+                    .try_into()
+                    .unwrap(),
+            ) as usize
+            + 4;
+        testhive[segment_list + 4..segment_list + 8].copy_from_slice(&(u32::MAX - 7).to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("C").unwrap().unwrap();
+        let mut reader = key_value.data().unwrap().into_reader();
+        let mut data = Vec::new();
+        let error = reader.read_to_end(&mut data).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Other);
+        assert!(error.get_ref().unwrap().is::<NtHiveError>());
+        assert_eq!(data, vec![b'C'; 16344]);
+    }
+
+    #[test]
+    fn test_filetime_data() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+
+        let key_value = key_node.value("qword").unwrap().unwrap();
+        assert_eq!(key_value.filetime_data().unwrap(), u64::MAX);
+
+        // A REG_DWORD is too short.
+        let key_value = key_node.value("dword").unwrap().unwrap();
+        assert!(matches!(
+            key_value.filetime_data(),
+            Err(NtHiveError::InvalidDataSize {
+                expected: 8,
+                actual: 4,
+                ..
+            })
+        ));
+
+        // "binary" has only 5 bytes.
+        let key_value = key_node.value("binary").unwrap().unwrap();
+        assert!(matches!(
+            key_value.filetime_data(),
+            Err(NtHiveError::InvalidDataSize {
+                expected: 8,
+                actual: 5,
+                ..
+            })
+        ));
+
+        // Store the FILETIME of 2021-01-01 00:00:00 UTC in "binary", whose data cell has enough room.
+        let filetime = 132_539_328_000_000_000u64;
+        let data_cell = cell_data(key_value.data_cell_offset().unwrap());
+        let (_, values_list_offset) = key_node.values_list_info();
+        testhive[data_cell..data_cell + 8].copy_from_slice(&filetime.to_le_bytes());
+
+        let values_list = cell_data(values_list_offset);
+        let key_value_header = testhive[values_list..]
+            .chunks_exact(4)
+            .map(|item| cell_data(u32::from_le_bytes(item.try_into().unwrap())))
+            .find(|&header| &testhive[header + 20..header + 26] == b"binary")
+            .unwrap();
+        testhive[key_value_header + 4..key_value_header + 8].copy_from_slice(&8u32.to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("binary").unwrap().unwrap();
+        assert_eq!(key_value.filetime_data().unwrap(), filetime);
+        assert_eq!(
+            key_value.system_time_data().unwrap().unwrap(),
+            std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_609_459_200)
+        );
+
+        #[cfg(feature = "time")]
+        {
+            let date_time = key_value.offset_date_time_data().unwrap().unwrap();
+            assert_eq!(date_time.unix_timestamp(), 1_609_459_200);
+
+            // u64::MAX is beyond the range of OffsetDateTime.
+            let key_value = key_node.value("qword").unwrap().unwrap();
+            assert_eq!(key_value.offset_date_time_data().unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_is_inline() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+
+        let key_value = key_node.value("dword").unwrap().unwrap();
+        assert!(key_value.is_inline());
+        assert!(matches!(key_value.data().unwrap(), KeyValueData::Small(_)));
+        assert!(key_value.data_slack().is_none());
+
+        let key_value = key_node.value("binary").unwrap().unwrap();
+        assert!(!key_value.is_inline());
+        assert!(key_value.data_slack().is_some());
+
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("C").unwrap().unwrap();
+        assert!(!key_value.is_inline());
+        assert!(matches!(key_value.data().unwrap(), KeyValueData::Big(_)));
+    }
+
+    #[test]
+    fn test_name_encoding() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("binary").unwrap().unwrap();
+        assert!(key_value.has_compressed_name());
+        assert_eq!(key_value.name().unwrap().miscoded_utf16le(), None);
+
+        let key_value_header = cell_data(key_value.cell_offset());
+        let index = key_node
+            .values()
+            .unwrap()
+            .unwrap()
+            .position(|key_value| key_value.unwrap().name().unwrap() == "binary")
+            .unwrap();
+
+        let name_value = |testhive: &[u8], name_length: u16, flags: u16| {
+            let mut testhive = testhive.to_vec();
+            testhive[key_value_header + 2..key_value_header + 4]
+                .copy_from_slice(&name_length.to_le_bytes());
+            testhive[key_value_header + 16..key_value_header + 18]
+                .copy_from_slice(&flags.to_le_bytes());
+
+            let hive = Hive::new(testhive.as_ref()).unwrap();
+            let root_key_node = hive.root_key_node().unwrap();
+            let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+            let key_value = key_node
+                .values()
+                .unwrap()
+                .unwrap()
+                .nth(index)
+                .unwrap()
+                .unwrap();
+            key_value.name().map(|name| {
+                (
+                    name.to_string_lossy(),
+                    name.miscoded_utf16le().map(|name| name.to_string_lossy()),
+                )
+            })
+        };
+
+        // An odd length is fine for a Latin1 name, but not for a UTF-16LE name.
+        assert_eq!(
+            name_value(&testhive, 5, 1).unwrap(),
+            (String::from("binar"), None)
+        );
+        assert_eq!(
+            name_value(&testhive, 5, 0),
+            Err(NtHiveError::InvalidSizeFieldAlignment {
+                offset: key_value_header + 2,
+                size: 5,
+                expected_alignment: 2,
+            })
+        );
+
+        // Such a value doesn't prevent looking up other values by name, and its name can still be read lossily.
+        let mut odd_length = testhive.clone();
+        odd_length[key_value_header + 2..key_value_header + 4].copy_from_slice(&5u16.to_le_bytes());
+        odd_length[key_value_header + 16..key_value_header + 18]
+            .copy_from_slice(&0u16.to_le_bytes());
+        let hive = Hive::new(odd_length.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert_eq!(
+            key_node.value("dword").unwrap().unwrap().dword_data(),
+            Ok(42)
+        );
+        assert!(key_node.value("binary").is_none());
+        let key_value = key_node
+            .values()
+            .unwrap()
+            .unwrap()
+            .nth(index)
+            .unwrap()
+            .unwrap();
+        assert_eq!(key_value.name_lossy().unwrap(), "\u{6962}\u{616e}\u{fffd}");
+
+        // A UTF-16LE name with the VALUE_COMP_NAME flag is detected.
+        testhive[key_value_header + 20..key_value_header + 26].copy_from_slice(b"b\0i\0n\0");
+        assert_eq!(
+            name_value(&testhive, 6, 1).unwrap(),
+            (String::from("b\0i\0n\0"), Some(String::from("bin")))
+        );
+        assert_eq!(
+            name_value(&testhive, 6, 0).unwrap(),
+            (String::from("bin"), None)
+        );
+    }
+
+    #[test]
+    fn test_missing_cell_offset() {
+        // A corrupted offset field containing `u32::MAX` is reported as a missing cell for that field.
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("binary").unwrap().unwrap();
+        let data_offset_field = 4096
+            + key_value.cell_offset() as usize
+            + 4
+            + core::mem::offset_of!(super::KeyValueHeader, data_offset);
+        crate::helpers::tests::write_u32(&mut testhive, data_offset_field, u32::MAX);
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("binary").unwrap().unwrap();
+        let expected_error = NtHiveError::MissingCell {
+            field_offset: data_offset_field,
+        };
+        assert_eq!(key_value.data().err(), Some(expected_error.clone()));
+        assert_eq!(key_value.data_slack(), Some(Err(expected_error)));
+
+        // An offset passed directly is rejected like any other offset beyond the end of the hive data.
+        assert_eq!(
+            hive.dump_cell(u32::MAX, false),
+            Err(NtHiveError::InvalidHeaderSize {
+                offset: testhive.len(),
+                expected: 4,
+                actual: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("dword").unwrap().unwrap();
+        let debug = format!("{key_value:?}");
+        assert!(debug.starts_with("KeyValue { name: \"dword\", cell_offset: 0x"));
+        assert!(debug.ends_with(", data_type: RegDWord, data_size: 4 }"));
+    }
+
+    #[test]
+    fn test_data_cow() {
+        use alloc::borrow::Cow;
+
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("dword").unwrap().unwrap();
+        let data = key_value.data_cow().unwrap();
+        assert!(matches!(data, Cow::Borrowed(_)));
+        assert_eq!(*data, 42u32.to_le_bytes());
+
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("A").unwrap().unwrap();
+        let data = key_value.data_cow().unwrap();
+        assert!(matches!(data, Cow::Borrowed(_)));
+        assert_eq!(*data, [b'A'; 16343]);
+
+        let key_value = key_node.value("C").unwrap().unwrap();
+        let data = key_value.data_cow().unwrap();
+        assert!(matches!(data, Cow::Owned(_)));
+        assert_eq!(*data, [b'C'; 16345]);
+
+        // A broken Big Data segment is an error.
+        let (values_count, values_list_offset) = key_node.values_list_info();
+        let values_list = cell_data(values_list_offset);
+        let key_value_header = (0..values_count as usize)
+            .map(|i| {
+                let offset = values_list + i * 4;
+                4096 + u32::from_le_bytes(testhive[offset..offset + 4].try_into().unwrap()) as usize
+                    + 4
+            })
+            .find(|&header| testhive[header + 2] == 1 && testhive[header + 20] == b'C')
+            .unwrap();
+        let big_data_header = 4096
+            + u32::from_le_bytes(
+                testhive[key_value_header + 8..key_value_header + 12]
+                    .try_into()
+                    .unwrap(),
+            ) as usize
+            + 4;
+        let segment_list = 4096
+            + u32::from_le_bytes(
+                testhive[big_data_header + 4..big_data_header + 8]
+                    .try_into()
+                    .unwrap(),
+            ) as usize
+            + 4;
+        testhive[segment_list..segment_list + 4].copy_from_slice(&(u32::MAX - 7).to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("C").unwrap().unwrap();
+        assert!(key_value.data_cow().is_err());
+    }
+
+    #[test]
+    fn test_data_crc32() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("binary").unwrap().unwrap();
+        assert_eq!(key_value.data_crc32().unwrap(), 0x470b_99f4);
+
+        // Prove that the CRC-32 is calculated over all Big Data segments.
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("A").unwrap().unwrap();
+        assert_eq!(key_value.data_crc32().unwrap(), 0x6d21_1c4b);
+
+        let key_value = key_node.value("C").unwrap().unwrap();
+        assert!(matches!(key_value.data().unwrap(), KeyValueData::Big(_)));
+        assert_eq!(key_value.data_crc32().unwrap(), 0xa213_f3f2);
+    }
+
+    #[test]
+    fn test_eq_data() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+
+        // Small data
+        let key_value_data = key_node.value("B").unwrap().unwrap().data().unwrap();
+        let mut expected = vec![b'B'; 16344];
+        assert!(key_value_data.eq_bytes(&expected).unwrap());
+        assert!(key_value_data
+            .eq_data(&KeyValueData::<&[u8]>::Small(&expected))
+            .unwrap());
+
+        expected[16343] = b'X';
+        assert!(!key_value_data.eq_bytes(&expected).unwrap());
+        assert!(!key_value_data
+            .eq_data(&KeyValueData::<&[u8]>::Small(&expected))
+            .unwrap());
+        assert!(!key_value_data.eq_bytes(&expected[..16343]).unwrap());
+
+        // Big data against itself, against contiguous data with different segment boundaries,
+        // and against Small data of another length.
+        let key_value_data = key_node.value("C").unwrap().unwrap().data().unwrap();
+        assert!(matches!(key_value_data, KeyValueData::Big(_)));
+        assert!(key_value_data.eq_data(&key_value_data).unwrap());
+
+        let mut expected = vec![b'C'; 16345];
+        let small_data = KeyValueData::<&[u8]>::Small(&expected);
+        assert!(key_value_data.eq_bytes(&expected).unwrap());
+        assert!(key_value_data.eq_data(&small_data).unwrap());
+        assert!(small_data.eq_data(&key_value_data).unwrap());
+
+        let other_key_value_data = key_node.value("B").unwrap().unwrap().data().unwrap();
+        assert!(!key_value_data.eq_data(&other_key_value_data).unwrap());
+
+        expected[16344] = b'X';
+        let small_data = KeyValueData::<&[u8]>::Small(&expected);
+        assert!(!key_value_data.eq_bytes(&expected).unwrap());
+        assert!(!key_value_data.eq_data(&small_data).unwrap());
+        assert!(!small_data.eq_data(&key_value_data).unwrap());
+    }
+
+    #[test]
+    fn test_data_len_checked() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("dword").unwrap().unwrap();
+        assert_eq!(key_value.data_len_checked().unwrap(), 4);
+
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("B").unwrap().unwrap();
+        assert_eq!(key_value.data_len_checked().unwrap(), 16344);
+        let key_value = key_node.value("C").unwrap().unwrap();
+        assert_eq!(key_value.data_len_checked().unwrap(), 16345);
+
+        // Find the Big Data segment list of "C" and let its second segment offset point to the
+        // first Hive Bin header, which is no allocated cell.
+        let (count, values_list_offset) = key_node.values_list_info();
+        let key_value_offset = (0..count as usize)
+            .map(|i| read_u32(&testhive, cell_data(values_list_offset) + i * 4))
+            .find(|&offset| testhive[cell_data(offset) + 20] == b'C')
+            .unwrap();
+        let big_data_offset = read_u32(&testhive, cell_data(key_value_offset) + 8);
+        let segment_list_offset = read_u32(&testhive, cell_data(big_data_offset) + 4);
+        let second_segment = cell_data(segment_list_offset) + 4;
+        testhive[second_segment..second_segment + 4].copy_from_slice(&0u32.to_le_bytes());
+
+        // data_size still reports the size from the header, but data_len_checked catches the corruption.
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("C").unwrap().unwrap();
+        assert_eq!(key_value.data_size(), 16345);
+        assert!(matches!(
+            key_value.data_len_checked(),
+            Err(NtHiveError::UnallocatedCell { .. })
+        ));
+    }
+
+    #[test]
+    fn test_data_as() {
+        use zerocopy::byteorder::LittleEndian;
+        use zerocopy::{FromBytes, Unaligned, U16};
+
+        #[derive(FromBytes, Unaligned)]
+        #[repr(C)]
+        struct TestStruct {
+            a: u8,
+            b: U16<LittleEndian>,
+        }
+
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+
+        // "binary" contains the bytes 1, 2, 3, 4, 5.
+        let key_value = key_node.value("binary").unwrap().unwrap();
+        let test_struct = key_value.data_as::<TestStruct>().unwrap();
+        assert_eq!(test_struct.a, 1);
+        assert_eq!(test_struct.b.get(), 0x0302);
+        assert_eq!(
+            key_value.data_as_exact::<[u8; 5]>().unwrap(),
+            [1, 2, 3, 4, 5]
+        );
+        assert!(matches!(
+            key_value.data_as_exact::<TestStruct>(),
+            Err(NtHiveError::InvalidDataSize {
+                expected: 3,
+                actual: 5,
+                ..
+            })
+        ));
+        assert!(matches!(
+            key_value.data_as::<[u8; 6]>(),
+            Err(NtHiveError::InvalidDataSize {
+                expected: 6,
+                actual: 5,
+                ..
+            })
+        ));
+
+        assert_eq!(key_value.data_as_slice_of::<u8>().unwrap(), [1, 2, 3, 4, 5]);
+        assert!(matches!(
+            key_value.data_as_slice_of::<U16<LittleEndian>>(),
+            Err(NtHiveError::InvalidSizeFieldAlignment {
+                size: 5,
+                expected_alignment: 2,
+                ..
+            })
+        ));
+
+        // Other data types are rejected.
+        let key_value = key_node.value("dword").unwrap().unwrap();
+        assert!(matches!(
+            key_value.data_as::<u8>(),
+            Err(NtHiveError::InvalidKeyValueDataType { .. })
+        ));
+
+        // Big Data works as well.
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("C").unwrap().unwrap();
+        assert_eq!(key_value.data_as::<[u8; 4]>().unwrap(), [b'C'; 4]);
+
+        // A `T` larger than the first Big Data segment is read across all segments.
+        let big_array = key_value.data_as_exact::<[u8; 16345]>().unwrap();
+        assert!(big_array.iter().all(|byte| *byte == b'C'));
+        assert!(matches!(
+            key_value.data_as::<[u8; 16346]>(),
+            Err(NtHiveError::InvalidDataSize {
+                expected: 16346,
+                actual: 16345,
+                ..
+            })
+        ));
+
+        let elements = key_value.data_as_slice_of::<u8>().unwrap();
+        assert_eq!(elements.len(), 16345);
+    }
+
+    #[test]
+    fn test_data_type_names() {
+        let data_types = (0..=0xb).map(|code| KeyValueDataType::n(code).unwrap());
+
+        for data_type in data_types {
+            let name = data_type.to_string();
+            assert!(name.starts_with("REG_"));
+            assert_eq!(name.parse::<KeyValueDataType>(), Ok(data_type));
+            assert_eq!(KeyValueDataType::n(data_type as u32), Some(data_type));
+            assert_eq!(KeyValueDataType::try_from(data_type as u32), Ok(data_type));
+
+            #[cfg(feature = "serde")]
+            {
+                let json = serde_json::to_string(&data_type).unwrap();
+                assert_eq!(json, format!("\"{name}\""));
+                assert_eq!(
+                    serde_json::from_str::<KeyValueDataType>(&json).unwrap(),
+                    data_type
+                );
+            }
+        }
+
+        assert_eq!(KeyValueDataType::RegSZ.to_string(), "REG_SZ");
+        assert_eq!(
+            "REG_DWORD_LITTLE_ENDIAN".parse(),
+            Ok(KeyValueDataType::RegDWord)
+        );
+        assert_eq!(KeyValueDataType::n(0xc), None);
+        assert_eq!(KeyValueDataType::try_from(0xc), Err(0xc));
+
+        assert_eq!(
+            "REG_FOO".parse::<KeyValueDataType>(),
+            Err(ParseKeyValueDataTypeError)
+        );
+        assert_eq!(
+            "reg_sz".parse::<KeyValueDataType>(),
+            Err(ParseKeyValueDataTypeError)
+        );
+
+        #[cfg(feature = "serde")]
+        assert!(serde_json::from_str::<KeyValueDataType>("\"REG_FOO\"").is_err());
+    }
 }
@@ -12,11 +12,12 @@ use crate::error::{NtHiveError, Result};
 use crate::helpers::byte_subrange;
 use crate::hive::Hive;
 use crate::key_value::KeyValue;
+use crate::list_capacity::ListCapacity;
 
 /// On-Disk Structure of a Key Values List item.
 #[allow(dead_code)]
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
+#[repr(C, packed)]
 struct KeyValuesListItem {
     key_value_offset: U32<LittleEndian>,
 }
@@ -29,7 +30,8 @@ impl KeyValuesListItemRange {
     where
         B: SplitByteSlice,
     {
-        let item = Ref::<&[u8], KeyValuesListItem>::from_bytes(&hive.data[self.0.clone()]).unwrap();
+        let item =
+            Ref::<&[u8], KeyValuesListItem>::from_bytes(hive.data_slice(self.0.clone())).unwrap();
         item.key_value_offset.get()
     }
 }
@@ -58,7 +60,9 @@ impl KeyValuesListItemRanges {
         count_field_offset: usize,
         cell_range: Range<usize>,
     ) -> Result<Self> {
-        let byte_count = count as usize * mem::size_of::<KeyValuesListItem>();
+        // `count` is a u32 from the hive, so this multiplication may overflow on 32-bit platforms.
+        // A saturated `byte_count` is rejected by `byte_subrange` below.
+        let byte_count = (count as usize).saturating_mul(mem::size_of::<KeyValuesListItem>());
 
         let items_range = byte_subrange(&cell_range, byte_count).ok_or_else(|| {
             NtHiveError::InvalidSizeField {
@@ -70,6 +74,13 @@ impl KeyValuesListItemRanges {
 
         Ok(Self { items_range })
     }
+
+    /// Returns the ranges of the items that follow the declared items up to the end of the cell.
+    fn stale(self, cell_range: &Range<usize>) -> Self {
+        Self {
+            items_range: self.items_range.end..cell_range.end,
+        }
+    }
 }
 
 impl Iterator for KeyValuesListItemRanges {
@@ -141,6 +152,38 @@ where
             key_values_list_item_ranges,
         })
     }
+
+    /// Like [`KeyValues::new`], but iterates the stale items following the declared `count` items.
+    pub(crate) fn new_stale(
+        hive: &'h Hive<B>,
+        count: u32,
+        count_field_offset: usize,
+        cell_range: Range<usize>,
+    ) -> Result<Self> {
+        let key_values_list_item_ranges =
+            KeyValuesListItemRanges::new(count, count_field_offset, cell_range.clone())?
+                .stale(&cell_range);
+
+        Ok(Self {
+            hive,
+            key_values_list_item_ranges,
+        })
+    }
+}
+
+/// Computes the [`ListCapacity`] of a Key Values List cell holding `count` items.
+pub(crate) fn key_values_list_capacity(
+    count: u32,
+    count_field_offset: usize,
+    cell_range: Range<usize>,
+) -> Result<ListCapacity> {
+    let key_values_list_item_ranges =
+        KeyValuesListItemRanges::new(count, count_field_offset, cell_range.clone())?;
+
+    Ok(ListCapacity {
+        allocated_size: cell_range.len(),
+        used_size: key_values_list_item_ranges.items_range.end - cell_range.start,
+    })
 }
 
 impl<'h, B> Iterator for KeyValues<'h, B>
@@ -152,7 +195,11 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         let key_values_list_item_range = self.key_values_list_item_ranges.next()?;
         let key_value_offset = key_values_list_item_range.key_value_offset(self.hive);
-        let cell_range = iter_try!(self.hive.cell_range_from_data_offset(key_value_offset));
+        let cell_range = iter_try!(self.hive.cell_range_from_offset_field(
+            key_value_offset,
+            self.hive
+                .offset_of_data_offset(key_values_list_item_range.0.start),
+        ));
         let key_value = iter_try!(KeyValue::new(self.hive, cell_range));
         Some(Ok(key_value))
     }
@@ -188,3 +235,30 @@ where
 
 impl<B> ExactSizeIterator for KeyValues<'_, B> where B: SplitByteSlice {}
 impl<B> FusedIterator for KeyValues<'_, B> where B: SplitByteSlice {}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::key_node_field;
+    use crate::key_node::KeyNodeField;
+    use crate::*;
+
+    #[test]
+    fn test_huge_values_count() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+
+        // Set `key_values_count` to the maximum and prove that this is caught without any overflow.
+        let count_offset = key_node_field(key_node.cell_offset(), KeyNodeField::KeyValuesCount);
+        testhive[count_offset..count_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert!(matches!(
+            key_node.values(),
+            Some(Err(NtHiveError::InvalidSizeField { .. }))
+        ));
+    }
+}
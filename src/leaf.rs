@@ -6,23 +6,29 @@ use core::mem;
 use core::ops::{Deref, Range};
 
 use zerocopy::byteorder::LittleEndian;
-use zerocopy::{
-    FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, SplitByteSliceMut,
-    Unaligned, U32,
-};
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, Unaligned, U32};
 
 use crate::error::{NtHiveError, Result};
 use crate::helpers::byte_subrange;
 use crate::hive::Hive;
 use crate::index_root::IndexRootItemRange;
-use crate::key_node::{KeyNode, KeyNodeMut};
+use crate::key_node::KeyNode;
 use crate::subkeys_list::SubkeysList;
 
+#[cfg(feature = "alloc")]
+use crate::dump::StructLayout;
+
+#[cfg(feature = "write")]
+use {
+    crate::key_node::KeyNodeMut, crate::subkeys_list::SubkeysListSnapshot,
+    zerocopy::SplitByteSliceMut,
+};
+
 /// On-Disk Structure of a Fast Leaf item (On-Disk Signature: `lf`).
 /// They are supported since Windows NT 4.
 #[allow(dead_code)]
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
+#[repr(C, packed)]
 struct FastLeafItem {
     key_node_offset: U32<LittleEndian>,
     name_hint: [u8; 4],
@@ -32,7 +38,7 @@ struct FastLeafItem {
 /// They are supported since Windows XP.
 #[allow(dead_code)]
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
+#[repr(C, packed)]
 struct HashLeafItem {
     key_node_offset: U32<LittleEndian>,
     name_hash: [u8; 4],
@@ -41,11 +47,27 @@ struct HashLeafItem {
 /// On-Disk Structure of an Index Leaf item (On-Disk Signature: `li`).
 /// They are supported in all Windows versions.
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
+#[repr(C, packed)]
 struct IndexLeafItem {
     key_node_offset: U32<LittleEndian>,
 }
 
+#[cfg(feature = "alloc")]
+pub(crate) const FAST_LEAF_ITEM_LAYOUT: StructLayout = struct_layout!(FastLeafItem {
+    key_node_offset,
+    name_hint,
+});
+
+#[cfg(feature = "alloc")]
+pub(crate) const HASH_LEAF_ITEM_LAYOUT: StructLayout = struct_layout!(HashLeafItem {
+    key_node_offset,
+    name_hash,
+});
+
+#[cfg(feature = "alloc")]
+pub(crate) const INDEX_LEAF_ITEM_LAYOUT: StructLayout =
+    struct_layout!(IndexLeafItem { key_node_offset });
+
 /// All known and supported Leaf types.
 ///
 /// We first had only Index Leafs, then got Fast Leafs with Windows NT 4 which add a
@@ -55,8 +77,10 @@ struct IndexLeafItem {
 /// Both Fast Leafs and Hash Leafs were introduced to speed up key lookups.
 /// However, their performance benefits are marginal to non-existing in 2020
 /// when we assume that the entire registry hive is randomly accessible.
-/// Therefore, the nt-hive crate treats all types equally by only accessing the
-/// `key_node_offset` field and ignoring all other fields.
+/// Therefore, the nt-hive crate mostly treats all types equally by only accessing the
+/// `key_node_offset` field.
+/// The only exception is the `name_hash`, which is cheap to compare and lets subkey lookups
+/// skip decoding the names of most non-matching Key Nodes in a Hash Leaf.
 #[derive(Clone, Copy)]
 pub(crate) enum LeafType {
     Fast,
@@ -95,9 +119,19 @@ impl LeafItemRange {
         // `IndexLeafItem` with additional fields.
         // As they all have the `key_node_offset` as their first field, treat them equally.
         let (index_leaf_item, _) =
-            Ref::<&[u8], IndexLeafItem>::from_prefix(&hive.data[self.0.clone()]).unwrap();
+            Ref::<&[u8], IndexLeafItem>::from_prefix(hive.data_slice(self.0.clone())).unwrap();
         index_leaf_item.key_node_offset.get()
     }
+
+    /// Returns the `name_hash` field of this Leaf item, which must be a Hash Leaf item.
+    pub fn name_hash<B>(&self, hive: &Hive<B>) -> u32
+    where
+        B: SplitByteSlice,
+    {
+        let hash_leaf_item =
+            Ref::<&[u8], HashLeafItem>::from_bytes(hive.data_slice(self.0.clone())).unwrap();
+        u32::from_le_bytes(hash_leaf_item.name_hash)
+    }
 }
 
 impl Deref for LeafItemRange {
@@ -142,17 +176,68 @@ impl LeafItemRanges {
         })
     }
 
+    pub(crate) fn leaf_type(&self) -> LeafType {
+        self.leaf_type
+    }
+
+    /// Returns the ranges of the items that follow the declared items up to the end of the data.
+    pub(crate) fn stale(self, data_range: &Range<usize>) -> Self {
+        Self {
+            items_range: self.items_range.end..data_range.end,
+            leaf_type: self.leaf_type,
+        }
+    }
+
+    /// Returns the number of data bytes used by the declared items.
+    pub(crate) fn used_size(&self, data_range: &Range<usize>) -> usize {
+        self.items_range.end - data_range.start
+    }
+
     pub fn from_index_root_item_range<B>(
         hive: &Hive<B>,
         index_root_item_range: IndexRootItemRange,
     ) -> Result<Self>
+    where
+        B: SplitByteSlice,
+    {
+        let subkeys_list = Self::index_root_item_leaf(hive, &index_root_item_range)?;
+        Self::from_index_root_leaf(hive, subkeys_list)
+    }
+
+    /// Like [`LeafItemRanges::from_index_root_item_range`], but additionally returns a snapshot of the Leaf header.
+    #[cfg(feature = "write")]
+    pub(crate) fn from_index_root_item_range_with_snapshot<B>(
+        hive: &Hive<B>,
+        index_root_item_range: &IndexRootItemRange,
+    ) -> Result<(Self, SubkeysListSnapshot)>
+    where
+        B: SplitByteSlice,
+    {
+        let subkeys_list = Self::index_root_item_leaf(hive, index_root_item_range)?;
+        let snapshot = SubkeysListSnapshot::new(&subkeys_list);
+        let leaf_item_ranges = Self::from_index_root_leaf(hive, subkeys_list)?;
+        Ok((leaf_item_ranges, snapshot))
+    }
+
+    fn index_root_item_leaf<'h, B>(
+        hive: &'h Hive<B>,
+        index_root_item_range: &IndexRootItemRange,
+    ) -> Result<SubkeysList<'h, B>>
     where
         B: SplitByteSlice,
     {
         let subkeys_list_offset = index_root_item_range.subkeys_list_offset(hive);
-        let cell_range = hive.cell_range_from_data_offset(subkeys_list_offset)?;
-        let subkeys_list = SubkeysList::new_without_index_root(hive, cell_range)?;
+        let cell_range = hive.cell_range_from_offset_field(
+            subkeys_list_offset,
+            hive.offset_of_data_offset(index_root_item_range.start),
+        )?;
+        SubkeysList::new_without_index_root(hive, cell_range)
+    }
 
+    fn from_index_root_leaf<B>(hive: &Hive<B>, subkeys_list: SubkeysList<B>) -> Result<Self>
+    where
+        B: SplitByteSlice,
+    {
         let header = subkeys_list.header();
         let count = header.count.get();
         let count_field_offset = hive.offset_of_field(&header.count);
@@ -258,6 +343,24 @@ where
             leaf_item_ranges,
         })
     }
+
+    /// Like [`LeafKeyNodes::new`], but iterates the stale items following the declared `count` items.
+    pub(crate) fn new_stale(
+        hive: &'h Hive<B>,
+        count: u16,
+        count_field_offset: usize,
+        data_range: Range<usize>,
+        leaf_type: LeafType,
+    ) -> Result<Self> {
+        let leaf_item_ranges =
+            LeafItemRanges::new(count, count_field_offset, data_range.clone(), leaf_type)?
+                .stale(&data_range);
+
+        Ok(Self {
+            hive,
+            leaf_item_ranges,
+        })
+    }
 }
 
 impl<'h, B> Iterator for LeafKeyNodes<'h, B>
@@ -312,11 +415,14 @@ impl<B> FusedIterator for LeafKeyNodes<'_, B> where B: SplitByteSlice {}
 /// On-Disk Signatures: `lf`, `lh`, `li`
 ///
 /// [`SubKeyNodesMut`]: crate::subkeys_list::SubKeyNodesMut
+#[cfg(feature = "write")]
 pub(crate) struct LeafKeyNodesMut<'h, B: SplitByteSliceMut> {
     hive: &'h mut Hive<B>,
     leaf_item_ranges: LeafItemRanges,
+    snapshot: SubkeysListSnapshot,
 }
 
+#[cfg(feature = "write")]
 impl<'h, B> LeafKeyNodesMut<'h, B>
 where
     B: SplitByteSliceMut,
@@ -327,6 +433,7 @@ where
         count_field_offset: usize,
         data_range: Range<usize>,
         leaf_type: LeafType,
+        snapshot: SubkeysListSnapshot,
     ) -> Result<Self> {
         let leaf_item_ranges =
             LeafItemRanges::new(count, count_field_offset, data_range, leaf_type)?;
@@ -334,6 +441,7 @@ where
         Ok(Self {
             hive,
             leaf_item_ranges,
+            snapshot,
         })
     }
 
@@ -341,8 +449,49 @@ where
     where
         'h: 'a,
     {
+        iter_try!(self.snapshot.verify(self.hive));
         let leaf_item_range = self.leaf_item_ranges.next()?;
         let key_node = iter_try!(KeyNodeMut::from_leaf_item_range(self.hive, leaf_item_range));
         Some(Ok(key_node))
     }
 }
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use crate::helpers::tests::{cell_data, key_node_field, read_u32};
+    use crate::hive::HIVE_BASE_BLOCK_SIZE;
+    use crate::key_node::KeyNodeField;
+    use crate::subkeys_list::SubKeyNodesMut;
+    use crate::*;
+
+    #[test]
+    fn test_concurrent_structural_modification() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let root_cell_offset = read_u32(&testhive, 36);
+        let subkeys_list_offset = read_u32(
+            &testhive,
+            key_node_field(root_cell_offset, KeyNodeField::SubkeysListOffset),
+        );
+
+        // The count field follows the cell header and the signature.
+        let count_start = cell_data(subkeys_list_offset) - HIVE_BASE_BLOCK_SIZE + 2;
+        let count_range = count_start..count_start + 2;
+
+        let mut hive = Hive::new(testhive.as_mut()).unwrap();
+        let mut root_key_node = hive.root_key_node_mut().unwrap();
+        let SubKeyNodesMut::Leaf(mut iter) = root_key_node.subkeys_mut().unwrap().unwrap() else {
+            panic!("The root key should have a Leaf as its Subkeys List");
+        };
+        assert!(iter.next().unwrap().is_ok());
+
+        // Shrink the list while iterating over it.
+        iter.hive
+            .data_slice_mut(count_range)
+            .copy_from_slice(&4u16.to_le_bytes());
+        assert!(matches!(
+            iter.next(),
+            Some(Err(NtHiveError::ConcurrentStructuralModification { offset }))
+                if offset == cell_data(subkeys_list_offset)
+        ));
+    }
+}
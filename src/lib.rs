@@ -19,27 +19,121 @@
 #[macro_use]
 mod helpers;
 
+mod architecture;
 mod big_data;
+#[cfg(feature = "std")]
+mod bodyfile;
+#[cfg(feature = "alloc")]
+mod cancel;
+mod control_sets;
+#[cfg(feature = "alloc")]
+mod descendants;
+#[cfg(feature = "std")]
+mod doctest;
+#[cfg(feature = "alloc")]
+mod dump;
 mod error;
+#[cfg(any(feature = "std", feature = "time"))]
+mod filetime;
+#[cfg(feature = "alloc")]
+mod fragments;
 mod hive;
+mod hive_bin;
 mod index_root;
+#[cfg(feature = "alloc")]
+mod inline_candidates;
 mod key_node;
+mod key_security;
 mod key_value;
 mod key_values_list;
 mod leaf;
+mod list_capacity;
+#[cfg(feature = "alloc")]
+mod recovery;
+#[cfg(feature = "alloc")]
+mod resource_list;
+mod security_descriptor;
+#[cfg(feature = "alloc")]
+pub mod simple;
+mod slack;
+#[cfg(feature = "alloc")]
+mod snapshot;
+#[cfg(feature = "alloc")]
+mod space;
 mod string;
 mod subkeys_list;
+#[cfg(feature = "alloc")]
+mod timeline;
+#[cfg(feature = "alloc")]
+mod value_search;
+#[cfg(feature = "alloc")]
+mod verify;
+#[cfg(feature = "alloc")]
+mod walker;
 
+pub use crate::architecture::*;
 pub use crate::big_data::*;
+#[cfg(feature = "std")]
+pub use crate::bodyfile::*;
+#[cfg(feature = "alloc")]
+pub use crate::cancel::*;
+pub use crate::control_sets::*;
+#[cfg(feature = "alloc")]
+pub use crate::descendants::*;
+#[cfg(feature = "std")]
+pub use crate::doctest::*;
 pub use crate::error::*;
 pub use crate::hive::*;
+pub use crate::hive_bin::*;
 pub use crate::index_root::*;
+#[cfg(feature = "alloc")]
+pub use crate::inline_candidates::*;
 pub use crate::key_node::*;
+pub use crate::key_security::*;
 pub use crate::key_value::*;
 pub use crate::key_values_list::*;
 pub use crate::leaf::*;
+pub use crate::list_capacity::*;
+#[cfg(feature = "alloc")]
+pub use crate::recovery::*;
+#[cfg(feature = "alloc")]
+pub use crate::resource_list::*;
+pub use crate::security_descriptor::*;
+pub use crate::slack::*;
+#[cfg(feature = "alloc")]
+pub use crate::snapshot::*;
+#[cfg(feature = "alloc")]
+pub use crate::space::*;
 pub use crate::string::*;
 pub use crate::subkeys_list::*;
+#[cfg(feature = "alloc")]
+pub use crate::timeline::*;
+#[cfg(feature = "alloc")]
+pub use crate::value_search::*;
+#[cfg(feature = "alloc")]
+pub use crate::verify::*;
+#[cfg(feature = "alloc")]
+pub use crate::walker::*;
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
+
+/// Without the `write` feature, no method can modify the bytes of a hive:
+///
+/// ```compile_fail
+/// let mut testhive = std::fs::read("testdata/testhive").unwrap();
+/// let mut hive = nt_hive::Hive::new(testhive.as_mut_slice()).unwrap();
+/// hive.clear_volatile_subkeys().unwrap();
+/// ```
+#[cfg(all(doctest, not(feature = "write")))]
+pub struct ReadOnlyBuild;
+
+/// With the `write` feature, the same code compiles:
+///
+/// ```
+/// let mut testhive = std::fs::read("testdata/testhive").unwrap();
+/// let mut hive = nt_hive::Hive::new(testhive.as_mut_slice()).unwrap();
+/// hive.clear_volatile_subkeys().unwrap();
+/// ```
+#[cfg(all(doctest, feature = "write"))]
+pub struct WritableBuild;
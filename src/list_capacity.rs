@@ -0,0 +1,40 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+/// Cells are allocated in multiples of this many bytes.
+const ALLOCATION_GRANULE: usize = 8;
+
+/// How much of a Key Values List or Subkeys List cell is actually used by the items it declares,
+/// as returned by [`KeyNode::values_list_capacity`] and [`KeyNode::subkeys_list_capacity`].
+///
+/// Windows allocates these lists with some slack and doesn't clear items when shrinking them.
+/// A list cell that is much larger than its declared count requires may therefore still contain
+/// stale items referencing removed values or subkeys.
+/// These can be retrieved via [`KeyNode::stale_values`] and [`KeyNode::stale_subkeys`].
+///
+/// [`KeyNode::values_list_capacity`]: crate::key_node::KeyNode::values_list_capacity
+/// [`KeyNode::subkeys_list_capacity`]: crate::key_node::KeyNode::subkeys_list_capacity
+/// [`KeyNode::stale_values`]: crate::key_node::KeyNode::stale_values
+/// [`KeyNode::stale_subkeys`]: crate::key_node::KeyNode::stale_subkeys
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ListCapacity {
+    /// Size of the list cell data in bytes, excluding the cell header.
+    pub allocated_size: usize,
+    /// Number of bytes required for the list header (if any) and the declared number of items.
+    pub used_size: usize,
+}
+
+impl ListCapacity {
+    /// Returns the number of allocated bytes following the declared items.
+    pub fn excess_capacity(&self) -> usize {
+        self.allocated_size - self.used_size
+    }
+
+    /// Returns `true` if the list cell is more than one allocation granule (8 bytes) larger than
+    /// required for the declared items.
+    ///
+    /// Such a list is a sign of a shrunk list, which may contain stale items.
+    pub fn is_oversized(&self) -> bool {
+        self.excess_capacity() > ALLOCATION_GRANULE
+    }
+}
@@ -0,0 +1,893 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::Range;
+
+use zerocopy::SplitByteSlice;
+
+use crate::error::{NtHiveError, Result};
+use crate::helpers::byte_subrange;
+use crate::hive::{CellHeader, Hive};
+use crate::hive_bin::HiveCells;
+use crate::key_node::{key_node_header_size, KeyNode, KeyNodeFlags};
+use crate::key_value::{KeyValue, KeyValueData, KeyValueDataType};
+use crate::key_values_list::KeyValues;
+use crate::string::NtHiveNameString;
+use crate::walker::KeyNodeWalker;
+
+/// Signature of a Key Node, used to find candidates in unallocated cells.
+const KEY_NODE_SIGNATURE: &[u8; 2] = b"nk";
+
+/// Signature of a Key Value, used to find candidates in unallocated cells.
+const KEY_VALUE_SIGNATURE: &[u8; 2] = b"vk";
+
+/// Alignment of cells and therefore of the candidates checked inside unallocated cells.
+const CELL_ALIGNMENT: usize = 8;
+
+/// Describes how much of the data belonging to a deleted key could be recovered.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RecoveryConfidence {
+    /// The Key Values List and all Key Values referenced by it are still intact.
+    Intact,
+    /// The Key Values List or some of the Key Values referenced by it have already been overwritten.
+    /// Only the Key Values that could still be parsed are reported.
+    Partial,
+}
+
+/// A deleted key found in the unallocated space of a hive, as returned by [`Hive::deleted_keys_report`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeletedKey {
+    /// Data offset of the cell that contained the Key Node.
+    pub cell_offset: u32,
+    /// Name of the deleted key.
+    pub name: String,
+    /// Last write timestamp of the deleted key as a raw Windows FILETIME.
+    pub timestamp: u64,
+    /// Data offset of the parent Key Node, as stored in the deleted Key Node.
+    pub parent_offset: u32,
+    /// Full path the deleted key would have in the current tree (without the root key name).
+    ///
+    /// This is `None` if the parent key is not part of the current tree anymore (i.e. the key is orphaned).
+    pub path: Option<String>,
+    /// Key Values of the deleted key that could still be parsed.
+    pub values: Vec<DeletedKeyValueReport>,
+    /// Whether all Key Values of the deleted key could be recovered.
+    pub values_confidence: RecoveryConfidence,
+}
+
+/// A Key Value belonging to a [`DeletedKey`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeletedKeyValueReport {
+    /// Data offset of the cell that contained the Key Value.
+    pub cell_offset: u32,
+    /// Name of the Key Value.
+    pub name: String,
+    /// Data type of the Key Value or `None` if it is unknown.
+    pub data_type: Option<KeyValueDataType>,
+    /// Size of the raw data of the Key Value.
+    pub data_size: u32,
+}
+
+/// A Key Node found in the unallocated space of a hive, as returned by [`Hive::deleted_key_nodes`].
+///
+/// In contrast to a [`KeyNode`], this is not part of the key tree anymore, and the cells it references
+/// may have been freed or reused.
+/// It therefore only offers the fields stored in the Key Node header itself along with a few methods
+/// following offsets, which return errors for cells that are no longer allocated.
+#[derive(Clone)]
+pub struct DeletedKeyNode<'h, B: SplitByteSlice> {
+    key_node: KeyNode<'h, B>,
+    cell_offset: u32,
+}
+
+impl<'h, B> DeletedKeyNode<'h, B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns the data offset of the (unallocated) cell containing this Key Node.
+    ///
+    /// If the Key Node has been found in the middle of a larger unallocated cell, this is the offset
+    /// where its original cell started.
+    pub fn cell_offset(&self) -> u32 {
+        self.cell_offset
+    }
+
+    /// Returns the flags of this deleted key.
+    pub fn flags(&self) -> KeyNodeFlags {
+        self.key_node.flags()
+    }
+
+    /// Returns the name of this deleted key.
+    ///
+    /// Returns an error if the name has been partially overwritten and doesn't fit into the cell anymore.
+    pub fn name(&self) -> Result<NtHiveNameString<'h>> {
+        self.key_node.name()
+    }
+
+    /// Returns the parent [`KeyNode`] of this deleted key in the current key tree.
+    ///
+    /// Returns an error if the parent has been deleted as well (see [`DeletedKeyNode::parent_offset`]
+    /// to find it among the other deleted keys).
+    /// Returns `None` if this deleted key has been a hive root.
+    pub fn parent(&self) -> Option<Result<KeyNode<'h, B>>> {
+        self.key_node.parent()
+    }
+
+    /// Returns the data offset of the parent Key Node, as stored in the header of this deleted key.
+    pub fn parent_offset(&self) -> u32 {
+        self.key_node.parent_offset()
+    }
+
+    /// Returns the number of subkeys this key had when it was deleted.
+    pub fn subkey_count(&self) -> u32 {
+        self.key_node.subkey_count()
+    }
+
+    /// Returns the last write timestamp of this deleted key as a raw Windows FILETIME.
+    pub fn timestamp(&self) -> u64 {
+        self.key_node.timestamp()
+    }
+
+    /// Returns the number of values this key had when it was deleted.
+    pub fn value_count(&self) -> u32 {
+        self.key_node.value_count()
+    }
+
+    /// Returns an iterator over the values of this deleted key, if its Key Values List is still allocated.
+    ///
+    /// Windows usually frees the Key Values List along with the key, so expect an
+    /// [`NtHiveError::UnallocatedCell`] error here.
+    /// [`Hive::deleted_keys_report`] recovers values from unallocated cells as well.
+    pub fn values(&self) -> Option<Result<KeyValues<'h, B>>> {
+        self.key_node.values()
+    }
+}
+
+/// A Key Value found in the unallocated space of a hive, as returned by [`Hive::deleted_key_values`].
+///
+/// Like a [`DeletedKeyNode`], this is not part of the key tree anymore.
+/// Its data cell has usually been freed along with it, but can still be read as long as it hasn't been reused.
+#[derive(Clone)]
+pub struct DeletedKeyValue<'h, B: SplitByteSlice> {
+    key_value: KeyValue<'h, B>,
+    cell_offset: u32,
+}
+
+impl<'h, B> DeletedKeyValue<'h, B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns the data offset of the (unallocated) cell containing this Key Value.
+    pub fn cell_offset(&self) -> u32 {
+        self.cell_offset
+    }
+
+    /// Returns the raw data bytes of this deleted value as [`KeyValueData`].
+    ///
+    /// In contrast to [`KeyValue::data`], the data cell may be unallocated.
+    /// Nothing guarantees that it still holds the original data though: check [`DeletedKeyValue::data_size`]
+    /// and the data type for plausibility.
+    /// Returns an error if the data cell is out of bounds or too small, or if a Big Data segment list
+    /// or segment has been freed.
+    pub fn data(&self) -> Result<KeyValueData<'h, B>> {
+        self.key_value.data_including_unallocated()
+    }
+
+    /// Returns the data offset of the cell holding the data of this deleted value (or its Big Data header).
+    ///
+    /// Returns `None` if the data is stored inline in the Key Value.
+    pub fn data_cell_offset(&self) -> Option<u32> {
+        self.key_value.referenced_cell_offset()
+    }
+
+    /// Returns the size of the raw data, as stored in the header of this deleted value.
+    pub fn data_size(&self) -> u32 {
+        self.key_value.data_size()
+    }
+
+    /// Returns the data type of this deleted value.
+    pub fn data_type(&self) -> Result<KeyValueDataType> {
+        self.key_value.data_type()
+    }
+
+    /// Returns the name of this deleted value.
+    pub fn name(&self) -> Result<NtHiveNameString<'h>> {
+        self.key_value.name()
+    }
+}
+
+/// Iterator over the data offsets of all 8-byte boundaries within the unallocated cells of a hive.
+///
+/// Windows merges adjacent unallocated cells, so a deleted structure may also be found in the middle
+/// of an unallocated cell.
+struct UnallocatedCandidates<'h, B: SplitByteSlice> {
+    cells: HiveCells<'h, B>,
+    /// Data offsets of the remaining candidates in the current unallocated cell.
+    candidates: Range<usize>,
+}
+
+impl<'h, B> UnallocatedCandidates<'h, B>
+where
+    B: SplitByteSlice,
+{
+    fn new(hive: &'h Hive<B>) -> Self {
+        Self {
+            cells: hive.cells(),
+            candidates: 0..0,
+        }
+    }
+}
+
+impl<B> Iterator for UnallocatedCandidates<'_, B>
+where
+    B: SplitByteSlice,
+{
+    /// Data offset of the candidate and end of the unallocated cell containing it.
+    type Item = Result<(usize, usize)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.candidates.is_empty() {
+            let cell = iter_try!(self.cells.next()?);
+            if !cell.is_allocated() {
+                let cell_offset = cell.data_offset() as usize;
+                self.candidates = cell_offset..cell_offset + cell.size() as usize;
+            }
+        }
+
+        let candidate_offset = self.candidates.start;
+        self.candidates.start += CELL_ALIGNMENT;
+        Some(Ok((candidate_offset, self.candidates.end)))
+    }
+}
+
+impl<B> FusedIterator for UnallocatedCandidates<'_, B> where B: SplitByteSlice {}
+
+/// Iterator over
+///   all Key Nodes found in the unallocated cells of a hive,
+///   returning a [`DeletedKeyNode`] for each candidate.
+///
+/// Every 8-byte boundary of an unallocated cell is checked for the `nk` signature followed by a parseable
+/// Key Node header.
+/// Iteration stops after the first invalid Hive Bin or cell (see [`HiveCells`]).
+///
+/// Created by [`Hive::deleted_key_nodes`].
+pub struct DeletedKeyNodes<'h, B: SplitByteSlice> {
+    hive: &'h Hive<B>,
+    candidates: UnallocatedCandidates<'h, B>,
+}
+
+impl<'h, B> Iterator for DeletedKeyNodes<'h, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<DeletedKeyNode<'h, B>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for candidate in self.candidates.by_ref() {
+            let (candidate_offset, cell_end) = iter_try!(candidate);
+
+            if let Some(key_node) = self.hive.key_node_candidate(candidate_offset, cell_end) {
+                return Some(Ok(DeletedKeyNode {
+                    key_node,
+                    cell_offset: candidate_offset as u32,
+                }));
+            }
+        }
+
+        None
+    }
+}
+
+impl<B> FusedIterator for DeletedKeyNodes<'_, B> where B: SplitByteSlice {}
+
+/// Iterator over
+///   all Key Values found in the unallocated cells of a hive,
+///   returning a [`DeletedKeyValue`] for each candidate.
+///
+/// Every 8-byte boundary of an unallocated cell is checked for the `vk` signature followed by a complete
+/// Key Value header.
+/// Iteration stops after the first invalid Hive Bin or cell (see [`HiveCells`]).
+///
+/// Created by [`Hive::deleted_key_values`].
+pub struct DeletedKeyValues<'h, B: SplitByteSlice> {
+    hive: &'h Hive<B>,
+    candidates: UnallocatedCandidates<'h, B>,
+}
+
+impl<'h, B> Iterator for DeletedKeyValues<'h, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<DeletedKeyValue<'h, B>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for candidate in self.candidates.by_ref() {
+            let (candidate_offset, cell_end) = iter_try!(candidate);
+
+            if let Some(key_value) = self.hive.key_value_candidate(candidate_offset, cell_end) {
+                return Some(Ok(DeletedKeyValue {
+                    key_value,
+                    cell_offset: candidate_offset as u32,
+                }));
+            }
+        }
+
+        None
+    }
+}
+
+impl<B> FusedIterator for DeletedKeyValues<'_, B> where B: SplitByteSlice {}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns an iterator over all Key Nodes found in the unallocated cells of this hive.
+    ///
+    /// Deleted keys usually survive in unallocated cells until the space is reused.
+    /// Each [`DeletedKeyNode`] carries its cell offset and the offset of its parent, which can be correlated
+    /// with the current key tree or with other deleted keys.
+    /// This is the lazy, low-level counterpart to [`Hive::deleted_keys_report`].
+    pub fn deleted_key_nodes(&self) -> DeletedKeyNodes<'_, B> {
+        DeletedKeyNodes {
+            hive: self,
+            candidates: UnallocatedCandidates::new(self),
+        }
+    }
+
+    /// Returns an iterator over all Key Values found in the unallocated cells of this hive.
+    ///
+    /// Each [`DeletedKeyValue`] carries its own cell offset and the offset of its data cell.
+    /// Its data can still be read as long as the data cell hasn't been reused, even though it is unallocated.
+    /// In contrast to [`Hive::deleted_keys_report`], this also finds values whose key has been overwritten.
+    pub fn deleted_key_values(&self) -> DeletedKeyValues<'_, B> {
+        DeletedKeyValues {
+            hive: self,
+            candidates: UnallocatedCandidates::new(self),
+        }
+    }
+
+    /// Scans all unallocated cells of this hive for deleted keys and reports them along with their context.
+    ///
+    /// Every Key Node found in unallocated space is parsed defensively.
+    /// Its parent offset is resolved against the current tree to determine the path the deleted key
+    /// would have today.
+    /// Key Values are recovered as long as the Key Values List is still intact in unallocated space.
+    ///
+    /// Candidates that cannot be parsed at all (e.g. because their name has been overwritten) are skipped.
+    /// Keys of the current tree that cannot be traversed only leave the paths of the deleted keys below them
+    /// unresolved.
+    /// Errors are only returned if the scan has been cancelled (see [`Hive::set_cancel_token`]).
+    pub fn deleted_keys_report(&self) -> Result<Vec<DeletedKey>> {
+        let mut deleted_keys = Vec::new();
+
+        for deleted_key_node in self.deleted_key_nodes() {
+            // A corrupted Hive Bin or cell ends our scan, but everything found so far is still worth reporting.
+            let deleted_key_node = match deleted_key_node {
+                Ok(deleted_key_node) => deleted_key_node,
+                Err(NtHiveError::Cancelled) => return Err(NtHiveError::Cancelled),
+                Err(_) => break,
+            };
+
+            if let Some(deleted_key) = self.parse_deleted_key(&deleted_key_node) {
+                deleted_keys.push(deleted_key);
+            }
+        }
+
+        self.resolve_deleted_key_paths(&mut deleted_keys)?;
+
+        Ok(deleted_keys)
+    }
+
+    /// Returns the Key Node at `candidate_offset` within an unallocated cell ending at `cell_end`,
+    /// if there is a signature and a parseable header.
+    fn key_node_candidate(
+        &self,
+        candidate_offset: usize,
+        cell_end: usize,
+    ) -> Option<KeyNode<'_, B>> {
+        let header_start = candidate_offset + mem::size_of::<CellHeader>();
+        let candidate_range = header_start..cell_end;
+        byte_subrange(
+            &candidate_range,
+            key_node_header_size(self.is_legacy_version()),
+        )?;
+
+        if self.data_slice(header_start..header_start + KEY_NODE_SIGNATURE.len())
+            != KEY_NODE_SIGNATURE
+        {
+            return None;
+        }
+
+        KeyNode::from_cell_range(self, candidate_range).ok()
+    }
+
+    /// Returns the Key Value at `candidate_offset` within an unallocated cell ending at `cell_end`,
+    /// if there is a signature and a complete header.
+    fn key_value_candidate(
+        &self,
+        candidate_offset: usize,
+        cell_end: usize,
+    ) -> Option<KeyValue<'_, B>> {
+        let header_start = candidate_offset + mem::size_of::<CellHeader>();
+        let signature_range = byte_subrange(&(header_start..cell_end), KEY_VALUE_SIGNATURE.len())?;
+        if self.data_slice(signature_range) != KEY_VALUE_SIGNATURE {
+            return None;
+        }
+
+        KeyValue::new(self, header_start..cell_end).ok()
+    }
+
+    fn parse_deleted_key(&self, deleted_key_node: &DeletedKeyNode<B>) -> Option<DeletedKey> {
+        let key_node = &deleted_key_node.key_node;
+        let name = key_node.name().ok()?.to_string_lossy();
+        let (values, values_confidence) = self.recover_deleted_values(key_node);
+
+        Some(DeletedKey {
+            cell_offset: deleted_key_node.cell_offset,
+            name,
+            timestamp: key_node.timestamp(),
+            parent_offset: key_node.parent_offset(),
+            path: None,
+            values,
+            values_confidence,
+        })
+    }
+
+    fn recover_deleted_values(
+        &self,
+        key_node: &KeyNode<B>,
+    ) -> (Vec<DeletedKeyValueReport>, RecoveryConfidence) {
+        let mut values = Vec::new();
+
+        let (count, key_values_list_offset) = key_node.values_list_info();
+        if count == 0 {
+            return (values, RecoveryConfidence::Intact);
+        }
+        if key_values_list_offset == u32::MAX {
+            return (values, RecoveryConfidence::Partial);
+        }
+
+        let Ok(cell_range) = self.any_cell_range_from_data_offset(key_values_list_offset) else {
+            return (values, RecoveryConfidence::Partial);
+        };
+        let Some(items_range) = (count as usize)
+            .checked_mul(mem::size_of::<u32>())
+            .and_then(|byte_count| byte_subrange(&cell_range, byte_count))
+        else {
+            return (values, RecoveryConfidence::Partial);
+        };
+
+        let mut confidence = RecoveryConfidence::Intact;
+
+        for item in self
+            .data_slice(items_range)
+            .chunks_exact(mem::size_of::<u32>())
+        {
+            let key_value_offset = u32::from_le_bytes(item.try_into().unwrap());
+
+            match self.parse_deleted_value(key_value_offset) {
+                Some(value) => values.push(value),
+                None => confidence = RecoveryConfidence::Partial,
+            }
+        }
+
+        (values, confidence)
+    }
+
+    fn parse_deleted_value(&self, key_value_offset: u32) -> Option<DeletedKeyValueReport> {
+        if key_value_offset == u32::MAX {
+            return None;
+        }
+
+        let cell_range = self
+            .any_cell_range_from_data_offset(key_value_offset)
+            .ok()?;
+        let key_value = KeyValue::new(self, cell_range).ok()?;
+        let name = key_value.name().ok()?.to_string_lossy();
+
+        Some(DeletedKeyValueReport {
+            cell_offset: key_value_offset,
+            name,
+            data_type: key_value.data_type().ok(),
+            data_size: key_value.data_size(),
+        })
+    }
+
+    /// Sets the `path` of every deleted key whose parent is still part of the current tree.
+    ///
+    /// Parts of the current tree that cannot be traversed are skipped, so only cancellation is reported
+    /// as an error.
+    fn resolve_deleted_key_paths(&self, deleted_keys: &mut [DeletedKey]) -> Result<()> {
+        if deleted_keys.is_empty() {
+            return Ok(());
+        }
+
+        // Only remember the paths of those keys that are actually referenced as parents.
+        let parent_offsets = deleted_keys
+            .iter()
+            .map(|deleted_key| deleted_key.parent_offset)
+            .collect::<BTreeSet<u32>>();
+        let mut parent_paths = BTreeMap::new();
+
+        let Ok(root_key_node) = self.root_key_node() else {
+            return Ok(());
+        };
+        if parent_offsets.contains(&root_key_node.cell_offset()) {
+            parent_paths.insert(root_key_node.cell_offset(), String::new());
+        }
+
+        if let Ok(mut walker) = KeyNodeWalker::new(&root_key_node) {
+            while let Some(key_node) = walker.next() {
+                // A broken subkey only leaves the deleted keys below it without a path.
+                let key_node = match key_node {
+                    Ok(key_node) => key_node,
+                    Err(NtHiveError::Cancelled) => return Err(NtHiveError::Cancelled),
+                    Err(_) => continue,
+                };
+
+                if parent_offsets.contains(&key_node.cell_offset()) {
+                    parent_paths.insert(key_node.cell_offset(), walker.path_string());
+                }
+            }
+        }
+
+        for deleted_key in deleted_keys {
+            if let Some(parent_path) = parent_paths.get(&deleted_key.parent_offset) {
+                let path = if parent_path.is_empty() {
+                    deleted_key.name.clone()
+                } else {
+                    alloc::format!("{parent_path}\\{}", deleted_key.name)
+                };
+                deleted_key.path = Some(path);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::{cell_data, key_node_field, read_u32, write_u32};
+    use crate::key_node::KeyNodeField;
+    use crate::*;
+
+    /// Marks the cell at the given data offset as unallocated.
+    fn free_cell(testhive: &mut [u8], cell_offset: u32) {
+        let offset = 4096 + cell_offset as usize;
+        let size = read_u32(testhive, offset) as i32;
+        write_u32(testhive, offset, size.unsigned_abs());
+    }
+
+    /// Deletes the subkey at `subkey_cell_offset` from the Leaf of its parent and frees its cell,
+    /// just like Windows does when deleting a key.
+    fn delete_subkey(testhive: &mut [u8], parent_cell_offset: u32, subkey_cell_offset: u32) {
+        // Decrement the `subkey_count` of the parent.
+        let subkey_count_field = key_node_field(parent_cell_offset, KeyNodeField::SubkeyCount);
+        let subkey_count = read_u32(testhive, subkey_count_field);
+        write_u32(testhive, subkey_count_field, subkey_count - 1);
+
+        // Remove the subkey from the Leaf.
+        let leaf_offset = read_u32(
+            testhive,
+            key_node_field(parent_cell_offset, KeyNodeField::SubkeysListOffset),
+        );
+        let leaf_header = cell_data(leaf_offset);
+        let item_size = if &testhive[leaf_header..leaf_header + 2] == b"li" {
+            4
+        } else {
+            8
+        };
+        let count = u16::from_le_bytes(
+            testhive[leaf_header + 2..leaf_header + 4]
+                .try_into()
+                .unwrap(),
+        );
+        let items_start = leaf_header + 4;
+        let items_end = items_start + count as usize * item_size;
+        let item_start = (items_start..items_end)
+            .step_by(item_size)
+            .find(|&item| read_u32(testhive, item) == subkey_cell_offset)
+            .unwrap();
+        testhive.copy_within(item_start + item_size..items_end, item_start);
+        testhive[leaf_header + 2..leaf_header + 4].copy_from_slice(&(count - 1).to_le_bytes());
+
+        free_cell(testhive, subkey_cell_offset);
+    }
+
+    #[test]
+    fn test_deleted_keys_report() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        // The unmodified testhive has no deleted keys.
+        assert!(hive.deleted_keys_report().unwrap().is_empty());
+
+        // Gather everything we need to delete "subpath-test\no-subkeys" and "data-test".
+        let root_key_node = hive.root_key_node().unwrap();
+        let root_offset = root_key_node.cell_offset();
+        let subpath_test = root_key_node.subkey("subpath-test").unwrap().unwrap();
+        let subpath_test_offset = subpath_test.cell_offset();
+        let no_subkeys = subpath_test.subkey("no-subkeys").unwrap().unwrap();
+        let no_subkeys_offset = no_subkeys.cell_offset();
+        let no_subkeys_timestamp = no_subkeys.timestamp();
+        let data_test = root_key_node.subkey("data-test").unwrap().unwrap();
+        let data_test_offset = data_test.cell_offset();
+        let data_test_timestamp = data_test.timestamp();
+        let (data_test_values_count, data_test_values_list_offset) = data_test.values_list_info();
+        let data_test_value_offsets = (0..data_test_values_count as usize)
+            .map(|i| read_u32(&testhive, cell_data(data_test_values_list_offset) + i * 4))
+            .collect::<Vec<_>>();
+
+        delete_subkey(&mut testhive, subpath_test_offset, no_subkeys_offset);
+        delete_subkey(&mut testhive, root_offset, data_test_offset);
+        free_cell(&mut testhive, data_test_values_list_offset);
+        for &value_offset in &data_test_value_offsets {
+            free_cell(&mut testhive, value_offset);
+        }
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        assert!(root_key_node.subkey("data-test").is_none());
+
+        let deleted_keys = hive.deleted_keys_report().unwrap();
+        assert_eq!(deleted_keys.len(), 2);
+
+        let deleted_key = deleted_keys
+            .iter()
+            .find(|deleted_key| deleted_key.name == "no-subkeys")
+            .unwrap();
+        assert_eq!(deleted_key.cell_offset, no_subkeys_offset);
+        assert_eq!(deleted_key.timestamp, no_subkeys_timestamp);
+        assert_eq!(deleted_key.parent_offset, subpath_test_offset);
+        assert_eq!(
+            deleted_key.path.as_deref(),
+            Some("subpath-test\\no-subkeys")
+        );
+        assert!(deleted_key.values.is_empty());
+        assert_eq!(deleted_key.values_confidence, RecoveryConfidence::Intact);
+
+        let deleted_key = deleted_keys
+            .iter()
+            .find(|deleted_key| deleted_key.name == "data-test")
+            .unwrap();
+        assert_eq!(deleted_key.timestamp, data_test_timestamp);
+        assert_eq!(deleted_key.path.as_deref(), Some("data-test"));
+        assert_eq!(deleted_key.values.len(), data_test_value_offsets.len());
+        assert_eq!(deleted_key.values_confidence, RecoveryConfidence::Intact);
+
+        let value = deleted_key
+            .values
+            .iter()
+            .find(|value| value.name == "dword")
+            .unwrap();
+        assert_eq!(value.data_type, Some(KeyValueDataType::RegDWord));
+        assert_eq!(value.data_size, 4);
+
+        // Overwrite the signature of one Key Value to get a partial recovery.
+        testhive[cell_data(data_test_value_offsets[0])] = 0;
+
+        // Additionally overwrite the parent offset of "no-subkeys" to make it an orphan.
+        write_u32(
+            &mut testhive,
+            key_node_field(no_subkeys_offset, KeyNodeField::Parent),
+            0x1234_5678,
+        );
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let deleted_keys = hive.deleted_keys_report().unwrap();
+
+        let deleted_key = deleted_keys
+            .iter()
+            .find(|deleted_key| deleted_key.name == "data-test")
+            .unwrap();
+        assert_eq!(deleted_key.values.len(), data_test_value_offsets.len() - 1);
+        assert_eq!(deleted_key.values_confidence, RecoveryConfidence::Partial);
+
+        let deleted_key = deleted_keys
+            .iter()
+            .find(|deleted_key| deleted_key.name == "no-subkeys")
+            .unwrap();
+        assert_eq!(deleted_key.path, None);
+
+        // A broken key in the current tree doesn't affect the paths of deleted keys elsewhere.
+        let root_key_node = hive.root_key_node().unwrap();
+        let subkey_test_offset = root_key_node
+            .subkey("subkey-test")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+        testhive[key_node_field(subkey_test_offset, KeyNodeField::Signature)] = 0;
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let deleted_keys = hive.deleted_keys_report().unwrap();
+        let deleted_key = deleted_keys
+            .iter()
+            .find(|deleted_key| deleted_key.name == "data-test")
+            .unwrap();
+        assert_eq!(deleted_key.path.as_deref(), Some("data-test"));
+    }
+
+    #[test]
+    fn test_deleted_key_nodes() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(hive.deleted_key_nodes().count(), 0);
+
+        let root_key_node = hive.root_key_node().unwrap();
+        let root_offset = root_key_node.cell_offset();
+        let data_test = root_key_node.subkey("data-test").unwrap().unwrap();
+        let data_test_offset = data_test.cell_offset();
+        let data_test_timestamp = data_test.timestamp();
+        let data_test_value_count = data_test.value_count();
+        let (_, data_test_values_list_offset) = data_test.values_list_info();
+
+        delete_subkey(&mut testhive, root_offset, data_test_offset);
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let deleted_key_nodes = hive
+            .deleted_key_nodes()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(deleted_key_nodes.len(), 1);
+
+        let deleted_key_node = &deleted_key_nodes[0];
+        assert_eq!(deleted_key_node.cell_offset(), data_test_offset);
+        assert_eq!(deleted_key_node.name().unwrap(), "data-test");
+        assert_eq!(deleted_key_node.timestamp(), data_test_timestamp);
+        assert_eq!(deleted_key_node.parent_offset(), root_offset);
+        assert_eq!(
+            deleted_key_node.parent().unwrap().unwrap().cell_offset(),
+            root_offset
+        );
+        assert_eq!(deleted_key_node.subkey_count(), 0);
+        assert_eq!(deleted_key_node.value_count(), data_test_value_count);
+
+        // The values are accessible as long as the Key Values List is still allocated.
+        let values = deleted_key_node.values().unwrap().unwrap();
+        assert_eq!(values.count() as u32, data_test_value_count);
+
+        free_cell(&mut testhive, data_test_values_list_offset);
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let deleted_key_node = hive.deleted_key_nodes().next().unwrap().unwrap();
+        assert!(matches!(
+            deleted_key_node.values(),
+            Some(Err(NtHiveError::UnallocatedCell { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_deleted_key_nodes_legacy() {
+        let mut testhive = crate::helpers::tests::legacy_testhive_vec();
+        let options = ValidationOptions {
+            legacy_versions: true,
+            ..ValidationOptions::strict()
+        };
+        let hive = Hive::with_options(testhive.as_ref(), options).unwrap();
+        assert_eq!(hive.deleted_key_nodes().count(), 0);
+
+        // The cell of "Sub" is too small for the longer header of Windows NT 4.0 and later.
+        let root_key_node = hive.root_key_node().unwrap();
+        let root_offset = root_key_node.cell_offset();
+        let sub_offset = root_key_node.subkey("Sub").unwrap().unwrap().cell_offset();
+        delete_subkey(&mut testhive, root_offset, sub_offset);
+
+        let hive = Hive::with_options(testhive.as_ref(), options).unwrap();
+        let deleted_key_nodes = hive
+            .deleted_key_nodes()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(deleted_key_nodes.len(), 1);
+        assert_eq!(deleted_key_nodes[0].cell_offset(), sub_offset);
+        assert_eq!(deleted_key_nodes[0].name().unwrap(), "Sub");
+        assert_eq!(deleted_key_nodes[0].parent_offset(), root_offset);
+
+        let deleted_keys = hive.deleted_keys_report().unwrap();
+        assert_eq!(deleted_keys.len(), 1);
+        assert_eq!(deleted_keys[0].path.as_deref(), Some("Sub"));
+    }
+
+    #[test]
+    fn test_deleted_key_values() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert_eq!(hive.deleted_key_values().count(), 0);
+
+        // Delete the "binary" and "dword" values of "data-test" like Windows does:
+        // Remove them from the Key Values List and free their cells along with the data cell.
+        let root_key_node = hive.root_key_node().unwrap();
+        let data_test = root_key_node.subkey("data-test").unwrap().unwrap();
+        let data_test_offset = data_test.cell_offset();
+        let (values_count, values_list_offset) = data_test.values_list_info();
+        let values_list = cell_data(values_list_offset);
+        let binary = data_test.value("binary").unwrap().unwrap();
+        let binary_data = binary.data().unwrap().into_vec().unwrap();
+
+        let mut value_offsets = (0..values_count as usize)
+            .map(|i| read_u32(&testhive, values_list + i * 4))
+            .collect::<Vec<_>>();
+        let mut deleted_value_offsets = Vec::new();
+        value_offsets.retain(|&value_offset| {
+            let header = cell_data(value_offset);
+            let name_length = u16::from_le_bytes([testhive[header + 2], testhive[header + 3]]);
+            let name = &testhive[header + 20..header + 20 + name_length as usize];
+            let deleted = name == b"binary" || name == b"dword";
+            if deleted {
+                deleted_value_offsets.push(value_offset);
+            }
+            !deleted
+        });
+        assert_eq!(deleted_value_offsets.len(), 2);
+
+        for (i, &value_offset) in value_offsets.iter().enumerate() {
+            write_u32(&mut testhive, values_list + i * 4, value_offset);
+        }
+        write_u32(
+            &mut testhive,
+            key_node_field(data_test_offset, KeyNodeField::KeyValuesCount),
+            value_offsets.len() as u32,
+        );
+
+        let binary_offset = deleted_value_offsets
+            .iter()
+            .copied()
+            .find(|&value_offset| testhive[cell_data(value_offset) + 20] == b'b')
+            .unwrap();
+        let binary_data_offset = read_u32(&testhive, cell_data(binary_offset) + 8);
+        for &value_offset in &deleted_value_offsets {
+            free_cell(&mut testhive, value_offset);
+        }
+        free_cell(&mut testhive, binary_data_offset);
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let data_test = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert!(data_test.value("binary").is_none());
+
+        let deleted_values = hive
+            .deleted_key_values()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(deleted_values.len(), 2);
+
+        let binary = deleted_values
+            .iter()
+            .find(|deleted_value| deleted_value.name().unwrap() == "binary")
+            .unwrap();
+        assert_eq!(binary.cell_offset(), binary_offset);
+        assert_eq!(binary.data_cell_offset(), Some(binary_data_offset));
+        assert_eq!(binary.data_type().unwrap(), KeyValueDataType::RegBinary);
+        assert_eq!(binary.data_size() as usize, binary_data.len());
+        assert_eq!(binary.data().unwrap().into_vec().unwrap(), binary_data);
+
+        // The data of "dword" is stored inline.
+        let dword = deleted_values
+            .iter()
+            .find(|deleted_value| deleted_value.name().unwrap() == "dword")
+            .unwrap();
+        assert_eq!(dword.data_cell_offset(), None);
+        assert_eq!(
+            dword.data().unwrap().into_vec().unwrap(),
+            42u32.to_le_bytes()
+        );
+
+        // A data cell that has been reused for something smaller can't hold the data anymore.
+        write_u32(&mut testhive, 4096 + binary_data_offset as usize, 8);
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let binary = hive
+            .deleted_key_values()
+            .map(Result::unwrap)
+            .find(|deleted_value| deleted_value.cell_offset() == binary_offset)
+            .unwrap();
+        assert!(matches!(
+            binary.data(),
+            Err(NtHiveError::InvalidDataSize { .. })
+        ));
+    }
+}
@@ -0,0 +1,1207 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::vec::Vec;
+use core::mem;
+
+use bitflags::bitflags;
+use zerocopy::byteorder::LittleEndian;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, Unaligned, U16, U32,
+};
+
+use crate::architecture::Arch;
+use crate::error::{NtHiveError, Result};
+use crate::key_value::{KeyValue, KeyValueDataType};
+
+/// Resource types (`CmResourceType*`) of a CM_PARTIAL_RESOURCE_DESCRIPTOR.
+const CM_RESOURCE_TYPE_NULL: u8 = 0;
+const CM_RESOURCE_TYPE_PORT: u8 = 1;
+const CM_RESOURCE_TYPE_INTERRUPT: u8 = 2;
+const CM_RESOURCE_TYPE_MEMORY: u8 = 3;
+const CM_RESOURCE_TYPE_DMA: u8 = 4;
+const CM_RESOURCE_TYPE_DEVICE_SPECIFIC: u8 = 5;
+const CM_RESOURCE_TYPE_BUS_NUMBER: u8 = 6;
+const CM_RESOURCE_TYPE_MEMORY_LARGE: u8 = 7;
+const CM_RESOURCE_TYPE_CONFIG_DATA: u8 = 128;
+const CM_RESOURCE_TYPE_DEVICE_PRIVATE: u8 = 129;
+
+/// Size of the union following an [`IoResourceDescriptorHeader`].
+///
+/// In contrast to [`partial_resource_union_size`], this is the same for all processor architectures,
+/// because the union is padded to the size of its largest member.
+const IO_RESOURCE_UNION_SIZE: usize = 24;
+
+/// On-Disk Structure of the count field of a CM_RESOURCE_LIST, followed by the full resource descriptors.
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct ResourceListHeader {
+    count: U32<LittleEndian>,
+}
+
+/// On-Disk Structure of a CM_FULL_RESOURCE_DESCRIPTOR header (including the embedded CM_PARTIAL_RESOURCE_LIST header),
+/// followed by the partial resource descriptors.
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct FullResourceDescriptorHeader {
+    interface_type: U32<LittleEndian>,
+    bus_number: U32<LittleEndian>,
+    version: U16<LittleEndian>,
+    revision: U16<LittleEndian>,
+    count: U32<LittleEndian>,
+}
+
+/// On-Disk Structure of a CM_PARTIAL_RESOURCE_DESCRIPTOR header, followed by a union whose size depends on the
+/// processor architecture (see [`partial_resource_union_size`]).
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct PartialResourceDescriptorHeader {
+    resource_type: u8,
+    share_disposition: u8,
+    flags: U16<LittleEndian>,
+}
+
+/// On-Disk Structure of an IO_RESOURCE_REQUIREMENTS_LIST header, followed by the alternative lists.
+#[allow(dead_code)]
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct ResourceRequirementsListHeader {
+    list_size: U32<LittleEndian>,
+    interface_type: U32<LittleEndian>,
+    bus_number: U32<LittleEndian>,
+    slot_number: U32<LittleEndian>,
+    reserved: [U32<LittleEndian>; 3],
+    alternative_lists: U32<LittleEndian>,
+}
+
+/// On-Disk Structure of an IO_RESOURCE_LIST header, followed by the I/O resource descriptors.
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct IoResourceListHeader {
+    version: U16<LittleEndian>,
+    revision: U16<LittleEndian>,
+    count: U32<LittleEndian>,
+}
+
+/// On-Disk Structure of an IO_RESOURCE_DESCRIPTOR header, followed by a union of [`IO_RESOURCE_UNION_SIZE`] bytes.
+#[allow(dead_code)]
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct IoResourceDescriptorHeader {
+    option: u8,
+    resource_type: u8,
+    share_disposition: u8,
+    spare1: u8,
+    flags: U16<LittleEndian>,
+    spare2: U16<LittleEndian>,
+}
+
+/// Returns the size of the union following a [`PartialResourceDescriptorHeader`].
+///
+/// The union contains a KAFFINITY for interrupts, which has the size of a pointer.
+const fn partial_resource_union_size(arch: Arch) -> usize {
+    match arch {
+        Arch::X86 => 12,
+        Arch::X64 => 16,
+    }
+}
+
+/// Scales the length (or alignment) of a `CmResourceTypeMemoryLarge` resource according to its `LARGE_*` flags.
+fn scale_large_memory(value: u32, flags: MemoryResourceFlags) -> u64 {
+    let value = u64::from(value);
+
+    if flags.contains(MemoryResourceFlags::LARGE_64) {
+        value << 32
+    } else if flags.contains(MemoryResourceFlags::LARGE_48) {
+        value << 16
+    } else if flags.contains(MemoryResourceFlags::LARGE_40) {
+        value << 8
+    } else {
+        value
+    }
+}
+
+bitflags! {
+    /// Flags of a [`PartialResource::Interrupt`] (`CM_RESOURCE_INTERRUPT_*`).
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct InterruptResourceFlags: u16 {
+        /// The interrupt is edge-triggered instead of level-sensitive.
+        const LATCHED = 0x0001;
+        /// This is a message-signaled interrupt.
+        const MESSAGE = 0x0002;
+        const POLICY_INCLUDED = 0x0004;
+        const SECONDARY_INTERRUPT = 0x0010;
+        /// The interrupt can wake the system.
+        const WAKE_HINT = 0x0020;
+    }
+}
+
+bitflags! {
+    /// Flags of a [`PartialResource::Memory`] (`CM_RESOURCE_MEMORY_*`).
+    ///
+    /// No flag means read/write memory.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct MemoryResourceFlags: u16 {
+        const READ_ONLY = 0x0001;
+        const WRITE_ONLY = 0x0002;
+        const PREFETCHABLE = 0x0004;
+        const COMBINED_WRITE = 0x0008;
+        const MEMORY_24 = 0x0010;
+        const CACHEABLE = 0x0020;
+        const WINDOW_DECODE = 0x0040;
+        const BAR = 0x0080;
+        const COMPAT_FOR_INACCESSIBLE_RANGE = 0x0100;
+        /// The length is stored in units of 256 bytes.
+        const LARGE_40 = 0x0200;
+        /// The length is stored in units of 64 KiB.
+        const LARGE_48 = 0x0400;
+        /// The length is stored in units of 4 GiB.
+        const LARGE_64 = 0x0800;
+    }
+}
+
+bitflags! {
+    /// Flags of a [`PartialResource::Port`] (`CM_RESOURCE_PORT_*`).
+    ///
+    /// No [`PortResourceFlags::IO`] flag means that the port is mapped into memory space.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct PortResourceFlags: u16 {
+        const IO = 0x0001;
+        const DECODE_10_BIT = 0x0004;
+        const DECODE_12_BIT = 0x0008;
+        const DECODE_16_BIT = 0x0010;
+        const POSITIVE_DECODE = 0x0020;
+        const PASSIVE_DECODE = 0x0040;
+        const WINDOW_DECODE = 0x0080;
+        const BAR = 0x0100;
+    }
+}
+
+bitflags! {
+    /// Flags of a [`PartialResource::Dma`] (`CM_RESOURCE_DMA_*`).
+    ///
+    /// No width flag means an 8-bit transfer width.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct DmaResourceFlags: u16 {
+        const DMA_16 = 0x0001;
+        const DMA_32 = 0x0002;
+        const DMA_8_AND_16 = 0x0004;
+        const BUS_MASTER = 0x0008;
+        const TYPE_A = 0x0010;
+        const TYPE_B = 0x0020;
+        const TYPE_F = 0x0040;
+    }
+}
+
+bitflags! {
+    /// Options of an [`IoResourceDescriptor`] (`IO_RESOURCE_*`).
+    ///
+    /// No option means that the resource is required.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct IoResourceOptions: u8 {
+        /// This is the preferred one of several alternative descriptors.
+        const PREFERRED = 0x01;
+        const DEFAULT = 0x02;
+        /// This descriptor is an alternative to the preceding one.
+        const ALTERNATIVE = 0x08;
+    }
+}
+
+/// Known bus types (INTERFACE_TYPE) of a [`FullResourceDescriptor`].
+///
+/// You can use [`InterfaceType::n`] on [`FullResourceDescriptor::interface_type`]
+/// to find out whether a descriptor has a known bus type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum InterfaceType {
+    Internal = 0,
+    Isa = 1,
+    Eisa = 2,
+    MicroChannel = 3,
+    TurboChannel = 4,
+    PciBus = 5,
+    VmeBus = 6,
+    NuBus = 7,
+    PcmciaBus = 8,
+    CBus = 9,
+    MpiBus = 10,
+    MpsaBus = 11,
+    ProcessorInternal = 12,
+    InternalPowerBus = 13,
+    PnpIsaBus = 14,
+    PnpBus = 15,
+    Vmcs = 16,
+    AcpiBus = 17,
+}
+
+impl InterfaceType {
+    /// Returns the variant for the given bus type or `None` if it is unknown.
+    pub const fn n(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Internal),
+            1 => Some(Self::Isa),
+            2 => Some(Self::Eisa),
+            3 => Some(Self::MicroChannel),
+            4 => Some(Self::TurboChannel),
+            5 => Some(Self::PciBus),
+            6 => Some(Self::VmeBus),
+            7 => Some(Self::NuBus),
+            8 => Some(Self::PcmciaBus),
+            9 => Some(Self::CBus),
+            10 => Some(Self::MpiBus),
+            11 => Some(Self::MpsaBus),
+            12 => Some(Self::ProcessorInternal),
+            13 => Some(Self::InternalPowerBus),
+            14 => Some(Self::PnpIsaBus),
+            15 => Some(Self::PnpBus),
+            16 => Some(Self::Vmcs),
+            17 => Some(Self::AcpiBus),
+            _ => None,
+        }
+    }
+}
+
+/// The resource described by a [`PartialResourceDescriptor`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PartialResource {
+    /// An unused descriptor (`CmResourceTypeNull`).
+    Null,
+    /// A range of I/O ports or memory-mapped ports (`CmResourceTypePort`).
+    Port {
+        start: u64,
+        length: u32,
+        flags: PortResourceFlags,
+    },
+    /// An interrupt (`CmResourceTypeInterrupt`).
+    Interrupt {
+        level: u16,
+        /// Processor group of the affinity mask (always zero before Windows 7).
+        group: u16,
+        vector: u32,
+        affinity: u64,
+        flags: InterruptResourceFlags,
+    },
+    /// A range of memory (`CmResourceTypeMemory` and `CmResourceTypeMemoryLarge`).
+    ///
+    /// For large memory ranges, the length has already been scaled according to the `LARGE_*` flags.
+    Memory {
+        start: u64,
+        length: u64,
+        flags: MemoryResourceFlags,
+    },
+    /// A DMA channel (`CmResourceTypeDma`).
+    Dma {
+        channel: u32,
+        port: u32,
+        flags: DmaResourceFlags,
+    },
+    /// Device-specific data following the descriptor (`CmResourceTypeDeviceSpecific`).
+    DeviceSpecific { data: Vec<u8> },
+    /// A range of bus numbers (`CmResourceTypeBusNumber`).
+    BusNumber { start: u32, length: u32 },
+    /// Any other resource type, along with the raw bytes of the union.
+    Other { resource_type: u8, data: Vec<u8> },
+}
+
+/// A single CM_PARTIAL_RESOURCE_DESCRIPTOR of a [`FullResourceDescriptor`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartialResourceDescriptor {
+    /// Raw resource type (`CmResourceType*`).
+    pub resource_type: u8,
+    /// Raw share disposition (`CmResourceShare*`, e.g. `1` for exclusive access by a device).
+    pub share_disposition: u8,
+    /// Raw flags, whose meaning depends on the resource type.
+    ///
+    /// The flags of known resource types are also part of [`PartialResourceDescriptor::resource`].
+    pub flags: u16,
+    /// The decoded resource.
+    pub resource: PartialResource,
+}
+
+/// A single CM_FULL_RESOURCE_DESCRIPTOR of a [`ResourceList`], describing all resources of a device on a bus.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FullResourceDescriptor {
+    /// Raw bus type (INTERFACE_TYPE).
+    ///
+    /// You can feed this value to [`InterfaceType::n`] to find out whether this is a known bus type.
+    pub interface_type: u32,
+    /// Number of the bus the device is attached to.
+    pub bus_number: u32,
+    /// Version of the partial resource list.
+    pub version: u16,
+    /// Revision of the partial resource list.
+    pub revision: u16,
+    /// All partial resource descriptors, in the order they are stored.
+    pub partial_descriptors: Vec<PartialResourceDescriptor>,
+}
+
+/// A decoded CM_RESOURCE_LIST, as stored in `REG_RESOURCE_LIST` values.
+///
+/// Windows stores these in keys below `HARDWARE\DESCRIPTION` and `HARDWARE\RESOURCEMAP`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResourceList {
+    /// All full resource descriptors, in the order they are stored.
+    pub full_descriptors: Vec<FullResourceDescriptor>,
+}
+
+/// The resource requirement described by an [`IoResourceDescriptor`].
+///
+/// In contrast to a [`PartialResource`], this describes a range of acceptable resources for the device.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IoResource {
+    /// An unused descriptor (`CmResourceTypeNull`).
+    Null,
+    /// A range of I/O ports or memory-mapped ports (`CmResourceTypePort`).
+    Port {
+        length: u32,
+        alignment: u32,
+        minimum_address: u64,
+        maximum_address: u64,
+        flags: PortResourceFlags,
+    },
+    /// A range of interrupt vectors (`CmResourceTypeInterrupt`).
+    Interrupt {
+        minimum_vector: u32,
+        maximum_vector: u32,
+        /// Raw IRQ_DEVICE_POLICY.
+        affinity_policy: u16,
+        /// Processor group of the targeted processors (always zero before Windows 7).
+        group: u16,
+        /// Raw IRQ_PRIORITY.
+        priority_policy: u32,
+        targeted_processors: u64,
+        flags: InterruptResourceFlags,
+    },
+    /// A range of memory (`CmResourceTypeMemory` and `CmResourceTypeMemoryLarge`).
+    ///
+    /// For large memory ranges, the length and alignment have already been scaled according to the `LARGE_*` flags.
+    Memory {
+        length: u64,
+        alignment: u64,
+        minimum_address: u64,
+        maximum_address: u64,
+        flags: MemoryResourceFlags,
+    },
+    /// A range of DMA channels (`CmResourceTypeDma`).
+    Dma {
+        minimum_channel: u32,
+        maximum_channel: u32,
+        flags: DmaResourceFlags,
+    },
+    /// A range of bus numbers (`CmResourceTypeBusNumber`).
+    BusNumber {
+        length: u32,
+        minimum_bus_number: u32,
+        maximum_bus_number: u32,
+    },
+    /// The priority of this alternative list (`CmResourceTypeConfigData`).
+    ConfigData { priority: u32 },
+    /// Data private to the device driver (`CmResourceTypeDevicePrivate`).
+    DevicePrivate { data: [u32; 3] },
+    /// Any other resource type, along with the raw bytes of the union.
+    Other { resource_type: u8, data: Vec<u8> },
+}
+
+/// A single IO_RESOURCE_DESCRIPTOR of an [`IoResourceList`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IoResourceDescriptor {
+    /// Whether this descriptor is required, preferred, or an alternative to the preceding one.
+    pub options: IoResourceOptions,
+    /// Raw resource type (`CmResourceType*`).
+    pub resource_type: u8,
+    /// Raw share disposition (`CmResourceShare*`, e.g. `1` for exclusive access by a device).
+    pub share_disposition: u8,
+    /// Raw flags, whose meaning depends on the resource type.
+    ///
+    /// The flags of known resource types are also part of [`IoResourceDescriptor::resource`].
+    pub flags: u16,
+    /// The decoded resource requirement.
+    pub resource: IoResource,
+}
+
+/// A single IO_RESOURCE_LIST of a [`ResourceRequirementsList`], describing one acceptable configuration
+/// of a device.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IoResourceList {
+    /// Version of the list.
+    pub version: u16,
+    /// Revision of the list.
+    pub revision: u16,
+    /// All I/O resource descriptors, in the order they are stored.
+    pub descriptors: Vec<IoResourceDescriptor>,
+}
+
+/// A decoded IO_RESOURCE_REQUIREMENTS_LIST, as stored in `REG_RESOURCE_REQUIREMENTS_LIST` values.
+///
+/// Windows stores these in the `BasicConfigVector` values of the `LogConf` keys of devices
+/// below `SYSTEM\CurrentControlSet\Enum`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResourceRequirementsList {
+    /// Raw bus type (INTERFACE_TYPE).
+    ///
+    /// You can feed this value to [`InterfaceType::n`] to find out whether this is a known bus type.
+    pub interface_type: u32,
+    /// Number of the bus the device is attached to.
+    pub bus_number: u32,
+    /// Slot number of the device on its bus.
+    pub slot_number: u32,
+    /// All alternative configurations of the device, in the order they are stored.
+    pub alternatives: Vec<IoResourceList>,
+}
+
+impl ResourceList {
+    /// Decodes the raw bytes of a CM_RESOURCE_LIST stored by a Windows installation of the given architecture.
+    ///
+    /// The architecture is required, because some descriptor fields have the size of a pointer.
+    /// All counts and sizes are checked against the length of `bytes`.
+    /// Offsets in errors are relative to the beginning of `bytes`.
+    pub fn from_bytes(bytes: &[u8], arch: Arch) -> Result<Self> {
+        let mut parser = Parser { bytes, position: 0 };
+        let header = parser.read::<ResourceListHeader>()?;
+
+        let mut full_descriptors = Vec::new();
+        for _ in 0..header.count.get() {
+            full_descriptors.push(parser.read_full_descriptor(arch)?);
+        }
+
+        Ok(Self { full_descriptors })
+    }
+}
+
+impl FullResourceDescriptor {
+    /// Decodes the raw bytes of a single CM_FULL_RESOURCE_DESCRIPTOR stored by a Windows installation of the given
+    /// architecture, as found in `REG_FULL_RESOURCE_DESCRIPTOR` values.
+    ///
+    /// The count of partial resource descriptors is checked against the length of `bytes` before decoding them,
+    /// and [`NtHiveError::InvalidDataSize`] is returned if they can't fit.
+    /// Trailing bytes after the last partial resource descriptor are ignored.
+    /// Offsets in errors are relative to the beginning of `bytes`.
+    pub fn from_bytes(bytes: &[u8], arch: Arch) -> Result<Self> {
+        let mut parser = Parser { bytes, position: 0 };
+        parser.read_full_descriptor(arch)
+    }
+}
+
+impl ResourceRequirementsList {
+    /// Decodes the raw bytes of an IO_RESOURCE_REQUIREMENTS_LIST stored by a Windows installation of the given
+    /// architecture.
+    ///
+    /// The architecture is required, because the targeted processors of an interrupt have the size of a pointer.
+    /// The `ListSize` field and all counts are checked against the length of `bytes`.
+    /// Offsets in errors are relative to the beginning of `bytes`.
+    pub fn from_bytes(bytes: &[u8], arch: Arch) -> Result<Self> {
+        let mut parser = Parser { bytes, position: 0 };
+        let header = parser.read::<ResourceRequirementsListHeader>()?;
+
+        let list_size = header.list_size.get() as usize;
+        if list_size > bytes.len() {
+            return Err(NtHiveError::InvalidSizeField {
+                offset: mem::offset_of!(ResourceRequirementsListHeader, list_size),
+                expected: list_size,
+                actual: bytes.len(),
+            });
+        }
+
+        let count = header.alternative_lists.get() as usize;
+        parser.check_count(
+            mem::offset_of!(ResourceRequirementsListHeader, alternative_lists),
+            count,
+            mem::size_of::<IoResourceListHeader>(),
+        )?;
+
+        let mut alternatives = Vec::with_capacity(count);
+        for _ in 0..count {
+            alternatives.push(parser.read_io_resource_list(arch)?);
+        }
+
+        Ok(Self {
+            interface_type: header.interface_type.get(),
+            bus_number: header.bus_number.get(),
+            slot_number: header.slot_number.get(),
+            alternatives,
+        })
+    }
+}
+
+/// Reads the structures of a CM_RESOURCE_LIST one after another, checking every size against the remaining bytes.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn read<T>(&mut self) -> Result<Ref<&'a [u8], T>>
+    where
+        T: FromBytes + Immutable + KnownLayout + Unaligned,
+    {
+        let bytes = self.read_bytes(mem::size_of::<T>())?;
+        Ok(Ref::from_bytes(bytes).unwrap())
+    }
+
+    fn read_bytes(&mut self, size: usize) -> Result<&'a [u8]> {
+        let remaining = &self.bytes[self.position..];
+        let bytes = remaining
+            .get(..size)
+            .ok_or(NtHiveError::InvalidHeaderSize {
+                offset: self.position,
+                expected: size,
+                actual: remaining.len(),
+            })?;
+
+        self.position += size;
+        Ok(bytes)
+    }
+
+    /// Rejects an implausible `count` (stored at `count_offset`) before reading any of the counted structures.
+    ///
+    /// Every one of them takes at least `min_size` bytes.
+    fn check_count(&self, count_offset: usize, count: usize, min_size: usize) -> Result<()> {
+        let expected = count.saturating_mul(min_size);
+        let remaining = self.bytes.len() - self.position;
+
+        if expected > remaining {
+            Err(NtHiveError::InvalidDataSize {
+                offset: count_offset,
+                expected,
+                actual: remaining,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn read_full_descriptor(&mut self, arch: Arch) -> Result<FullResourceDescriptor> {
+        let header = self.read::<FullResourceDescriptorHeader>()?;
+
+        let count = header.count.get() as usize;
+        self.check_count(
+            self.position - mem::size_of::<U32<LittleEndian>>(),
+            count,
+            mem::size_of::<PartialResourceDescriptorHeader>() + partial_resource_union_size(arch),
+        )?;
+
+        let mut partial_descriptors = Vec::with_capacity(count);
+        for _ in 0..count {
+            partial_descriptors.push(self.read_partial_descriptor(arch)?);
+        }
+
+        Ok(FullResourceDescriptor {
+            interface_type: header.interface_type.get(),
+            bus_number: header.bus_number.get(),
+            version: header.version.get(),
+            revision: header.revision.get(),
+            partial_descriptors,
+        })
+    }
+
+    fn read_partial_descriptor(&mut self, arch: Arch) -> Result<PartialResourceDescriptor> {
+        let header = self.read::<PartialResourceDescriptorHeader>()?;
+        let union_offset = self.position;
+        let union = self.read_bytes(partial_resource_union_size(arch))?;
+        let flags = header.flags.get();
+
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(union[offset..offset + 4].try_into().unwrap());
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(union[offset..offset + 8].try_into().unwrap());
+
+        let resource = match header.resource_type {
+            CM_RESOURCE_TYPE_NULL => PartialResource::Null,
+            CM_RESOURCE_TYPE_PORT => PartialResource::Port {
+                start: u64_at(0),
+                length: u32_at(8),
+                flags: PortResourceFlags::from_bits_truncate(flags),
+            },
+            CM_RESOURCE_TYPE_INTERRUPT => {
+                let affinity = match arch {
+                    Arch::X86 => u64::from(u32_at(8)),
+                    Arch::X64 => u64_at(8),
+                };
+
+                PartialResource::Interrupt {
+                    level: u16::from_le_bytes([union[0], union[1]]),
+                    group: u16::from_le_bytes([union[2], union[3]]),
+                    vector: u32_at(4),
+                    affinity,
+                    flags: InterruptResourceFlags::from_bits_truncate(flags),
+                }
+            }
+            CM_RESOURCE_TYPE_MEMORY | CM_RESOURCE_TYPE_MEMORY_LARGE => {
+                let flags = MemoryResourceFlags::from_bits_truncate(flags);
+                let length = if header.resource_type == CM_RESOURCE_TYPE_MEMORY {
+                    u64::from(u32_at(8))
+                } else {
+                    scale_large_memory(u32_at(8), flags)
+                };
+
+                PartialResource::Memory {
+                    start: u64_at(0),
+                    length,
+                    flags,
+                }
+            }
+            CM_RESOURCE_TYPE_DMA => PartialResource::Dma {
+                channel: u32_at(0),
+                port: u32_at(4),
+                flags: DmaResourceFlags::from_bits_truncate(flags),
+            },
+            CM_RESOURCE_TYPE_DEVICE_SPECIFIC => {
+                // The data directly follows this descriptor.
+                let data_size = u32_at(0) as usize;
+                let remaining = self.bytes.len() - self.position;
+                if data_size > remaining {
+                    return Err(NtHiveError::InvalidSizeField {
+                        offset: union_offset,
+                        expected: data_size,
+                        actual: remaining,
+                    });
+                }
+
+                PartialResource::DeviceSpecific {
+                    data: self.read_bytes(data_size)?.to_vec(),
+                }
+            }
+            CM_RESOURCE_TYPE_BUS_NUMBER => PartialResource::BusNumber {
+                start: u32_at(0),
+                length: u32_at(4),
+            },
+            resource_type => PartialResource::Other {
+                resource_type,
+                data: union.to_vec(),
+            },
+        };
+
+        Ok(PartialResourceDescriptor {
+            resource_type: header.resource_type,
+            share_disposition: header.share_disposition,
+            flags,
+            resource,
+        })
+    }
+
+    fn read_io_resource_list(&mut self, arch: Arch) -> Result<IoResourceList> {
+        let header = self.read::<IoResourceListHeader>()?;
+
+        let count = header.count.get() as usize;
+        self.check_count(
+            self.position - mem::size_of::<U32<LittleEndian>>(),
+            count,
+            mem::size_of::<IoResourceDescriptorHeader>() + IO_RESOURCE_UNION_SIZE,
+        )?;
+
+        let mut descriptors = Vec::with_capacity(count);
+        for _ in 0..count {
+            descriptors.push(self.read_io_resource_descriptor(arch)?);
+        }
+
+        Ok(IoResourceList {
+            version: header.version.get(),
+            revision: header.revision.get(),
+            descriptors,
+        })
+    }
+
+    fn read_io_resource_descriptor(&mut self, arch: Arch) -> Result<IoResourceDescriptor> {
+        let header = self.read::<IoResourceDescriptorHeader>()?;
+        let union = self.read_bytes(IO_RESOURCE_UNION_SIZE)?;
+        let flags = header.flags.get();
+
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(union[offset..offset + 4].try_into().unwrap());
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(union[offset..offset + 8].try_into().unwrap());
+
+        let resource = match header.resource_type {
+            CM_RESOURCE_TYPE_NULL => IoResource::Null,
+            CM_RESOURCE_TYPE_PORT => IoResource::Port {
+                length: u32_at(0),
+                alignment: u32_at(4),
+                minimum_address: u64_at(8),
+                maximum_address: u64_at(16),
+                flags: PortResourceFlags::from_bits_truncate(flags),
+            },
+            CM_RESOURCE_TYPE_INTERRUPT => {
+                let targeted_processors = match arch {
+                    Arch::X86 => u64::from(u32_at(16)),
+                    Arch::X64 => u64_at(16),
+                };
+
+                IoResource::Interrupt {
+                    minimum_vector: u32_at(0),
+                    maximum_vector: u32_at(4),
+                    affinity_policy: u16::from_le_bytes([union[8], union[9]]),
+                    group: u16::from_le_bytes([union[10], union[11]]),
+                    priority_policy: u32_at(12),
+                    targeted_processors,
+                    flags: InterruptResourceFlags::from_bits_truncate(flags),
+                }
+            }
+            CM_RESOURCE_TYPE_MEMORY | CM_RESOURCE_TYPE_MEMORY_LARGE => {
+                let flags = MemoryResourceFlags::from_bits_truncate(flags);
+                let (length, alignment) = if header.resource_type == CM_RESOURCE_TYPE_MEMORY {
+                    (u64::from(u32_at(0)), u64::from(u32_at(4)))
+                } else {
+                    (
+                        scale_large_memory(u32_at(0), flags),
+                        scale_large_memory(u32_at(4), flags),
+                    )
+                };
+
+                IoResource::Memory {
+                    length,
+                    alignment,
+                    minimum_address: u64_at(8),
+                    maximum_address: u64_at(16),
+                    flags,
+                }
+            }
+            CM_RESOURCE_TYPE_DMA => IoResource::Dma {
+                minimum_channel: u32_at(0),
+                maximum_channel: u32_at(4),
+                flags: DmaResourceFlags::from_bits_truncate(flags),
+            },
+            CM_RESOURCE_TYPE_BUS_NUMBER => IoResource::BusNumber {
+                length: u32_at(0),
+                minimum_bus_number: u32_at(4),
+                maximum_bus_number: u32_at(8),
+            },
+            CM_RESOURCE_TYPE_CONFIG_DATA => IoResource::ConfigData {
+                priority: u32_at(0),
+            },
+            CM_RESOURCE_TYPE_DEVICE_PRIVATE => IoResource::DevicePrivate {
+                data: [u32_at(0), u32_at(4), u32_at(8)],
+            },
+            resource_type => IoResource::Other {
+                resource_type,
+                data: union.to_vec(),
+            },
+        };
+
+        Ok(IoResourceDescriptor {
+            options: IoResourceOptions::from_bits_truncate(header.option),
+            resource_type: header.resource_type,
+            share_disposition: header.share_disposition,
+            flags,
+            resource,
+        })
+    }
+}
+
+impl<B> KeyValue<'_, B>
+where
+    B: SplitByteSlice,
+{
+    /// Checks if this is a `REG_RESOURCE_LIST` Key Value and decodes its data into a [`ResourceList`] in that case.
+    ///
+    /// The architecture of the Windows installation the hive originates from is required, because some descriptor
+    /// fields have the size of a pointer.
+    /// See [`ResourceList::from_bytes`] for details.
+    pub fn resource_list(&self, arch: Arch) -> Result<ResourceList> {
+        match self.data_type()? {
+            KeyValueDataType::RegResourceList => (),
+            data_type => {
+                return Err(NtHiveError::InvalidKeyValueDataType {
+                    expected: &[KeyValueDataType::RegResourceList],
+                    actual: data_type,
+                });
+            }
+        }
+
+        let data = self.data_cow()?;
+        ResourceList::from_bytes(&data, arch)
+    }
+
+    /// Checks if this is a `REG_RESOURCE_REQUIREMENTS_LIST` Key Value and decodes its data into a
+    /// [`ResourceRequirementsList`] in that case.
+    ///
+    /// See [`ResourceRequirementsList::from_bytes`] for details.
+    pub fn resource_requirements_list(&self, arch: Arch) -> Result<ResourceRequirementsList> {
+        match self.data_type()? {
+            KeyValueDataType::RegResourceRequirementsList => (),
+            data_type => {
+                return Err(NtHiveError::InvalidKeyValueDataType {
+                    expected: &[KeyValueDataType::RegResourceRequirementsList],
+                    actual: data_type,
+                });
+            }
+        }
+
+        let data = self.data_cow()?;
+        ResourceRequirementsList::from_bytes(&data, arch)
+    }
+
+    /// Checks if this is a `REG_FULL_RESOURCE_DESCRIPTOR` Key Value and decodes its data into a
+    /// [`FullResourceDescriptor`] in that case.
+    ///
+    /// Windows stores these in the `Configuration Data` values of keys below `HARDWARE\DESCRIPTION\System`.
+    /// See [`FullResourceDescriptor::from_bytes`] for details.
+    pub fn full_resource_descriptor(&self, arch: Arch) -> Result<FullResourceDescriptor> {
+        match self.data_type()? {
+            KeyValueDataType::RegFullResourceDescriptor => (),
+            data_type => {
+                return Err(NtHiveError::InvalidKeyValueDataType {
+                    expected: &[KeyValueDataType::RegFullResourceDescriptor],
+                    actual: data_type,
+                });
+            }
+        }
+
+        let data = self.data_cow()?;
+        FullResourceDescriptor::from_bytes(&data, arch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::{cell_data, read_u32};
+    use crate::*;
+
+    fn full_descriptor_header(interface_type: u32, bus_number: u32, count: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&interface_type.to_le_bytes());
+        bytes.extend_from_slice(&bus_number.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes
+    }
+
+    fn partial_descriptor(resource_type: u8, flags: u16, union: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::from([resource_type, 1]);
+        bytes.extend_from_slice(&flags.to_le_bytes());
+        bytes.extend_from_slice(union);
+        bytes
+    }
+
+    /// Turns the Key Value "A" of "big-data-test" into a value of the given type holding `bytes`.
+    fn set_big_data_test_a(testhive: &mut [u8], bytes: &[u8], data_type: KeyValueDataType) {
+        let hive = Hive::new(&*testhive).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let (values_count, values_list_offset) = key_node.values_list_info();
+
+        let values_list = cell_data(values_list_offset);
+        let header = (0..values_count as usize)
+            .map(|i| cell_data(read_u32(testhive, values_list + i * 4)))
+            .find(|&header| testhive[header + 2] == 1 && testhive[header + 20] == b'A')
+            .unwrap();
+        let data_start = cell_data(read_u32(testhive, header + 8));
+
+        testhive[data_start..data_start + bytes.len()].copy_from_slice(bytes);
+        testhive[header + 4..header + 8].copy_from_slice(&(bytes.len() as u32).to_le_bytes());
+        testhive[header + 12..header + 16].copy_from_slice(&(data_type as u32).to_le_bytes());
+    }
+
+    /// Returns a resource list with an I/O port range, an interrupt, a large memory range,
+    /// and 3 bytes of device-specific data, laid out for a 64-bit Windows installation.
+    fn x64_resource_list() -> Vec<u8> {
+        let mut bytes = Vec::from(1u32.to_le_bytes());
+        bytes.extend(full_descriptor_header(5, 2, 4));
+
+        let mut port = Vec::from(0x3f8u64.to_le_bytes());
+        port.extend_from_slice(&8u32.to_le_bytes());
+        port.extend_from_slice(&[0; 4]);
+        bytes.extend(partial_descriptor(1, 0x0005, &port));
+
+        let mut interrupt = Vec::from(4u16.to_le_bytes());
+        interrupt.extend_from_slice(&1u16.to_le_bytes());
+        interrupt.extend_from_slice(&0x34u32.to_le_bytes());
+        interrupt.extend_from_slice(&0x1_0000_0003u64.to_le_bytes());
+        bytes.extend(partial_descriptor(2, 0x0001, &interrupt));
+
+        let mut memory = Vec::from(0xfe00_0000u64.to_le_bytes());
+        memory.extend_from_slice(&0x10u32.to_le_bytes());
+        memory.extend_from_slice(&[0; 4]);
+        bytes.extend(partial_descriptor(7, 0x0400, &memory));
+
+        let mut device_specific = Vec::from(3u32.to_le_bytes());
+        device_specific.extend_from_slice(&[0; 12]);
+        bytes.extend(partial_descriptor(5, 0, &device_specific));
+        bytes.extend_from_slice(b"abc");
+
+        bytes
+    }
+
+    #[test]
+    fn test_resource_list() {
+        let bytes = x64_resource_list();
+        let resource_list = ResourceList::from_bytes(&bytes, Arch::X64).unwrap();
+        assert_eq!(resource_list.full_descriptors.len(), 1);
+
+        let full_descriptor = &resource_list.full_descriptors[0];
+        assert_eq!(
+            InterfaceType::n(full_descriptor.interface_type),
+            Some(InterfaceType::PciBus)
+        );
+        assert_eq!(full_descriptor.bus_number, 2);
+        assert_eq!(
+            full_descriptor
+                .partial_descriptors
+                .iter()
+                .map(|partial_descriptor| partial_descriptor.resource.clone())
+                .collect::<Vec<_>>(),
+            [
+                PartialResource::Port {
+                    start: 0x3f8,
+                    length: 8,
+                    flags: PortResourceFlags::IO | PortResourceFlags::DECODE_10_BIT,
+                },
+                PartialResource::Interrupt {
+                    level: 4,
+                    group: 1,
+                    vector: 0x34,
+                    affinity: 0x1_0000_0003,
+                    flags: InterruptResourceFlags::LATCHED,
+                },
+                PartialResource::Memory {
+                    start: 0xfe00_0000,
+                    length: 0x10_0000,
+                    flags: MemoryResourceFlags::LARGE_48,
+                },
+                PartialResource::DeviceSpecific {
+                    data: b"abc".to_vec(),
+                },
+            ]
+        );
+        assert_eq!(full_descriptor.partial_descriptors[0].share_disposition, 1);
+
+        // Every truncation is an error and not a panic.
+        for length in 0..bytes.len() {
+            assert!(ResourceList::from_bytes(&bytes[..length], Arch::X64).is_err());
+        }
+
+        let device_specific_size_field = bytes.len() - 3 - 16;
+        assert_eq!(
+            ResourceList::from_bytes(&bytes[..bytes.len() - 1], Arch::X64),
+            Err(NtHiveError::InvalidSizeField {
+                offset: device_specific_size_field,
+                expected: 3,
+                actual: 2,
+            })
+        );
+
+        let mut too_many_descriptors = bytes.clone();
+        too_many_descriptors[0..4].copy_from_slice(&2u32.to_le_bytes());
+        assert_eq!(
+            ResourceList::from_bytes(&too_many_descriptors, Arch::X64),
+            Err(NtHiveError::InvalidHeaderSize {
+                offset: bytes.len(),
+                expected: 16,
+                actual: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_full_resource_descriptor() {
+        // "Configuration Data" of a disk controller below `HARDWARE\DESCRIPTION\System` of a 64-bit installation.
+        let bytes = include_bytes!("../testdata/full-resource-descriptor.bin");
+        let full_descriptor = FullResourceDescriptor::from_bytes(bytes, Arch::X64).unwrap();
+        assert_eq!(
+            InterfaceType::n(full_descriptor.interface_type),
+            Some(InterfaceType::Isa)
+        );
+        assert_eq!(full_descriptor.bus_number, 0);
+        assert_eq!(full_descriptor.version, 1);
+        assert_eq!(full_descriptor.revision, 1);
+        assert_eq!(
+            full_descriptor
+                .partial_descriptors
+                .iter()
+                .map(|partial_descriptor| partial_descriptor.resource.clone())
+                .collect::<Vec<_>>(),
+            [
+                PartialResource::Port {
+                    start: 0x1f0,
+                    length: 8,
+                    flags: PortResourceFlags::IO | PortResourceFlags::DECODE_16_BIT,
+                },
+                PartialResource::Interrupt {
+                    level: 14,
+                    group: 0,
+                    vector: 14,
+                    affinity: u64::MAX,
+                    flags: InterruptResourceFlags::LATCHED,
+                },
+                PartialResource::DeviceSpecific {
+                    data: (0..8).collect(),
+                },
+            ]
+        );
+
+        // A count exceeding the remaining bytes is rejected before decoding any partial descriptor.
+        let mut too_many_descriptors = bytes.to_vec();
+        too_many_descriptors[12..16].copy_from_slice(&4u32.to_le_bytes());
+        assert_eq!(
+            FullResourceDescriptor::from_bytes(&too_many_descriptors, Arch::X64),
+            Err(NtHiveError::InvalidDataSize {
+                offset: 12,
+                expected: 80,
+                actual: bytes.len() - 16,
+            })
+        );
+
+        for length in 0..bytes.len() {
+            assert!(FullResourceDescriptor::from_bytes(&bytes[..length], Arch::X64).is_err());
+        }
+    }
+
+    #[test]
+    fn test_key_value_full_resource_descriptor() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+
+        let key_value = key_node.value("A").unwrap().unwrap();
+        assert!(matches!(
+            key_value.full_resource_descriptor(Arch::X64),
+            Err(NtHiveError::InvalidKeyValueDataType { .. })
+        ));
+
+        let bytes = include_bytes!("../testdata/full-resource-descriptor.bin");
+        set_big_data_test_a(
+            &mut testhive,
+            bytes,
+            KeyValueDataType::RegFullResourceDescriptor,
+        );
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("A").unwrap().unwrap();
+        assert_eq!(
+            key_value.full_resource_descriptor(Arch::X64),
+            FullResourceDescriptor::from_bytes(bytes, Arch::X64)
+        );
+        assert!(matches!(
+            key_value.resource_list(Arch::X64),
+            Err(NtHiveError::InvalidKeyValueDataType { .. })
+        ));
+    }
+
+    #[test]
+    fn test_resource_requirements_list() {
+        // "BasicConfigVector" of a PCI device of a 64-bit installation, with an I/O port range as an alternative
+        // to the memory range.
+        let bytes = include_bytes!("../testdata/resource-requirements-list.bin");
+        let requirements = ResourceRequirementsList::from_bytes(bytes, Arch::X64).unwrap();
+        assert_eq!(
+            InterfaceType::n(requirements.interface_type),
+            Some(InterfaceType::PciBus)
+        );
+        assert_eq!(requirements.bus_number, 0);
+        assert_eq!(requirements.slot_number, 0x10);
+        assert_eq!(requirements.alternatives.len(), 1);
+
+        let alternative = &requirements.alternatives[0];
+        assert_eq!((alternative.version, alternative.revision), (1, 1));
+        assert_eq!(
+            alternative
+                .descriptors
+                .iter()
+                .map(|descriptor| descriptor.options)
+                .collect::<Vec<_>>(),
+            [
+                IoResourceOptions::empty(),
+                IoResourceOptions::empty(),
+                IoResourceOptions::ALTERNATIVE,
+                IoResourceOptions::empty(),
+            ]
+        );
+        assert_eq!(
+            alternative
+                .descriptors
+                .iter()
+                .map(|descriptor| descriptor.resource.clone())
+                .collect::<Vec<_>>(),
+            [
+                IoResource::Memory {
+                    length: 0x1000,
+                    alignment: 0x1000,
+                    minimum_address: 0,
+                    maximum_address: 0xffff_ffff,
+                    flags: MemoryResourceFlags::empty(),
+                },
+                IoResource::Interrupt {
+                    minimum_vector: 0x10,
+                    maximum_vector: 0x10,
+                    affinity_policy: 0,
+                    group: 0,
+                    priority_policy: 0,
+                    targeted_processors: 0,
+                    flags: InterruptResourceFlags::empty(),
+                },
+                IoResource::Port {
+                    length: 8,
+                    alignment: 8,
+                    minimum_address: 0x1f0,
+                    maximum_address: 0x1f7,
+                    flags: PortResourceFlags::IO | PortResourceFlags::DECODE_16_BIT,
+                },
+                IoResource::DevicePrivate { data: [1, 2, 3] },
+            ]
+        );
+
+        // Every truncation is an error and not a panic.
+        for length in 0..bytes.len() {
+            assert!(ResourceRequirementsList::from_bytes(&bytes[..length], Arch::X64).is_err());
+        }
+
+        let mut too_many_descriptors = bytes.to_vec();
+        too_many_descriptors[36..40].copy_from_slice(&5u32.to_le_bytes());
+        assert_eq!(
+            ResourceRequirementsList::from_bytes(&too_many_descriptors, Arch::X64),
+            Err(NtHiveError::InvalidDataSize {
+                offset: 36,
+                expected: 5 * 32,
+                actual: 4 * 32,
+            })
+        );
+
+        let mut too_large_list_size = bytes.to_vec();
+        too_large_list_size[0..4].copy_from_slice(&169u32.to_le_bytes());
+        assert_eq!(
+            ResourceRequirementsList::from_bytes(&too_large_list_size, Arch::X64),
+            Err(NtHiveError::InvalidSizeField {
+                offset: 0,
+                expected: 169,
+                actual: 168,
+            })
+        );
+
+        // The same data is decoded from a REG_RESOURCE_REQUIREMENTS_LIST value.
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        set_big_data_test_a(
+            &mut testhive,
+            bytes,
+            KeyValueDataType::RegResourceRequirementsList,
+        );
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("A").unwrap().unwrap();
+        assert_eq!(
+            key_value.resource_requirements_list(Arch::X64).unwrap(),
+            requirements
+        );
+    }
+
+    #[test]
+    fn test_key_value_resource_list() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+
+        let key_value = key_node.value("binary").unwrap().unwrap();
+        assert!(matches!(
+            key_value.resource_list(Arch::X64),
+            Err(NtHiveError::InvalidKeyValueDataType { .. })
+        ));
+        assert!(matches!(
+            key_value.resource_requirements_list(Arch::X64),
+            Err(NtHiveError::InvalidKeyValueDataType { .. })
+        ));
+    }
+}
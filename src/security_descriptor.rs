@@ -0,0 +1,708 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::Range;
+
+use zerocopy::byteorder::LittleEndian;
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Ref, Unaligned, U16, U32};
+
+use crate::error::{NtHiveError, Result};
+use crate::helpers::byte_subrange;
+
+/// The DACL of a security descriptor is valid if this control flag is set.
+const SE_DACL_PRESENT: u16 = 0x0004;
+
+/// This flag of an object ACE indicates that an object type GUID follows.
+const ACE_OBJECT_TYPE_PRESENT: u32 = 0x0000_0001;
+
+/// This flag of an object ACE indicates that an inherited object type GUID follows.
+const ACE_INHERITED_OBJECT_TYPE_PRESENT: u32 = 0x0000_0002;
+
+/// Size of a GUID in an object ACE.
+const GUID_SIZE: usize = 16;
+
+/// On-Disk Structure of a self-relative SECURITY_DESCRIPTOR header.
+#[allow(dead_code)]
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct SecurityDescriptorHeader {
+    revision: u8,
+    sbz1: u8,
+    control: U16<LittleEndian>,
+    owner_offset: U32<LittleEndian>,
+    group_offset: U32<LittleEndian>,
+    sacl_offset: U32<LittleEndian>,
+    dacl_offset: U32<LittleEndian>,
+}
+
+/// On-Disk Structure of an ACL header.
+#[allow(dead_code)]
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct AclHeader {
+    revision: u8,
+    sbz1: u8,
+    acl_size: U16<LittleEndian>,
+    ace_count: U16<LittleEndian>,
+    sbz2: U16<LittleEndian>,
+}
+
+/// On-Disk Structure of an ACE header, followed by the access mask shared by all ACE types.
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct AceHeader {
+    ace_type: u8,
+    ace_flags: u8,
+    ace_size: U16<LittleEndian>,
+    access_mask: U32<LittleEndian>,
+}
+
+/// On-Disk Structure of a SID header, followed by the sub authorities.
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C, packed)]
+struct SidHeader {
+    revision: u8,
+    sub_authority_count: u8,
+    identifier_authority: [u8; 6],
+}
+
+/// Known ACE types.
+///
+/// You can use [`AceType::n`] on the value returned by [`Ace::ace_type`]
+/// to find out whether an ACE has a known type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum AceType {
+    AccessAllowed = 0x00,
+    AccessDenied = 0x01,
+    SystemAudit = 0x02,
+    SystemAlarm = 0x03,
+    AccessAllowedCompound = 0x04,
+    AccessAllowedObject = 0x05,
+    AccessDeniedObject = 0x06,
+    SystemAuditObject = 0x07,
+    SystemAlarmObject = 0x08,
+    AccessAllowedCallback = 0x09,
+    AccessDeniedCallback = 0x0a,
+    AccessAllowedCallbackObject = 0x0b,
+    AccessDeniedCallbackObject = 0x0c,
+    SystemAuditCallback = 0x0d,
+    SystemAlarmCallback = 0x0e,
+    SystemAuditCallbackObject = 0x0f,
+    SystemAlarmCallbackObject = 0x10,
+    SystemMandatoryLabel = 0x11,
+    SystemResourceAttribute = 0x12,
+    SystemScopedPolicyId = 0x13,
+    SystemProcessTrustLabel = 0x14,
+    SystemAccessFilter = 0x15,
+}
+
+impl AceType {
+    /// Returns the variant for the given ACE type or `None` if it is unknown.
+    pub const fn n(value: u8) -> Option<Self> {
+        match value {
+            0x00 => Some(Self::AccessAllowed),
+            0x01 => Some(Self::AccessDenied),
+            0x02 => Some(Self::SystemAudit),
+            0x03 => Some(Self::SystemAlarm),
+            0x04 => Some(Self::AccessAllowedCompound),
+            0x05 => Some(Self::AccessAllowedObject),
+            0x06 => Some(Self::AccessDeniedObject),
+            0x07 => Some(Self::SystemAuditObject),
+            0x08 => Some(Self::SystemAlarmObject),
+            0x09 => Some(Self::AccessAllowedCallback),
+            0x0a => Some(Self::AccessDeniedCallback),
+            0x0b => Some(Self::AccessAllowedCallbackObject),
+            0x0c => Some(Self::AccessDeniedCallbackObject),
+            0x0d => Some(Self::SystemAuditCallback),
+            0x0e => Some(Self::SystemAlarmCallback),
+            0x0f => Some(Self::SystemAuditCallbackObject),
+            0x10 => Some(Self::SystemAlarmCallbackObject),
+            0x11 => Some(Self::SystemMandatoryLabel),
+            0x12 => Some(Self::SystemResourceAttribute),
+            0x13 => Some(Self::SystemScopedPolicyId),
+            0x14 => Some(Self::SystemProcessTrustLabel),
+            0x15 => Some(Self::SystemAccessFilter),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<u8> for AceType {
+    type Error = u8;
+
+    /// Returns the unknown value as error if it doesn't correspond to any variant.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::n(value).ok_or(value)
+    }
+}
+
+/// Returns a subrange of `range` beginning `relative_offset` bytes after its start and
+/// encompassing `byte_count` bytes, or `None` if that exceeds `range`.
+fn byte_subrange_at(
+    range: &Range<usize>,
+    relative_offset: usize,
+    byte_count: usize,
+) -> Option<Range<usize>> {
+    let start = range.start.checked_add(relative_offset)?;
+    if start > range.end {
+        return None;
+    }
+
+    byte_subrange(&(start..range.end), byte_count)
+}
+
+/// A self-relative SECURITY_DESCRIPTOR, as stored in a Key Security cell.
+///
+/// All offsets and sizes stored in the descriptor are checked against the bounds of the descriptor
+/// before they are used.
+#[derive(Clone, Copy)]
+pub struct SecurityDescriptor<'h> {
+    bytes: &'h [u8],
+    offset: usize,
+}
+
+impl<'h> SecurityDescriptor<'h> {
+    /// Creates a security descriptor from the raw bytes of a self-relative SECURITY_DESCRIPTOR
+    /// (e.g. those returned by [`KeyNode::security_descriptor_raw`]).
+    ///
+    /// Offsets in errors returned for this security descriptor are relative to the beginning of `bytes`.
+    ///
+    /// [`KeyNode::security_descriptor_raw`]: crate::key_node::KeyNode::security_descriptor_raw
+    pub fn new(bytes: &'h [u8]) -> Result<Self> {
+        Self::with_offset(bytes, 0)
+    }
+
+    /// Creates a security descriptor from `bytes` located `offset` bytes after the beginning of the hive.
+    pub(crate) fn with_offset(bytes: &'h [u8], offset: usize) -> Result<Self> {
+        if bytes.len() < mem::size_of::<SecurityDescriptorHeader>() {
+            return Err(NtHiveError::InvalidHeaderSize {
+                offset,
+                expected: mem::size_of::<SecurityDescriptorHeader>(),
+                actual: bytes.len(),
+            });
+        }
+
+        Ok(Self { bytes, offset })
+    }
+
+    /// Returns the raw bytes of this security descriptor.
+    pub fn as_bytes(&self) -> &'h [u8] {
+        self.bytes
+    }
+
+    /// Returns the raw control flags (`SE_*`) of this security descriptor.
+    pub fn control(&self) -> u16 {
+        self.header().control.get()
+    }
+
+    /// Returns the discretionary ACL of this security descriptor.
+    ///
+    /// Returns `None` if this security descriptor has no DACL or a NULL DACL (which grants
+    /// full access to everyone).
+    pub fn dacl(&self) -> Option<Result<Acl<'h>>> {
+        let header = self.header();
+        if header.control.get() & SE_DACL_PRESENT == 0 {
+            return None;
+        }
+
+        let dacl_offset = header.dacl_offset.get();
+        if dacl_offset == 0 {
+            return None;
+        }
+
+        Some(Acl::new(self.bytes, self.offset, dacl_offset as usize))
+    }
+
+    /// Returns the primary group SID of this security descriptor.
+    ///
+    /// Returns `None` if this security descriptor has no group SID.
+    pub fn group_sid(&self) -> Option<Result<Sid<'h>>> {
+        let group_offset = self.header().group_offset.get();
+        self.sid_at(group_offset)
+    }
+
+    fn header(&self) -> Ref<&'h [u8], SecurityDescriptorHeader> {
+        let (header, _) = Ref::from_prefix(self.bytes).unwrap();
+        header
+    }
+
+    /// Returns the owner SID of this security descriptor.
+    ///
+    /// Returns `None` if this security descriptor has no owner SID.
+    pub fn owner_sid(&self) -> Option<Result<Sid<'h>>> {
+        let owner_offset = self.header().owner_offset.get();
+        self.sid_at(owner_offset)
+    }
+
+    /// Returns the revision of this security descriptor.
+    pub fn revision(&self) -> u8 {
+        self.header().revision
+    }
+
+    fn sid_at(&self, sid_offset: u32) -> Option<Result<Sid<'h>>> {
+        if sid_offset == 0 {
+            return None;
+        }
+
+        let descriptor_range = 0..self.bytes.len();
+        Some(Sid::new(
+            self.bytes,
+            self.offset,
+            &descriptor_range,
+            sid_offset as usize,
+        ))
+    }
+}
+
+/// An access control list (ACL) of a [`SecurityDescriptor`].
+#[derive(Clone)]
+pub struct Acl<'h> {
+    bytes: &'h [u8],
+    offset: usize,
+    header_range: Range<usize>,
+    aces_range: Range<usize>,
+}
+
+impl<'h> Acl<'h> {
+    fn new(bytes: &'h [u8], offset: usize, acl_offset: usize) -> Result<Self> {
+        let descriptor_range = 0..bytes.len();
+        let header_range =
+            byte_subrange_at(&descriptor_range, acl_offset, mem::size_of::<AclHeader>())
+                .ok_or_else(|| NtHiveError::InvalidHeaderSize {
+                    offset: offset + acl_offset,
+                    expected: mem::size_of::<AclHeader>(),
+                    actual: bytes.len().saturating_sub(acl_offset),
+                })?;
+
+        let header = Ref::<&[u8], AclHeader>::from_bytes(&bytes[header_range.clone()]).unwrap();
+        let acl_size = header.acl_size.get() as usize;
+        let acl_range =
+            byte_subrange_at(&descriptor_range, acl_offset, acl_size).ok_or_else(|| {
+                NtHiveError::InvalidSizeField {
+                    offset: offset + acl_offset + 2,
+                    expected: acl_size,
+                    actual: bytes.len() - acl_offset,
+                }
+            })?;
+        if acl_size < header_range.len() {
+            return Err(NtHiveError::InvalidHeaderSize {
+                offset: offset + acl_offset,
+                expected: header_range.len(),
+                actual: acl_size,
+            });
+        }
+
+        let aces_range = header_range.end..acl_range.end;
+
+        Ok(Self {
+            bytes,
+            offset,
+            header_range,
+            aces_range,
+        })
+    }
+
+    /// Returns the number of ACEs in this ACL, as stored in its header.
+    pub fn ace_count(&self) -> u16 {
+        self.header().ace_count.get()
+    }
+
+    /// Returns an iterator over the ACEs (access control entries) of this ACL.
+    pub fn aces(&self) -> Aces<'h> {
+        Aces {
+            bytes: self.bytes,
+            offset: self.offset,
+            aces_range: self.aces_range.clone(),
+            remaining_count: self.ace_count(),
+        }
+    }
+
+    fn header(&self) -> Ref<&'h [u8], AclHeader> {
+        Ref::from_bytes(&self.bytes[self.header_range.clone()]).unwrap()
+    }
+
+    /// Returns the revision of this ACL.
+    pub fn revision(&self) -> u8 {
+        self.header().revision
+    }
+}
+
+/// Iterator over
+///   all ACEs of an [`Acl`],
+///   returning an [`Ace`] for each ACE.
+///
+/// Iteration stops after the first ACE that fails validation.
+#[derive(Clone)]
+pub struct Aces<'h> {
+    bytes: &'h [u8],
+    offset: usize,
+    aces_range: Range<usize>,
+    remaining_count: u16,
+}
+
+impl<'h> Iterator for Aces<'h> {
+    type Item = Result<Ace<'h>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_count == 0 {
+            return None;
+        }
+
+        let result = Ace::new(self.bytes, self.offset, &self.aces_range);
+        match &result {
+            Ok(ace) => {
+                self.aces_range.start = ace.range.end;
+                self.remaining_count -= 1;
+            }
+            Err(_) => self.remaining_count = 0,
+        }
+
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining_count as usize))
+    }
+}
+
+impl FusedIterator for Aces<'_> {}
+
+/// A single access control entry (ACE) of an [`Acl`].
+#[derive(Clone)]
+pub struct Ace<'h> {
+    bytes: &'h [u8],
+    offset: usize,
+    range: Range<usize>,
+}
+
+impl<'h> Ace<'h> {
+    fn new(bytes: &'h [u8], offset: usize, aces_range: &Range<usize>) -> Result<Self> {
+        let header_range =
+            byte_subrange(aces_range, mem::size_of::<AceHeader>()).ok_or_else(|| {
+                NtHiveError::InvalidHeaderSize {
+                    offset: offset + aces_range.start,
+                    expected: mem::size_of::<AceHeader>(),
+                    actual: aces_range.len(),
+                }
+            })?;
+
+        let header = Ref::<&[u8], AceHeader>::from_bytes(&bytes[header_range.clone()]).unwrap();
+        let ace_size = header.ace_size.get() as usize;
+        let range =
+            byte_subrange(aces_range, ace_size).ok_or_else(|| NtHiveError::InvalidSizeField {
+                offset: offset + aces_range.start + 2,
+                expected: ace_size,
+                actual: aces_range.len(),
+            })?;
+        if ace_size < header_range.len() {
+            return Err(NtHiveError::InvalidHeaderSize {
+                offset: offset + aces_range.start,
+                expected: header_range.len(),
+                actual: ace_size,
+            });
+        }
+
+        Ok(Self {
+            bytes,
+            offset,
+            range,
+        })
+    }
+
+    /// Returns the access mask of this ACE.
+    pub fn access_mask(&self) -> u32 {
+        self.header().access_mask.get()
+    }
+
+    /// Returns the raw type of this ACE.
+    ///
+    /// You can use [`AceType::n`] on this value to find out whether it is a known type.
+    pub fn ace_type(&self) -> u8 {
+        self.header().ace_type
+    }
+
+    /// Returns the raw flags (`*_ACE` inheritance and audit flags) of this ACE.
+    pub fn flags(&self) -> u8 {
+        self.header().ace_flags
+    }
+
+    fn header(&self) -> Ref<&'h [u8], AceHeader> {
+        let (header, _) = Ref::from_prefix(&self.bytes[self.range.clone()]).unwrap();
+        header
+    }
+
+    /// Returns the SID of the trustee of this ACE.
+    ///
+    /// Returns `None` for ACE types that don't have a single SID (compound ACEs and unknown types).
+    /// For object ACEs, the object type GUIDs preceding the SID are skipped.
+    pub fn sid(&self) -> Option<Result<Sid<'h>>> {
+        let mut sid_offset = mem::size_of::<AceHeader>();
+
+        match AceType::n(self.ace_type())? {
+            AceType::AccessAllowedCompound => return None,
+            AceType::AccessAllowedObject
+            | AceType::AccessDeniedObject
+            | AceType::SystemAuditObject
+            | AceType::SystemAlarmObject
+            | AceType::AccessAllowedCallbackObject
+            | AceType::AccessDeniedCallbackObject
+            | AceType::SystemAuditCallbackObject
+            | AceType::SystemAlarmCallbackObject => {
+                let object_flags_range =
+                    byte_subrange_at(&self.range, sid_offset, mem::size_of::<u32>()).ok_or_else(
+                        || NtHiveError::InvalidHeaderSize {
+                            offset: self.offset + self.range.start + sid_offset,
+                            expected: mem::size_of::<u32>(),
+                            actual: self.range.len() - sid_offset,
+                        },
+                    );
+                let object_flags_range = iter_try!(object_flags_range);
+                let object_flags =
+                    u32::from_le_bytes(self.bytes[object_flags_range].try_into().unwrap());
+                sid_offset += mem::size_of::<u32>();
+
+                if object_flags & ACE_OBJECT_TYPE_PRESENT != 0 {
+                    sid_offset += GUID_SIZE;
+                }
+                if object_flags & ACE_INHERITED_OBJECT_TYPE_PRESENT != 0 {
+                    sid_offset += GUID_SIZE;
+                }
+            }
+            _ => (),
+        }
+
+        Some(Sid::new(self.bytes, self.offset, &self.range, sid_offset))
+    }
+}
+
+/// A security identifier (SID), e.g. `S-1-5-32-544` for the local Administrators group.
+///
+/// Use its [`Display`](fmt::Display) implementation to format it in the usual string form
+/// without any allocations.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Sid<'h> {
+    bytes: &'h [u8],
+}
+
+impl<'h> Sid<'h> {
+    /// Creates a SID located `relative_offset` bytes after the beginning of `range`,
+    /// which it must not exceed.
+    fn new(
+        bytes: &'h [u8],
+        offset: usize,
+        range: &Range<usize>,
+        relative_offset: usize,
+    ) -> Result<Self> {
+        let sid_offset = offset + range.start + relative_offset;
+        let remaining_size = range.len().saturating_sub(relative_offset);
+
+        let header_range = byte_subrange_at(range, relative_offset, mem::size_of::<SidHeader>())
+            .ok_or(NtHiveError::InvalidHeaderSize {
+                offset: sid_offset,
+                expected: mem::size_of::<SidHeader>(),
+                actual: remaining_size,
+            })?;
+
+        let header = Ref::<&[u8], SidHeader>::from_bytes(&bytes[header_range]).unwrap();
+        let sid_size = mem::size_of::<SidHeader>()
+            + header.sub_authority_count as usize * mem::size_of::<u32>();
+        let sid_range = byte_subrange_at(range, relative_offset, sid_size).ok_or_else(|| {
+            NtHiveError::InvalidSizeField {
+                offset: sid_offset + 1,
+                expected: sid_size,
+                actual: remaining_size,
+            }
+        })?;
+
+        Ok(Self {
+            bytes: &bytes[sid_range],
+        })
+    }
+
+    /// Returns the raw bytes of this SID.
+    pub fn as_bytes(&self) -> &'h [u8] {
+        self.bytes
+    }
+
+    fn header(&self) -> Ref<&'h [u8], SidHeader> {
+        let (header, _) = Ref::from_prefix(self.bytes).unwrap();
+        header
+    }
+
+    /// Returns the identifier authority of this SID (e.g. `5` for `SECURITY_NT_AUTHORITY`).
+    pub fn identifier_authority(&self) -> u64 {
+        let mut authority = [0u8; 8];
+        authority[2..].copy_from_slice(&self.header().identifier_authority);
+        u64::from_be_bytes(authority)
+    }
+
+    /// Returns the revision of this SID.
+    pub fn revision(&self) -> u8 {
+        self.header().revision
+    }
+
+    /// Returns an iterator over the sub authorities of this SID.
+    pub fn sub_authorities(&self) -> impl Iterator<Item = u32> + 'h {
+        self.bytes[mem::size_of::<SidHeader>()..]
+            .chunks_exact(mem::size_of::<u32>())
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+}
+
+impl fmt::Display for Sid<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "S-{}-", self.revision())?;
+
+        // Identifier authorities that don't fit into 32 bits are written in hexadecimal like Windows does.
+        let identifier_authority = self.identifier_authority();
+        if identifier_authority > u32::MAX as u64 {
+            write!(f, "0x{identifier_authority:012X}")?;
+        } else {
+            write!(f, "{identifier_authority}")?;
+        }
+
+        for sub_authority in self.sub_authorities() {
+            write!(f, "-{sub_authority}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::cell_data;
+    use crate::*;
+
+    #[test]
+    fn test_security_descriptor() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_security = root_key_node.key_security().unwrap().unwrap();
+        let descriptor_offset = cell_data(key_security.cell_offset()) + 20;
+
+        let descriptor = root_key_node.security_descriptor().unwrap().unwrap();
+        assert_eq!(descriptor.revision(), 1);
+        assert_eq!(descriptor.control(), 0x8004);
+        assert_eq!(
+            descriptor.owner_sid().unwrap().unwrap().to_string(),
+            "S-1-5-32-544"
+        );
+        assert_eq!(
+            descriptor.group_sid().unwrap().unwrap().to_string(),
+            "S-1-5-32-544"
+        );
+
+        let dacl = descriptor.dacl().unwrap().unwrap();
+        assert_eq!(dacl.revision(), 2);
+        assert_eq!(dacl.ace_count(), 4);
+
+        let aces = dacl
+            .aces()
+            .map(|ace| {
+                let ace = ace.unwrap();
+                assert_eq!(AceType::n(ace.ace_type()), Some(AceType::AccessAllowed));
+                let sid = ace.sid().unwrap().unwrap().to_string();
+                (ace.flags(), ace.access_mask(), sid)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            aces,
+            [
+                (0x02, 0x000f_003f, "S-1-5-18".to_owned()),
+                (0x02, 0x000f_003f, "S-1-5-32-544".to_owned()),
+                (0x02, 0x0002_0019, "S-1-1-0".to_owned()),
+                (0x02, 0x0002_0019, "S-1-5-12".to_owned()),
+            ]
+        );
+
+        // Owner and group SIDs are located at offsets 112 and 128, the DACL at offset 20 and
+        // its first ACE at offset 28.
+        let owner_offset_field = descriptor_offset + 4;
+        let owner_offset = descriptor_offset + 112;
+        let dacl_offset = descriptor_offset + 20;
+        let first_ace_offset = dacl_offset + 8;
+
+        // The owner SID claims more sub authorities than fit into the descriptor.
+        let mut corrupted = testhive.clone();
+        corrupted[owner_offset + 1] = 10;
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let descriptor = root_key_node.security_descriptor().unwrap().unwrap();
+        assert_eq!(
+            descriptor.owner_sid().unwrap(),
+            Err(NtHiveError::InvalidSizeField {
+                offset: owner_offset + 1,
+                expected: 48,
+                actual: 32,
+            })
+        );
+
+        // The owner offset points beyond the descriptor.
+        let mut corrupted = testhive.clone();
+        corrupted[owner_offset_field..owner_offset_field + 4]
+            .copy_from_slice(&1000u32.to_le_bytes());
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let descriptor = root_key_node.security_descriptor().unwrap().unwrap();
+        assert_eq!(
+            descriptor.owner_sid().unwrap(),
+            Err(NtHiveError::InvalidHeaderSize {
+                offset: descriptor_offset + 1000,
+                expected: 8,
+                actual: 0,
+            })
+        );
+
+        // The size of the first ACE exceeds the DACL.
+        testhive[first_ace_offset + 2..first_ace_offset + 4].copy_from_slice(&200u16.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let descriptor = root_key_node.security_descriptor().unwrap().unwrap();
+        let dacl = descriptor.dacl().unwrap().unwrap();
+        let mut aces = dacl.aces();
+        assert_eq!(
+            aces.next().unwrap().err(),
+            Some(NtHiveError::InvalidSizeField {
+                offset: first_ace_offset + 2,
+                expected: 200,
+                actual: 84,
+            })
+        );
+        assert!(aces.next().is_none());
+    }
+
+    #[test]
+    fn test_sid_display() {
+        // A minimal security descriptor without DACL, whose owner SID has a large identifier authority.
+        let mut bytes = vec![
+            1, 0, 0x00, 0x80, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        bytes.extend_from_slice(&[1, 2, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        bytes.extend_from_slice(&42u32.to_le_bytes());
+        bytes.extend_from_slice(&4711u32.to_le_bytes());
+
+        let descriptor = SecurityDescriptor::new(&bytes).unwrap();
+        assert!(descriptor.group_sid().is_none());
+        assert!(descriptor.dacl().is_none());
+
+        let sid = descriptor.owner_sid().unwrap().unwrap();
+        assert_eq!(sid.identifier_authority(), 0x0102_0304_0506);
+        assert_eq!(sid.sub_authorities().collect::<Vec<_>>(), [42, 4711]);
+        assert_eq!(sid.to_string(), "S-1-0x010203040506-42-4711");
+
+        assert_eq!(
+            SecurityDescriptor::new(&bytes[..19]).err(),
+            Some(NtHiveError::InvalidHeaderSize {
+                offset: 0,
+                expected: 20,
+                actual: 19,
+            })
+        );
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+//! One-shot functions for reading a single key or value out of a hive.
+//!
+//! Each function parses the hive, looks up the requested item, copies everything into owned types,
+//! and drops the hive again.
+//! This is a lot less efficient than keeping a [`Hive`] around for multiple lookups, but much easier
+//! to wrap in bindings for other languages.
+//!
+//! [`Hive`]: crate::hive::Hive
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::Result;
+use crate::hive::Hive;
+use crate::key_node::KeyNode;
+use crate::key_value::KeyValue;
+
+/// Options for [`read_key`] and [`read_value`].
+#[derive(Clone, Debug, Default)]
+pub struct SimpleOptions {
+    /// Skip validating the base block of the hive (see [`Hive::without_validation`]).
+    ///
+    /// [`Hive::without_validation`]: crate::hive::Hive::without_validation
+    pub skip_validation: bool,
+    /// Fill unreadable Big Data segments with zeros instead of failing
+    /// (see [`KeyValueData::into_vec_lossy`]).
+    ///
+    /// [`KeyValueData::into_vec_lossy`]: crate::key_value::KeyValueData::into_vec_lossy
+    pub lossy_data: bool,
+}
+
+/// A single value along with its data, as returned by [`read_value`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimpleValue {
+    /// Name of the value. The default value of a key has an empty name.
+    pub name: String,
+    /// Raw data type code of the value (e.g. `4` for `REG_DWORD`).
+    pub data_type: u32,
+    /// Data bytes of the value.
+    pub data: Vec<u8>,
+}
+
+/// Name, type, and size of a single value, as returned in a [`SimpleKey`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimpleValueSummary {
+    /// Name of the value. The default value of a key has an empty name.
+    pub name: String,
+    /// Raw data type code of the value (e.g. `4` for `REG_DWORD`).
+    pub data_type: u32,
+    /// Size of the data in bytes, as stored in the value header.
+    pub data_size: u32,
+}
+
+/// Subkeys and values of a single key, as returned by [`read_key`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimpleKey {
+    /// Names of all subkeys, in the order they are stored in the hive.
+    pub subkey_names: Vec<String>,
+    /// All values, in the order they are stored in the hive.
+    pub values: Vec<SimpleValueSummary>,
+}
+
+/// Reads the subkey names and value summaries of the key at `key_path`.
+///
+/// `key_path` is relative to the root key of the hive, with path elements separated by backslashes.
+/// An empty path refers to the root key.
+/// Names that aren't valid UTF-16 are converted lossily.
+///
+/// Returns `Ok(None)` if the key doesn't exist, and an error if the hive or any structure along the
+/// path is corrupted.
+pub fn read_key(
+    hive_bytes: &[u8],
+    key_path: &str,
+    options: &SimpleOptions,
+) -> Result<Option<SimpleKey>> {
+    let hive = open_hive(hive_bytes, options)?;
+    let Some(key_node) = find_key_node(&hive, key_path)? else {
+        return Ok(None);
+    };
+
+    let mut subkey_names = Vec::new();
+    if let Some(subkeys) = key_node.subkeys() {
+        for subkey in subkeys? {
+            subkey_names.push(subkey?.name()?.to_string_lossy());
+        }
+    }
+
+    let mut values = Vec::new();
+    if let Some(key_values) = key_node.values() {
+        for key_value in key_values? {
+            let key_value = key_value?;
+            values.push(SimpleValueSummary {
+                name: key_value.name_lossy()?,
+                data_type: key_value.data_type_code(),
+                data_size: key_value.data_size(),
+            });
+        }
+    }
+
+    Ok(Some(SimpleKey {
+        subkey_names,
+        values,
+    }))
+}
+
+/// Reads the value `value_name` of the key at `key_path` along with its data.
+///
+/// `key_path` is relative to the root key of the hive, with path elements separated by backslashes.
+/// An empty `value_name` refers to the default value of the key.
+///
+/// Returns `Ok(None)` if the key or value doesn't exist, and an error if the hive or any structure
+/// along the path is corrupted.
+pub fn read_value(
+    hive_bytes: &[u8],
+    key_path: &str,
+    value_name: &str,
+    options: &SimpleOptions,
+) -> Result<Option<SimpleValue>> {
+    let hive = open_hive(hive_bytes, options)?;
+    let Some(key_node) = find_key_node(&hive, key_path)? else {
+        return Ok(None);
+    };
+    let Some(key_value) = key_node.value(value_name).transpose()? else {
+        return Ok(None);
+    };
+
+    let data = read_data(&key_value, options)?;
+
+    Ok(Some(SimpleValue {
+        name: key_value.name_lossy()?,
+        data_type: key_value.data_type_code(),
+        data,
+    }))
+}
+
+fn open_hive<'a>(hive_bytes: &'a [u8], options: &SimpleOptions) -> Result<Hive<&'a [u8]>> {
+    if options.skip_validation {
+        Hive::without_validation(hive_bytes)
+    } else {
+        Hive::new(hive_bytes)
+    }
+}
+
+fn find_key_node<'h>(
+    hive: &'h Hive<&[u8]>,
+    key_path: &str,
+) -> Result<Option<KeyNode<'h, &'h [u8]>>> {
+    let root_key_node = hive.root_key_node()?;
+    root_key_node.subpath(key_path).transpose()
+}
+
+fn read_data(key_value: &KeyValue<&[u8]>, options: &SimpleOptions) -> Result<Vec<u8>> {
+    let data = key_value.data()?;
+
+    if options.lossy_data {
+        let (data, _gaps) = data.into_vec_lossy();
+        Ok(data)
+    } else {
+        data.into_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::{cell_data, key_node_field, read_u32};
+    use crate::key_node::KeyNodeField;
+    use crate::simple::*;
+
+    #[test]
+    fn test_read_value() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let options = SimpleOptions::default();
+
+        let value = read_value(&testhive, "data-test", "dword", &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            value,
+            SimpleValue {
+                name: "dword".to_owned(),
+                data_type: 4,
+                data: 42u32.to_le_bytes().to_vec(),
+            }
+        );
+
+        // Leading backslashes and case differences don't matter.
+        let value = read_value(&testhive, "\\DATA-TEST", "DWORD", &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(value.name, "dword");
+
+        // Big Data is read entirely.
+        let value = read_value(&testhive, "big-data-test", "C", &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(value.data.len(), 16345);
+
+        // Misses
+        assert_eq!(
+            read_value(&testhive, "data-test", "non-existing", &options).unwrap(),
+            None
+        );
+        assert_eq!(
+            read_value(&testhive, "non-existing", "dword", &options).unwrap(),
+            None
+        );
+
+        // A broken base block is only accepted when skipping validation.
+        let mut corrupted = testhive.clone();
+        corrupted[508..512].copy_from_slice(&0u32.to_le_bytes());
+        assert!(read_value(&corrupted, "data-test", "dword", &options).is_err());
+        let options = SimpleOptions {
+            skip_validation: true,
+            ..Default::default()
+        };
+        let value = read_value(&corrupted, "data-test", "dword", &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(value.data, 42u32.to_le_bytes());
+
+        // A broken Subkeys List along the path is an error and not a miss.
+        let mut corrupted = testhive.clone();
+        let root_cell_offset = read_u32(&corrupted, 36);
+        let subkeys_list = cell_data(read_u32(
+            &corrupted,
+            key_node_field(root_cell_offset, KeyNodeField::SubkeysListOffset),
+        ));
+        corrupted[subkeys_list..subkeys_list + 2].copy_from_slice(b"xx");
+        assert!(read_value(&corrupted, "data-test", "dword", &options).is_err());
+    }
+
+    #[test]
+    fn test_read_key() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let options = SimpleOptions::default();
+
+        let key = read_key(&testhive, "", &options).unwrap().unwrap();
+        assert_eq!(key.subkey_names.len(), 5);
+        assert_eq!(key.subkey_names[0], "big-data-test");
+        assert!(key.values.is_empty());
+
+        let key = read_key(&testhive, "data-test", &options).unwrap().unwrap();
+        assert!(key.subkey_names.is_empty());
+        assert_eq!(key.values.len(), 9);
+        let dword = key
+            .values
+            .iter()
+            .find(|value| value.name == "dword")
+            .unwrap();
+        assert_eq!(dword.data_type, 4);
+        assert_eq!(dword.data_size, 4);
+
+        assert_eq!(read_key(&testhive, "non-existing", &options).unwrap(), None);
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use core::iter::FusedIterator;
+
+use zerocopy::SplitByteSlice;
+
+use crate::error::Result;
+use crate::hive::Hive;
+use crate::hive_bin::HiveCells;
+use crate::key_node::KeyNode;
+use crate::key_security::KeySecurity;
+use crate::key_value::KeyValue;
+
+/// The structure whose cell contains a [`SlackRegion`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SlackRegionKind {
+    /// Bytes after the name of a Key Node (see [`KeyNode::slack`]).
+    KeyNode,
+    /// Bytes after the security descriptor of a Key Security cell.
+    KeySecurity,
+    /// Bytes after the name of a Key Value.
+    KeyValue,
+    /// Bytes after the data of a Key Value, which is stored in a separate cell (see [`KeyValue::data_slack`]).
+    KeyValueData,
+}
+
+/// Unused bytes at the end of an allocated cell, as returned by [`Hive::slack_regions`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SlackRegion<'h> {
+    kind: SlackRegionKind,
+    cell_offset: u32,
+    offset: usize,
+    bytes: &'h [u8],
+}
+
+impl<'h> SlackRegion<'h> {
+    fn new<B>(hive: &'h Hive<B>, kind: SlackRegionKind, cell_offset: u32, bytes: &'h [u8]) -> Self
+    where
+        B: SplitByteSlice,
+    {
+        Self {
+            kind,
+            cell_offset,
+            offset: hive.offset_of_field(bytes),
+            bytes,
+        }
+    }
+
+    /// Returns the unused bytes.
+    pub fn bytes(&self) -> &'h [u8] {
+        self.bytes
+    }
+
+    /// Returns the data offset of the cell containing these bytes.
+    pub fn cell_offset(&self) -> u32 {
+        self.cell_offset
+    }
+
+    /// Returns the structure whose cell contains these bytes.
+    pub fn kind(&self) -> SlackRegionKind {
+        self.kind
+    }
+
+    /// Returns the offset of the first unused byte relative to the very beginning of the hive bytes.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// Iterator over
+///   the slack space of all allocated cells of a hive,
+///   returning a [`SlackRegion`] for each cell with at least one unused byte.
+///
+/// Cells are identified by their signature (`nk`, `vk`, `sk`).
+/// The slack of the data cell of a Key Value is returned right after the slack of the Key Value itself,
+/// no matter where the data cell is stored.
+/// Iteration stops after the first invalid Hive Bin or cell (see [`HiveCells`]).
+///
+/// Created by [`Hive::slack_regions`].
+pub struct SlackRegions<'h, B: SplitByteSlice> {
+    hive: &'h Hive<B>,
+    cells: HiveCells<'h, B>,
+    /// Slack of the data cell of the Key Value returned last.
+    pending: Option<SlackRegion<'h>>,
+}
+
+impl<'h, B> SlackRegions<'h, B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns the slack regions of the allocated cell at `cell_offset`, if it contains a parseable structure.
+    ///
+    /// The first region is returned immediately, the second one (if any) is stored in `self.pending`.
+    fn scan_cell(&mut self, cell_offset: u32) -> Option<SlackRegion<'h>> {
+        let hive = self.hive;
+        let cell_range = hive.cell_range_from_data_offset(cell_offset).ok()?;
+        let signature = hive.data_slice(cell_range.clone()).get(..2)?;
+
+        match signature {
+            b"nk" => {
+                let key_node = KeyNode::from_cell_range(hive, cell_range).ok()?;
+                let bytes = key_node.slack().ok()?;
+                Some(SlackRegion::new(
+                    hive,
+                    SlackRegionKind::KeyNode,
+                    cell_offset,
+                    bytes,
+                ))
+            }
+            b"sk" => {
+                let key_security = KeySecurity::from_cell_range(hive, cell_range).ok()?;
+                let bytes = key_security.slack().ok()?;
+                Some(SlackRegion::new(
+                    hive,
+                    SlackRegionKind::KeySecurity,
+                    cell_offset,
+                    bytes,
+                ))
+            }
+            b"vk" => {
+                let key_value = KeyValue::new(hive, cell_range).ok()?;
+                self.pending = key_value
+                    .data_slack()
+                    .and_then(|data_slack| data_slack.ok())
+                    .map(|bytes| {
+                        SlackRegion::new(
+                            hive,
+                            SlackRegionKind::KeyValueData,
+                            key_value.data_offset(),
+                            bytes,
+                        )
+                    });
+
+                let bytes = key_value.name_slack().ok()?;
+                Some(SlackRegion::new(
+                    hive,
+                    SlackRegionKind::KeyValue,
+                    cell_offset,
+                    bytes,
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'h, B> Iterator for SlackRegions<'h, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<SlackRegion<'h>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(region) = self.pending.take() {
+                if !region.bytes.is_empty() {
+                    return Some(Ok(region));
+                }
+            }
+
+            let cell = iter_try!(self.cells.next()?);
+            if !cell.is_allocated() {
+                continue;
+            }
+
+            if let Some(region) = self.scan_cell(cell.data_offset()) {
+                if !region.bytes.is_empty() {
+                    return Some(Ok(region));
+                }
+            }
+        }
+    }
+}
+
+impl<B> FusedIterator for SlackRegions<'_, B> where B: SplitByteSlice {}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns an iterator over the slack space of all allocated cells of this hive.
+    ///
+    /// Cells are 8-byte aligned and frequently larger than the structure stored in them, so their last bytes
+    /// may contain remnants of previous data.
+    /// This covers the bytes after the names of Key Nodes and Key Values, after the data of Key Values,
+    /// and after the security descriptors of Key Security cells.
+    /// Cells that cannot be parsed are skipped, because it is unknown how many of their bytes are used.
+    /// Unallocated cells are not part of this, as all of their bytes are unused (see [`Hive::cells`]).
+    pub fn slack_regions(&self) -> SlackRegions<'_, B> {
+        SlackRegions {
+            hive: self,
+            cells: self.cells(),
+            pending: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::{cell_data, key_node_field};
+    use crate::key_node::KeyNodeField;
+    use crate::*;
+
+    #[test]
+    fn test_slack() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        // The root key name "ROOT" leaves 4 bytes of its cell unused.
+        assert_eq!(root_key_node.slack().unwrap(), [0; 4]);
+
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = key_node.value("binary").unwrap().unwrap();
+        let data_cell = cell_data(key_value.data_offset());
+        let data_cell_size =
+            i32::from_le_bytes(testhive[data_cell - 4..data_cell].try_into().unwrap());
+        let data_slack_len = key_value.data_slack().unwrap().unwrap().len();
+        assert_eq!(
+            data_slack_len,
+            data_cell_size.unsigned_abs() as usize - 4 - 5
+        );
+
+        // Inline data and Big Data have no data cell slack.
+        let key_value = key_node.value("dword").unwrap().unwrap();
+        assert!(key_value.data_slack().is_none());
+        let key_node = root_key_node.subkey("big-data-test").unwrap().unwrap();
+        let key_value = key_node.value("C").unwrap().unwrap();
+        assert!(key_value.data_slack().is_none());
+
+        // Remnants in the slack of the root key are found by the hive-wide scan.
+        let root_cell_offset = root_key_node.cell_offset();
+        let root_slack_offset = key_node_field(root_cell_offset, KeyNodeField::KeyName) + 4;
+        testhive[root_slack_offset..root_slack_offset + 4].copy_from_slice(b"OLD!");
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let regions = hive.slack_regions().collect::<Result<Vec<_>>>().unwrap();
+        assert!(regions.iter().all(|region| !region.bytes().is_empty()));
+
+        let root_region = regions
+            .iter()
+            .find(|region| region.cell_offset() == root_cell_offset)
+            .unwrap();
+        assert_eq!(root_region.kind(), SlackRegionKind::KeyNode);
+        assert_eq!(root_region.offset(), root_slack_offset);
+        assert_eq!(root_region.bytes(), b"OLD!");
+
+        let data_region = regions
+            .iter()
+            .find(|region| region.offset() == data_cell + 5)
+            .unwrap();
+        assert_eq!(data_region.kind(), SlackRegionKind::KeyValueData);
+        assert_eq!(data_region.bytes().len(), data_slack_len);
+
+        assert!(regions
+            .iter()
+            .any(|region| region.kind() == SlackRegionKind::KeyValue));
+
+        // The only Key Security cell is completely filled by its security descriptor.
+        assert!(regions
+            .iter()
+            .all(|region| region.kind() != SlackRegionKind::KeySecurity));
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use zerocopy::SplitByteSlice;
+
+use crate::error::Result;
+use crate::key_node::{KeyNode, KeyNodeFlags};
+use crate::key_value::{KeyValue, KeyValueDataType};
+use crate::string::NameEncoding;
+
+fn name_encoding(has_compressed_name: bool) -> NameEncoding {
+    if has_compressed_name {
+        NameEncoding::Latin1
+    } else {
+        NameEncoding::Utf16
+    }
+}
+
+/// Owned copy of a single Key Value, as part of a [`KeyNodeSnapshot`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyValueSnapshot {
+    /// Name of the Key Value (converted lossily if it isn't valid UTF-16).
+    pub name: String,
+    /// Encoding of the name in the hive, taken from the `VALUE_COMP_NAME` flag.
+    ///
+    /// Writers re-encoding the name should honor it instead of choosing an encoding based on the characters.
+    pub name_encoding: NameEncoding,
+    /// Raw numeric data type, as stored in the hive.
+    ///
+    /// Use [`KeyValueSnapshot::known_data_type`] to get a [`KeyValueDataType`].
+    pub data_type: u32,
+    /// Raw data bytes, with all Big Data segments concatenated.
+    pub data: Vec<u8>,
+}
+
+impl KeyValueSnapshot {
+    fn new<B>(key_value: &KeyValue<'_, B>) -> Result<Self>
+    where
+        B: SplitByteSlice,
+    {
+        Ok(Self {
+            name: key_value.name_lossy()?,
+            name_encoding: name_encoding(key_value.has_compressed_name()),
+            data_type: key_value.data_type_code(),
+            data: key_value.data()?.into_vec()?,
+        })
+    }
+
+    /// Returns the data type of this Key Value or `None` if it is unknown.
+    pub fn known_data_type(&self) -> Option<KeyValueDataType> {
+        KeyValueDataType::n(self.data_type)
+    }
+}
+
+/// Owned copy of a single key, returned by [`KeyNode::snapshot`].
+///
+/// This doesn't borrow the hive, so it can be kept after the hive has been dropped.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyNodeSnapshot {
+    /// Name of the key (converted lossily if it isn't valid UTF-16).
+    pub name: String,
+    /// Encoding of the name in the hive, taken from the `KEY_COMP_NAME` flag.
+    ///
+    /// Writers re-encoding the name should honor it instead of choosing an encoding based on the characters.
+    pub name_encoding: NameEncoding,
+    /// Last write time of the key as a raw Windows FILETIME (see [`KeyNode::timestamp`]).
+    pub timestamp: u64,
+    /// Flags of the key.
+    pub flags: KeyNodeFlags,
+    /// All Key Values of the key, in the order of the Key Values List.
+    pub values: Vec<KeyValueSnapshot>,
+    /// Names of all subkeys, in sorted order.
+    /// The subkeys themselves are not captured.
+    pub subkey_names: Vec<String>,
+}
+
+impl<B> KeyNode<'_, B>
+where
+    B: SplitByteSlice,
+{
+    /// Reads this key completely into an owned [`KeyNodeSnapshot`].
+    ///
+    /// The snapshot contains the name, timestamp, and flags of this key, all of its Key Values including
+    /// their data, and the names of its subkeys.
+    /// Subkeys are not captured beyond their names. Call this function on each subkey if you need more.
+    ///
+    /// Any error while reading a Key Value or subkey name aborts the snapshot.
+    pub fn snapshot(&self) -> Result<KeyNodeSnapshot> {
+        let values = match self.values() {
+            Some(key_values) => key_values?
+                .map(|key_value| KeyValueSnapshot::new(&key_value?))
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+
+        let subkey_names = match self.subkeys() {
+            Some(subkeys) => subkeys?
+                .map(|subkey| Ok(subkey?.name()?.to_string_lossy()))
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+
+        Ok(KeyNodeSnapshot {
+            name: self.name()?.to_string_lossy(),
+            name_encoding: name_encoding(self.has_compressed_name()),
+            timestamp: self.timestamp(),
+            flags: self.flags(),
+            values,
+            subkey_names,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_snapshot() {
+        let testhive = crate::helpers::tests::testhive_vec();
+
+        let (data_test, subpath_test) = {
+            let hive = Hive::new(testhive.as_ref()).unwrap();
+            let root_key_node = hive.root_key_node().unwrap();
+            let data_test = root_key_node.subkey("data-test").unwrap().unwrap();
+            let subpath_test = root_key_node.subkey("subpath-test").unwrap().unwrap();
+            (
+                data_test.snapshot().unwrap(),
+                subpath_test.snapshot().unwrap(),
+            )
+        };
+
+        // The hive has been dropped here, but the snapshots are still usable.
+        assert_eq!(data_test.name, "data-test");
+        assert_eq!(data_test.flags, KeyNodeFlags::KEY_COMP_NAME);
+        assert!(data_test.subkey_names.is_empty());
+
+        let dword = data_test
+            .values
+            .iter()
+            .find(|value| value.name == "dword")
+            .unwrap();
+        assert_eq!(dword.known_data_type(), Some(KeyValueDataType::RegDWord));
+        assert_eq!(dword.data, 42u32.to_le_bytes());
+
+        let reg_multi_sz_big = data_test
+            .values
+            .iter()
+            .find(|value| value.name == "reg-multi-sz-big")
+            .unwrap();
+        assert_eq!(
+            reg_multi_sz_big.known_data_type(),
+            Some(KeyValueDataType::RegMultiSZ)
+        );
+        assert_eq!(reg_multi_sz_big.data.len(), 16426);
+
+        assert_eq!(subpath_test.name, "subpath-test");
+        assert!(subpath_test.values.is_empty());
+        assert_eq!(
+            subpath_test.subkey_names,
+            [
+                "no-subkeys",
+                "with-single-level-subkey",
+                "with-two-levels-of-subkeys"
+            ]
+        );
+
+        // Compare against the live key.
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        assert_eq!(data_test.timestamp, key_node.timestamp());
+        assert_eq!(data_test.values.len(), key_node.value_count() as usize);
+    }
+
+    #[test]
+    fn test_snapshot_name_encoding() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node
+            .subkey("character-encoding-test")
+            .unwrap()
+            .unwrap();
+
+        // "äöü" fits into Latin1, but "Deseret Uppercase H" (U+10410) needs UTF-16.
+        let latin1 = key_node.subkey("äöü").unwrap().unwrap();
+        let utf16 = key_node.subkey("𐐐").unwrap().unwrap();
+        assert!(latin1.has_compressed_name());
+        assert!(!utf16.has_compressed_name());
+
+        let latin1 = latin1.snapshot().unwrap();
+        assert_eq!(latin1.name, "äöü");
+        assert_eq!(latin1.name_encoding, NameEncoding::Latin1);
+        assert!(latin1.flags.contains(KeyNodeFlags::KEY_COMP_NAME));
+
+        let utf16 = utf16.snapshot().unwrap();
+        assert_eq!(utf16.name, "𐐐");
+        assert_eq!(utf16.name_encoding, NameEncoding::Utf16);
+        assert!(!utf16.flags.contains(KeyNodeFlags::KEY_COMP_NAME));
+
+        // Value names carry their encoding as well.
+        let data_test = root_key_node.subkey("data-test").unwrap().unwrap();
+        let key_value = data_test.value("dword").unwrap().unwrap();
+        assert!(key_value.has_compressed_name());
+        assert_eq!(key_value.name().unwrap().encoding(), NameEncoding::Latin1);
+        let snapshot = data_test.snapshot().unwrap();
+        assert!(snapshot
+            .values
+            .iter()
+            .all(|value| value.name_encoding == NameEncoding::Latin1));
+    }
+}
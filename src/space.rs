@@ -0,0 +1,207 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::vec::Vec;
+
+use zerocopy::SplitByteSlice;
+
+use crate::error::Result;
+use crate::hive::Hive;
+use crate::hive_bin::HiveBin;
+
+/// Space usage of a single Hive Bin, as part of a [`SpaceReport`].
+///
+/// All sizes include the 4-byte cell headers, but not the header of the bin itself.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BinSpace {
+    /// Data offset of the bin (relative to the start of the hive data).
+    pub offset: u32,
+    /// Size of the bin in bytes, including its header.
+    pub size: u32,
+    /// Total size of all allocated cells of the bin.
+    pub allocated_bytes: u32,
+    /// Number of allocated cells of the bin.
+    pub allocated_cells: u32,
+    /// Total size of all free cells of the bin.
+    pub free_bytes: u32,
+    /// Number of free cells of the bin.
+    pub free_cells: u32,
+    /// Size of the largest free cell of the bin, or zero if the bin has no free cells.
+    pub largest_free_cell: u32,
+}
+
+impl BinSpace {
+    fn new<B>(hive_bin: &HiveBin<'_, B>) -> Result<Self>
+    where
+        B: SplitByteSlice,
+    {
+        let mut bin_space = Self {
+            offset: hive_bin.offset(),
+            size: hive_bin.size(),
+            ..Default::default()
+        };
+
+        for cell in hive_bin.cells() {
+            let cell = cell?;
+            let size = cell.size();
+
+            if cell.is_allocated() {
+                bin_space.allocated_bytes += size;
+                bin_space.allocated_cells += 1;
+            } else {
+                bin_space.free_bytes += size;
+                bin_space.free_cells += 1;
+                bin_space.largest_free_cell = bin_space.largest_free_cell.max(size);
+            }
+        }
+
+        Ok(bin_space)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for BinSpace {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("BinSpace", 7)?;
+        state.serialize_field("offset", &self.offset)?;
+        state.serialize_field("size", &self.size)?;
+        state.serialize_field("allocated_bytes", &self.allocated_bytes)?;
+        state.serialize_field("allocated_cells", &self.allocated_cells)?;
+        state.serialize_field("free_bytes", &self.free_bytes)?;
+        state.serialize_field("free_cells", &self.free_cells)?;
+        state.serialize_field("largest_free_cell", &self.largest_free_cell)?;
+        state.end()
+    }
+}
+
+/// Space usage of an entire hive, as returned by [`Hive::space_report`].
+///
+/// All sizes include the 4-byte cell headers, but not the headers of the bins.
+/// Lots of free space spread over many small free cells indicates a fragmented hive.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SpaceReport {
+    /// Total size of all allocated cells.
+    pub allocated_bytes: u64,
+    /// Number of allocated cells.
+    pub allocated_cells: u64,
+    /// Total size of all free cells.
+    pub free_bytes: u64,
+    /// Number of free cells.
+    pub free_cells: u64,
+    /// Size of the largest free cell, or zero if the hive has no free cells.
+    pub largest_free_cell: u32,
+    /// Space usage of each Hive Bin, in the order of [`Hive::bins`].
+    pub bins: Vec<BinSpace>,
+}
+
+impl SpaceReport {
+    fn add_bin(&mut self, bin_space: BinSpace) {
+        self.allocated_bytes += u64::from(bin_space.allocated_bytes);
+        self.allocated_cells += u64::from(bin_space.allocated_cells);
+        self.free_bytes += u64::from(bin_space.free_bytes);
+        self.free_cells += u64::from(bin_space.free_cells);
+        self.largest_free_cell = self.largest_free_cell.max(bin_space.largest_free_cell);
+        self.bins.push(bin_space);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SpaceReport {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("SpaceReport", 6)?;
+        state.serialize_field("allocated_bytes", &self.allocated_bytes)?;
+        state.serialize_field("allocated_cells", &self.allocated_cells)?;
+        state.serialize_field("free_bytes", &self.free_bytes)?;
+        state.serialize_field("free_cells", &self.free_cells)?;
+        state.serialize_field("largest_free_cell", &self.largest_free_cell)?;
+        state.serialize_field("bins", self.bins.as_slice())?;
+        state.end()
+    }
+}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Walks all cells of all Hive Bins and sums up the allocated and free space.
+    ///
+    /// This answers the question why a hive is as large as it is, and how much could be gained by compacting it.
+    /// Returns the first error encountered while walking the bins and cells, including
+    /// [`NtHiveError::Cancelled`] if the walk has been cancelled (see [`Hive::set_cancel_token`]).
+    ///
+    /// [`NtHiveError::Cancelled`]: crate::error::NtHiveError::Cancelled
+    pub fn space_report(&self) -> Result<SpaceReport> {
+        let mut report = SpaceReport::default();
+
+        for hive_bin in self.bins() {
+            let bin_space = BinSpace::new(&hive_bin?)?;
+            report.add_bin(bin_space);
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_space_report() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let report = hive.space_report().unwrap();
+
+        // Allocated and free cells fill the payload of all bins.
+        let payload_size = report
+            .bins
+            .iter()
+            .map(|bin_space| u64::from(bin_space.size) - 32)
+            .sum::<u64>();
+        assert_eq!(report.allocated_bytes + report.free_bytes, payload_size);
+        assert_eq!(report.bins.len(), hive.bins().count());
+        assert_eq!(report.bins[0].offset, 0);
+
+        assert!(report.free_cells > 0);
+        assert!(u64::from(report.largest_free_cell) <= report.free_bytes);
+        assert_eq!(
+            report.largest_free_cell,
+            report
+                .bins
+                .iter()
+                .map(|bin_space| bin_space.largest_free_cell)
+                .max()
+                .unwrap()
+        );
+        assert_eq!(
+            report.allocated_cells + report.free_cells,
+            hive.cells().count() as u64
+        );
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_value(&report).unwrap();
+            assert_eq!(json["free_bytes"], report.free_bytes);
+            assert_eq!(json["bins"][0]["size"], report.bins[0].size);
+        }
+
+        // A broken cell size is an error.
+        let first_cell = 4096 + 0x20;
+        testhive[first_cell..first_cell + 4].copy_from_slice(&0i32.to_le_bytes());
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        assert!(matches!(
+            hive.space_report(),
+            Err(NtHiveError::InvalidSizeFieldAlignment { offset, .. }) if offset == first_cell
+        ));
+    }
+}
@@ -1213,6 +1213,52 @@ fn utf16_code_unit_to_uppercase(unit: u16) -> u16 {
     }
 }
 
+/// Calculates the hash of a key name as stored in Hash Leaf items.
+///
+/// This is the algorithm used by Windows: Every UTF-16 code unit of the uppercased name is added
+/// to the hash after multiplying the previous hash by 37.
+pub(crate) trait NameHash {
+    fn name_hash(&self) -> u32;
+}
+
+fn name_hash_of_code_units<I>(code_units: I) -> u32
+where
+    I: Iterator<Item = u16>,
+{
+    code_units.fold(0u32, |hash, unit| {
+        hash.wrapping_mul(37)
+            .wrapping_add(utf16_code_unit_to_uppercase(unit) as u32)
+    })
+}
+
+impl NameHash for str {
+    fn name_hash(&self) -> u32 {
+        name_hash_of_code_units(self.encode_utf16())
+    }
+}
+
+impl NameHash for NtHiveNameString<'_> {
+    fn name_hash(&self) -> u32 {
+        match self {
+            Self::Latin1(_) => name_hash_of_code_units(self.latin1_iter()),
+            Self::Utf16LE(_) => name_hash_of_code_units(self.utf16le_iter()),
+        }
+    }
+}
+
+/// Encoding of a key name or value name as stored in hive data.
+///
+/// Windows stores a name in Latin1 ("compressed name") whenever all of its characters fit,
+/// and marks this via the `KEY_COMP_NAME` or `VALUE_COMP_NAME` flag.
+/// Owned copies of a name need to keep the encoding to reconstruct its original bytes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NameEncoding {
+    /// One byte per character of the Latin1 (ISO-8859-1) character set.
+    Latin1,
+    /// UTF-16 (Little-Endian).
+    Utf16,
+}
+
 /// Zero-copy representation of a key name or value name string stored in hive data.
 /// Can be either in Latin1 (ISO-8859-1) or UTF-16 (Little-Endian).
 ///
@@ -1281,6 +1327,76 @@ impl<'h> NtHiveNameString<'h> {
         }
     }
 
+    /// Compares the beginning of `self` with `prefix`, using the same case-insensitive ordering
+    /// as the [`Ord`] implementation.
+    ///
+    /// Returns [`Ordering::Equal`] if `self` starts with `prefix`.
+    /// Otherwise, returns whether `self` is sorted before or after all strings starting with `prefix`.
+    pub(crate) fn cmp_prefix(&self, prefix: &str) -> Ordering {
+        let prefix_len = prefix.encode_utf16().count();
+        let prefix_iter = prefix.encode_utf16();
+
+        match self {
+            Self::Latin1(_) => Self::cmp_iter(self.latin1_iter().take(prefix_len), prefix_iter),
+            Self::Utf16LE(_) => Self::cmp_iter(self.utf16le_iter().take(prefix_len), prefix_iter),
+        }
+    }
+
+    /// Returns the raw bytes of `self`, as stored in the hive.
+    ///
+    /// These are Latin1 bytes or UTF-16LE code units, depending on the variant.
+    /// Use this for byte-exact matching or hashing of names without decoding them.
+    pub const fn as_bytes(&self) -> &'h [u8] {
+        match self {
+            Self::Latin1(bytes) => bytes,
+            Self::Utf16LE(bytes) => bytes,
+        }
+    }
+
+    /// Returns an iterator over the characters of `self`, replacing invalid data with the
+    /// replacement character (U+FFFD) like the [`Display`](fmt::Display) implementation does.
+    ///
+    /// Surrogate pairs are always returned as a single character.
+    fn chars_lossy(&self) -> impl Iterator<Item = char> + '_ {
+        let (bytes, bytes_per_code_unit) = match self {
+            Self::Latin1(bytes) => (*bytes, 1),
+            Self::Utf16LE(bytes) => (&bytes[..bytes.len() & !1], 2),
+        };
+
+        let code_units = bytes.chunks(bytes_per_code_unit).map(|chunk| match chunk {
+            [latin1] => *latin1 as u16,
+            [low, high] => u16::from_le_bytes([*low, *high]),
+            _ => unreachable!(),
+        });
+
+        char::decode_utf16(code_units).map(|x| x.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Returns the number of characters of `self`, as output by the [`Display`](fmt::Display)
+    /// implementation.
+    ///
+    /// In contrast to [`NtHiveNameString::len`], this counts a character outside the Basic
+    /// Multilingual Plane (stored as a UTF-16 surrogate pair) as a single character.
+    /// Use it to calculate the display width of a name without allocating.
+    pub fn char_count(&self) -> usize {
+        self.chars_lossy().count()
+    }
+
+    /// Returns a [`Display`](fmt::Display) implementation that outputs at most `max_chars`
+    /// characters of `self`.
+    ///
+    /// If `self` has to be truncated, the last of these characters is an ellipsis (`…`), so the output
+    /// never exceeds `max_chars` characters.
+    ///
+    /// This works directly on the stored bytes without allocating.
+    /// A surrogate pair is never split: If it doesn't fit anymore, it is omitted entirely.
+    pub fn display_truncated(&self, max_chars: usize) -> impl fmt::Display + '_ {
+        DisplayTruncated {
+            name: self,
+            max_chars,
+        }
+    }
+
     fn latin1_iter(&'h self) -> impl Iterator<Item = u16> + 'h {
         match self {
             Self::Latin1(bytes) => bytes.iter().map(|byte| *byte as u16),
@@ -1297,6 +1413,27 @@ impl<'h> NtHiveNameString<'h> {
         }
     }
 
+    /// Returns the encoding of `self` as stored in the hive data.
+    pub const fn encoding(&self) -> NameEncoding {
+        match self {
+            Self::Latin1(_) => NameEncoding::Latin1,
+            Self::Utf16LE(_) => NameEncoding::Utf16,
+        }
+    }
+
+    /// Checks that `self` and `other` are an exact, case-sensitive match.
+    ///
+    /// In contrast, the [`PartialEq`] implementations perform a case-insensitive comparison
+    /// like Windows does.
+    pub fn eq_case_sensitive(&self, other: &str) -> bool {
+        let other_iter = other.encode_utf16();
+
+        match self {
+            Self::Latin1(_) => self.latin1_iter().eq(other_iter),
+            Self::Utf16LE(_) => self.utf16le_iter().eq(other_iter),
+        }
+    }
+
     /// Returns `true` if `self` has a length of zero bytes.
     pub const fn is_empty(&self) -> bool {
         self.len() == 0
@@ -1307,10 +1444,33 @@ impl<'h> NtHiveNameString<'h> {
     /// This length is in bytes, not characters! In other words,
     /// it may not be what a human considers the length of the string.
     pub const fn len(&self) -> usize {
-        match self {
-            Self::Latin1(bytes) => bytes.len(),
-            Self::Utf16LE(bytes) => bytes.len(),
+        self.as_bytes().len()
+    }
+
+    /// Checks if `self` is a Latin1 name that is actually stored in UTF-16LE and returns it reinterpreted
+    /// as such.
+    ///
+    /// A Latin1 name with an even length, whose bytes at all odd positions are NUL and whose bytes at all
+    /// even positions are not, is the UTF-16LE encoding of a name consisting only of Latin1 characters.
+    /// Windows would have stored such a name in Latin1 right away, so this pattern strongly suggests that
+    /// the `KEY_COMP_NAME` or `VALUE_COMP_NAME` flag has been set wrongly.
+    /// Decoding it as Latin1 would result in a garbled name with a NUL after every character.
+    ///
+    /// Returns `None` for any other name, including all UTF-16LE names.
+    /// The returned name refers to the same bytes as `self`.
+    pub fn miscoded_utf16le(&self) -> Option<NtHiveNameString<'h>> {
+        let Self::Latin1(bytes) = self else {
+            return None;
+        };
+
+        if bytes.is_empty() || bytes.len() % 2 != 0 {
+            return None;
         }
+
+        let looks_like_utf16le = bytes
+            .chunks_exact(2)
+            .all(|two_bytes| two_bytes[0] != 0 && two_bytes[1] == 0);
+        looks_like_utf16le.then_some(Self::Utf16LE(bytes))
     }
 
     /// Attempts to convert `self` to an owned `String`.
@@ -1363,6 +1523,31 @@ impl fmt::Display for NtHiveNameString<'_> {
     }
 }
 
+/// Returned by [`NtHiveNameString::display_truncated`].
+struct DisplayTruncated<'a, 'h> {
+    name: &'a NtHiveNameString<'h>,
+    max_chars: usize,
+}
+
+impl fmt::Display for DisplayTruncated<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.name.char_count() <= self.max_chars {
+            return fmt::Display::fmt(self.name, f);
+        }
+
+        // Reserve the last character for the ellipsis.
+        let Some(kept_chars) = self.max_chars.checked_sub(1) else {
+            return Ok(());
+        };
+
+        for single_char in self.name.chars_lossy().take(kept_chars) {
+            single_char.fmt(f)?;
+        }
+
+        '\u{2026}'.fmt(f)
+    }
+}
+
 impl Ord for NtHiveNameString<'_> {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
@@ -1497,6 +1682,124 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_as_bytes() {
+        assert_eq!(NtHiveNameString::Latin1(b"Hello").as_bytes(), b"Hello");
+
+        let hello = [b'H', 0, b'e', 0, b'l', 0, b'l', 0, b'o', 0];
+        assert_eq!(NtHiveNameString::Utf16LE(&hello).as_bytes(), hello);
+
+        // The returned slice is borrowed from the hive and not from the name string.
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = crate::hive::Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+        let name_bytes = key_node.name().unwrap().as_bytes();
+        assert_eq!(name_bytes, b"data-test");
+    }
+
+    #[test]
+    fn test_display_truncated() {
+        let utf16le = |string: &str| {
+            string
+                .encode_utf16()
+                .flat_map(|utf16_code_point| utf16_code_point.to_le_bytes())
+                .collect::<Vec<u8>>()
+        };
+
+        let latin1 = NtHiveNameString::Latin1(b"Hell\xD6");
+        assert_eq!(latin1.char_count(), 5);
+        assert_eq!(latin1.display_truncated(3).to_string(), "He\u{2026}");
+        assert_eq!(latin1.display_truncated(4).to_string(), "Hel\u{2026}");
+        assert_eq!(latin1.display_truncated(5).to_string(), "HellÖ");
+        assert_eq!(latin1.display_truncated(6).to_string(), "HellÖ");
+        assert_eq!(latin1.display_truncated(1).to_string(), "\u{2026}");
+        assert_eq!(latin1.display_truncated(0).to_string(), "");
+
+        // "ä" is in the Basic Multilingual Plane, the Deseret characters need surrogate pairs.
+        let bytes = utf16le("ä\u{10410}\u{10438}b");
+        let utf16 = NtHiveNameString::Utf16LE(&bytes);
+        assert_eq!(utf16.len(), 12);
+        assert_eq!(utf16.char_count(), 4);
+        assert_eq!(utf16.display_truncated(1).to_string(), "\u{2026}");
+        assert_eq!(utf16.display_truncated(2).to_string(), "ä\u{2026}");
+        assert_eq!(utf16.display_truncated(3).to_string(), "ä\u{10410}\u{2026}");
+        assert_eq!(
+            utf16.display_truncated(4).to_string(),
+            "ä\u{10410}\u{10438}b"
+        );
+
+        for max_chars in 0..=4 {
+            assert!(!utf16
+                .display_truncated(max_chars)
+                .to_string()
+                .contains(char::REPLACEMENT_CHARACTER));
+        }
+
+        // An empty name is never truncated.
+        assert_eq!(
+            NtHiveNameString::Utf16LE(&[])
+                .display_truncated(0)
+                .to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_miscoded_utf16le() {
+        let name = NtHiveNameString::Latin1(b"A\0b\0\xe4\0");
+        let reinterpreted = name.miscoded_utf16le().unwrap();
+        assert_eq!(reinterpreted.encoding(), NameEncoding::Utf16);
+        assert!(reinterpreted.eq_case_sensitive("Ab\u{e4}"));
+        assert_eq!(reinterpreted.as_bytes(), name.as_bytes());
+
+        // Regular Latin1 names, odd lengths, NULs at even positions, and UTF-16LE names are left alone.
+        assert_eq!(NtHiveNameString::Latin1(b"Ab").miscoded_utf16le(), None);
+        assert_eq!(NtHiveNameString::Latin1(b"A\0b").miscoded_utf16le(), None);
+        assert_eq!(
+            NtHiveNameString::Latin1(b"A\0\0\0").miscoded_utf16le(),
+            None
+        );
+        assert_eq!(NtHiveNameString::Latin1(b"").miscoded_utf16le(), None);
+        assert_eq!(
+            NtHiveNameString::Utf16LE(b"A\0b\0").miscoded_utf16le(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_name_hash() {
+        assert_eq!("".name_hash(), 0);
+        assert_eq!("a".name_hash(), 0x41);
+        assert_eq!("ab".name_hash(), 0x41 * 37 + 0x42);
+        assert_eq!("AB".name_hash(), "ab".name_hash());
+        assert_eq!(
+            NtHiveNameString::Latin1(b"Ab").name_hash(),
+            "ab".name_hash()
+        );
+        assert_eq!(
+            NtHiveNameString::Utf16LE(&[b'a', 0, b'B', 0]).name_hash(),
+            "ab".name_hash()
+        );
+        assert_eq!(
+            NtHiveNameString::Latin1(b"\xE4").name_hash(),
+            "\u{C4}".name_hash()
+        );
+    }
+
+    #[test]
+    fn test_eq_case_sensitive() {
+        assert!(NtHiveNameString::Latin1(b"Hello").eq_case_sensitive("Hello"));
+        assert!(!NtHiveNameString::Latin1(b"Hello").eq_case_sensitive("hello"));
+        assert!(!NtHiveNameString::Latin1(b"Hello").eq_case_sensitive("Hell"));
+        assert!(NtHiveNameString::Latin1(b"Hell\xD6").eq_case_sensitive("HellÖ"));
+        assert!(!NtHiveNameString::Latin1(b"Hell\xD6").eq_case_sensitive("Hellö"));
+
+        let hello = [b'H', 0, b'e', 0, b'l', 0, b'l', 0, b'o', 0];
+        assert!(NtHiveNameString::Utf16LE(&hello).eq_case_sensitive("Hello"));
+        assert!(!NtHiveNameString::Utf16LE(&hello).eq_case_sensitive("HELLO"));
+    }
+
     #[test]
     fn test_is_empty() {
         assert!(NtHiveNameString::Latin1(b"").is_empty());
@@ -1,32 +1,90 @@
 // Copyright 2020-2025 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: GPL-2.0-or-later
 
+use core::cmp::Ordering;
 use core::iter::FusedIterator;
 use core::mem;
 use core::ops::Range;
 
 use zerocopy::byteorder::LittleEndian;
-use zerocopy::{
-    FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, SplitByteSliceMut,
-    Unaligned, U16,
-};
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Ref, SplitByteSlice, Unaligned, U16};
 
 use crate::error::{NtHiveError, Result};
 use crate::helpers::byte_subrange;
 use crate::hive::Hive;
-use crate::index_root::{IndexRootKeyNodes, IndexRootKeyNodesMut};
-use crate::key_node::{KeyNode, KeyNodeMut};
-use crate::leaf::{LeafKeyNodes, LeafKeyNodesMut, LeafType};
+use crate::index_root::{IndexRootItemRanges, IndexRootKeyNodes};
+use crate::key_node::KeyNode;
+use crate::leaf::{LeafItemRange, LeafItemRanges, LeafKeyNodes, LeafType};
+use crate::list_capacity::ListCapacity;
+
+#[cfg(feature = "alloc")]
+use crate::dump::StructLayout;
+
+#[cfg(feature = "write")]
+use {
+    crate::index_root::IndexRootKeyNodesMut, crate::key_node::KeyNodeMut,
+    crate::leaf::LeafKeyNodesMut, zerocopy::SplitByteSliceMut,
+};
 
 /// On-Disk Structure of a Subkeys List header.
 /// This is common for all subkey types (Fast Leaf, Hash Leaf, Index Leaf, Index Root).
 #[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
-#[repr(packed)]
+#[repr(C, packed)]
 pub(crate) struct SubkeysListHeader {
     pub(crate) signature: [u8; 2],
     pub(crate) count: U16<LittleEndian>,
 }
 
+#[cfg(feature = "alloc")]
+pub(crate) const SUBKEYS_LIST_HEADER_LAYOUT: StructLayout =
+    struct_layout!(SubkeysListHeader { signature, count });
+
+/// Signature and count of a Subkeys List at the time a mutable iterator over it has been created.
+///
+/// Mutable iterators hand out mutable [`KeyNode`]s, through which the Subkeys List may be rewritten.
+/// Comparing the header against this snapshot before every step turns such a modification into
+/// an error instead of silently iterating over outdated ranges.
+#[cfg(feature = "write")]
+pub(crate) struct SubkeysListSnapshot {
+    header_range: Range<usize>,
+    signature: [u8; 2],
+    count: u16,
+}
+
+#[cfg(feature = "write")]
+impl SubkeysListSnapshot {
+    pub(crate) fn new<B>(subkeys_list: &SubkeysList<B>) -> Self
+    where
+        B: SplitByteSlice,
+    {
+        let header = subkeys_list.header();
+
+        Self {
+            header_range: subkeys_list.header_range.clone(),
+            signature: header.signature,
+            count: header.count.get(),
+        }
+    }
+
+    /// Checks that the header of the Subkeys List still matches this snapshot.
+    pub(crate) fn verify<B>(&self, hive: &Hive<B>) -> Result<()>
+    where
+        B: SplitByteSlice,
+    {
+        let header =
+            Ref::<&[u8], SubkeysListHeader>::from_bytes(hive.data_slice(self.header_range.clone()))
+                .unwrap();
+
+        if header.signature == self.signature && header.count.get() == self.count {
+            Ok(())
+        } else {
+            Err(NtHiveError::ConcurrentStructuralModification {
+                offset: hive.offset_of_data_offset(self.header_range.start),
+            })
+        }
+    }
+}
+
 /// Subkeys of a single [`KeyNode`].
 ///
 /// A Subkeys List generalizes over all structures used to manage subkeys.
@@ -76,32 +134,62 @@ where
         Ok(subkeys_list)
     }
 
+    /// Computes the [`ListCapacity`] of this Subkeys List.
+    pub(crate) fn capacity(&self) -> Result<ListCapacity> {
+        let header = self.header();
+        let count = header.count.get();
+        let count_field_offset = self.hive.offset_of_field(&header.count);
+        let data_range = self.data_range.clone();
+
+        let used_data_size = match &header.signature {
+            b"lf" | b"lh" | b"li" => {
+                // Fast Leaf, Hash Leaf or Index Leaf
+                let leaf_type = LeafType::from_signature(&header.signature).unwrap();
+                LeafItemRanges::new(count, count_field_offset, data_range.clone(), leaf_type)?
+                    .used_size(&data_range)
+            }
+            b"ri" => {
+                // Index Root
+                IndexRootItemRanges::new(count, count_field_offset, data_range.clone())?
+                    .used_size(&data_range)
+            }
+            _ => unreachable!(),
+        };
+
+        Ok(ListCapacity {
+            allocated_size: self.header_range.len() + data_range.len(),
+            used_size: self.header_range.len() + used_data_size,
+        })
+    }
+
     pub(crate) fn header(&self) -> Ref<&[u8], SubkeysListHeader> {
-        Ref::from_bytes(&self.hive.data[self.header_range.clone()]).unwrap()
+        Ref::from_bytes(self.hive.data_slice(self.header_range.clone())).unwrap()
     }
 
     fn validate_signature(&self, index_root_supported: bool) -> Result<()> {
         let header = self.header();
 
+        let legacy = self.hive.is_legacy_version();
+
         match &header.signature {
-            // Index Leaf / Fast Leaf / Hash Leaf
-            b"lf" | b"lh" | b"li" => return Ok(()),
+            // Index Leaf
+            b"li" => return Ok(()),
+
+            // Fast Leaf / Hash Leaf, which have been introduced with Windows NT 4.0 / Windows XP
+            b"lf" | b"lh" if !legacy => return Ok(()),
 
             // Index Root
-            b"ri" => {
-                if index_root_supported {
-                    return Ok(());
-                }
-            }
+            b"ri" if index_root_supported => return Ok(()),
 
             // Anything else
             _ => (),
         }
 
-        let expected_signature: &[u8] = if index_root_supported {
-            b"lf|lh|li|ri"
-        } else {
-            b"lf|lh|li"
+        let expected_signature: &[u8] = match (legacy, index_root_supported) {
+            (false, true) => b"lf|lh|li|ri",
+            (false, false) => b"lf|lh|li",
+            (true, true) => b"li|ri",
+            (true, false) => b"li",
         };
 
         Err(NtHiveError::InvalidTwoByteSignature {
@@ -131,6 +219,16 @@ where
     B: SplitByteSlice,
 {
     pub(crate) fn new(hive: &'h Hive<B>, cell_range: Range<usize>) -> Result<Self> {
+        Self::new_internal(hive, cell_range, false)
+    }
+
+    /// Like [`SubKeyNodes::new`], but iterates the subkeys referenced by the stale items
+    /// following the declared items of the Subkeys List.
+    pub(crate) fn new_stale(hive: &'h Hive<B>, cell_range: Range<usize>) -> Result<Self> {
+        Self::new_internal(hive, cell_range, true)
+    }
+
+    fn new_internal(hive: &'h Hive<B>, cell_range: Range<usize>, stale: bool) -> Result<Self> {
         let subkeys_list = SubkeysList::new(hive, cell_range)?;
         let header = subkeys_list.header();
         let signature = header.signature;
@@ -142,13 +240,20 @@ where
             b"lf" | b"lh" | b"li" => {
                 // Fast Leaf, Hash Leaf or Index Leaf
                 let leaf_type = LeafType::from_signature(&signature).unwrap();
-                let iter =
-                    LeafKeyNodes::new(hive, count, count_field_offset, data_range, leaf_type)?;
+                let iter = if stale {
+                    LeafKeyNodes::new_stale(hive, count, count_field_offset, data_range, leaf_type)?
+                } else {
+                    LeafKeyNodes::new(hive, count, count_field_offset, data_range, leaf_type)?
+                };
                 Ok(Self::Leaf(iter))
             }
             b"ri" => {
                 // Index Root
-                let iter = IndexRootKeyNodes::new(hive, count, count_field_offset, data_range)?;
+                let iter = if stale {
+                    IndexRootKeyNodes::new_stale(hive, count, count_field_offset, data_range)?
+                } else {
+                    IndexRootKeyNodes::new(hive, count, count_field_offset, data_range)?
+                };
                 Ok(Self::IndexRoot(iter))
             }
             _ => unreachable!(),
@@ -200,6 +305,145 @@ where
 
 impl<B> FusedIterator for SubKeyNodes<'_, B> where B: SplitByteSlice {}
 
+/// Iterator over
+///   all subkeys of a [`KeyNode`] whose names start with a given prefix,
+///   returning a constant [`KeyNode`] for each subkey.
+///
+/// The first matching subkey is located via binary search, and iteration stops at the first
+/// subkey that doesn't match anymore.
+///
+/// Created by [`KeyNode::subkeys_with_prefix`].
+pub struct SubKeyNodesWithPrefix<'h, 'p, B: SplitByteSlice> {
+    hive: &'h Hive<B>,
+    prefix: &'p str,
+    index_root_item_ranges: Option<IndexRootItemRanges>,
+    leaf_item_ranges: Option<LeafItemRanges>,
+}
+
+impl<'h, 'p, B> SubKeyNodesWithPrefix<'h, 'p, B>
+where
+    B: SplitByteSlice,
+{
+    pub(crate) fn new(
+        hive: &'h Hive<B>,
+        cell_range: Range<usize>,
+        prefix: &'p str,
+    ) -> Result<Self> {
+        let mut iter = Self {
+            hive,
+            prefix,
+            index_root_item_ranges: None,
+            leaf_item_ranges: None,
+        };
+
+        match SubKeyNodes::new(hive, cell_range)? {
+            SubKeyNodes::IndexRoot(index_root_key_nodes) => {
+                let mut index_root_item_ranges = IndexRootItemRanges::from(index_root_key_nodes);
+
+                // Find the first Index Root item whose LAST Key Node doesn't come before the prefix.
+                let mut left = 0;
+                let mut right = index_root_item_ranges.len();
+
+                while left < right {
+                    let mid = (left + right) / 2;
+                    let index_root_item_range = index_root_item_ranges.clone().nth(mid).unwrap();
+                    let leaf_item_ranges =
+                        LeafItemRanges::from_index_root_item_range(hive, index_root_item_range)?;
+
+                    // `from_index_root_item_range` guarantees that the Leaf has at least one item.
+                    let leaf_item_range = leaf_item_ranges.last().unwrap();
+                    if iter.cmp_prefix(leaf_item_range)? == Ordering::Less {
+                        left = mid + 1;
+                    } else {
+                        right = mid;
+                    }
+                }
+
+                if let Some(index_root_item_range) = index_root_item_ranges.nth(left) {
+                    let leaf_item_ranges =
+                        LeafItemRanges::from_index_root_item_range(hive, index_root_item_range)?;
+                    iter.leaf_item_ranges = Some(iter.seek_in_leaf(leaf_item_ranges)?);
+                    iter.index_root_item_ranges = Some(index_root_item_ranges);
+                }
+            }
+            SubKeyNodes::Leaf(leaf_key_nodes) => {
+                let leaf_item_ranges = LeafItemRanges::from(leaf_key_nodes);
+                iter.leaf_item_ranges = Some(iter.seek_in_leaf(leaf_item_ranges)?);
+            }
+        }
+
+        Ok(iter)
+    }
+
+    fn cmp_prefix(&self, leaf_item_range: LeafItemRange) -> Result<Ordering> {
+        let key_node = KeyNode::from_leaf_item_range(self.hive, leaf_item_range)?;
+        let name = key_node.name()?;
+        Ok(name.cmp_prefix(self.prefix))
+    }
+
+    /// Advances `leaf_item_ranges` to the first Leaf item that doesn't come before the prefix.
+    fn seek_in_leaf(&self, mut leaf_item_ranges: LeafItemRanges) -> Result<LeafItemRanges> {
+        let mut left = 0;
+        let mut right = leaf_item_ranges.len();
+
+        while left < right {
+            let mid = (left + right) / 2;
+            let leaf_item_range = leaf_item_ranges.clone().nth(mid).unwrap();
+
+            if self.cmp_prefix(leaf_item_range)? == Ordering::Less {
+                left = mid + 1;
+            } else {
+                right = mid;
+            }
+        }
+
+        if left > 0 {
+            leaf_item_ranges.nth(left - 1);
+        }
+
+        Ok(leaf_item_ranges)
+    }
+}
+
+impl<'h, B> Iterator for SubKeyNodesWithPrefix<'h, '_, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<KeyNode<'h, B>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(leaf_item_range) = self.leaf_item_ranges.as_mut()?.next() {
+                let key_node = iter_try!(KeyNode::from_leaf_item_range(self.hive, leaf_item_range));
+                let name = iter_try!(key_node.name());
+
+                if name.cmp_prefix(self.prefix) == Ordering::Equal {
+                    return Some(Ok(key_node));
+                }
+
+                // All following subkeys come after the prefix.
+                self.leaf_item_ranges = None;
+                return None;
+            }
+
+            // The current Leaf has been fully iterated, so continue with the next Index Root item (if any).
+            let index_root_item_range = self.index_root_item_ranges.as_mut()?.next();
+            let Some(index_root_item_range) = index_root_item_range else {
+                self.leaf_item_ranges = None;
+                return None;
+            };
+
+            let leaf_item_ranges = iter_try!(LeafItemRanges::from_index_root_item_range(
+                self.hive,
+                index_root_item_range
+            ));
+            self.leaf_item_ranges = Some(leaf_item_ranges);
+        }
+    }
+}
+
+impl<B> FusedIterator for SubKeyNodesWithPrefix<'_, '_, B> where B: SplitByteSlice {}
+
 /// Iterator over
 ///   all subkeys of a [`KeyNode`],
 ///   returning a mutable [`KeyNode`] for each subkey.
@@ -208,11 +452,13 @@ impl<B> FusedIterator for SubKeyNodes<'_, B> where B: SplitByteSlice {}
 /// Refer to them for a more technical documentation.
 ///
 /// On-Disk Signatures: `lf`, `lh`, `li`, `ri`
+#[cfg(feature = "write")]
 pub(crate) enum SubKeyNodesMut<'h, B: SplitByteSliceMut> {
     IndexRoot(IndexRootKeyNodesMut<'h, B>),
     Leaf(LeafKeyNodesMut<'h, B>),
 }
 
+#[cfg(feature = "write")]
 impl<'h, B> SubKeyNodesMut<'h, B>
 where
     B: SplitByteSliceMut,
@@ -223,26 +469,39 @@ where
         let signature = header.signature;
         let count = header.count.get();
         let count_field_offset = subkeys_list.hive.offset_of_field(&header.count);
-        let data_range = subkeys_list.data_range;
+        let data_range = subkeys_list.data_range.clone();
+        let snapshot = SubkeysListSnapshot::new(&subkeys_list);
 
         match &signature {
             b"lf" | b"lh" | b"li" => {
                 // Fast Leaf, Hash Leaf or Index Leaf
                 let leaf_type = LeafType::from_signature(&signature).unwrap();
-                let iter =
-                    LeafKeyNodesMut::new(hive, count, count_field_offset, data_range, leaf_type)?;
+                let iter = LeafKeyNodesMut::new(
+                    hive,
+                    count,
+                    count_field_offset,
+                    data_range,
+                    leaf_type,
+                    snapshot,
+                )?;
                 Ok(Self::Leaf(iter))
             }
             b"ri" => {
                 // Index Root
-                let iter = IndexRootKeyNodesMut::new(hive, count, count_field_offset, data_range)?;
+                let iter = IndexRootKeyNodesMut::new(
+                    hive,
+                    count,
+                    count_field_offset,
+                    data_range,
+                    snapshot,
+                )?;
                 Ok(Self::IndexRoot(iter))
             }
             _ => unreachable!(),
         }
     }
 
-    pub fn next(&mut self) -> Option<Result<KeyNodeMut<B>>> {
+    pub fn next(&mut self) -> Option<Result<KeyNodeMut<'_, B>>> {
         match self {
             Self::IndexRoot(iter) => iter.next(),
             Self::Leaf(iter) => iter.next(),
@@ -0,0 +1,180 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+use zerocopy::SplitByteSlice;
+
+use crate::error::Result;
+use crate::key_node::KeyNode;
+use crate::walker::KeyNodeWalker;
+
+/// A key along with its last write timestamp, as returned by [`KeyTimeline`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimelineEntry {
+    /// Absolute path of the key, starting with a backslash (see [`KeyNode::full_path`]).
+    pub path: String,
+    /// Last write timestamp of the key as a raw Windows FILETIME (see [`KeyNode::timestamp`]).
+    pub timestamp: u64,
+}
+
+/// Iterator over
+///   a [`KeyNode`] and all keys below it in depth-first order,
+///   returning a [`TimelineEntry`] for each key.
+///
+/// Created by [`KeyNode::timeline`].
+pub struct KeyTimeline<'h, B: SplitByteSlice> {
+    start_key_node: Option<KeyNode<'h, B>>,
+    start_path: String,
+    walker: KeyNodeWalker<'h, B>,
+}
+
+impl<'h, B> KeyTimeline<'h, B>
+where
+    B: SplitByteSlice,
+{
+    fn new(key_node: &KeyNode<'h, B>) -> Result<Self> {
+        let start_path = key_node.full_path()?;
+        let walker = KeyNodeWalker::new(key_node)?;
+
+        Ok(Self {
+            start_key_node: Some(key_node.clone()),
+            start_path,
+            walker,
+        })
+    }
+}
+
+impl<B> Iterator for KeyTimeline<'_, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<TimelineEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(start_key_node) = self.start_key_node.take() {
+            return Some(Ok(TimelineEntry {
+                path: self.start_path.clone(),
+                timestamp: start_key_node.timestamp(),
+            }));
+        }
+
+        let key_node = iter_try!(self.walker.next()?);
+
+        // The full path of the root key already ends with a backslash.
+        let mut path = self.start_path.clone();
+        if !path.ends_with('\\') {
+            path.push('\\');
+        }
+        path.push_str(&self.walker.path_string());
+
+        Some(Ok(TimelineEntry {
+            path,
+            timestamp: key_node.timestamp(),
+        }))
+    }
+}
+
+impl<B> FusedIterator for KeyTimeline<'_, B> where B: SplitByteSlice {}
+
+impl<'h, B> KeyNode<'h, B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns an iterator over this key and all keys below it, along with their last write timestamps.
+    ///
+    /// Keys are visited lazily in depth-first order, starting with this key.
+    /// This is the basis for building a registry timeline.
+    /// Use [`KeyNode::timeline_sorted`] to get all keys sorted by their timestamps instead.
+    pub fn timeline(&self) -> Result<KeyTimeline<'h, B>> {
+        KeyTimeline::new(self)
+    }
+
+    /// Returns this key and all keys below it, sorted by their last write timestamps (oldest first).
+    ///
+    /// Keys with the same timestamp are returned in depth-first order.
+    pub fn timeline_sorted(&self) -> Result<Vec<TimelineEntry>> {
+        let mut entries = self.timeline()?.collect::<Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.timestamp);
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::key_node_field;
+    use crate::key_node::KeyNodeField;
+    use crate::*;
+
+    #[test]
+    fn test_timeline() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        let entries = root_key_node
+            .timeline()
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(entries.len(), 528);
+        assert_eq!(entries[0].path, "\\");
+        assert_eq!(entries[0].timestamp, root_key_node.timestamp());
+        assert_eq!(entries[1].path, "\\big-data-test");
+
+        // The timeline of a subkey only covers that subkey and the keys below it.
+        let key_node = root_key_node
+            .subpath("subpath-test\\with-two-levels-of-subkeys")
+            .unwrap()
+            .unwrap();
+        let paths = key_node
+            .timeline()
+            .unwrap()
+            .map(|entry| entry.unwrap().path)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            paths,
+            [
+                "\\subpath-test\\with-two-levels-of-subkeys",
+                "\\subpath-test\\with-two-levels-of-subkeys\\subkey1",
+                "\\subpath-test\\with-two-levels-of-subkeys\\subkey1\\subkey2",
+            ]
+        );
+
+        // Make "subkey2" the oldest and "subkey1" the newest key.
+        let timestamp_field = |key_node: KeyNode<&[u8]>| {
+            key_node_field(key_node.cell_offset(), KeyNodeField::Timestamp)
+        };
+        let subkey1_field = timestamp_field(key_node.subkey("subkey1").unwrap().unwrap());
+        let subkey2_field = timestamp_field(key_node.subpath("subkey1\\subkey2").unwrap().unwrap());
+        testhive[subkey1_field..subkey1_field + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        testhive[subkey2_field..subkey2_field + 8].copy_from_slice(&0u64.to_le_bytes());
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node
+            .subpath("subpath-test\\with-two-levels-of-subkeys")
+            .unwrap()
+            .unwrap();
+        let entries = key_node.timeline_sorted().unwrap();
+        assert_eq!(
+            entries,
+            [
+                TimelineEntry {
+                    path: "\\subpath-test\\with-two-levels-of-subkeys\\subkey1\\subkey2".to_owned(),
+                    timestamp: 0,
+                },
+                TimelineEntry {
+                    path: "\\subpath-test\\with-two-levels-of-subkeys".to_owned(),
+                    timestamp: key_node.timestamp(),
+                },
+                TimelineEntry {
+                    path: "\\subpath-test\\with-two-levels-of-subkeys\\subkey1".to_owned(),
+                    timestamp: u64::MAX,
+                },
+            ]
+        );
+    }
+}
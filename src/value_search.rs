@@ -0,0 +1,206 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::string::String;
+use core::iter::FusedIterator;
+
+use zerocopy::SplitByteSlice;
+
+use crate::error::Result;
+use crate::hive::Hive;
+use crate::key_node::KeyNode;
+use crate::key_values_list::KeyValues;
+use crate::walker::KeyNodeWalker;
+
+/// A Key Value whose data contains the searched bytes, as returned by [`ValueDataMatches`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValueDataMatch {
+    /// Path of the key containing the Key Value (without the root key name).
+    pub key_path: String,
+    /// Name of the Key Value.
+    pub value_name: String,
+}
+
+/// Iterator over
+///   all Key Values of a hive whose data contains a given byte sequence,
+///   returning a [`ValueDataMatch`] for each Key Value.
+///
+/// Created by [`Hive::search_value_data`].
+pub struct ValueDataMatches<'h, 'n, B: SplitByteSlice> {
+    needle: &'n [u8],
+    root_key_node: Option<KeyNode<'h, B>>,
+    walker: KeyNodeWalker<'h, B>,
+    current: Option<(String, KeyValues<'h, B>)>,
+}
+
+impl<'h, 'n, B> ValueDataMatches<'h, 'n, B>
+where
+    B: SplitByteSlice,
+{
+    fn new(hive: &'h Hive<B>, needle: &'n [u8]) -> Result<Self> {
+        let root_key_node = hive.root_key_node()?;
+        let walker = KeyNodeWalker::new(&root_key_node)?;
+
+        Ok(Self {
+            needle,
+            root_key_node: Some(root_key_node),
+            walker,
+            current: None,
+        })
+    }
+
+    /// Moves on to the Key Values of the next key.
+    /// Returns `None` when all keys have been visited.
+    fn next_key(&mut self) -> Option<Result<()>> {
+        let (key_node, key_path) = match self.root_key_node.take() {
+            Some(root_key_node) => (root_key_node, String::new()),
+            None => {
+                let key_node = iter_try!(self.walker.next()?);
+                (key_node, self.walker.path_string())
+            }
+        };
+
+        if let Some(key_values) = key_node.values() {
+            let key_values = iter_try!(key_values);
+            self.current = Some((key_path, key_values));
+        }
+
+        Some(Ok(()))
+    }
+}
+
+impl<B> Iterator for ValueDataMatches<'_, '_, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<ValueDataMatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some((key_path, key_values)) = &mut self.current else {
+                iter_try!(self.next_key()?);
+                continue;
+            };
+
+            let Some(key_value) = key_values.next() else {
+                self.current = None;
+                continue;
+            };
+
+            let key_value = iter_try!(key_value);
+            let data = iter_try!(key_value.data());
+            if !iter_try!(data.contains_bytes(self.needle)) {
+                continue;
+            }
+
+            let value_name = iter_try!(key_value.name()).to_string_lossy();
+            return Some(Ok(ValueDataMatch {
+                key_path: key_path.clone(),
+                value_name,
+            }));
+        }
+    }
+}
+
+impl<B> FusedIterator for ValueDataMatches<'_, '_, B> where B: SplitByteSlice {}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns an iterator over all Key Values whose data contains `needle`.
+    ///
+    /// Keys are visited in depth-first order, starting with the root key.
+    /// Matches are found lazily while iterating.
+    /// Big Data is searched segment by segment without copying the entire data,
+    /// so matches spanning segment boundaries are found as well.
+    pub fn search_value_data<'n>(&self, needle: &'n [u8]) -> Result<ValueDataMatches<'_, 'n, B>> {
+        ValueDataMatches::new(self, needle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn search(hive: &Hive<&[u8]>, needle: &[u8]) -> Vec<(String, String)> {
+        hive.search_value_data(needle)
+            .unwrap()
+            .map(|value_match| {
+                let value_match = value_match.unwrap();
+                (value_match.key_path, value_match.value_name)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_search_value_data() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+
+        // "sz-test" in UTF-16LE.
+        let needle = "sz-test"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<u8>>();
+        let matches = search(&hive, &needle);
+        assert_eq!(
+            matches,
+            [
+                ("data-test".to_owned(), "reg-sz".to_owned()),
+                (
+                    "data-test".to_owned(),
+                    "reg-sz-with-terminating-nul".to_owned()
+                ),
+                ("data-test".to_owned(), "reg-expand-sz".to_owned()),
+                ("data-test".to_owned(), "reg-multi-sz".to_owned()),
+            ]
+        );
+
+        // Big Data is searched as well.
+        let needle = "9012345678901234567890"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<u8>>();
+        let matches = search(&hive, &needle);
+        assert_eq!(
+            matches,
+            [("data-test".to_owned(), "reg-multi-sz-big".to_owned())]
+        );
+
+        // Place a marker that only exists across the boundary of the first two Big Data segments.
+        let segment_offsets = {
+            let root_key_node = hive.root_key_node().unwrap();
+            let key_node = root_key_node.subkey("data-test").unwrap().unwrap();
+            let key_value = key_node.value("reg-multi-sz-big").unwrap().unwrap();
+            let KeyValueData::Big(iter) = key_value.data().unwrap() else {
+                panic!("reg-multi-sz-big must be Big Data");
+            };
+
+            iter.map(|slice_data| {
+                let slice_data = slice_data.unwrap();
+                let start = slice_data.as_ptr() as usize - testhive.as_ptr() as usize;
+                start..start + slice_data.len()
+            })
+            .collect::<Vec<_>>()
+        };
+        let mut testhive = testhive.clone();
+        let boundary = segment_offsets[0].end;
+        testhive[boundary - 3..boundary].copy_from_slice(b"MAR");
+        let boundary = segment_offsets[1].start;
+        testhive[boundary..boundary + 3].copy_from_slice(b"KER");
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let matches = search(&hive, b"MARKER");
+        assert_eq!(
+            matches,
+            [("data-test".to_owned(), "reg-multi-sz-big".to_owned())]
+        );
+
+        // The DWORD 42 in little-endian.
+        let matches = search(&hive, &42u32.to_le_bytes());
+        assert!(matches.contains(&("data-test".to_owned(), "dword".to_owned())));
+
+        assert!(search(&hive, b"this is nowhere in the hive").is_empty());
+    }
+}
@@ -0,0 +1,427 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::vec::Vec;
+
+use zerocopy::SplitByteSlice;
+
+use crate::error::{NtHiveError, Result};
+use crate::hive::Hive;
+use crate::key_node::KeyNode;
+use crate::key_security::SecurityReferenceMismatch;
+use crate::key_value::{KeyValue, KeyValueData};
+use crate::list_capacity::ListCapacity;
+use crate::walker::KeyNodeWalker;
+
+/// A single problem found by [`Hive::verify`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VerifyFinding {
+    /// A structure is corrupted, making some data of the hive inaccessible.
+    Fatal(NtHiveError),
+    /// The number of subkeys stored in the header of the key at `offset` doesn't match the number of
+    /// subkeys in its Subkeys List.
+    SubkeyCountMismatch {
+        offset: usize,
+        expected: u32,
+        actual: usize,
+    },
+    /// The Key Values List or Subkeys List of the key at `offset` is much larger than required and may
+    /// contain stale items (see [`ListCapacity::is_oversized`]).
+    OversizedList {
+        offset: usize,
+        capacity: ListCapacity,
+    },
+    /// The reference count of a Key Security cell doesn't match the number of keys referencing it.
+    SecurityReferenceMismatch(SecurityReferenceMismatch),
+}
+
+impl VerifyFinding {
+    /// Returns `true` if this finding is a corruption that makes data inaccessible,
+    /// and `false` if it is just a warning about an inconsistency.
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Self::Fatal(_))
+    }
+}
+
+impl From<NtHiveError> for VerifyFinding {
+    fn from(error: NtHiveError) -> Self {
+        Self::Fatal(error)
+    }
+}
+
+/// All problems found by [`Hive::verify`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VerifyReport {
+    /// All findings in the order they have been found.
+    pub findings: Vec<VerifyFinding>,
+}
+
+impl VerifyReport {
+    /// Returns `true` if no problems have been found.
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Returns an iterator over all fatal findings.
+    pub fn fatal(&self) -> impl Iterator<Item = &VerifyFinding> {
+        self.findings.iter().filter(|finding| finding.is_fatal())
+    }
+
+    /// Returns an iterator over all findings that are just warnings.
+    pub fn warnings(&self) -> impl Iterator<Item = &VerifyFinding> {
+        self.findings.iter().filter(|finding| !finding.is_fatal())
+    }
+
+    fn check_key_node<B>(&mut self, key_node: &KeyNode<B>)
+    where
+        B: SplitByteSlice,
+    {
+        let offset = key_node.header_offset();
+
+        if let Err(e) = key_node.name() {
+            self.findings.push(e.into());
+        }
+
+        if let Some(Err(e)) = key_node.class_name_raw() {
+            self.findings.push(e.into());
+        }
+
+        if let Some(Err(e)) = key_node.key_security() {
+            self.findings.push(e.into());
+        }
+
+        // Errors of the subkeys themselves are found when visiting them, so just compare the counts here.
+        match key_node.subkeys() {
+            Some(Ok(subkeys)) => {
+                let expected = key_node.subkey_count();
+                let actual = subkeys.count();
+                if expected as usize != actual {
+                    self.findings.push(VerifyFinding::SubkeyCountMismatch {
+                        offset,
+                        expected,
+                        actual,
+                    });
+                }
+            }
+            Some(Err(_)) => {
+                // Already reported while traversing the keys.
+            }
+            None => {
+                if key_node.subkey_count() != 0 {
+                    self.findings.push(VerifyFinding::SubkeyCountMismatch {
+                        offset,
+                        expected: key_node.subkey_count(),
+                        actual: 0,
+                    });
+                }
+            }
+        }
+
+        if let Some(Ok(capacity)) = key_node.subkeys_list_capacity() {
+            self.check_list_capacity(offset, capacity);
+        }
+
+        match key_node.values_list_capacity() {
+            Some(Ok(capacity)) => self.check_list_capacity(offset, capacity),
+            Some(Err(e)) => self.findings.push(e.into()),
+            None => (),
+        }
+
+        if let Some(Ok(key_values)) = key_node.values() {
+            for key_value in key_values {
+                match key_value {
+                    Ok(key_value) => self.check_key_value(&key_value),
+                    Err(e) => self.findings.push(e.into()),
+                }
+            }
+        }
+    }
+
+    fn check_key_value<B>(&mut self, key_value: &KeyValue<B>)
+    where
+        B: SplitByteSlice,
+    {
+        if let Err(e) = key_value.name() {
+            self.findings.push(e.into());
+        }
+
+        match key_value.data() {
+            Ok(KeyValueData::Small(_)) => (),
+            Ok(KeyValueData::Big(slices)) => {
+                // Stop at the first broken segment, as all following ones are usually broken as well.
+                if let Some(Err(e)) = slices.into_iter().find(|slice| slice.is_err()) {
+                    self.findings.push(e.into());
+                }
+            }
+            Err(e) => self.findings.push(e.into()),
+        }
+    }
+
+    fn check_list_capacity(&mut self, offset: usize, capacity: ListCapacity) {
+        if capacity.is_oversized() {
+            self.findings
+                .push(VerifyFinding::OversizedList { offset, capacity });
+        }
+    }
+}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Checks the entire hive for structural problems.
+    ///
+    /// In contrast to the lazy checks performed while accessing keys and values, this visits every key
+    /// and value reachable from the root key and collects all problems instead of stopping at the first one.
+    /// A broken Subkeys List or a key referenced more than once only skips the affected subkeys,
+    /// while the values of the key and all other keys are still checked.
+    /// It checks that the Hive Bins form a contiguous chain (see [`Hive::validate_bins`]),
+    /// that all referenced cells are allocated and have the right signatures, that all lists
+    /// fit into their cells, and that all Big Data segments can be read.
+    /// Inconsistencies that don't make any data inaccessible (like mismatching counts) are reported as
+    /// warnings (see [`VerifyFinding::is_fatal`]).
+    ///
+    /// If the check is cancelled (see [`Hive::set_cancel_token`]), the report ends with a fatal
+    /// [`NtHiveError::Cancelled`] finding and contains everything found so far.
+    pub fn verify(&self) -> VerifyReport {
+        let mut report = VerifyReport::default();
+
+        if let Err(e) = self.validate() {
+            report.findings.push(e.into());
+        }
+
+        if let Err(e) = self.validate_bins() {
+            let cancelled = e == NtHiveError::Cancelled;
+            report.findings.push(e.into());
+
+            if cancelled {
+                return report;
+            }
+        }
+
+        let root_key_node = match self.root_key_node() {
+            Ok(root_key_node) => root_key_node,
+            Err(e) => {
+                report.findings.push(e.into());
+                return report;
+            }
+        };
+
+        if let Err(e) = self.verify_key_tree(root_key_node, &mut report) {
+            report.findings.push(e.into());
+            return report;
+        }
+
+        // Reference counts can only be verified if all keys could be visited.
+        if report.fatal().next().is_some() {
+            return report;
+        }
+
+        match self.verify_security_references() {
+            Ok(mismatches) => report.findings.extend(
+                mismatches
+                    .into_iter()
+                    .map(VerifyFinding::SecurityReferenceMismatch),
+            ),
+            Err(e) => report.findings.push(e.into()),
+        }
+
+        report
+    }
+
+    /// Checks all keys below and including `root_key_node` in depth-first order.
+    ///
+    /// Unlike [`KeyNode::descendants`], this doesn't stop at the first error, but records it and continues
+    /// with the next key that can be reached.
+    /// Keys are visited via the shared [`KeyNodeWalker`], so repeated keys, the maximum key depth and
+    /// cancellation are handled the same way as for all other walks.
+    /// Only returns an error if the check has been cancelled.
+    fn verify_key_tree(&self, root_key_node: KeyNode<B>, report: &mut VerifyReport) -> Result<()> {
+        report.check_key_node(&root_key_node);
+
+        let walker = match KeyNodeWalker::new(&root_key_node) {
+            Ok(walker) => walker,
+            Err(e) => {
+                report.findings.push(e.into());
+                return Ok(());
+            }
+        };
+
+        for key_node in walker {
+            match key_node {
+                Ok(key_node) => report.check_key_node(&key_node),
+                Err(NtHiveError::Cancelled) => return Err(NtHiveError::Cancelled),
+                Err(e) => report.findings.push(e.into()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::{cell_data, key_node_field, read_u32};
+    use crate::key_node::KeyNodeField;
+    use crate::*;
+
+    fn key_node_offset(testhive: &[u8], path: &str) -> u32 {
+        let hive = Hive::new(testhive).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node.subpath(path).unwrap().unwrap();
+        key_node.cell_offset()
+    }
+
+    /// Returns the offset of the data of the cell referenced by the offset field at `field`.
+    fn referenced_cell_data(testhive: &[u8], field: usize) -> usize {
+        cell_data(read_u32(testhive, field))
+    }
+
+    #[test]
+    fn test_verify() {
+        let testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let report = hive.verify();
+        assert!(report.is_clean(), "{:?}", report.findings);
+
+        // A value with a broken signature is fatal, but the other values are still checked.
+        let mut corrupted = testhive.clone();
+        let data_test = key_node_offset(&testhive, "data-test");
+        let values_list = referenced_cell_data(
+            &testhive,
+            key_node_field(data_test, KeyNodeField::KeyValuesListOffset),
+        );
+        let first_value = referenced_cell_data(&testhive, values_list + 4);
+        corrupted[first_value..first_value + 2].copy_from_slice(b"xx");
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        let report = hive.verify();
+        assert_eq!(report.findings.len(), 1);
+        assert!(matches!(
+            report.findings[0],
+            VerifyFinding::Fatal(NtHiveError::InvalidTwoByteSignature { offset, .. })
+                if offset == first_value
+        ));
+
+        // A wrong subkey count is just a warning.
+        let mut corrupted = testhive.clone();
+        let subpath_test = key_node_offset(&testhive, "subpath-test");
+        let subkey_count_field = key_node_field(subpath_test, KeyNodeField::SubkeyCount);
+        corrupted[subkey_count_field..subkey_count_field + 4].copy_from_slice(&4u32.to_le_bytes());
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        let report = hive.verify();
+        assert_eq!(
+            report.findings,
+            [VerifyFinding::SubkeyCountMismatch {
+                offset: cell_data(subpath_test),
+                expected: 4,
+                actual: 3,
+            }]
+        );
+        assert_eq!(report.fatal().count(), 0);
+        assert_eq!(report.warnings().count(), 1);
+
+        // So is a shrunk Key Values List.
+        let mut corrupted = testhive.clone();
+        let values_count_field = key_node_field(data_test, KeyNodeField::KeyValuesCount);
+        corrupted[values_count_field..values_count_field + 4].copy_from_slice(&1u32.to_le_bytes());
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        let report = hive.verify();
+        assert!(matches!(
+            report.findings[..],
+            [VerifyFinding::OversizedList { offset, capacity }]
+                if offset == cell_data(data_test) && capacity.excess_capacity() == 32
+        ));
+
+        // Unallocated and unreadable cells are fatal and reported for every key.
+        let mut corrupted = testhive.clone();
+        let big_data_test = key_node_offset(&testhive, "big-data-test");
+        let subpath_test_subkeys_list = referenced_cell_data(
+            &testhive,
+            key_node_field(subpath_test, KeyNodeField::SubkeysListOffset),
+        ) - 4;
+        let cell_size = i32::from_le_bytes(
+            corrupted[subpath_test_subkeys_list..subpath_test_subkeys_list + 4]
+                .try_into()
+                .unwrap(),
+        );
+        corrupted[subpath_test_subkeys_list..subpath_test_subkeys_list + 4]
+            .copy_from_slice(&(-cell_size).to_le_bytes());
+        let values_list_field = key_node_field(big_data_test, KeyNodeField::KeyValuesListOffset);
+        corrupted[values_list_field..values_list_field + 4]
+            .copy_from_slice(&0x7fff_fff0u32.to_le_bytes());
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        let report = hive.verify();
+        assert_eq!(report.warnings().count(), 0);
+        assert_eq!(report.fatal().count(), 2);
+        assert!(report.findings.iter().any(|finding| matches!(
+            finding,
+            VerifyFinding::Fatal(NtHiveError::UnallocatedCell { offset, .. })
+                if *offset == subpath_test_subkeys_list
+        )));
+        assert!(report.findings.iter().any(|finding| matches!(
+            finding,
+            VerifyFinding::Fatal(NtHiveError::InvalidHeaderSize { offset, .. })
+                if *offset == 4096 + 0x7fff_fff0
+        )));
+
+        // The values of a key are still checked if its Subkeys List is broken.
+        let mut corrupted = testhive.clone();
+        corrupted[first_value..first_value + 2].copy_from_slice(b"xx");
+        let subkey_count_field = key_node_field(data_test, KeyNodeField::SubkeyCount);
+        corrupted[subkey_count_field..subkey_count_field + 4].copy_from_slice(&1u32.to_le_bytes());
+        let subkeys_list_field = key_node_field(data_test, KeyNodeField::SubkeysListOffset);
+        corrupted[subkeys_list_field..subkeys_list_field + 4]
+            .copy_from_slice(&0x7fff_fff0u32.to_le_bytes());
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        let report = hive.verify();
+        assert_eq!(report.fatal().count(), 2, "{:?}", report.findings);
+        assert!(report.findings.iter().any(|finding| matches!(
+            finding,
+            VerifyFinding::Fatal(NtHiveError::InvalidHeaderSize { offset, .. })
+                if *offset == 4096 + 0x7fff_fff0
+        )));
+        assert!(report.findings.iter().any(|finding| matches!(
+            finding,
+            VerifyFinding::Fatal(NtHiveError::InvalidTwoByteSignature { offset, .. })
+                if *offset == first_value
+        )));
+
+        // A key referenced twice is reported, but doesn't stop checking the other keys.
+        // Let "data-test" reference all subkeys of the root key, including itself.
+        let mut corrupted = testhive.clone();
+        let root_cell_offset = hive.root_key_node().unwrap().cell_offset();
+        let subkeys_fields = |cell_offset| {
+            key_node_field(cell_offset, KeyNodeField::SubkeyCount)
+                ..key_node_field(cell_offset, KeyNodeField::VolatileSubkeysListOffset)
+        };
+        corrupted.copy_within(
+            subkeys_fields(root_cell_offset),
+            subkeys_fields(data_test).start,
+        );
+        corrupted[first_value..first_value + 2].copy_from_slice(b"xx");
+        let hive = Hive::new(corrupted.as_ref()).unwrap();
+        let report = hive.verify();
+        let root_key_node = hive.root_key_node().unwrap();
+        let repeated_offsets = report
+            .findings
+            .iter()
+            .filter_map(|finding| match finding {
+                VerifyFinding::Fatal(NtHiveError::RepeatedKeyNode { offset }) => Some(*offset),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let root_subkey_offsets = root_key_node
+            .subkeys()
+            .unwrap()
+            .unwrap()
+            .map(|subkey| cell_data(subkey.unwrap().cell_offset()))
+            .collect::<Vec<_>>();
+        assert_eq!(repeated_offsets, root_subkey_offsets);
+        assert!(report.findings.iter().any(|finding| matches!(
+            finding,
+            VerifyFinding::Fatal(NtHiveError::InvalidTwoByteSignature { offset, .. })
+                if *offset == first_value
+        )));
+        assert_eq!(report.fatal().count(), root_subkey_offsets.len() + 1);
+    }
+}
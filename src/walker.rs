@@ -0,0 +1,542 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+use zerocopy::SplitByteSlice;
+
+use crate::cancel::CANCEL_CHECK_INTERVAL;
+use crate::error::{NtHiveError, Result};
+use crate::hive::Hive;
+use crate::key_node::KeyNode;
+use crate::key_value::KeyValue;
+use crate::key_values_list::KeyValues;
+use crate::string::NtHiveNameString;
+use crate::subkeys_list::SubKeyNodes;
+
+/// Maximum nesting depth of keys, as enforced by Windows.
+/// Anything deeper can only come from a corrupted hive (e.g. a subkeys list referencing an ancestor).
+pub(crate) const MAX_KEY_DEPTH: usize = 512;
+
+/// Joins the given key names to a path, with the names separated by backslashes.
+///
+/// Names that aren't valid UTF-16 are converted lossily.
+pub(crate) fn join_path(names: &[NtHiveNameString]) -> String {
+    names
+        .iter()
+        .map(|name| name.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("\\")
+}
+
+/// Order in which the values and subkeys of a key are visited by [`WalkEvents`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TraversalOrder {
+    /// Visit all values of a key before descending into its subkeys.
+    ///
+    /// This is the order of `.reg` files and of `Hive::export_bodyfile`.
+    #[default]
+    ValuesFirst,
+    /// Visit all subkeys of a key (including everything below them) before its values.
+    SubkeysFirst,
+}
+
+/// A single step of a depth-first walk over keys and values, as returned by [`WalkEvents`].
+pub enum WalkEvent<'h, B: SplitByteSlice> {
+    /// A key is entered.
+    ///
+    /// All following [`WalkEvent::Value`] events up to the matching [`WalkEvent::LeaveKey`] belong to this key
+    /// or its subkeys.
+    EnterKey(KeyNode<'h, B>),
+    /// A value of the innermost key that has been entered, but not left yet.
+    Value(KeyValue<'h, B>),
+    /// All values and subkeys of a key have been visited.
+    LeaveKey(KeyNode<'h, B>),
+}
+
+/// A key that has been entered by [`WalkEvents`], along with the progress of visiting its values and subkeys.
+struct WalkFrame<'h, B: SplitByteSlice> {
+    key_node: KeyNode<'h, B>,
+    subkeys: Option<SubKeyNodes<'h, B>>,
+    /// `true` if the Subkeys List of this key still needs to be looked up.
+    subkeys_pending: bool,
+    values: Option<KeyValues<'h, B>>,
+    /// `true` if the Key Values List of this key still needs to be looked up.
+    values_pending: bool,
+}
+
+/// Iterator over
+///   all keys and values below (and including) a [`KeyNode`] in depth-first order,
+///   returning a [`WalkEvent`] for each step.
+///
+/// Every key is announced by [`WalkEvent::EnterKey`] and finished by [`WalkEvent::LeaveKey`].
+/// In between, its values and subkeys are visited in the given [`TraversalOrder`].
+/// Consumers can therefore assemble any output format without looking up a key twice.
+///
+/// The traversal state is kept on the heap, and the names of all keys on the current path are
+/// available via [`WalkEvents::path`].
+/// A key referenced more than once (e.g. a subkeys list referencing an ancestor in a corrupted hive)
+/// is returned as [`NtHiveError::RepeatedKeyNode`] and not entered again, so the walk always terminates.
+/// A key with a corrupted Subkeys List is still entered and its values are visited,
+/// but the error is returned in place of its subkeys.
+/// The [`CancelToken`] of the hive is checked every 64 keys.
+/// All other iterators walking keys are built on top of this one.
+///
+/// Created by [`KeyNode::walk_events`] and [`Hive::walk_events`].
+///
+/// [`CancelToken`]: crate::cancel::CancelToken
+pub struct WalkEvents<'h, B: SplitByteSlice> {
+    hive: &'h Hive<B>,
+    order: TraversalOrder,
+    include_values: bool,
+    stack: Vec<WalkFrame<'h, B>>,
+    names: Vec<NtHiveNameString<'h>>,
+    /// `true` if [`WalkEvent::EnterKey`] still needs to be returned for the start key.
+    start_pending: bool,
+    visited_keys: usize,
+    /// Cell offsets of all keys that have been entered so far.
+    visited_offsets: BTreeSet<u32>,
+}
+
+impl<'h, B> WalkEvents<'h, B>
+where
+    B: SplitByteSlice,
+{
+    /// Creates a walker starting at `key_node`.
+    ///
+    /// If `include_values` is `false`, no [`WalkEvent::Value`] events are returned and no Key Values List
+    /// is ever looked up.
+    pub(crate) fn new(
+        key_node: &KeyNode<'h, B>,
+        order: TraversalOrder,
+        include_values: bool,
+    ) -> Result<Self> {
+        let subkeys = key_node.subkeys().transpose()?;
+        let stack = Vec::from([WalkFrame {
+            key_node: key_node.clone(),
+            subkeys,
+            subkeys_pending: false,
+            values: None,
+            values_pending: include_values,
+        }]);
+
+        Ok(Self {
+            hive: key_node.hive(),
+            order,
+            include_values,
+            stack,
+            names: Vec::new(),
+            start_pending: true,
+            visited_keys: 0,
+            visited_offsets: BTreeSet::from([key_node.cell_offset()]),
+        })
+    }
+
+    /// Returns the names of all keys from below the start key up to (and including) the innermost key
+    /// that has been entered, but not left yet.
+    ///
+    /// This is empty while visiting the start key itself.
+    pub fn path(&self) -> &[NtHiveNameString<'h>] {
+        &self.names
+    }
+
+    fn next_subkey(&mut self) -> Option<Result<WalkEvent<'h, B>>> {
+        let depth = self.stack.len();
+        let frame = self.stack.last_mut()?;
+
+        if frame.subkeys_pending {
+            // A key with a broken Subkeys List has still been entered, so its values can be visited.
+            frame.subkeys_pending = false;
+            frame.subkeys = iter_try!(frame.key_node.subkeys().transpose());
+
+            if frame.subkeys.is_some() && depth > MAX_KEY_DEPTH {
+                frame.subkeys = None;
+                return Some(Err(NtHiveError::MaximumKeyDepthExceeded {
+                    offset: frame.key_node.header_offset(),
+                    max_depth: MAX_KEY_DEPTH,
+                }));
+            }
+        }
+
+        frame.subkeys.as_ref()?;
+
+        if self.visited_keys % CANCEL_CHECK_INTERVAL == 0 {
+            if let Err(e) = self.hive.check_cancelled() {
+                // Make sure that nothing is returned after the cancellation.
+                self.stack.clear();
+                self.names.clear();
+                return Some(Err(e));
+            }
+        }
+        self.visited_keys += 1;
+
+        let frame = self.stack.last_mut()?;
+        let Some(key_node) = frame.subkeys.as_mut()?.next() else {
+            frame.subkeys = None;
+            return None;
+        };
+        let key_node = iter_try!(key_node);
+
+        if !self.visited_offsets.insert(key_node.cell_offset()) {
+            // Entering this key again could repeat a cycle forever.
+            return Some(Err(NtHiveError::RepeatedKeyNode {
+                offset: key_node.header_offset(),
+            }));
+        }
+
+        let name = iter_try!(key_node.name());
+
+        self.names.push(name);
+        self.stack.push(WalkFrame {
+            key_node: key_node.clone(),
+            subkeys: None,
+            subkeys_pending: true,
+            values: None,
+            values_pending: self.include_values,
+        });
+
+        Some(Ok(WalkEvent::EnterKey(key_node)))
+    }
+
+    fn next_value(&mut self) -> Option<Result<WalkEvent<'h, B>>> {
+        let frame = self.stack.last_mut()?;
+
+        if frame.values_pending {
+            frame.values_pending = false;
+            frame.values = iter_try!(frame.key_node.values().transpose());
+        }
+
+        let values = frame.values.as_mut()?;
+        let Some(key_value) = values.next() else {
+            frame.values = None;
+            return None;
+        };
+
+        Some(key_value.map(WalkEvent::Value))
+    }
+}
+
+impl<'h, B> Iterator for WalkEvents<'h, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<WalkEvent<'h, B>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start_pending {
+            self.start_pending = false;
+            let key_node = self.stack.first()?.key_node.clone();
+            return Some(Ok(WalkEvent::EnterKey(key_node)));
+        }
+
+        let event = match self.order {
+            TraversalOrder::ValuesFirst => self.next_value().or_else(|| self.next_subkey()),
+            TraversalOrder::SubkeysFirst => self.next_subkey().or_else(|| self.next_value()),
+        };
+        if event.is_some() {
+            return event;
+        }
+
+        // All values and subkeys of the innermost key have been visited, so continue one level up.
+        let frame = self.stack.pop()?;
+        self.names.truncate(self.stack.len().saturating_sub(1));
+        Some(Ok(WalkEvent::LeaveKey(frame.key_node)))
+    }
+}
+
+impl<B> FusedIterator for WalkEvents<'_, B> where B: SplitByteSlice {}
+
+impl<'h, B> KeyNode<'h, B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns an iterator over [`WalkEvent`]s for this key, all keys below it, and all their values.
+    ///
+    /// The first event enters this key and the last one leaves it.
+    /// Returns an error if the Subkeys List of this key is corrupted.
+    pub fn walk_events(&self, order: TraversalOrder) -> Result<WalkEvents<'h, B>> {
+        WalkEvents::new(self, order, true)
+    }
+}
+
+impl<B> Hive<B>
+where
+    B: SplitByteSlice,
+{
+    /// Returns an iterator over [`WalkEvent`]s for all keys and values of this hive, starting at the root key.
+    ///
+    /// See [`KeyNode::walk_events`].
+    pub fn walk_events(&self, order: TraversalOrder) -> Result<WalkEvents<'_, B>> {
+        self.root_key_node()?.walk_events(order)
+    }
+}
+
+/// Iterator over
+///   all keys below a [`KeyNode`] in depth-first order,
+///   returning a constant [`KeyNode`] for each key.
+///
+/// This is a [`WalkEvents`] iterator without values that only returns the entered keys (except the start key).
+/// Callers interested in paths therefore don't need to look up any name twice.
+///
+/// The [`CancelToken`] of the hive is checked every [`CANCEL_CHECK_INTERVAL`] keys, so every operation
+/// built on top of this walker can be cancelled.
+///
+/// [`CancelToken`]: crate::cancel::CancelToken
+pub(crate) struct KeyNodeWalker<'h, B: SplitByteSlice> {
+    events: WalkEvents<'h, B>,
+}
+
+impl<'h, B> KeyNodeWalker<'h, B>
+where
+    B: SplitByteSlice,
+{
+    pub(crate) fn new(key_node: &KeyNode<'h, B>) -> Result<Self> {
+        let mut events = WalkEvents::new(key_node, TraversalOrder::ValuesFirst, false)?;
+        events.start_pending = false;
+
+        Ok(Self { events })
+    }
+
+    /// Returns the names of all keys from below the start key up to (and including) the key
+    /// that has last been returned by `next`.
+    pub(crate) fn path(&self) -> &[NtHiveNameString<'h>] {
+        self.events.path()
+    }
+
+    /// Returns the result of [`KeyNodeWalker::path`] as a single string (see [`join_path`]).
+    pub(crate) fn path_string(&self) -> String {
+        join_path(self.path())
+    }
+}
+
+impl<'h, B> Iterator for KeyNodeWalker<'h, B>
+where
+    B: SplitByteSlice,
+{
+    type Item = Result<KeyNode<'h, B>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match iter_try!(self.events.next()?) {
+                WalkEvent::EnterKey(key_node) => return Some(Ok(key_node)),
+                WalkEvent::Value(_) | WalkEvent::LeaveKey(_) => continue,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::tests::{cell_data, key_node_field, read_u32, write_u16, write_u32};
+    use crate::key_node::KeyNodeField;
+    use crate::*;
+
+    fn event_names<B>(events: WalkEvents<B>) -> Vec<String>
+    where
+        B: zerocopy::SplitByteSlice,
+    {
+        events
+            .map(|event| match event.unwrap() {
+                WalkEvent::EnterKey(key_node) => format!("+{}", key_node.name().unwrap()),
+                WalkEvent::Value(key_value) => format!("={}", key_value.name().unwrap()),
+                WalkEvent::LeaveKey(key_node) => format!("-{}", key_node.name().unwrap()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_walk_events() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+
+        // No key of the testhive has both values and subkeys, so give the first two values of "data-test"
+        // to "with-two-levels-of-subkeys".
+        let data_test = root_key_node.subkey("data-test").unwrap().unwrap();
+        let (_, values_list_offset) = data_test.values_list_info();
+        let value_names = data_test
+            .values()
+            .unwrap()
+            .unwrap()
+            .take(2)
+            .map(|key_value| key_value.unwrap().name().unwrap().to_string_lossy())
+            .collect::<Vec<_>>();
+        let key_node = root_key_node
+            .subpath("subpath-test\\with-two-levels-of-subkeys")
+            .unwrap()
+            .unwrap();
+        let key_node_offset = key_node.cell_offset();
+        write_u32(
+            &mut testhive,
+            key_node_field(key_node_offset, KeyNodeField::KeyValuesCount),
+            2,
+        );
+        write_u32(
+            &mut testhive,
+            key_node_field(key_node_offset, KeyNodeField::KeyValuesListOffset),
+            values_list_offset,
+        );
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let key_node = root_key_node
+            .subpath("subpath-test\\with-two-levels-of-subkeys")
+            .unwrap()
+            .unwrap();
+
+        let events = key_node.walk_events(TraversalOrder::ValuesFirst).unwrap();
+        assert_eq!(
+            event_names(events),
+            [
+                "+with-two-levels-of-subkeys".to_owned(),
+                format!("={}", value_names[0]),
+                format!("={}", value_names[1]),
+                "+subkey1".to_owned(),
+                "+subkey2".to_owned(),
+                "-subkey2".to_owned(),
+                "-subkey1".to_owned(),
+                "-with-two-levels-of-subkeys".to_owned(),
+            ]
+        );
+
+        let events = key_node.walk_events(TraversalOrder::SubkeysFirst).unwrap();
+        assert_eq!(
+            event_names(events),
+            [
+                "+with-two-levels-of-subkeys".to_owned(),
+                "+subkey1".to_owned(),
+                "+subkey2".to_owned(),
+                "-subkey2".to_owned(),
+                "-subkey1".to_owned(),
+                format!("={}", value_names[0]),
+                format!("={}", value_names[1]),
+                "-with-two-levels-of-subkeys".to_owned(),
+            ]
+        );
+
+        // The path always ends with the innermost key that has been entered, but not left yet.
+        let mut events = key_node.walk_events(TraversalOrder::SubkeysFirst).unwrap();
+        let mut paths = Vec::new();
+        while let Some(event) = events.next() {
+            if let WalkEvent::LeaveKey(_) = event.unwrap() {
+                paths.push(join_path(events.path()));
+            }
+        }
+        assert_eq!(paths, ["subkey1", "", ""]);
+
+        // Every key of the hive is entered and left exactly once.
+        let events = hive
+            .walk_events(TraversalOrder::default())
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let enter_count = events
+            .iter()
+            .filter(|event| matches!(event, WalkEvent::EnterKey(_)))
+            .count();
+        let leave_count = events
+            .iter()
+            .filter(|event| matches!(event, WalkEvent::LeaveKey(_)))
+            .count();
+        assert_eq!(enter_count, 528);
+        assert_eq!(leave_count, 528);
+        assert!(matches!(events.last(), Some(WalkEvent::LeaveKey(key_node)) if key_node.is_root()));
+    }
+
+    #[test]
+    fn test_walk_events_repeated_key_node() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let root_cell_offset = root_key_node.cell_offset();
+        let data_test_offset = root_key_node
+            .subkey("data-test")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+
+        // Let "data-test" reference all subkeys of the root key, including itself.
+        let subkeys_fields = |cell_offset| {
+            key_node_field(cell_offset, KeyNodeField::SubkeyCount)
+                ..key_node_field(cell_offset, KeyNodeField::VolatileSubkeysListOffset)
+        };
+        testhive.copy_within(
+            subkeys_fields(root_cell_offset),
+            subkeys_fields(data_test_offset).start,
+        );
+
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let mut root_subkey_offsets = root_key_node
+            .subkeys()
+            .unwrap()
+            .unwrap()
+            .map(|subkey| subkey.unwrap().header_offset())
+            .collect::<Vec<_>>();
+        root_subkey_offsets.sort_unstable();
+
+        // Every root subkey is reported once when it is referenced again, and every key is still entered once.
+        let mut enter_count = 0;
+        let mut repeated_offsets = Vec::new();
+        for event in hive.walk_events(TraversalOrder::default()).unwrap() {
+            match event {
+                Ok(WalkEvent::EnterKey(_)) => enter_count += 1,
+                Ok(_) => (),
+                Err(NtHiveError::RepeatedKeyNode { offset }) => repeated_offsets.push(offset),
+                Err(e) => panic!("Unexpected error: {e}"),
+            }
+        }
+        repeated_offsets.sort_unstable();
+        assert_eq!(repeated_offsets, root_subkey_offsets);
+        assert_eq!(enter_count, 528);
+    }
+
+    #[test]
+    fn test_walk_events_broken_subkeys_list() {
+        let mut testhive = crate::helpers::tests::testhive_vec();
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let subpath_test_offset = hive
+            .root_key_node()
+            .unwrap()
+            .subkey("subpath-test")
+            .unwrap()
+            .unwrap()
+            .cell_offset();
+
+        // Overwrite the signature of the Subkeys List of "subpath-test".
+        let subkeys_list_offset = read_u32(
+            &testhive,
+            key_node_field(subpath_test_offset, KeyNodeField::SubkeysListOffset),
+        );
+        write_u16(&mut testhive, cell_data(subkeys_list_offset), 0);
+
+        // "subpath-test" is still entered and left, and the error is returned in place of its subkeys.
+        let hive = Hive::new(testhive.as_ref()).unwrap();
+        let subpath_test = hive
+            .root_key_node()
+            .unwrap()
+            .subkey("subpath-test")
+            .unwrap()
+            .unwrap();
+        let root_key_node = hive.root_key_node().unwrap();
+        let events = root_key_node
+            .walk_events(TraversalOrder::default())
+            .unwrap()
+            .skip_while(|event| {
+                !matches!(event, Ok(WalkEvent::EnterKey(key_node)) if *key_node == subpath_test)
+            })
+            .take(3)
+            .collect::<Vec<_>>();
+        assert!(matches!(events[0], Ok(WalkEvent::EnterKey(_))));
+        assert!(matches!(
+            events[1],
+            Err(NtHiveError::InvalidTwoByteSignature { .. })
+        ));
+        assert!(
+            matches!(&events[2], Ok(WalkEvent::LeaveKey(key_node)) if *key_node == subpath_test)
+        );
+    }
+}